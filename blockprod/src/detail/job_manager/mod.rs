@@ -183,6 +183,7 @@ impl JobManager {
                                         "Chainstate subscriber failed to send new tip",
                                     );
                                 }
+                                ChainstateEvent::Reorg(_) => {}
                             },
                         );
 
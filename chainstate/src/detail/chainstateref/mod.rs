@@ -32,7 +32,7 @@ use common::{
         tokens::TokenAuxiliaryData,
         tokens::{get_tokens_issuance_count, TokenId},
         Block, ChainConfig, GenBlock, GenBlockId, OutPoint, OutPointSourceId, Transaction,
-        TxOutput,
+        TxMainChainPosition, TxOutput,
     },
     primitives::{id::WithId, BlockHeight, Id, Idable},
     time_getter::TimeGetter,
@@ -193,6 +193,16 @@ impl<'a, S: BlockchainStorageRead, V: TransactionVerificationStrategy> Chainstat
         self.db_tx.get_mainchain_tx_index(tx_id).map_err(PropertyQueryError::from)
     }
 
+    pub fn get_mainchain_tx_by_position(
+        &self,
+        tx_position: &TxMainChainPosition,
+    ) -> Result<Option<Transaction>, PropertyQueryError> {
+        log::trace!("Loading transaction at position: {:?}", tx_position);
+        self.db_tx
+            .get_mainchain_tx_by_position(tx_position)
+            .map_err(PropertyQueryError::from)
+    }
+
     pub fn get_block_id_by_height(
         &self,
         height: &BlockHeight,
@@ -647,7 +657,8 @@ impl<'a, S: BlockchainStorageRead, V: TransactionVerificationStrategy> Chainstat
                             )
                         }
                     }
-                    common::chain::signature::inputsig::InputWitness::Standard(_) => (),
+                    common::chain::signature::inputsig::InputWitness::Standard(_)
+                    | common::chain::signature::inputsig::InputWitness::ScriptHash(_) => (),
                 }
             }
         }
@@ -44,6 +44,7 @@ use tx_verifier::transaction_verifier;
 use std::{collections::VecDeque, sync::Arc};
 
 use itertools::Itertools;
+use tokio::sync::broadcast;
 
 use chainstate_storage::{
     BlockchainStorage, BlockchainStorageRead, BlockchainStorageWrite, SealedStorageTag,
@@ -54,7 +55,7 @@ use common::{
     chain::{
         block::{signed_block_header::SignedBlockHeader, timestamp::BlockTimestamp},
         config::ChainConfig,
-        Block, TxOutput,
+        Block, GenBlock, GenBlockId, TxOutput,
     },
     primitives::{id::WithId, BlockHeight, Id, Idable},
     time_getter::TimeGetter,
@@ -79,6 +80,10 @@ type ChainstateEventHandler = EventHandler<ChainstateEvent>;
 
 pub type OrphanErrorHandler = dyn Fn(&BlockError) + Send + Sync;
 
+/// The number of events kept for the RPC subscribers, a subscriber that falls further behind
+/// misses the oldest events
+const RPC_EVENTS_CAPACITY: usize = 1000;
+
 #[must_use]
 pub struct Chainstate<S, V> {
     chain_config: Arc<ChainConfig>,
@@ -88,6 +93,8 @@ pub struct Chainstate<S, V> {
     orphan_blocks: OrphansProxy,
     custom_orphan_error_hook: Option<Arc<OrphanErrorHandler>>,
     events_controller: EventsController<ChainstateEvent>,
+    /// The channel shared by the RPC subscribers, created with the first subscription
+    rpc_events: Option<broadcast::Sender<ChainstateEvent>>,
     time_getter: TimeGetter,
     is_initial_block_download_finished: bool,
 }
@@ -138,6 +145,23 @@ impl<S: BlockchainStorage, V: TransactionVerificationStrategy> Chainstate<S, V>
         self.events_controller.subscribe_to_events(handler);
     }
 
+    /// Subscribe to the events through a channel shared by all the RPC subscribers.
+    /// Unlike the event handlers, a subscriber goes away together with its receiver.
+    pub fn subscribe_to_rpc_events(&mut self) -> broadcast::Receiver<ChainstateEvent> {
+        if let Some(sender) = &self.rpc_events {
+            return sender.subscribe();
+        }
+
+        let (sender, receiver) = broadcast::channel(RPC_EVENTS_CAPACITY);
+        let handler_sender = sender.clone();
+        // Sending fails only while there are no subscribers
+        self.events_controller.subscribe_to_events(Arc::new(move |event| {
+            let _ = handler_sender.send(event);
+        }));
+        self.rpc_events = Some(sender);
+        receiver
+    }
+
     pub fn new(
         chain_config: Arc<ChainConfig>,
         chainstate_config: ChainstateConfig,
@@ -195,6 +219,7 @@ impl<S: BlockchainStorage, V: TransactionVerificationStrategy> Chainstate<S, V>
             orphan_blocks,
             custom_orphan_error_hook,
             events_controller: EventsController::new(),
+            rpc_events: None,
             time_getter,
             is_initial_block_download_finished: false,
         }
@@ -260,9 +285,16 @@ impl<S: BlockchainStorage, V: TransactionVerificationStrategy> Chainstate<S, V>
         Ok(())
     }
 
-    fn broadcast_new_tip_event(&self, new_block_index: &Option<BlockIndex>) {
+    fn broadcast_new_tip_event(
+        &self,
+        old_tip_id: &Id<GenBlock>,
+        new_block_index: &Option<BlockIndex>,
+    ) {
         match new_block_index {
             Some(ref new_block_index) => {
+                if new_block_index.prev_block_id() != old_tip_id {
+                    self.broadcast_reorg_event(old_tip_id);
+                }
                 let new_height = new_block_index.block_height();
                 let new_id = *new_block_index.block_id();
                 self.events_controller.broadcast(ChainstateEvent::NewTip(new_id, new_height))
@@ -271,6 +303,37 @@ impl<S: BlockchainStorage, V: TransactionVerificationStrategy> Chainstate<S, V>
         }
     }
 
+    fn broadcast_reorg_event(&self, old_tip_id: &Id<GenBlock>) {
+        match self.disconnected_blocks(old_tip_id) {
+            Ok(disconnected) if disconnected.is_empty() => (),
+            Ok(disconnected) => {
+                self.events_controller.broadcast(ChainstateEvent::Reorg(disconnected))
+            }
+            Err(e) => log::error!("Failed to collect the blocks disconnected by a reorg: {e}"),
+        }
+    }
+
+    /// Walk back from the old tip to the mainchain, collecting the blocks that left it
+    fn disconnected_blocks(
+        &self,
+        old_tip_id: &Id<GenBlock>,
+    ) -> Result<Vec<Id<Block>>, PropertyQueryError> {
+        let chainstate_ref = self.make_db_tx_ro()?;
+        let mut disconnected = Vec::new();
+        let mut block_id = *old_tip_id;
+        while !chainstate_ref.is_block_in_main_chain(&block_id)? {
+            let block_index = match block_id.classify(&self.chain_config) {
+                GenBlockId::Genesis(_) => break,
+                GenBlockId::Block(id) => chainstate_ref
+                    .get_block_index(&id)?
+                    .ok_or(PropertyQueryError::BlockNotFound(id))?,
+            };
+            disconnected.push(*block_index.block_id());
+            block_id = *block_index.prev_block_id();
+        }
+        Ok(disconnected)
+    }
+
     fn attempt_to_process_block(
         &mut self,
         block: WithId<Block>,
@@ -358,6 +421,12 @@ impl<S: BlockchainStorage, V: TransactionVerificationStrategy> Chainstate<S, V>
     ) -> Result<Option<BlockIndex>, BlockError> {
         let block_id = block.get_id();
 
+        let old_tip_id = self
+            .query()
+            .and_then(|query| query.get_best_block_id())
+            .map_err(BlockError::BestBlockLoadError)
+            .log_err()?;
+
         let result = self.attempt_to_process_block(block, block_source)?;
 
         let new_block_index_after_orphans = self.process_orphans_of(block_id)?;
@@ -367,7 +436,7 @@ impl<S: BlockchainStorage, V: TransactionVerificationStrategy> Chainstate<S, V>
             None => result,
         };
 
-        self.broadcast_new_tip_event(&result);
+        self.broadcast_new_tip_event(&old_tip_id, &result);
 
         if let Some(ref bi) = result {
             log::info!(
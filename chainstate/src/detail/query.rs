@@ -22,7 +22,8 @@ use common::{
             RPCFungibleTokenInfo, RPCNonFungibleTokenInfo, RPCTokenInfo, TokenAuxiliaryData,
             TokenData, TokenId,
         },
-        Block, GenBlock, OutPointSourceId, Transaction, TxMainChainIndex, TxOutput,
+        Block, GenBlock, OutPointSourceId, SpendablePosition, Transaction, TxMainChainIndex,
+        TxOutput,
     },
     primitives::{BlockDistance, BlockHeight, Id, Idable},
 };
@@ -209,6 +210,24 @@ impl<'a, S: BlockchainStorageRead, V: TransactionVerificationStrategy> Chainstat
         self.chainstate_ref.get_mainchain_tx_index(tx_id)
     }
 
+    pub fn get_mainchain_tx(
+        &self,
+        tx_id: &Id<Transaction>,
+    ) -> Result<Option<(Transaction, Id<Block>)>, PropertyQueryError> {
+        let tx_index = match self.get_mainchain_tx_index(&(*tx_id).into())? {
+            Some(tx_index) => tx_index,
+            None => return Ok(None),
+        };
+        match tx_index.position() {
+            SpendablePosition::Transaction(position) => Ok(self
+                .chainstate_ref
+                .get_mainchain_tx_by_position(position)?
+                .map(|tx| (tx, *position.block_id()))),
+            // Only block rewards are indexed by a block id
+            SpendablePosition::BlockReward(_) => Ok(None),
+        }
+    }
+
     pub fn get_token_info_for_rpc(
         &self,
         token_id: TokenId,
@@ -32,12 +32,14 @@ use common::{
     primitives::{Amount, BlockHeight, Id},
 };
 use pos_accounting::{DelegationData, PoolData};
+use tokio::sync::broadcast;
 use utils::eventhandler::EventHandler;
 
 use utxo::Utxo;
 
 pub trait ChainstateInterface: Send {
     fn subscribe_to_events(&mut self, handler: Arc<dyn Fn(ChainstateEvent) + Send + Sync>);
+    fn subscribe_to_rpc_events(&mut self) -> broadcast::Receiver<ChainstateEvent>;
     fn process_block(
         &mut self,
         block: Block,
@@ -104,6 +106,13 @@ pub trait ChainstateInterface: Send {
         &self,
         tx_id: &OutPointSourceId,
     ) -> Result<Option<TxMainChainIndex>, ChainstateError>;
+
+    /// Returns a mainchain transaction and the id of the block that contains it.
+    /// Requires the transaction index to be enabled.
+    fn get_mainchain_tx(
+        &self,
+        tx_id: &Id<Transaction>,
+    ) -> Result<Option<(Transaction, Id<Block>)>, ChainstateError>;
     fn subscribers(&self) -> &Vec<EventHandler<ChainstateEvent>>;
     fn calculate_median_time_past(
         &self,
@@ -38,6 +38,8 @@ use common::{
     primitives::{id::WithId, Amount, BlockHeight, Id},
 };
 use pos_accounting::{DelegationData, PoSAccountingView, PoolData};
+use tokio::sync::broadcast;
+use tx_verifier::transaction_verifier::storage::HasTxIndexDisabledError;
 use utils::eventhandler::EventHandler;
 use utxo::{Utxo, UtxosView};
 
@@ -58,6 +60,10 @@ impl<S: BlockchainStorage, V: TransactionVerificationStrategy> ChainstateInterfa
         self.chainstate.subscribe_to_events(handler)
     }
 
+    fn subscribe_to_rpc_events(&mut self) -> broadcast::Receiver<ChainstateEvent> {
+        self.chainstate.subscribe_to_rpc_events()
+    }
+
     fn process_block(
         &mut self,
         block: Block,
@@ -250,6 +256,21 @@ impl<S: BlockchainStorage, V: TransactionVerificationStrategy> ChainstateInterfa
             .map_err(ChainstateError::FailedToReadProperty)
     }
 
+    fn get_mainchain_tx(
+        &self,
+        tx_id: &Id<Transaction>,
+    ) -> Result<Option<(Transaction, Id<Block>)>, ChainstateError> {
+        utils::ensure!(
+            *self.chainstate.chainstate_config().tx_index_enabled,
+            ChainstateError::tx_index_disabled_error()
+        );
+        self.chainstate
+            .query()
+            .map_err(ChainstateError::from)?
+            .get_mainchain_tx(tx_id)
+            .map_err(ChainstateError::FailedToReadProperty)
+    }
+
     fn subscribers(&self) -> &Vec<EventHandler<ChainstateEvent>> {
         self.chainstate.events_controller().subscribers()
     }
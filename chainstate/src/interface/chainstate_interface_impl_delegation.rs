@@ -40,6 +40,7 @@ use common::{
     primitives::Amount,
 };
 use pos_accounting::{DelegationData, PoolData};
+use tokio::sync::broadcast;
 use utils::eventhandler::EventHandler;
 use utxo::Utxo;
 
@@ -56,6 +57,10 @@ where
         self.deref_mut().subscribe_to_events(handler)
     }
 
+    fn subscribe_to_rpc_events(&mut self) -> broadcast::Receiver<ChainstateEvent> {
+        self.deref_mut().subscribe_to_rpc_events()
+    }
+
     fn process_block(
         &mut self,
         block: Block,
@@ -163,6 +168,13 @@ where
         self.deref().get_mainchain_tx_index(tx_id)
     }
 
+    fn get_mainchain_tx(
+        &self,
+        tx_id: &Id<Transaction>,
+    ) -> Result<Option<(Transaction, Id<Block>)>, ChainstateError> {
+        self.deref().get_mainchain_tx(tx_id)
+    }
+
     fn subscribers(&self) -> &Vec<EventHandler<ChainstateEvent>> {
         self.deref().subscribers()
     }
@@ -53,6 +53,9 @@ use detail::Chainstate;
 #[derive(Debug, Clone)]
 pub enum ChainstateEvent {
     NewTip(Id<Block>, BlockHeight),
+    /// Blocks disconnected from the mainchain by a reorg, starting from the old tip. Sent before
+    /// the `NewTip` event of the new chain.
+    Reorg(Vec<Id<Block>>),
 }
 
 #[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
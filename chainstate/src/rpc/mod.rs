@@ -15,18 +15,53 @@
 
 //! Chainstate subsystem RPC handler
 
-use std::io::{Read, Write};
+mod types;
 
-use crate::{Block, BlockSource, ChainInfo, GenBlock};
+use std::{
+    collections::BTreeMap,
+    io::{Read, Write},
+    sync::Arc,
+};
+
+use crate::{Block, BlockSource, ChainInfo, ChainstateError, ChainstateEvent, GenBlock};
+use chainstate_types::BlockIndex;
 use common::{
     chain::{
         tokens::{RPCTokenInfo, TokenId},
-        PoolId,
+        ChainConfig, DelegationId, OutPoint, PoolId, Transaction,
     },
     primitives::{Amount, BlockHeight, Id},
 };
-use rpc::Result as RpcResult;
+use rpc::{
+    subscription::{PendingSubscriptionSink, SubscriptionResult},
+    Result as RpcResult,
+};
 use serialization::hex_encoded::HexEncoded;
+use utxo::Utxo;
+
+pub use types::{
+    RpcBlock, RpcBlockHeader, RpcConsensusData, RpcMainChainTransaction, RpcOutPoint,
+    RpcOutPointSource, RpcOutputTimeLock, RpcOutputValue, RpcTransaction, RpcTxOutput, RpcUtxo,
+};
+
+/// Chainstate event as delivered to the RPC subscribers
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RpcChainstateEvent {
+    NewTip { id: Id<Block>, height: BlockHeight },
+    Reorg { disconnected_blocks: Vec<Id<Block>> },
+}
+
+impl From<ChainstateEvent> for RpcChainstateEvent {
+    fn from(event: ChainstateEvent) -> Self {
+        match event {
+            ChainstateEvent::NewTip(id, height) => Self::NewTip { id, height },
+            ChainstateEvent::Reorg(disconnected_blocks) => Self::Reorg {
+                disconnected_blocks,
+            },
+        }
+    }
+}
 
 #[rpc::rpc(server, client, namespace = "chainstate")]
 trait ChainstateRpc {
@@ -42,6 +77,26 @@ trait ChainstateRpc {
     #[method(name = "get_block")]
     async fn get_block(&self, id: Id<Block>) -> RpcResult<Option<HexEncoded<Block>>>;
 
+    /// Returns the decoded header of the block with the given id.
+    #[method(name = "get_block_header")]
+    async fn get_block_header(&self, id: Id<Block>) -> RpcResult<Option<RpcBlockHeader>>;
+
+    /// Returns the decoded block with the given id.
+    #[method(name = "get_block_json")]
+    async fn get_block_json(&self, id: Id<Block>) -> RpcResult<Option<RpcBlock>>;
+
+    /// Returns a decoded mainchain transaction and the block containing it.
+    /// Fails if the transaction index is disabled.
+    #[method(name = "get_transaction")]
+    async fn get_transaction(
+        &self,
+        id: Id<Transaction>,
+    ) -> RpcResult<Option<RpcMainChainTransaction>>;
+
+    /// Returns the unspent output at the given outpoint
+    #[method(name = "get_utxo")]
+    async fn get_utxo(&self, outpoint: RpcOutPoint) -> RpcResult<Option<RpcUtxo>>;
+
     /// Submit a block to be included in the chain
     #[method(name = "submit_block")]
     async fn submit_block(&self, block_hex: HexEncoded<Block>) -> RpcResult<()>;
@@ -69,6 +124,24 @@ trait ChainstateRpc {
     #[method(name = "stake_pool_balance")]
     async fn stake_pool_balance(&self, pool_id: PoolId) -> RpcResult<Option<Amount>>;
 
+    /// Get the amount pledged by the owner of a stake pool
+    #[method(name = "stake_pool_pledge")]
+    async fn stake_pool_pledge(&self, pool_id: PoolId) -> RpcResult<Option<Amount>>;
+
+    /// Get the shares of all the delegations to a stake pool
+    #[method(name = "stake_pool_delegations_shares")]
+    async fn stake_pool_delegations_shares(
+        &self,
+        pool_id: PoolId,
+    ) -> RpcResult<Option<BTreeMap<DelegationId, Amount>>>;
+
+    /// Get the balance of a delegation, including the rewards it has received
+    #[method(name = "stake_delegation_balance")]
+    async fn stake_delegation_balance(
+        &self,
+        delegation_id: DelegationId,
+    ) -> RpcResult<Option<Amount>>;
+
     /// Get token information
     #[method(name = "token_info")]
     async fn token_info(&self, token_id: TokenId) -> RpcResult<Option<RPCTokenInfo>>;
@@ -88,6 +161,14 @@ trait ChainstateRpc {
     /// Return information about the chain.
     #[method(name = "info")]
     async fn info(&self) -> RpcResult<ChainInfo>;
+
+    /// Subscribe to new tip and reorg notifications. Only available over websocket.
+    #[subscription(
+        name = "subscribe_events",
+        unsubscribe = "unsubscribe_events",
+        item = RpcChainstateEvent,
+    )]
+    async fn subscribe_events(&self) -> SubscriptionResult;
 }
 
 #[async_trait::async_trait]
@@ -106,6 +187,64 @@ impl ChainstateRpcServer for super::ChainstateHandle {
         Ok(block.map(HexEncoded::new))
     }
 
+    async fn get_block_header(&self, id: Id<Block>) -> RpcResult<Option<RpcBlockHeader>> {
+        let block_index: Option<BlockIndex> =
+            rpc::handle_result(self.call(move |this| this.get_block_index(&id)).await)?;
+        Ok(block_index.map(|bi| RpcBlockHeader::new(bi.block_header(), bi.block_height())))
+    }
+
+    async fn get_block_json(&self, id: Id<Block>) -> RpcResult<Option<RpcBlock>> {
+        let (chain_config, block): (Arc<ChainConfig>, Option<(Block, BlockHeight)>) =
+            rpc::handle_result(
+                self.call(move |this| {
+                    let block = match this.get_block_index(&id)? {
+                        Some(bi) => this.get_block(id)?.map(|block| (block, bi.block_height())),
+                        None => None,
+                    };
+                    Ok::<_, ChainstateError>((Arc::clone(this.get_chain_config()), block))
+                })
+                .await,
+            )?;
+        block
+            .map(|(block, height)| rpc::handle_result(RpcBlock::new(&chain_config, &block, height)))
+            .transpose()
+    }
+
+    async fn get_transaction(
+        &self,
+        id: Id<Transaction>,
+    ) -> RpcResult<Option<RpcMainChainTransaction>> {
+        let (chain_config, tx): (Arc<ChainConfig>, Option<(Transaction, Id<Block>)>) =
+            rpc::handle_result(
+                self.call(move |this| {
+                    let tx = this.get_mainchain_tx(&id)?;
+                    Ok::<_, ChainstateError>((Arc::clone(this.get_chain_config()), tx))
+                })
+                .await,
+            )?;
+        tx.map(|(tx, block_id)| {
+            let transaction = rpc::handle_result(RpcTransaction::new(&chain_config, &tx))?;
+            Ok(RpcMainChainTransaction {
+                block_id,
+                transaction,
+            })
+        })
+        .transpose()
+    }
+
+    async fn get_utxo(&self, outpoint: RpcOutPoint) -> RpcResult<Option<RpcUtxo>> {
+        let outpoint = OutPoint::from(outpoint);
+        let (chain_config, utxo): (Arc<ChainConfig>, Option<Utxo>) = rpc::handle_result(
+            self.call(move |this| {
+                let utxo = this.utxo(&outpoint)?;
+                Ok::<_, ChainstateError>((Arc::clone(this.get_chain_config()), utxo))
+            })
+            .await,
+        )?;
+        utxo.map(|utxo| rpc::handle_result(RpcUtxo::new(&chain_config, &utxo)))
+            .transpose()
+    }
+
     async fn submit_block(&self, block: HexEncoded<Block>) -> RpcResult<()> {
         let res = self
             .call_mut(move |this| this.process_block(block.take(), BlockSource::Local))
@@ -143,6 +282,34 @@ impl ChainstateRpcServer for super::ChainstateHandle {
         rpc::handle_result(self.call(move |this| this.get_stake_pool_balance(pool_id)).await)
     }
 
+    async fn stake_pool_pledge(&self, pool_id: PoolId) -> RpcResult<Option<Amount>> {
+        rpc::handle_result(
+            self.call(move |this| {
+                this.get_stake_pool_data(pool_id)
+                    .map(|pool_data| pool_data.map(|pool_data| pool_data.pledge_amount()))
+            })
+            .await,
+        )
+    }
+
+    async fn stake_pool_delegations_shares(
+        &self,
+        pool_id: PoolId,
+    ) -> RpcResult<Option<BTreeMap<DelegationId, Amount>>> {
+        rpc::handle_result(
+            self.call(move |this| this.get_stake_pool_delegations_shares(pool_id)).await,
+        )
+    }
+
+    async fn stake_delegation_balance(
+        &self,
+        delegation_id: DelegationId,
+    ) -> RpcResult<Option<Amount>> {
+        rpc::handle_result(
+            self.call(move |this| this.get_stake_delegation_balance(delegation_id)).await,
+        )
+    }
+
     async fn token_info(&self, token_id: TokenId) -> RpcResult<Option<RPCTokenInfo>> {
         rpc::handle_result(self.call(move |this| this.get_token_info_for_rpc(token_id)).await)
     }
@@ -175,6 +342,11 @@ impl ChainstateRpcServer for super::ChainstateHandle {
     async fn info(&self) -> RpcResult<ChainInfo> {
         rpc::handle_result(self.call(move |this| this.info()).await)
     }
+
+    async fn subscribe_events(&self, pending: PendingSubscriptionSink) -> SubscriptionResult {
+        let receiver = self.call_mut(|this| this.subscribe_to_rpc_events()).await?;
+        rpc::subscription::pipe_from_receiver::<_, RpcChainstateEvent>(pending, receiver).await
+    }
 }
 
 #[cfg(test)]
@@ -182,7 +354,7 @@ mod test {
     use super::*;
     use crate::{ChainstateConfig, DefaultTransactionVerificationStrategy};
     use serde_json::Value;
-    use std::{future::Future, sync::Arc};
+    use std::future::Future;
 
     async fn with_chainstate<F: 'static + Send + Future<Output = ()>>(
         proc: impl 'static + Send + FnOnce(crate::ChainstateHandle) -> F,
@@ -240,4 +412,50 @@ mod test {
         })
         .await
     }
+
+    #[tokio::test]
+    async fn decoded_rpc_requests() {
+        with_chainstate(|handle| async {
+            let rpc = handle.into_rpc();
+            let chain_config = common::chain::config::create_unit_test_config();
+            let genesis_id = chain_config.genesis_block_id();
+
+            let outpoint = RpcOutPoint {
+                source: RpcOutPointSource::BlockReward {
+                    block_id: genesis_id,
+                },
+                index: 0,
+            };
+            let res: RpcResult<Option<RpcUtxo>> =
+                rpc.call("chainstate_get_utxo", [outpoint.clone()]).await;
+            let expected_utxo = Utxo::new_for_blockchain(
+                chain_config.genesis_block().utxos()[0].clone(),
+                BlockHeight::zero(),
+            );
+            assert_eq!(
+                res.unwrap(),
+                Some(RpcUtxo::new(&chain_config, &expected_utxo).unwrap())
+            );
+
+            let json = serde_json::to_value(&outpoint).unwrap();
+            assert_eq!(json["source"]["type"], "block_reward");
+
+            let missing_outpoint = RpcOutPoint {
+                index: 1,
+                ..outpoint
+            };
+            let res: RpcResult<Option<RpcUtxo>> =
+                rpc.call("chainstate_get_utxo", [missing_outpoint]).await;
+            assert_eq!(res.unwrap(), None);
+
+            let unknown_block = Id::<Block>::new(common::primitives::H256::zero());
+            let res: RpcResult<Value> =
+                rpc.call("chainstate_get_block_header", [unknown_block]).await;
+            assert!(matches!(res, Ok(Value::Null)));
+            let res: RpcResult<Value> =
+                rpc.call("chainstate_get_block_json", [unknown_block]).await;
+            assert!(matches!(res, Ok(Value::Null)));
+        })
+        .await
+    }
 }
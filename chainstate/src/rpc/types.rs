@@ -0,0 +1,356 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Decoded forms of blocks and transactions returned by the chainstate RPC
+//!
+//! Destinations are shown as addresses and coin amounts as decimal strings. Token transfers are
+//! shown in atoms since the number of decimals of a token is only known from its issuance.
+
+use common::{
+    address::{Address, AddressError},
+    chain::{
+        block::{signed_block_header::SignedBlockHeader, ConsensusData},
+        timelock::OutputTimeLock,
+        tokens::{OutputValue, TokenData, TokenId},
+        Block, ChainConfig, DelegationId, Destination, GenBlock, OutPoint, OutPointSourceId,
+        PoolId, Transaction, TxOutput,
+    },
+    primitives::{Amount, BlockHeight, Id, Idable, H256},
+};
+use utxo::{Utxo, UtxoSource};
+
+fn coin_amount(chain_config: &ChainConfig, amount: Amount) -> String {
+    amount.into_fixedpoint_str(chain_config.coin_decimals())
+}
+
+fn address(chain_config: &ChainConfig, destination: &Destination) -> Result<String, AddressError> {
+    Ok(Address::from_destination(chain_config, destination)?.get().to_owned())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RpcConsensusData {
+    None,
+    #[serde(rename = "pow")]
+    PoW,
+    #[serde(rename = "pos")]
+    PoS {
+        pool_id: PoolId,
+    },
+}
+
+impl From<&ConsensusData> for RpcConsensusData {
+    fn from(data: &ConsensusData) -> Self {
+        match data {
+            ConsensusData::None => Self::None,
+            ConsensusData::PoW(_) => Self::PoW,
+            ConsensusData::PoS(data) => Self::PoS {
+                pool_id: *data.stake_pool_id(),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RpcBlockHeader {
+    pub id: Id<Block>,
+    pub prev_block_id: Id<GenBlock>,
+    pub height: BlockHeight,
+    /// Seconds since the unix epoch
+    pub timestamp: u64,
+    pub consensus_data: RpcConsensusData,
+}
+
+impl RpcBlockHeader {
+    pub fn new(header: &SignedBlockHeader, height: BlockHeight) -> Self {
+        Self {
+            id: header.block_id(),
+            prev_block_id: *header.prev_block_id(),
+            height,
+            timestamp: header.timestamp().as_int_seconds(),
+            consensus_data: header.consensus_data().into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RpcBlock {
+    pub header: RpcBlockHeader,
+    pub merkle_root: H256,
+    pub witness_merkle_root: H256,
+    pub reward: Vec<RpcTxOutput>,
+    pub transactions: Vec<RpcTransaction>,
+}
+
+impl RpcBlock {
+    pub fn new(
+        chain_config: &ChainConfig,
+        block: &Block,
+        height: BlockHeight,
+    ) -> Result<Self, AddressError> {
+        let reward = block
+            .block_reward()
+            .outputs()
+            .iter()
+            .map(|output| RpcTxOutput::new(chain_config, output))
+            .collect::<Result<_, _>>()?;
+        let transactions = block
+            .transactions()
+            .iter()
+            .map(|tx| RpcTransaction::new(chain_config, tx.transaction()))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            header: RpcBlockHeader::new(block.header(), height),
+            merkle_root: block.merkle_root(),
+            witness_merkle_root: block.witness_merkle_root(),
+            reward,
+            transactions,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RpcTransaction {
+    pub id: Id<Transaction>,
+    pub is_replaceable: bool,
+    pub inputs: Vec<RpcOutPoint>,
+    pub outputs: Vec<RpcTxOutput>,
+}
+
+impl RpcTransaction {
+    pub fn new(chain_config: &ChainConfig, tx: &Transaction) -> Result<Self, AddressError> {
+        let outputs = tx
+            .outputs()
+            .iter()
+            .map(|output| RpcTxOutput::new(chain_config, output))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            id: tx.get_id(),
+            is_replaceable: tx.is_replaceable(),
+            inputs: tx.inputs().iter().map(|input| input.outpoint().into()).collect(),
+            outputs,
+        })
+    }
+}
+
+/// A transaction found through the transaction index together with the block that contains it
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RpcMainChainTransaction {
+    pub block_id: Id<Block>,
+    pub transaction: RpcTransaction,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RpcOutPointSource {
+    Transaction { tx_id: Id<Transaction> },
+    BlockReward { block_id: Id<GenBlock> },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RpcOutPoint {
+    pub source: RpcOutPointSource,
+    pub index: u32,
+}
+
+impl From<&OutPoint> for RpcOutPoint {
+    fn from(outpoint: &OutPoint) -> Self {
+        let source = match outpoint.tx_id() {
+            OutPointSourceId::Transaction(tx_id) => RpcOutPointSource::Transaction { tx_id },
+            OutPointSourceId::BlockReward(block_id) => RpcOutPointSource::BlockReward { block_id },
+        };
+        Self {
+            source,
+            index: outpoint.output_index(),
+        }
+    }
+}
+
+impl From<RpcOutPoint> for OutPoint {
+    fn from(outpoint: RpcOutPoint) -> Self {
+        let source = match outpoint.source {
+            RpcOutPointSource::Transaction { tx_id } => OutPointSourceId::Transaction(tx_id),
+            RpcOutPointSource::BlockReward { block_id } => OutPointSourceId::BlockReward(block_id),
+        };
+        OutPoint::new(source, outpoint.index)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RpcOutputValue {
+    Coin {
+        amount: String,
+    },
+    TokenTransfer {
+        token_id: TokenId,
+        amount_atoms: String,
+    },
+    TokenIssuance {
+        ticker: String,
+        amount: String,
+    },
+    NftIssuance {
+        name: String,
+    },
+}
+
+impl RpcOutputValue {
+    pub fn new(chain_config: &ChainConfig, value: &OutputValue) -> Self {
+        match value {
+            OutputValue::Coin(amount) => Self::Coin {
+                amount: coin_amount(chain_config, *amount),
+            },
+            OutputValue::Token(token_data) => match token_data.as_ref() {
+                TokenData::TokenTransfer(transfer) => Self::TokenTransfer {
+                    token_id: transfer.token_id,
+                    amount_atoms: transfer.amount.into_atoms().to_string(),
+                },
+                TokenData::TokenIssuance(issuance) => Self::TokenIssuance {
+                    ticker: String::from_utf8_lossy(&issuance.token_ticker).into_owned(),
+                    amount: issuance
+                        .amount_to_issue
+                        .into_fixedpoint_str(issuance.number_of_decimals),
+                },
+                TokenData::NftIssuance(issuance) => Self::NftIssuance {
+                    name: String::from_utf8_lossy(&issuance.metadata.name).into_owned(),
+                },
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RpcOutputTimeLock {
+    UntilHeight { height: BlockHeight },
+    UntilTime { timestamp: u64 },
+    ForBlockCount { block_count: u64 },
+    ForSeconds { seconds: u64 },
+}
+
+impl From<&OutputTimeLock> for RpcOutputTimeLock {
+    fn from(timelock: &OutputTimeLock) -> Self {
+        match timelock {
+            OutputTimeLock::UntilHeight(height) => Self::UntilHeight { height: *height },
+            OutputTimeLock::UntilTime(time) => Self::UntilTime {
+                timestamp: time.as_int_seconds(),
+            },
+            OutputTimeLock::ForBlockCount(block_count) => Self::ForBlockCount {
+                block_count: *block_count,
+            },
+            OutputTimeLock::ForSeconds(seconds) => Self::ForSeconds { seconds: *seconds },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RpcTxOutput {
+    Transfer {
+        value: RpcOutputValue,
+        destination: String,
+    },
+    LockThenTransfer {
+        value: RpcOutputValue,
+        destination: String,
+        timelock: RpcOutputTimeLock,
+    },
+    Burn {
+        value: RpcOutputValue,
+    },
+    CreateStakePool {
+        pool_id: PoolId,
+        pledge: String,
+        staker: String,
+        decommission_key: String,
+        margin_ratio_per_thousand: u16,
+        cost_per_block: String,
+    },
+    ProduceBlockFromStake {
+        destination: String,
+        pool_id: PoolId,
+    },
+    CreateDelegationId {
+        destination: String,
+        pool_id: PoolId,
+    },
+    DelegateStaking {
+        amount: String,
+        delegation_id: DelegationId,
+    },
+}
+
+impl RpcTxOutput {
+    pub fn new(chain_config: &ChainConfig, output: &TxOutput) -> Result<Self, AddressError> {
+        let result = match output {
+            TxOutput::Transfer(value, destination) => Self::Transfer {
+                value: RpcOutputValue::new(chain_config, value),
+                destination: address(chain_config, destination)?,
+            },
+            TxOutput::LockThenTransfer(value, destination, timelock) => Self::LockThenTransfer {
+                value: RpcOutputValue::new(chain_config, value),
+                destination: address(chain_config, destination)?,
+                timelock: timelock.into(),
+            },
+            TxOutput::Burn(value) => Self::Burn {
+                value: RpcOutputValue::new(chain_config, value),
+            },
+            TxOutput::CreateStakePool(pool_id, pool_data) => Self::CreateStakePool {
+                pool_id: *pool_id,
+                pledge: coin_amount(chain_config, pool_data.value()),
+                staker: address(chain_config, pool_data.staker())?,
+                decommission_key: address(chain_config, pool_data.decommission_key())?,
+                margin_ratio_per_thousand: pool_data.margin_ratio_per_thousand().value(),
+                cost_per_block: coin_amount(chain_config, pool_data.cost_per_block()),
+            },
+            TxOutput::ProduceBlockFromStake(destination, pool_id) => Self::ProduceBlockFromStake {
+                destination: address(chain_config, destination)?,
+                pool_id: *pool_id,
+            },
+            TxOutput::CreateDelegationId(destination, pool_id) => Self::CreateDelegationId {
+                destination: address(chain_config, destination)?,
+                pool_id: *pool_id,
+            },
+            TxOutput::DelegateStaking(amount, delegation_id) => Self::DelegateStaking {
+                amount: coin_amount(chain_config, *amount),
+                delegation_id: *delegation_id,
+            },
+        };
+        Ok(result)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RpcUtxo {
+    pub output: RpcTxOutput,
+    /// Height of the block that created the output
+    pub height: Option<BlockHeight>,
+}
+
+impl RpcUtxo {
+    pub fn new(chain_config: &ChainConfig, utxo: &Utxo) -> Result<Self, AddressError> {
+        let height = match utxo.source() {
+            UtxoSource::Blockchain(height) => Some(*height),
+            UtxoSource::Mempool => None,
+        };
+        Ok(Self {
+            output: RpcTxOutput::new(chain_config, utxo.output())?,
+            height,
+        })
+    }
+}
@@ -23,8 +23,10 @@ use chainstate::ChainstateEvent;
 use chainstate::CheckBlockError;
 use chainstate::OrphanCheckError;
 use common::chain::block::timestamp::BlockTimestamp;
+use common::chain::{Block, GenBlock};
 use common::primitives::id::Idable;
 use common::primitives::BlockHeight;
+use common::primitives::Id;
 use crypto::random::Rng;
 use rstest::rstest;
 use test_utils::random::make_seedable_rng;
@@ -35,6 +37,7 @@ use chainstate_test_framework::OrphanErrorHandler;
 use chainstate_test_framework::{TestChainstate, TestFramework};
 
 type ErrorList = Arc<Mutex<Vec<BlockError>>>;
+type ReorgList = Arc<Mutex<Vec<Vec<Id<Block>>>>>;
 
 // Subscribe to events, process a block and check that the `NewTip` event is triggered.
 #[rstest]
@@ -135,6 +138,70 @@ fn several_subscribers_several_events(#[case] seed: Seed) {
     });
 }
 
+// Switching to a fork with more chain trust reports the blocks that left the mainchain, tip first.
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn reorg_event(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let mut tf = TestFramework::builder(&mut rng).build();
+        let genesis_id = tf.genesis().get_id().into();
+
+        tf.create_chain(&genesis_id, 2, &mut rng).unwrap();
+        let old_chain = [tf.block_id(2), tf.block_id(1)];
+
+        let reorgs = subscribe_to_reorgs(&mut tf.chainstate);
+        let events = subscribe(&mut tf.chainstate, 1);
+
+        // Extending the mainchain is not a reorg.
+        let old_tip = tf.create_chain(&tf.best_block_id(), 1, &mut rng).unwrap();
+        tf.chainstate.wait_for_all_events();
+        assert!(reorgs.lock().unwrap().is_empty());
+
+        let new_tip = tf.create_chain(&genesis_id, 4, &mut rng).unwrap();
+        tf.chainstate.wait_for_all_events();
+        assert_eq!(tf.best_block_id(), new_tip);
+
+        let guard = reorgs.lock().unwrap();
+        assert_eq!(guard.len(), 1);
+        let disconnected: Vec<Id<GenBlock>> = guard[0].iter().map(|id| (*id).into()).collect();
+        assert_eq!(disconnected, [old_tip, old_chain[0], old_chain[1]]);
+
+        let (id, height) = events.lock().unwrap().last().cloned().unwrap();
+        assert_eq!(Id::<GenBlock>::from(id), new_tip);
+        assert_eq!(height, BlockHeight::new(4));
+    });
+}
+
+// The RPC subscribers share a single event handler and get the events through a channel.
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn rpc_subscribers(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let mut tf = TestFramework::builder(&mut rng).build();
+
+        let mut receivers =
+            [tf.chainstate.subscribe_to_rpc_events(), tf.chainstate.subscribe_to_rpc_events()];
+        assert_eq!(tf.chainstate.subscribers().len(), 1);
+
+        let block = tf.make_block_builder().add_test_transaction_from_best_block(&mut rng).build();
+        let block_id = block.get_id();
+        tf.process_block(block, BlockSource::Local).unwrap();
+        tf.chainstate.wait_for_all_events();
+
+        for receiver in &mut receivers {
+            assert!(matches!(
+                receiver.try_recv(),
+                Ok(ChainstateEvent::NewTip(id, height))
+                    if id == block_id && height == BlockHeight::new(1)
+            ));
+        }
+    });
+}
+
 // An orphan block is rejected during processing, so it shouldn't trigger the new tip event.
 #[rstest]
 #[trace]
@@ -214,6 +281,20 @@ fn custom_orphan_error_hook(#[case] seed: Seed) {
     });
 }
 
+// Collects the disconnected blocks of every reorg.
+fn subscribe_to_reorgs(chainstate: &mut TestChainstate) -> ReorgList {
+    let reorgs = Arc::new(Mutex::new(Vec::new()));
+
+    let reorgs_ = Arc::clone(&reorgs);
+    let handler = Arc::new(move |event: ChainstateEvent| match event {
+        ChainstateEvent::NewTip(_, _) => {}
+        ChainstateEvent::Reorg(disconnected) => reorgs_.lock().unwrap().push(disconnected),
+    });
+    chainstate.subscribe_to_events(handler);
+
+    reorgs
+}
+
 // Subscribes to events N times emulating different subscribers.
 fn subscribe(chainstate: &mut TestChainstate, n: usize) -> EventList {
     let events = Arc::new(Mutex::new(Vec::new()));
@@ -224,6 +305,7 @@ fn subscribe(chainstate: &mut TestChainstate, n: usize) -> EventList {
             ChainstateEvent::NewTip(block_id, block_height) => {
                 events_.lock().unwrap().push((block_id, block_height));
             }
+            ChainstateEvent::Reorg(_) => {}
         });
         chainstate.subscribe_to_events(handler);
     }
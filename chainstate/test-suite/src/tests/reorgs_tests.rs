@@ -386,6 +386,7 @@ fn subscribe_to_events(tf: &mut TestFramework, events: &EventList) {
                 events.lock().unwrap().push((block_id, block_height));
                 assert!(!events.lock().unwrap().is_empty());
             }
+            ChainstateEvent::Reorg(_) => {}
         },
     );
     tf.chainstate.subscribe_to_events(subscribe_func);
@@ -15,6 +15,9 @@
 
 use common::address::pubkeyhash::PublicKeyHash;
 use common::chain::classic_multisig::ClassicMultisigChallenge;
+use common::chain::signature::inputsig::authorize_script_hash_spend::{
+    sign_script_hash_spending, AuthorizedScriptHashSpend,
+};
 use common::chain::signature::inputsig::classical_multisig::authorize_classical_multisig::AuthorizedClassicalMultisigSpend;
 use common::chain::signed_transaction::SignedTransaction;
 use common::primitives::Idable;
@@ -107,6 +110,102 @@ fn signed_tx(#[case] seed: Seed) {
 
 // TODO: add more tests for signatures with errors (for all kinds of output purposes)
 
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn signed_script_hash_tx(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+        let mut tf = TestFramework::builder(&mut rng).build();
+
+        let (private_key, public_key) =
+            PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+        let (other_private_key, _) = PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+
+        let redeem_script = script::Builder::new()
+            .push_slice(&public_key.encode())
+            .push_opcode(script::opcodes::all::OP_CHECKSIG)
+            .into_script();
+
+        // The first transaction locks the coins to the hash of the redeem script.
+        let tx_1 = TransactionBuilder::new()
+            .add_input(
+                TxInput::new(
+                    OutPointSourceId::BlockReward(
+                        tf.chainstate.get_chain_config().genesis_block_id(),
+                    ),
+                    0,
+                ),
+                InputWitness::NoSignature(None),
+            )
+            .add_output(TxOutput::Transfer(
+                OutputValue::Coin(Amount::from_atoms(100)),
+                Destination::ScriptHash(redeem_script.get_id()),
+            ))
+            .build();
+        tf.make_block_builder()
+            .add_transaction(tx_1.clone())
+            .build_and_process()
+            .unwrap();
+
+        let tx = TransactionBuilder::new()
+            .add_input(
+                TxInput::new(
+                    OutPointSourceId::Transaction(tx_1.transaction().get_id()),
+                    0,
+                ),
+                InputWitness::NoSignature(None),
+            )
+            .add_output(TxOutput::Transfer(
+                OutputValue::Coin(Amount::from_atoms(100)),
+                anyonecanspend_address(),
+            ))
+            .build()
+            .transaction()
+            .clone();
+
+        let spend_with_key = |key: &PrivateKey| {
+            let signature = sign_script_hash_spending(
+                key,
+                SigHashType::try_from(SigHashType::ALL).unwrap(),
+                &tx,
+                &[&tx_1.transaction().outputs()[0]],
+                0,
+            )
+            .unwrap();
+            let spender = AuthorizedScriptHashSpend::new(
+                redeem_script.clone(),
+                script::Builder::new().push_slice(&signature).into_script(),
+            );
+            SignedTransaction::new(tx.clone(), vec![InputWitness::ScriptHash(spender)])
+                .expect("invalid witness count")
+        };
+
+        // A signature by a key other than the one in the redeem script is rejected.
+        let process_result = tf
+            .make_block_builder()
+            .add_transaction(spend_with_key(&other_private_key))
+            .build_and_process();
+        assert_eq!(
+            process_result.unwrap_err(),
+            chainstate::ChainstateError::ProcessBlockError(
+                chainstate::BlockError::StateUpdateFailed(
+                    chainstate::ConnectTransactionError::SignatureVerificationFailed(
+                        common::chain::signature::TransactionSigError::ScriptExecutionFailed(
+                            script::Error::VerifyFail
+                        )
+                    )
+                )
+            )
+        );
+
+        tf.make_block_builder()
+            .add_transaction(spend_with_key(&private_key))
+            .build_and_process()
+            .unwrap();
+    });
+}
+
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
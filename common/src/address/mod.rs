@@ -14,13 +14,16 @@
 // limitations under the License.
 
 use self::pubkeyhash::PublicKeyHash;
-use crate::chain::ChainConfig;
-use crate::primitives::{encoding, Bech32Error, DecodedArbitraryDataFromBech32};
+use crate::chain::{ChainConfig, Destination};
+use crate::primitives::{encoding, Amount, Bech32Error, DecodedArbitraryDataFromBech32};
 use crypto::key::PublicKey;
 pub mod pubkeyhash;
-use serialization::{Decode, Encode, Input};
+use serialization::{Decode, DecodeAll, Encode, Input};
 use utils::qrcode::{qrcode_from_str, QrCode, QrCodeError};
 
+/// The URI scheme of the payment requests that ask for an amount of coins to be sent to an address
+pub const PAYMENT_REQUEST_SCHEME: &str = "mintlayer";
+
 pub trait AddressableData<T: AsRef<[u8]>> {
     fn encode(&self) -> Result<String, Bech32Error> {
         encoding::encode(self.hrp(), self.data())
@@ -45,6 +48,8 @@ pub enum AddressError {
     InvalidPrefix(String),
     #[error("QR Code error: {0}")]
     QrCodeError(#[from] QrCodeError),
+    #[error("The address data is not a supported destination")]
+    UnsupportedDestination,
 }
 
 impl From<Bech32Error> for AddressError {
@@ -99,6 +104,53 @@ impl Address {
         Self::from_public_key_hash(cfg, &public_key_hash)
     }
 
+    /// The address of the classic multisig challenge with the hash `challenge_hash`.
+    ///
+    /// The data of the address is the encoded destination, which is longer than a public key hash,
+    /// so a multisig address can't be mistaken for a single key address.
+    pub fn from_classic_multisig_hash(
+        cfg: &ChainConfig,
+        challenge_hash: &PublicKeyHash,
+    ) -> Result<Self, AddressError> {
+        let encoded = Destination::ClassicMultisig(*challenge_hash).encode();
+        Address::new(cfg, encoded)
+    }
+
+    /// The address displayed for a destination. Public key hashes are encoded as is, any other
+    /// destination as its encoding, so that only single key addresses are that short.
+    pub fn from_destination(
+        cfg: &ChainConfig,
+        destination: &Destination,
+    ) -> Result<Self, AddressError> {
+        match destination {
+            Destination::Address(public_key_hash) => {
+                Self::from_public_key_hash(cfg, public_key_hash)
+            }
+            Destination::AnyoneCanSpend
+            | Destination::PublicKey(_)
+            | Destination::ScriptHash(_)
+            | Destination::ClassicMultisig(_) => Address::new(cfg, destination.encode()),
+        }
+    }
+
+    /// The destination that is paid to by sending to this address
+    pub fn destination(&self) -> Result<Destination, AddressError> {
+        let data = self.data_internal()?;
+        if let Ok(public_key_hash) = PublicKeyHash::try_from(data.clone()) {
+            return Ok(Destination::Address(public_key_hash));
+        }
+        match Destination::decode_all(&mut data.as_slice()) {
+            Ok(destination @ Destination::ClassicMultisig(_)) => Ok(destination),
+            Ok(
+                Destination::AnyoneCanSpend
+                | Destination::Address(_)
+                | Destination::PublicKey(_)
+                | Destination::ScriptHash(_),
+            )
+            | Err(_) => Err(AddressError::UnsupportedDestination),
+        }
+    }
+
     pub fn from_str(cfg: &ChainConfig, address: &str) -> Result<Self, AddressError> {
         let address = Self {
             address: address.to_owned(),
@@ -115,6 +167,29 @@ impl Address {
         let qrcode = qrcode_from_str(&self.address)?;
         Ok(qrcode)
     }
+
+    /// The text to hand out to receive coins: the address itself,
+    /// or a `mintlayer:<address>?amount=<coins>` URI if `amount` is specified
+    pub fn payment_request(&self, cfg: &ChainConfig, amount: Option<Amount>) -> String {
+        match amount {
+            Some(amount) => format!(
+                "{PAYMENT_REQUEST_SCHEME}:{}?amount={}",
+                self.address,
+                amount.into_fixedpoint_str(cfg.coin_decimals())
+            ),
+            None => self.address.clone(),
+        }
+    }
+
+    /// The QR code of the payment request for this address, see `payment_request`
+    pub fn payment_request_qrcode(
+        &self,
+        cfg: &ChainConfig,
+        amount: Option<Amount>,
+    ) -> Result<impl QrCode, AddressError> {
+        let qrcode = qrcode_from_str(self.payment_request(cfg, amount))?;
+        Ok(qrcode)
+    }
 }
 
 impl Decode for Address {
@@ -171,4 +246,70 @@ mod tests {
         assert_eq!(&address1.address[0..hrp.len()], hrp);
         assert_eq!(address1, Address::from_str(&cfg, address2.get()).unwrap());
     }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn address_destinations(#[case] seed: Seed) {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+        let cfg = create_mainnet();
+        let (_priv_key, pub_key) = PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+        let public_key_hash = PublicKeyHash::from(&pub_key);
+
+        let address = Address::from_public_key_hash(&cfg, &public_key_hash).unwrap();
+        assert_eq!(
+            address.destination(),
+            Ok(Destination::Address(public_key_hash))
+        );
+
+        let multisig_address = Address::from_classic_multisig_hash(&cfg, &public_key_hash).unwrap();
+        assert_ne!(multisig_address, address);
+        assert_eq!(
+            multisig_address.destination(),
+            Ok(Destination::ClassicMultisig(public_key_hash))
+        );
+
+        let pub_key_address =
+            Address::new(&cfg, Destination::PublicKey(pub_key.clone()).encode()).unwrap();
+        assert_eq!(
+            pub_key_address.destination(),
+            Err(AddressError::UnsupportedDestination)
+        );
+
+        assert_eq!(
+            Address::from_destination(&cfg, &Destination::Address(public_key_hash)),
+            Ok(address)
+        );
+        assert_eq!(
+            Address::from_destination(&cfg, &Destination::ClassicMultisig(public_key_hash)),
+            Ok(multisig_address)
+        );
+        assert_eq!(
+            Address::from_destination(&cfg, &Destination::PublicKey(pub_key)),
+            Ok(pub_key_address)
+        );
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn payment_request(#[case] seed: Seed) {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+        let cfg = create_mainnet();
+        let (_priv_key, pub_key) = PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+        let address = Address::from_public_key(&cfg, &pub_key).unwrap();
+
+        assert_eq!(address.payment_request(&cfg, None), address.get());
+        let amount = Amount::from_fixedpoint_str("12.5", cfg.coin_decimals()).unwrap();
+        assert_eq!(
+            address.payment_request(&cfg, Some(amount)),
+            format!("mintlayer:{}?amount=12.5", address.get())
+        );
+
+        let qrcode = address.payment_request_qrcode(&cfg, Some(amount)).unwrap();
+        assert_eq!(
+            qrcode.as_vec(),
+            qrcode_from_str(address.payment_request(&cfg, Some(amount))).unwrap().as_vec()
+        );
+    }
 }
@@ -36,7 +36,7 @@ impl std::fmt::Display for BlockTimestamp {
 }
 
 impl BlockTimestamp {
-    pub fn from_int_seconds(timestamp: BlockTimestampInternalType) -> Self {
+    pub const fn from_int_seconds(timestamp: BlockTimestampInternalType) -> Self {
         Self { timestamp }
     }
 
@@ -16,7 +16,7 @@
 use crate::{
     address::pubkeyhash::PublicKeyHash,
     chain::{tokens::OutputValue, DelegationId, PoolId},
-    primitives::{Amount, Id},
+    primitives::{id, Amount, Id, Idable},
 };
 use script::Script;
 use serialization::{Decode, Encode};
@@ -41,6 +41,13 @@ pub enum Destination {
     ClassicMultisig(PublicKeyHash),
 }
 
+impl Idable for Script {
+    type Tag = Script;
+    fn get_id(&self) -> Id<Script> {
+        Id::new(id::hash_encoded(self))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
 pub enum TxOutput {
     #[codec(index = 0)]
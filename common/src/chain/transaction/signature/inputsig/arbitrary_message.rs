@@ -0,0 +1,273 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Signatures of arbitrary messages, used to prove the ownership of an address.
+//!
+//! The message is wrapped in a prefix and a suffix before it is hashed, so that the hash
+//! can't be the signature hash of a transaction and the signatures can't be used to spend coins.
+
+use crypto::key::PrivateKey;
+use serialization::{Decode, DecodeAll, Encode};
+use thiserror::Error;
+
+use crate::{
+    address::{Address, AddressError},
+    chain::{signature::TransactionSigError, ChainConfig, Destination},
+    primitives::{id::default_hash, H256},
+};
+
+use super::{
+    authorize_pubkey_spend::{
+        sign_pubkey_spending, verify_public_key_spending, AuthorizedPublicKeySpend,
+    },
+    authorize_pubkeyhash_spend::{
+        sign_address_spending, verify_address_spending, AuthorizedPublicKeyHashSpend,
+    },
+};
+
+pub const MESSAGE_MAGIC_PREFIX: &str = "===MINTLAYER MESSAGE BEGIN===\n";
+pub const MESSAGE_MAGIC_SUFFIX: &str = "\n===MINTLAYER MESSAGE END===";
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum SignArbitraryMessageError {
+    #[error("Address error: {0}")]
+    Address(#[from] AddressError),
+    #[error("Messages can't be signed by destination {0:?}")]
+    UnsupportedDestination(Destination),
+    #[error("Invalid message signature encoding")]
+    InvalidSignatureEncoding,
+    #[error("Signature error: {0}")]
+    Signature(#[from] TransactionSigError),
+}
+
+/// The hash that is signed instead of the message itself
+pub fn produce_message_challenge(message: &[u8]) -> H256 {
+    let wrapped_message = MESSAGE_MAGIC_PREFIX
+        .as_bytes()
+        .iter()
+        .chain(message)
+        .chain(MESSAGE_MAGIC_SUFFIX.as_bytes())
+        .copied()
+        .collect::<Vec<_>>();
+    default_hash(wrapped_message)
+}
+
+/// A signature of a message made by the owner of a destination.
+///
+/// It contains everything needed to verify it against the destination, i.e. the public key
+/// is included for the `Destination::Address` destinations.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct ArbitraryMessageSignature {
+    raw_signature: Vec<u8>,
+}
+
+impl ArbitraryMessageSignature {
+    pub fn from_data(raw_signature: Vec<u8>) -> Self {
+        Self { raw_signature }
+    }
+
+    pub fn as_raw(&self) -> &[u8] {
+        &self.raw_signature
+    }
+
+    pub fn into_raw(self) -> Vec<u8> {
+        self.raw_signature
+    }
+
+    pub fn produce_uniparty_signature(
+        private_key: &PrivateKey,
+        destination: &Destination,
+        message: &[u8],
+    ) -> Result<Self, SignArbitraryMessageError> {
+        let challenge = produce_message_challenge(message);
+        let raw_signature = match destination {
+            Destination::Address(public_key_hash) => {
+                sign_address_spending(private_key, public_key_hash, &challenge)?.encode()
+            }
+            Destination::PublicKey(public_key) => {
+                sign_pubkey_spending(private_key, public_key, &challenge)?.encode()
+            }
+            Destination::AnyoneCanSpend
+            | Destination::ScriptHash(_)
+            | Destination::ClassicMultisig(_) => {
+                return Err(SignArbitraryMessageError::UnsupportedDestination(
+                    destination.clone(),
+                ))
+            }
+        };
+        Ok(Self { raw_signature })
+    }
+
+    /// Check that the signature of `message` was made by the owner of `destination`
+    pub fn verify_signature(
+        &self,
+        destination: &Destination,
+        message: &[u8],
+    ) -> Result<(), SignArbitraryMessageError> {
+        let challenge = produce_message_challenge(message);
+        match destination {
+            Destination::Address(public_key_hash) => {
+                let signature =
+                    AuthorizedPublicKeyHashSpend::decode_all(&mut self.raw_signature.as_slice())
+                        .map_err(|_| SignArbitraryMessageError::InvalidSignatureEncoding)?;
+                verify_address_spending(public_key_hash, &signature, &challenge)?;
+            }
+            Destination::PublicKey(public_key) => {
+                let signature =
+                    AuthorizedPublicKeySpend::decode_all(&mut self.raw_signature.as_slice())
+                        .map_err(|_| SignArbitraryMessageError::InvalidSignatureEncoding)?;
+                verify_public_key_spending(public_key, &signature, &challenge)?;
+            }
+            Destination::AnyoneCanSpend
+            | Destination::ScriptHash(_)
+            | Destination::ClassicMultisig(_) => {
+                return Err(SignArbitraryMessageError::UnsupportedDestination(
+                    destination.clone(),
+                ))
+            }
+        }
+        Ok(())
+    }
+
+    /// Same as `verify_signature`, for the destination of an address string
+    pub fn verify_signature_for_address(
+        &self,
+        chain_config: &ChainConfig,
+        address: &str,
+        message: &[u8],
+    ) -> Result<(), SignArbitraryMessageError> {
+        let destination = Address::from_str(chain_config, address)?.destination()?;
+        self.verify_signature(&destination, message)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::address::pubkeyhash::PublicKeyHash;
+    use crate::chain::config::create_mainnet;
+    use crypto::key::KeyKind;
+    use crypto::random::Rng;
+    use rstest::rstest;
+    use test_utils::random::{make_seedable_rng, Seed};
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn sign_and_verify(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let (private_key, public_key) =
+            PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+        let (_, other_public_key) = PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+        let message = (0..rng.gen_range(0..100)).map(|_| rng.gen::<u8>()).collect::<Vec<_>>();
+
+        for (destination, other_destination) in [
+            (
+                Destination::Address(PublicKeyHash::from(&public_key)),
+                Destination::Address(PublicKeyHash::from(&other_public_key)),
+            ),
+            (
+                Destination::PublicKey(public_key.clone()),
+                Destination::PublicKey(other_public_key.clone()),
+            ),
+        ] {
+            let signature = ArbitraryMessageSignature::produce_uniparty_signature(
+                &private_key,
+                &destination,
+                &message,
+            )
+            .unwrap();
+            signature.verify_signature(&destination, &message).unwrap();
+
+            let decoded =
+                ArbitraryMessageSignature::decode_all(&mut signature.encode().as_slice()).unwrap();
+            assert_eq!(decoded, signature);
+
+            let mut other_message = message.clone();
+            other_message.push(rng.gen());
+            assert!(signature.verify_signature(&destination, &other_message).is_err());
+            assert!(signature.verify_signature(&other_destination, &message).is_err());
+
+            // Only the owner of the destination can sign
+            assert!(ArbitraryMessageSignature::produce_uniparty_signature(
+                &private_key,
+                &other_destination,
+                &message,
+            )
+            .is_err());
+        }
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn unsupported_destinations(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let (private_key, _) = PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+        let destination = Destination::AnyoneCanSpend;
+        assert_eq!(
+            ArbitraryMessageSignature::produce_uniparty_signature(
+                &private_key,
+                &destination,
+                b"message"
+            ),
+            Err(SignArbitraryMessageError::UnsupportedDestination(
+                destination.clone()
+            ))
+        );
+        assert_eq!(
+            ArbitraryMessageSignature::from_data(vec![]).verify_signature(&destination, b"message"),
+            Err(SignArbitraryMessageError::UnsupportedDestination(
+                destination
+            ))
+        );
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn verify_for_address(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let chain_config = create_mainnet();
+        let (private_key, public_key) =
+            PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+        let address = Address::from_public_key(&chain_config, &public_key).unwrap();
+        let destination = address.destination().unwrap();
+
+        let signature = ArbitraryMessageSignature::produce_uniparty_signature(
+            &private_key,
+            &destination,
+            b"message",
+        )
+        .unwrap();
+        signature
+            .verify_signature_for_address(&chain_config, address.get(), b"message")
+            .unwrap();
+        assert!(matches!(
+            signature.verify_signature_for_address(&chain_config, "invalid", b"message"),
+            Err(SignArbitraryMessageError::Address(_))
+        ));
+    }
+
+    #[test]
+    fn challenge_is_domain_separated() {
+        let message = b"message";
+        assert_ne!(produce_message_challenge(message), default_hash(message));
+        assert_ne!(
+            produce_message_challenge(message),
+            produce_message_challenge(b"")
+        );
+    }
+}
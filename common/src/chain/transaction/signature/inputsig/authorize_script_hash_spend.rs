@@ -0,0 +1,298 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crypto::key::{PrivateKey, PublicKey, Signature};
+use script::{context::ParseResult, Context, Script};
+use serialization::{Decode, DecodeAll, Encode};
+use utils::ensure;
+
+use crate::{
+    chain::{
+        signature::{
+            sighash::{sighashtype::SigHashType, signature_hash},
+            Signable, TransactionSigError,
+        },
+        TxOutput,
+    },
+    primitives::{Id, Idable},
+};
+
+/// The maximum number of public keys a multisig in a redeem script can use
+pub const MAX_PUBKEYS_PER_MULTISIG: usize = 20;
+
+/// The maximum size of a redeem script or its arguments, in bytes
+pub const MAX_SCRIPT_SIZE: usize = 10_000;
+
+/// The witness of an input spending a `Destination::ScriptHash` output. It reveals the script the
+/// output commits to and the data-push-only script that satisfies it.
+#[derive(Debug, Clone, Encode, Decode, Eq, PartialEq, Ord, PartialOrd)]
+pub struct AuthorizedScriptHashSpend {
+    redeem_script: Script,
+    arguments: Script,
+}
+
+impl AuthorizedScriptHashSpend {
+    pub fn new(redeem_script: Script, arguments: Script) -> Self {
+        Self {
+            redeem_script,
+            arguments,
+        }
+    }
+
+    pub fn redeem_script(&self) -> &Script {
+        &self.redeem_script
+    }
+
+    pub fn arguments(&self) -> &Script {
+        &self.arguments
+    }
+}
+
+/// Script interpreter context that checks signatures against the transaction being verified.
+///
+/// A signature in a script is an encoded `Signature` followed by the sighash type byte and signs
+/// the same sighash a standard signature of the input would. The subscript is not part of the
+/// signed message since the spent output already commits to the redeem script through its hash.
+pub struct TransactionSignatureChecker<'a, T> {
+    tx: &'a T,
+    inputs_utxos: &'a [&'a TxOutput],
+    input_num: usize,
+}
+
+impl<'a, T: Signable> TransactionSignatureChecker<'a, T> {
+    pub fn new(tx: &'a T, inputs_utxos: &'a [&'a TxOutput], input_num: usize) -> Self {
+        Self {
+            tx,
+            inputs_utxos,
+            input_num,
+        }
+    }
+}
+
+impl<T: Signable> Context for TransactionSignatureChecker<'_, T> {
+    const MAX_PUBKEYS_PER_MULTISIG: usize = MAX_PUBKEYS_PER_MULTISIG;
+    const MAX_SCRIPT_SIZE: usize = MAX_SCRIPT_SIZE;
+
+    type Public = PublicKey;
+    type SignatureData = (PublicKey, Signature, SigHashType);
+
+    fn parse_pubkey(&self, pk: &[u8]) -> ParseResult<Self::Public> {
+        PublicKey::decode_all(&mut &pk[..]).ok().into()
+    }
+
+    fn parse_signature(&self, pk: Self::Public, sig: &[u8]) -> Option<Self::SignatureData> {
+        let (sighash_byte, sig) = sig.split_last()?;
+        let sighash_type = SigHashType::try_from(*sighash_byte).ok()?;
+        let signature = Signature::decode_all(&mut &sig[..]).ok()?;
+        Some((pk, signature, sighash_type))
+    }
+
+    fn verify_signature(
+        &self,
+        sig: &Self::SignatureData,
+        _subscript: &[u8],
+        _codesep_idx: u32,
+    ) -> bool {
+        let (pubkey, signature, sighash_type) = sig;
+        signature_hash(*sighash_type, self.tx, self.inputs_utxos, self.input_num)
+            .map_or(false, |sighash| {
+                pubkey.verify_message(signature, &sighash.encode())
+            })
+    }
+}
+
+pub fn verify_script_hash_spending<T: Signable>(
+    script_hash: &Id<Script>,
+    spender: &AuthorizedScriptHashSpend,
+    tx: &T,
+    inputs_utxos: &[&TxOutput],
+    input_num: usize,
+) -> Result<(), TransactionSigError> {
+    ensure!(
+        spender.redeem_script.get_id() == *script_hash,
+        TransactionSigError::RedeemScriptHashMismatch
+    );
+    let checker = TransactionSignatureChecker::new(tx, inputs_utxos, input_num);
+    script::verify_witness_lock(&checker, &spender.arguments, &spender.redeem_script)
+        .map_err(TransactionSigError::ScriptExecutionFailed)
+}
+
+/// Produce a signature to be pushed by the arguments of a script hash spend
+pub fn sign_script_hash_spending<T: Signable>(
+    private_key: &PrivateKey,
+    sighash_type: SigHashType,
+    tx: &T,
+    inputs_utxos: &[&TxOutput],
+    input_num: usize,
+) -> Result<Vec<u8>, TransactionSigError> {
+    let sighash = signature_hash(sighash_type, tx, inputs_utxos, input_num)?;
+    let signature = private_key
+        .sign_message(&sighash.encode())
+        .map_err(TransactionSigError::ProducingSignatureFailed)?;
+
+    let mut script_signature = signature.encode();
+    script_signature.push(sighash_type.get());
+    Ok(script_signature)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::chain::{
+        signature::tests::utils::{generate_inputs_utxos, generate_unsigned_tx, sig_hash_types},
+        Destination,
+    };
+    use crypto::key::KeyKind;
+    use crypto::random::Rng;
+    use rstest::rstest;
+    use script::{opcodes, Builder};
+    use test_utils::random::Seed;
+
+    const INPUTS: usize = 10;
+    const OUTPUTS: usize = 10;
+
+    fn checksig_script(public_key: &PublicKey) -> Script {
+        Builder::new()
+            .push_slice(&public_key.encode())
+            .push_opcode(opcodes::all::OP_CHECKSIG)
+            .into_script()
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn verify_checksig_redeem_script(#[case] seed: Seed) {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+
+        let (private_key, public_key) =
+            PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+        let redeem_script = checksig_script(&public_key);
+        let destination = Destination::ScriptHash(redeem_script.get_id());
+
+        let (inputs_utxos, _priv_keys) = generate_inputs_utxos(&mut rng, INPUTS);
+        let inputs_utxos = inputs_utxos.iter().collect::<Vec<_>>();
+        let tx = generate_unsigned_tx(&mut rng, &destination, INPUTS, OUTPUTS).unwrap();
+
+        for sighash_type in sig_hash_types() {
+            let input = rng.gen_range(0..INPUTS);
+            let signature =
+                sign_script_hash_spending(&private_key, sighash_type, &tx, &inputs_utxos, input)
+                    .unwrap();
+            let spender = AuthorizedScriptHashSpend::new(
+                redeem_script.clone(),
+                Builder::new().push_slice(&signature).into_script(),
+            );
+            verify_script_hash_spending(
+                &redeem_script.get_id(),
+                &spender,
+                &tx,
+                &inputs_utxos,
+                input,
+            )
+            .unwrap_or_else(|_| panic!("{sighash_type:X?}"));
+
+            // The signature commits to the input it was produced for
+            let other_input = (input + 1) % INPUTS;
+            assert_eq!(
+                verify_script_hash_spending(
+                    &redeem_script.get_id(),
+                    &spender,
+                    &tx,
+                    &inputs_utxos,
+                    other_input,
+                ),
+                Err(TransactionSigError::ScriptExecutionFailed(
+                    script::Error::VerifyFail
+                )),
+                "{sighash_type:X?}"
+            );
+        }
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn redeem_script_hash_mismatch(#[case] seed: Seed) {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+
+        let (private_key, public_key) =
+            PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+        let (_, other_public_key) = PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+        let redeem_script = checksig_script(&public_key);
+        let script_hash = checksig_script(&other_public_key).get_id();
+        let destination = Destination::ScriptHash(script_hash);
+
+        let (inputs_utxos, _priv_keys) = generate_inputs_utxos(&mut rng, INPUTS);
+        let inputs_utxos = inputs_utxos.iter().collect::<Vec<_>>();
+        let tx = generate_unsigned_tx(&mut rng, &destination, INPUTS, OUTPUTS).unwrap();
+
+        let input = rng.gen_range(0..INPUTS);
+        let signature = sign_script_hash_spending(
+            &private_key,
+            SigHashType::default(),
+            &tx,
+            &inputs_utxos,
+            input,
+        )
+        .unwrap();
+        let spender = AuthorizedScriptHashSpend::new(
+            redeem_script,
+            Builder::new().push_slice(&signature).into_script(),
+        );
+        assert_eq!(
+            verify_script_hash_spending(&script_hash, &spender, &tx, &inputs_utxos, input),
+            Err(TransactionSigError::RedeemScriptHashMismatch)
+        );
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn arguments_must_be_push_only(#[case] seed: Seed) {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+
+        let redeem_script = Builder::new().push_opcode(opcodes::all::OP_EQUAL).into_script();
+        let destination = Destination::ScriptHash(redeem_script.get_id());
+
+        let (inputs_utxos, _priv_keys) = generate_inputs_utxos(&mut rng, INPUTS);
+        let inputs_utxos = inputs_utxos.iter().collect::<Vec<_>>();
+        let tx = generate_unsigned_tx(&mut rng, &destination, INPUTS, OUTPUTS).unwrap();
+        let input = rng.gen_range(0..INPUTS);
+
+        let spender = AuthorizedScriptHashSpend::new(
+            redeem_script.clone(),
+            Builder::new().push_int(7).push_int(7).into_script(),
+        );
+        verify_script_hash_spending(&redeem_script.get_id(), &spender, &tx, &inputs_utxos, input)
+            .unwrap();
+
+        let spender = AuthorizedScriptHashSpend::new(
+            redeem_script.clone(),
+            Builder::new().push_int(7).push_opcode(opcodes::all::OP_DUP).into_script(),
+        );
+        assert_eq!(
+            verify_script_hash_spending(
+                &redeem_script.get_id(),
+                &spender,
+                &tx,
+                &inputs_utxos,
+                input
+            ),
+            Err(TransactionSigError::ScriptExecutionFailed(
+                script::Error::PushOnly
+            ))
+        );
+    }
+}
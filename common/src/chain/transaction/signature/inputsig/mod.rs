@@ -13,13 +13,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod arbitrary_message;
 pub mod authorize_pubkey_spend;
 pub mod authorize_pubkeyhash_spend;
+pub mod authorize_script_hash_spend;
 pub mod classical_multisig;
 pub mod standard_signature;
 
 use serialization::{Decode, Encode};
 
+use authorize_script_hash_spend::AuthorizedScriptHashSpend;
 use standard_signature::StandardInputSignature;
 
 #[derive(Debug, Encode, Decode, Clone, Eq, PartialEq, Ord, PartialOrd)]
@@ -28,4 +31,6 @@ pub enum InputWitness {
     NoSignature(Option<Vec<u8>>),
     #[codec(index = 1)]
     Standard(StandardInputSignature),
+    #[codec(index = 2)]
+    ScriptHash(AuthorizedScriptHashSpend),
 }
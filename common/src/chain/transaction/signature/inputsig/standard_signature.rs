@@ -61,6 +61,10 @@ impl StandardInputSignature {
         self.sighash_type
     }
 
+    pub fn raw_signature(&self) -> &[u8] {
+        &self.raw_signature
+    }
+
     pub fn from_data<T: AsRef<[u8]>>(raw_data: T) -> Result<Self, TransactionSigError> {
         let decoded_sig = StandardInputSignature::decode_all(&mut raw_data.as_ref())
             .map_err(|_| TransactionSigError::DecodingWitnessFailed)?;
@@ -82,7 +86,10 @@ impl StandardInputSignature {
                 let sig_components = AuthorizedPublicKeySpend::from_data(&self.raw_signature)?;
                 verify_public_key_spending(pubkey, &sig_components, sighash)?
             }
-            Destination::ScriptHash(_) => return Err(TransactionSigError::Unsupported),
+            Destination::ScriptHash(_) => {
+                // ScriptHash must use InputWitness::ScriptHash, so this is unreachable
+                return Err(TransactionSigError::AttemptedToVerifyStandardSignatureForScriptHash);
+            }
             Destination::AnyoneCanSpend => {
                 // AnyoneCanSpend must use InputWitness::NoSignature, so this is unreachable
                 return Err(
@@ -116,7 +123,10 @@ impl StandardInputSignature {
                 let sig = sign_pubkey_spending(private_key, pubkey, &sighash)?;
                 sig.encode()
             }
-            Destination::ScriptHash(_) => return Err(TransactionSigError::Unsupported),
+            Destination::ScriptHash(_) => {
+                // ScriptHash must use InputWitness::ScriptHash, see `sign_script_hash_spending`
+                return Err(TransactionSigError::AttemptedToProduceStandardSignatureForScriptHash);
+            }
 
             Destination::AnyoneCanSpend => {
                 // AnyoneCanSpend must use InputWitness::NoSignature, so this is unreachable
@@ -17,6 +17,7 @@ use crate::chain::{ChainConfig, SignedTransaction, TxInput};
 
 use self::{
     inputsig::{
+        authorize_script_hash_spend::verify_script_hash_spending,
         classical_multisig::{
             authorize_classical_multisig::ClassicalMultisigSigningError,
             multisig_partial_signature::PartiallySignedMultisigStructureError,
@@ -72,6 +73,12 @@ pub enum TransactionSigError {
     AttemptedToVerifyStandardSignatureForAnyoneCanSpend,
     #[error("AnyoneCanSpend should not use standard signatures, so producing a signature for it is not possible")]
     AttemptedToProduceSignatureForAnyoneCanSpend,
+    #[error(
+        "ScriptHash must be spent by revealing the redeem script, not with a standard signature"
+    )]
+    AttemptedToVerifyStandardSignatureForScriptHash,
+    #[error("ScriptHash must be spent by revealing the redeem script, so producing a standard signature for it is not possible")]
+    AttemptedToProduceStandardSignatureForScriptHash,
     #[error("Classical multisig signature attempted in uni-party function")]
     AttemptedToProduceClassicalMultisigSignatureForAnyoneCanSpend,
     #[error("Number of signatures does not match number of inputs")]
@@ -90,6 +97,12 @@ pub enum TransactionSigError {
     InvalidClassicalMultisigAuthorization,
     #[error("Standard signature creation failed. Incomplete classical multisig authorization")]
     IncompleteClassicalMultisigAuthorization,
+    #[error("Script witness provided for a destination that is not a script hash")]
+    ScriptWitnessForNonScriptHashDestination,
+    #[error("The redeem script does not match the script hash of the destination")]
+    RedeemScriptHashMismatch,
+    #[error("Redeem script execution failed: {0}")]
+    ScriptExecutionFailed(script::Error),
     #[error("Unsupported yet!")]
     Unsupported,
 }
@@ -179,6 +192,17 @@ pub fn verify_signature<T: Transactable>(
             inputs_utxos,
             input_num,
         )?,
+        InputWitness::ScriptHash(spender) => match outpoint_destination {
+            Destination::ScriptHash(script_hash) => {
+                verify_script_hash_spending(script_hash, spender, tx, inputs_utxos, input_num)?
+            }
+            Destination::AnyoneCanSpend
+            | Destination::Address(_)
+            | Destination::PublicKey(_)
+            | Destination::ClassicMultisig(_) => {
+                return Err(TransactionSigError::ScriptWitnessForNonScriptHashDestination)
+            }
+        },
     }
     Ok(())
 }
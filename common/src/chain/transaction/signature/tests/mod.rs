@@ -662,6 +662,7 @@ fn check_mutate_witness(
         let signature = match &tx_updater.witness[0] {
             InputWitness::Standard(signature) => signature,
             InputWitness::NoSignature(_) => panic!("Unexpected InputWitness::NoSignature"),
+            InputWitness::ScriptHash(_) => panic!("Unexpected InputWitness::ScriptHash"),
         };
 
         let raw_signature = signature.raw_signature().iter().map(|b| b.wrapping_add(1)).collect();
@@ -96,8 +96,7 @@ fn test_mutate_tx_internal_data(#[case] seed: Seed) {
                     expected
                 );
             }
-            // Not implemented.
-            Err(TransactionSigError::Unsupported) => {
+            Err(TransactionSigError::AttemptedToProduceStandardSignatureForScriptHash) => {
                 assert!(matches!(destination, Destination::ScriptHash(_)))
             }
             Err(TransactionSigError::AttemptedToProduceSignatureForAnyoneCanSpend) => {
@@ -0,0 +1,102 @@
+// Copyright (c) 2021-2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::num::NonZeroUsize;
+
+use super::Amount;
+
+/// The fee paid per 1000 bytes of an encoded transaction
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+pub struct FeeRate {
+    amount_per_kb: Amount,
+}
+
+impl FeeRate {
+    pub const fn new(amount_per_kb: Amount) -> Self {
+        Self { amount_per_kb }
+    }
+
+    /// The fee rate of a transaction of `tx_size` bytes paying `total_tx_fee`, None on overflow
+    pub fn from_total_tx_fee(total_tx_fee: Amount, tx_size: NonZeroUsize) -> Option<Self> {
+        let tx_size = u128::try_from(usize::from(tx_size)).expect("div_up conversion");
+        let scaled_fee = (total_tx_fee * 1000)?;
+        let amount_per_kb = (scaled_fee / tx_size).expect("tx_size nonzero");
+        Some(Self { amount_per_kb })
+    }
+
+    /// The fee of a transaction of `size` bytes rounded up, None on overflow
+    pub fn compute_fee(&self, size: usize) -> Option<Amount> {
+        let size = u128::try_from(size).expect("compute_fee conversion");
+        let fee = (self.amount_per_kb * size)?;
+        // +999 for ceil operation
+        let ceil_add = Amount::from_atoms(999);
+        let fee = ((fee + ceil_add)? / 1000).expect("valid division");
+        Some(fee)
+    }
+
+    pub const fn atoms_per_kb(&self) -> u128 {
+        self.amount_per_kb.into_atoms()
+    }
+}
+
+impl std::ops::Add for FeeRate {
+    type Output = Option<Self>;
+    fn add(self, other: Self) -> Self::Output {
+        (self.amount_per_kb + other.amount_per_kb).map(FeeRate::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_total_tx_fee() {
+        let fee = Amount::from_atoms(7);
+        let tx_size = usize::MAX;
+        let rate = FeeRate::from_total_tx_fee(fee, NonZeroUsize::new(tx_size).unwrap()).unwrap();
+        assert_eq!(
+            rate,
+            FeeRate {
+                amount_per_kb: Amount::from_atoms(0)
+            }
+        );
+
+        let fee = Amount::from_atoms(u128::MAX);
+        let tx_size = 1;
+        let res = FeeRate::from_total_tx_fee(fee, NonZeroUsize::new(tx_size).unwrap());
+        assert_eq!(res, None);
+
+        let fee = Amount::from_atoms(u128::MAX - 1);
+        let tx_size = 3;
+        let res = FeeRate::from_total_tx_fee(fee, NonZeroUsize::new(tx_size).unwrap());
+        assert_eq!(res, None);
+    }
+
+    #[test]
+    fn test_compute_fee() {
+        let rate = FeeRate::new(Amount::from_atoms(1000));
+        assert_eq!(rate.compute_fee(0), Some(Amount::ZERO));
+        assert_eq!(rate.compute_fee(250), Some(Amount::from_atoms(250)));
+
+        let rate = FeeRate::new(Amount::from_atoms(1500));
+        assert_eq!(rate.compute_fee(3), Some(Amount::from_atoms(5)));
+
+        let rate = FeeRate::new(Amount::MAX);
+        assert_eq!(rate.compute_fee(2), None);
+    }
+}
@@ -17,6 +17,7 @@ pub mod amount;
 pub mod compact;
 pub mod encoding;
 pub mod error;
+pub mod fee_rate;
 mod hash_encoded;
 pub mod height;
 pub mod id;
@@ -30,6 +31,7 @@ pub mod version_tag;
 pub use amount::Amount;
 pub use compact::Compact;
 pub use encoding::{Bech32Error, DecodedArbitraryDataFromBech32};
+pub use fee_rate::FeeRate;
 pub use height::{BlockDistance, BlockHeight};
 pub use id::{Id, Idable, H256};
 pub use version_tag::VersionTag;
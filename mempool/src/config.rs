@@ -28,6 +28,10 @@ pub const MAX_BLOCK_SIZE_BYTES: usize = 1_000_000;
 
 pub const MAX_BIP125_REPLACEMENT_CANDIDATES: usize = 100;
 
+// Standardness limits for the witnesses of inputs spending script hash outputs
+pub const MAX_STANDARD_REDEEM_SCRIPT_SIZE: usize = 520;
+pub const MAX_STANDARD_SCRIPT_ARGUMENTS_SIZE: usize = 1650;
+
 // TODO this should really be taken from some global node settings
 pub const RELAY_FEE_PER_BYTE: usize = 1;
 
@@ -35,4 +39,8 @@ pub const MAX_MEMPOOL_SIZE_BYTES: usize = 300_000_000;
 
 pub const DEFAULT_MEMPOOL_EXPIRY: Duration = Duration::new(336 * 60 * 60, 0);
 
+// The number of events kept for the RPC subscribers, a subscriber that falls further behind
+// misses the oldest events
+pub const RPC_EVENTS_CAPACITY: usize = 1000;
+
 pub const ROLLING_FEE_DECAY_INTERVAL: Time = Duration::new(10, 0);
@@ -81,6 +81,12 @@ pub enum MempoolPolicyError {
     DescendantOfExpiredTransaction,
     #[error("Relay fee overflow error")]
     RelayFeeOverflow,
+    #[error("Redeem script of input {input_index} is too large ({size} bytes)")]
+    NonStandardRedeemScriptSize { input_index: usize, size: usize },
+    #[error("Redeem script arguments of input {input_index} are too large ({size} bytes)")]
+    NonStandardScriptArgumentsSize { input_index: usize, size: usize },
+    #[error("Redeem script arguments of input {input_index} are not push-only")]
+    NonStandardScriptArguments { input_index: usize },
 }
 
 #[derive(Debug, Error, PartialEq, Eq)]
@@ -13,13 +13,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{error::Error, tx_accumulator::TransactionAccumulator, MempoolEvent};
+use crate::{error::Error, tx_accumulator::TransactionAccumulator, MempoolEvent, TxMempoolInfo};
 use common::{
     chain::{GenBlock, SignedTransaction, Transaction},
-    primitives::Id,
+    primitives::{FeeRate, Id},
 };
 use std::sync::Arc;
 use subsystem::{CallRequest, ShutdownRequest};
+use tokio::sync::broadcast;
 
 pub trait MempoolInterface: Send + Sync {
     /// Add a transaction to mempool
@@ -34,9 +35,30 @@ pub trait MempoolInterface: Send + Sync {
     /// Check given transaction is contained in the mempool
     fn contains_transaction(&self, tx: &Id<Transaction>) -> Result<bool, Error>;
 
+    /// Get the mempool data of all transactions, in the same order as `get_all`
+    fn get_all_info(&self) -> Result<Vec<TxMempoolInfo>, Error>;
+
+    /// Get the mempool data of a specific transaction
+    fn transaction_info(&self, id: &Id<Transaction>) -> Result<Option<TxMempoolInfo>, Error>;
+
+    /// Remove a transaction together with its descendants, returning the ids of removed ones
+    fn evict_transaction(&mut self, id: &Id<Transaction>) -> Result<Vec<Id<Transaction>>, Error>;
+
     /// Best block ID according to mempool. May be temporarily out of sync with chainstate.
     fn best_block_id(&self) -> Id<GenBlock>;
 
+    /// The minimum fee rate a new transaction has to pay to be accepted into the mempool
+    fn get_fee_rate(&self) -> FeeRate;
+
+    /// The fee rate floor raised by evicting transactions when the mempool is full
+    fn rolling_minimum_fee_rate(&self) -> FeeRate;
+
+    /// Estimated memory usage of the mempool in bytes
+    fn memory_usage(&self) -> usize;
+
+    /// The memory usage in bytes above which the mempool evicts transactions
+    fn max_size(&self) -> usize;
+
     /// Collect transactions by putting them in given accumulator
     fn collect_txs(
         &self,
@@ -48,6 +70,9 @@ pub trait MempoolInterface: Send + Sync {
         &mut self,
         handler: Arc<dyn Fn(MempoolEvent) + Send + Sync>,
     ) -> Result<(), Error>;
+
+    /// Subscribe to events emitted by mempool through the channel shared by the RPC subscribers
+    fn subscribe_to_rpc_events(&mut self) -> Result<broadcast::Receiver<MempoolEvent>, Error>;
 }
 
 #[async_trait::async_trait]
@@ -15,18 +15,18 @@
 
 use crate::{
     error::Error, pool::Mempool, tx_accumulator::TransactionAccumulator, GetMemoryUsage,
-    MempoolEvent, MempoolInterface, MempoolSubsystemInterface,
+    MempoolEvent, MempoolInterface, MempoolSubsystemInterface, TxMempoolInfo,
 };
 use chainstate::chainstate_interface::ChainstateInterface;
 use common::{
     chain::{ChainConfig, GenBlock, SignedTransaction, Transaction},
-    primitives::Id,
+    primitives::{FeeRate, Id},
     time_getter::TimeGetter,
 };
 use logging::log;
 use std::sync::Arc;
 use subsystem::{CallRequest, ShutdownRequest};
-use tokio::sync::mpsc;
+use tokio::sync::{broadcast, mpsc};
 use utils::tap_error_log::LogError;
 
 /// Mempool initializer
@@ -105,7 +105,7 @@ impl<M: GetMemoryUsage + Sync + Send + 'static> MempoolSubsystemInterface for Me
 
 impl<M: GetMemoryUsage + Sync + Send + 'static> MempoolInterface for Mempool<M> {
     fn add_transaction(&mut self, tx: SignedTransaction) -> Result<(), Error> {
-        self.add_transaction(tx)
+        self.with_tx_events(|this| this.add_transaction(tx))
     }
 
     fn get_all(&self) -> Result<Vec<SignedTransaction>, Error> {
@@ -120,10 +120,38 @@ impl<M: GetMemoryUsage + Sync + Send + 'static> MempoolInterface for Mempool<M>
         Ok(self.transaction(id).cloned())
     }
 
+    fn get_all_info(&self) -> Result<Vec<TxMempoolInfo>, Error> {
+        Ok(self.get_all_info()?)
+    }
+
+    fn transaction_info(&self, id: &Id<Transaction>) -> Result<Option<TxMempoolInfo>, Error> {
+        Ok(self.transaction_info(id)?)
+    }
+
+    fn evict_transaction(&mut self, id: &Id<Transaction>) -> Result<Vec<Id<Transaction>>, Error> {
+        Ok(self.with_tx_events(|this| this.evict_transaction(id)))
+    }
+
     fn best_block_id(&self) -> Id<GenBlock> {
         self.best_block_id()
     }
 
+    fn get_fee_rate(&self) -> FeeRate {
+        self.get_fee_rate()
+    }
+
+    fn rolling_minimum_fee_rate(&self) -> FeeRate {
+        self.rolling_minimum_fee_rate()
+    }
+
+    fn memory_usage(&self) -> usize {
+        self.get_memory_usage()
+    }
+
+    fn max_size(&self) -> usize {
+        self.max_size()
+    }
+
     fn collect_txs(
         &self,
         tx_accumulator: Box<dyn TransactionAccumulator + Send>,
@@ -138,6 +166,10 @@ impl<M: GetMemoryUsage + Sync + Send + 'static> MempoolInterface for Mempool<M>
         self.subscribe_to_events(handler);
         Ok(())
     }
+
+    fn subscribe_to_rpc_events(&mut self) -> Result<broadcast::Receiver<MempoolEvent>, Error> {
+        Ok(self.subscribe_to_rpc_events())
+    }
 }
 
 /// Mempool constructor
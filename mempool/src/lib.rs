@@ -16,13 +16,14 @@
 #![deny(clippy::clone_on_ref_ptr)]
 
 use common::{
-    chain::Block,
-    primitives::{BlockHeight, Id},
+    chain::{Block, Transaction},
+    primitives::{Amount, BlockHeight, FeeRate, Id},
 };
 pub use interface::{
     mempool_interface::{MempoolInterface, MempoolSubsystemInterface},
     mempool_interface_impl::make_mempool,
 };
+pub use pool::fee::Fee;
 
 use crate::{error::Error as MempoolError, get_memory_usage::GetMemoryUsage};
 
@@ -39,6 +40,21 @@ pub mod tx_accumulator;
 #[derive(Debug, Clone)]
 pub enum MempoolEvent {
     NewTip(Id<Block>, BlockHeight),
+    TransactionAdded(Id<Transaction>),
+    TransactionRemoved(Id<Transaction>),
+}
+
+/// The data the mempool keeps about one of its transactions
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct TxMempoolInfo {
+    pub tx_id: Id<Transaction>,
+    pub fee: Amount,
+    pub size: usize,
+    pub fee_rate: FeeRate,
+    /// Unconfirmed transactions this one spends from, directly or indirectly
+    pub ancestors: Vec<Id<Transaction>>,
+    /// Transactions spending from this one, directly or indirectly
+    pub descendants: Vec<Id<Transaction>>,
 }
 
 pub type MempoolHandle = subsystem::Handle<dyn MempoolInterface>;
@@ -14,22 +14,25 @@
 // limitations under the License.
 
 use parking_lot::RwLock;
-use std::{collections::BTreeSet, mem, num::NonZeroUsize, sync::Arc, time::Duration};
+use std::{collections::BTreeSet, num::NonZeroUsize, sync::Arc, time::Duration};
 
 use chainstate::{
     chainstate_interface::ChainstateInterface,
-    tx_verifier::transaction_verifier::{TransactionSourceForConnect, TransactionVerifierDelta},
+    tx_verifier::transaction_verifier::{
+        TransactionSource, TransactionSourceForConnect, TransactionVerifierDelta,
+    },
 };
 use common::{
     chain::{
-        block::timestamp::BlockTimestamp, Block, ChainConfig, GenBlock, SignedTransaction,
-        Transaction,
+        block::timestamp::BlockTimestamp, signature::inputsig::InputWitness, Block, ChainConfig,
+        GenBlock, SignedTransaction, Transaction,
     },
-    primitives::{amount::Amount, BlockHeight, Id, Idable},
+    primitives::{amount::Amount, BlockHeight, FeeRate, Id, Idable},
     time_getter::TimeGetter,
 };
 use logging::log;
 use serialization::Encode;
+use tokio::sync::broadcast;
 use utils::{
     ensure, eventhandler::EventsController, shallow_clone::ShallowClone, tap_error_log::LogError,
 };
@@ -37,23 +40,23 @@ use utils::{
 use self::{
     entry::{TxEntry, TxEntryWithFee},
     fee::Fee,
-    feerate::{FeeRate, INCREMENTAL_RELAY_FEE_RATE, INCREMENTAL_RELAY_THRESHOLD},
+    feerate::{INCREMENTAL_RELAY_FEE_RATE, INCREMENTAL_RELAY_THRESHOLD},
     rolling_fee_rate::RollingFeeRate,
     spends_unconfirmed::SpendsUnconfirmed,
-    store::{Conflicts, MempoolRemovalReason, MempoolStore, TxMempoolEntry},
+    store::{Conflicts, MempoolRemovalReason, MempoolStore, TxMempoolEntry, TxStoreChange},
 };
 use crate::{
     error::{Error, MempoolPolicyError, TxValidationError},
     get_memory_usage::GetMemoryUsage,
     tx_accumulator::TransactionAccumulator,
-    MempoolEvent,
+    MempoolEvent, TxMempoolInfo,
 };
 
 use crate::config::*;
 
 mod entry;
 pub mod fee;
-mod feerate;
+pub mod feerate;
 mod reorg;
 mod rolling_fee_rate;
 mod spends_unconfirmed;
@@ -77,6 +80,8 @@ pub struct Mempool<M> {
     clock: TimeGetter,
     memory_usage_estimator: M,
     events_controller: EventsController<MempoolEvent>,
+    /// The channel shared by the RPC subscribers, created with the first subscription
+    rpc_events: Option<broadcast::Sender<MempoolEvent>>,
     tx_verifier: tx_verifier::TransactionVerifier,
 }
 
@@ -116,6 +121,7 @@ impl<M> Mempool<M> {
             clock,
             memory_usage_estimator,
             events_controller: Default::default(),
+            rpc_events: None,
             tx_verifier,
         }
     }
@@ -139,13 +145,18 @@ impl<M> Mempool<M> {
         );
 
         // Clear the store, returning the list of transactions it contained previously
-        mem::replace(&mut self.store, MempoolStore::new()).into_transactions()
+        self.store.take_transactions()
     }
 
     pub fn best_block_id(&self) -> Id<GenBlock> {
         utxo::UtxosStorageRead::get_best_block_for_utxos(&self.tx_verifier)
             .expect("best block to exist")
     }
+
+    /// The memory usage above which the mempool starts evicting transactions
+    pub fn max_size(&self) -> usize {
+        self.max_size
+    }
 }
 
 // Rolling-fee-related methods
@@ -201,6 +212,21 @@ impl<M: GetMemoryUsage> Mempool<M> {
         )
     }
 
+    /// The minimum fee rate a new transaction has to pay to be accepted into the mempool
+    pub fn get_fee_rate(&self) -> FeeRate {
+        let relay_fee_rate = FeeRate::new(Amount::from_atoms(
+            u128::try_from(RELAY_FEE_PER_BYTE * 1000).expect("relay fee rate conversion"),
+        ));
+        std::cmp::max(self.get_update_min_fee_rate(), relay_fee_rate)
+    }
+
+    /// The rolling minimum fee rate with any pending decay applied. It is raised above the
+    /// relay fee rate when transactions are evicted because the mempool is full.
+    pub fn rolling_minimum_fee_rate(&self) -> FeeRate {
+        self.get_update_min_fee_rate();
+        self.rolling_fee_rate.read().rolling_minimum_fee_rate()
+    }
+
     fn drop_rolling_fee(&self) {
         let mut rolling_fee_rate = self.rolling_fee_rate.write();
         (*rolling_fee_rate).set_rolling_minimum_fee_rate(FeeRate::new(Amount::from_atoms(0)));
@@ -351,6 +377,41 @@ impl<M: GetMemoryUsage> Mempool<M> {
             MempoolPolicyError::TransactionAlreadyInMempool
         );
 
+        self.check_standard_script_witnesses(tx)?;
+
+        Ok(())
+    }
+
+    // Script hash spends are valid in blocks with much larger scripts, but we only relay
+    // the reasonably sized ones
+    fn check_standard_script_witnesses(
+        &self,
+        tx: &SignedTransaction,
+    ) -> Result<(), MempoolPolicyError> {
+        for (input_index, witness) in tx.signatures().iter().enumerate() {
+            let spender = match witness {
+                InputWitness::ScriptHash(spender) => spender,
+                InputWitness::NoSignature(_) | InputWitness::Standard(_) => continue,
+            };
+
+            let size = spender.redeem_script().len();
+            ensure!(
+                size <= MAX_STANDARD_REDEEM_SCRIPT_SIZE,
+                MempoolPolicyError::NonStandardRedeemScriptSize { input_index, size },
+            );
+
+            let size = spender.arguments().len();
+            ensure!(
+                size <= MAX_STANDARD_SCRIPT_ARGUMENTS_SIZE,
+                MempoolPolicyError::NonStandardScriptArgumentsSize { input_index, size },
+            );
+
+            ensure!(
+                spender.arguments().is_push_only(),
+                MempoolPolicyError::NonStandardScriptArguments { input_index },
+            );
+        }
+
         Ok(())
     }
 
@@ -391,7 +452,10 @@ impl<M: GetMemoryUsage> Mempool<M> {
     ) -> Result<Fee, MempoolPolicyError> {
         let minimum_fee_rate = self.get_update_min_fee_rate();
         log::debug!("minimum fee rate {:?}", minimum_fee_rate);
-        let res = minimum_fee_rate.compute_fee(tx.encoded_size());
+        let res = minimum_fee_rate
+            .compute_fee(tx.encoded_size())
+            .map(Fee::from)
+            .ok_or(MempoolPolicyError::FeeOverflow);
         log::debug!("minimum_mempool_fee for tx: {:?}", res);
         res
     }
@@ -657,10 +721,13 @@ impl<M: GetMemoryUsage> Mempool<M> {
                 removed.descendant_score(),
                 removed.size()
             );
-            removed_fees.push(FeeRate::from_total_tx_fee(
-                removed.fee(),
-                NonZeroUsize::new(removed.size()).expect("transaction cannot have zero size"),
-            )?);
+            removed_fees.push(
+                FeeRate::from_total_tx_fee(
+                    *removed.fee(),
+                    NonZeroUsize::new(removed.size()).expect("transaction cannot have zero size"),
+                )
+                .ok_or(MempoolPolicyError::FeeOverflow)?,
+            );
             self.store
                 .drop_tx_and_descendants(removed.tx_id(), MempoolRemovalReason::SizeLimit);
         }
@@ -702,6 +769,85 @@ impl<M: GetMemoryUsage> Mempool<M> {
             .collect()
     }
 
+    pub fn get_all_info(&self) -> Result<Vec<TxMempoolInfo>, MempoolPolicyError> {
+        self.store
+            .txs_by_descendant_score
+            .values()
+            .flatten()
+            .map(|id| self.tx_info(self.store.get_entry(id).expect("entry")))
+            .collect()
+    }
+
+    pub fn transaction_info(
+        &self,
+        id: &Id<Transaction>,
+    ) -> Result<Option<TxMempoolInfo>, MempoolPolicyError> {
+        self.store.get_entry(id).map(|entry| self.tx_info(entry)).transpose()
+    }
+
+    fn tx_info(&self, entry: &TxMempoolEntry) -> Result<TxMempoolInfo, MempoolPolicyError> {
+        let fee_rate = FeeRate::from_total_tx_fee(
+            *entry.fee(),
+            NonZeroUsize::new(entry.size()).expect("transaction cannot have zero size"),
+        )
+        .ok_or(MempoolPolicyError::FeeOverflow)?;
+        Ok(TxMempoolInfo {
+            tx_id: entry.tx_id(),
+            fee: *entry.fee(),
+            size: entry.size(),
+            fee_rate,
+            ancestors: entry.unconfirmed_ancestors(&self.store).iter().copied().collect(),
+            descendants: entry.unconfirmed_descendants(&self.store).iter().copied().collect(),
+        })
+    }
+
+    /// Remove a transaction together with its descendants from the mempool. Returns the ids of
+    /// the removed transactions, which is empty if the transaction is not in the mempool.
+    pub fn evict_transaction(&mut self, tx_id: &Id<Transaction>) -> Vec<Id<Transaction>> {
+        let mut evicted: Vec<_> = match self.store.get_entry(tx_id) {
+            Some(entry) => std::iter::once(*tx_id)
+                .chain(entry.unconfirmed_descendants(&self.store).iter().copied())
+                .map(|id| {
+                    let seq_no = self.store.seq_nos_by_tx.get(&id).expect("tx entry must exist");
+                    let tx = self.store.get_entry(&id).expect("entry").transaction().clone();
+                    (*seq_no, tx)
+                })
+                .collect(),
+            None => return Vec::new(),
+        };
+        log::info!(
+            "Evicting tx {tx_id} together with {} descendants",
+            evicted.len() - 1
+        );
+
+        self.store.drop_tx_and_descendants(*tx_id, MempoolRemovalReason::Evicted);
+
+        // Disconnect the evicted transactions from the verifier, descendants first, so that the
+        // outputs they have spent become spendable again
+        evicted.sort_by(|(a, _), (b, _)| b.cmp(a));
+        let disconnected = evicted.iter().try_for_each(|(_, tx)| {
+            self.tx_verifier.disconnect_transaction(&TransactionSource::Mempool, tx)
+        });
+        let mut evicted: Vec<_> =
+            evicted.into_iter().map(|(_, tx)| tx.transaction().get_id()).collect();
+
+        if let Err(e) = disconnected {
+            // The verifier state could not be updated in place, rebuild it from the remaining
+            // transactions instead. Those that are no longer accepted are evicted as well.
+            log::warn!("Failed to disconnect evicted transactions, rebuilding mempool: {e}");
+            for entry in self.reset() {
+                let entry_id = *entry.tx_id();
+                if let Err(e) = self.add_transaction_entry(entry) {
+                    log::warn!("Evicting {entry_id:?} from mempool: {e:?}");
+                    evicted.push(entry_id);
+                }
+            }
+        }
+
+        evicted.sort();
+        evicted
+    }
+
     pub fn collect_txs(
         &self,
         mut tx_accumulator: Box<dyn TransactionAccumulator>,
@@ -743,12 +889,49 @@ impl<M: GetMemoryUsage> Mempool<M> {
         self.events_controller.subscribe_to_events(handler)
     }
 
+    /// Subscribe to the events through a channel shared by all the RPC subscribers.
+    /// Unlike the event handlers, a subscriber goes away together with its receiver.
+    pub fn subscribe_to_rpc_events(&mut self) -> broadcast::Receiver<MempoolEvent> {
+        if let Some(sender) = &self.rpc_events {
+            return sender.subscribe();
+        }
+
+        let (sender, receiver) = broadcast::channel(RPC_EVENTS_CAPACITY);
+        let handler_sender = sender.clone();
+        // Sending fails only while there are no subscribers
+        self.events_controller.subscribe_to_events(Arc::new(move |event| {
+            let _ = handler_sender.send(event);
+        }));
+        self.rpc_events = Some(sender);
+        receiver
+    }
+
+    /// Run a mempool update and notify subscribers about the transactions it added or removed.
+    ///
+    /// Updates such as a new tip may take transactions out and put them back in, the store
+    /// only reports the transactions that are added or removed by the update as a whole.
+    pub fn with_tx_events<T>(&mut self, update: impl FnOnce(&mut Self) -> T) -> T {
+        let result = update(self);
+
+        for (tx_id, change) in self.store.take_tx_changes() {
+            let event = match change {
+                TxStoreChange::Added => MempoolEvent::TransactionAdded(tx_id),
+                TxStoreChange::Removed => MempoolEvent::TransactionRemoved(tx_id),
+            };
+            self.events_controller.broadcast(event);
+        }
+
+        result
+    }
+
     pub fn process_chainstate_event(&mut self, evt: chainstate::ChainstateEvent) {
         log::info!("mempool: Processing chainstate event {evt:?}");
         match evt {
             chainstate::ChainstateEvent::NewTip(block_id, block_height) => {
-                self.new_tip_set(block_id, block_height);
+                self.with_tx_events(|this| this.new_tip_set(block_id, block_height));
             }
+            // The mempool is brought in line with the new chain once its tip is announced
+            chainstate::ChainstateEvent::Reorg(_) => {}
         }
     }
 
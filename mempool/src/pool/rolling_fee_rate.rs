@@ -13,10 +13,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use common::primitives::Amount;
+use common::primitives::{Amount, FeeRate};
 use logging::log;
 
-use super::feerate::FeeRate;
 use crate::config::Time;
 
 #[derive(Clone, Copy, Debug)]
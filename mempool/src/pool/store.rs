@@ -15,7 +15,10 @@
 
 use std::{
     cmp::Ordering,
-    collections::{btree_map::Entry::Occupied, BTreeMap, BTreeSet},
+    collections::{
+        btree_map::Entry::{Occupied, Vacant},
+        BTreeMap, BTreeSet,
+    },
 };
 
 use common::{
@@ -103,6 +106,17 @@ pub struct MempoolStore {
     pub txs_by_seq_no: BTreeMap<usize, Id<Transaction>>,
     pub seq_nos_by_tx: BTreeMap<Id<Transaction>, usize>,
     next_seq_no: usize,
+
+    // The transactions added to or removed from the store since the changes were last taken,
+    // used to notify the mempool event subscribers. A transaction that is removed and added back
+    // (or the other way round) in the meantime is not recorded.
+    tx_changes: BTreeMap<Id<Transaction>, TxStoreChange>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxStoreChange {
+    Added,
+    Removed,
 }
 
 // If a transaction is removed from the mempool for any reason other than inclusion in a block,
@@ -118,6 +132,7 @@ pub enum MempoolRemovalReason {
     Expiry,
     SizeLimit,
     Replaced,
+    Evicted,
 }
 
 impl MempoolStore {
@@ -131,6 +146,7 @@ impl MempoolStore {
             txs_by_seq_no: BTreeMap::new(),
             seq_nos_by_tx: BTreeMap::new(),
             next_seq_no: 0,
+            tx_changes: BTreeMap::new(),
         }
     }
 
@@ -247,9 +263,23 @@ impl MempoolStore {
             .insert(tx_id);
         self.txs_by_seq_no.insert(seq_no, tx_id);
         self.seq_nos_by_tx.insert(tx_id, seq_no);
+        self.record_tx_change(tx_id, TxStoreChange::Added);
         Ok(())
     }
 
+    fn record_tx_change(&mut self, tx_id: Id<Transaction>, change: TxStoreChange) {
+        match self.tx_changes.entry(tx_id) {
+            Occupied(recorded) if *recorded.get() != change => drop(recorded.remove_entry()),
+            Occupied(_) => {}
+            Vacant(entry) => drop(entry.insert(change)),
+        }
+    }
+
+    /// Take the transactions added to or removed from the store since the last call
+    pub fn take_tx_changes(&mut self) -> BTreeMap<Id<Transaction>, TxStoreChange> {
+        std::mem::take(&mut self.tx_changes)
+    }
+
     fn add_to_descendant_score_index(&mut self, entry: &TxMempoolEntry) {
         self.refresh_ancestors(entry);
         self.txs_by_descendant_score
@@ -324,6 +354,7 @@ impl MempoolStore {
                 self.update_descendant_state_for_drop(&entry)
             }
             self.drop_tx(&entry);
+            self.record_tx_change(*tx_id, TxStoreChange::Removed);
         } else {
             assert!(!self.txs_by_descendant_score.values().flatten().any(|id| *id == *tx_id));
             assert!(!self.spender_txs.iter().any(|(_, id)| *id == *tx_id));
@@ -424,8 +455,19 @@ impl MempoolStore {
         self.spender_txs.get(outpoint).cloned()
     }
 
+    /// Take all the transactions out of the store in the original order of insertion.
+    /// They are recorded as removed until they are added back.
+    pub fn take_transactions(&mut self) -> impl Iterator<Item = TxEntry> {
+        let mut old_store = std::mem::replace(self, Self::new());
+        self.tx_changes = std::mem::take(&mut old_store.tx_changes);
+        for tx_id in old_store.txs_by_id.keys() {
+            self.record_tx_change(*tx_id, TxStoreChange::Removed);
+        }
+        old_store.into_transactions()
+    }
+
     /// Take all the transactions from the store in the original order of insertion
-    pub fn into_transactions(self) -> impl Iterator<Item = TxEntry> {
+    fn into_transactions(self) -> impl Iterator<Item = TxEntry> {
         let Self {
             mut txs_by_id,
             txs_by_seq_no,
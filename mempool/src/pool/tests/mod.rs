@@ -15,7 +15,7 @@
 
 use super::*;
 use crate::{
-    get_memory_usage::MockGetMemoryUsage, tx_accumulator::DefaultTxAccumulator,
+    get_memory_usage::MockGetMemoryUsage, tx_accumulator::DefaultTxAccumulator, MempoolInterface,
     SystemUsageEstimator,
 };
 use chainstate::{
@@ -25,7 +25,9 @@ use common::{
     chain::{
         block::{timestamp::BlockTimestamp, Block, BlockReward, ConsensusData},
         config::ChainConfig,
-        signature::inputsig::InputWitness,
+        signature::inputsig::{
+            authorize_script_hash_spend::AuthorizedScriptHashSpend, InputWitness,
+        },
         tokens::OutputValue,
         transaction::{Destination, TxInput, TxOutput},
         OutPoint, OutPointSourceId, Transaction,
@@ -124,6 +126,147 @@ async fn add_single_tx() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn tx_events() -> anyhow::Result<()> {
+    let mut mempool = setup().await;
+
+    let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let events_ = Arc::clone(&events);
+    mempool.subscribe_to_events(Arc::new(move |event: MempoolEvent| match event {
+        MempoolEvent::TransactionAdded(tx_id) => events_.lock().unwrap().push((true, tx_id)),
+        MempoolEvent::TransactionRemoved(tx_id) => events_.lock().unwrap().push((false, tx_id)),
+        MempoolEvent::NewTip(_, _) => {}
+    }));
+
+    let outpoint_source_id = mempool.chain_config.genesis_block_id().into();
+    let relay_fee: Fee = Amount::from_atoms(get_relay_fee_from_tx_size(TX_SPEND_INPUT_SIZE)).into();
+    let tx = tx_spend_input(
+        &mempool,
+        TxInput::new(outpoint_source_id, 0),
+        InputWitness::NoSignature(Some(DUMMY_WITNESS_MSG.to_vec())),
+        relay_fee,
+        0,
+    )
+    .await?;
+    let tx_id = tx.transaction().get_id();
+
+    MempoolInterface::add_transaction(&mut mempool, tx.clone())?;
+    mempool.events_controller.wait_for_all_events();
+    assert_eq!(*events.lock().unwrap(), vec![(true, tx_id)]);
+
+    // A rejected transaction does not produce any events
+    assert!(MempoolInterface::add_transaction(&mut mempool, tx).is_err());
+    mempool.events_controller.wait_for_all_events();
+    assert_eq!(*events.lock().unwrap(), vec![(true, tx_id)]);
+
+    // Transactions taken out and put back by the same update are not reported
+    mempool.with_tx_events(|this| {
+        let txs = this.reset().collect::<Vec<_>>();
+        txs.into_iter().try_for_each(|tx| this.add_transaction_entry(tx))
+    })?;
+    mempool.events_controller.wait_for_all_events();
+    assert_eq!(*events.lock().unwrap(), vec![(true, tx_id)]);
+
+    let mut rpc_events = mempool.subscribe_to_rpc_events();
+    mempool.with_tx_events(|this| this.store.remove_tx(&tx_id, MempoolRemovalReason::Block));
+    mempool.events_controller.wait_for_all_events();
+    assert_eq!(*events.lock().unwrap(), vec![(true, tx_id), (false, tx_id)]);
+    assert!(matches!(
+        rpc_events.try_recv(),
+        Ok(MempoolEvent::TransactionRemoved(id)) if id == tx_id
+    ));
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn evict_tx_with_descendants() -> anyhow::Result<()> {
+    let mut mempool = setup().await;
+
+    let outpoint_source_id = mempool.chain_config.genesis_block_id().into();
+    let root = tx_spend_input(
+        &mempool,
+        TxInput::new(outpoint_source_id, 0),
+        InputWitness::NoSignature(Some(DUMMY_WITNESS_MSG.to_vec())),
+        None,
+        0,
+    )
+    .await?;
+    let root_id = root.transaction().get_id();
+    mempool.add_transaction(root)?;
+
+    let relay_fee: Fee = Amount::from_atoms(get_relay_fee_from_tx_size(TX_SPEND_INPUT_SIZE)).into();
+    let parent = tx_spend_input(
+        &mempool,
+        TxInput::new(OutPointSourceId::Transaction(root_id), 0),
+        InputWitness::NoSignature(Some(DUMMY_WITNESS_MSG.to_vec())),
+        relay_fee,
+        0,
+    )
+    .await?;
+    let parent_id = parent.transaction().get_id();
+    mempool.add_transaction(parent.clone())?;
+
+    let unrelated = tx_spend_input(
+        &mempool,
+        TxInput::new(OutPointSourceId::Transaction(root_id), 1),
+        InputWitness::NoSignature(Some(DUMMY_WITNESS_MSG.to_vec())),
+        None,
+        0,
+    )
+    .await?;
+    let unrelated_id = unrelated.transaction().get_id();
+    mempool.add_transaction(unrelated)?;
+
+    let child = tx_spend_input(
+        &mempool,
+        TxInput::new(OutPointSourceId::Transaction(parent_id), 0),
+        InputWitness::NoSignature(Some(DUMMY_WITNESS_MSG.to_vec())),
+        None,
+        0,
+    )
+    .await?;
+    let child_id = child.transaction().get_id();
+    mempool.add_transaction(child)?;
+
+    let parent_info = mempool.transaction_info(&parent_id)?.expect("parent in mempool");
+    assert_eq!(parent_info.fee, *relay_fee);
+    assert_eq!(parent_info.size, parent.encoded_size());
+    assert_eq!(
+        parent_info.fee_rate,
+        FeeRate::from_total_tx_fee(*relay_fee, NonZeroUsize::new(parent_info.size).unwrap())
+            .unwrap()
+    );
+    assert_eq!(parent_info.ancestors, vec![root_id]);
+    assert_eq!(parent_info.descendants, vec![child_id]);
+
+    let child_info = mempool.transaction_info(&child_id)?.expect("child in mempool");
+    assert_eq!(
+        child_info.ancestors.into_iter().collect::<BTreeSet<_>>(),
+        BTreeSet::from([root_id, parent_id])
+    );
+    assert!(child_info.descendants.is_empty());
+    assert_eq!(mempool.get_all_info()?.len(), 4);
+
+    let evicted: BTreeSet<_> = mempool.evict_transaction(&parent_id).into_iter().collect();
+    assert_eq!(evicted, BTreeSet::from([parent_id, child_id]));
+    assert_eq!(mempool.transaction_info(&parent_id)?, None);
+    assert_eq!(mempool.transaction_info(&child_id)?, None);
+
+    // The transactions that don't depend on the evicted one are kept
+    assert!(mempool.contains_transaction(&root_id));
+    assert!(mempool.contains_transaction(&unrelated_id));
+    assert_eq!(mempool.get_all().len(), 2);
+    let root_info = mempool.transaction_info(&root_id)?.expect("root in mempool");
+    assert_eq!(root_info.descendants, vec![unrelated_id]);
+    assert!(mempool.evict_transaction(&parent_id).is_empty());
+
+    // The outputs spent by the evicted transactions are spendable again
+    mempool.add_transaction(parent)?;
+    assert!(mempool.contains_transaction(&parent_id));
+    mempool.store.assert_valid();
+    Ok(())
+}
+
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
@@ -187,6 +330,19 @@ async fn tx_no_inputs() {
     mempool.store.assert_valid();
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn fee_rate_covers_relay_fee() {
+    let mempool = setup().await;
+    let fee_rate = mempool.get_fee_rate();
+
+    let relay_fee = Amount::from_atoms(get_relay_fee_from_tx_size(TX_SPEND_INPUT_SIZE));
+    assert_eq!(
+        fee_rate.compute_fee(TX_SPEND_INPUT_SIZE).unwrap(),
+        relay_fee
+    );
+    assert!(fee_rate >= mempool.get_minimum_rolling_fee());
+}
+
 // TODO this is copy-pasted from libp2p's test utils. This function should be extracted to an
 // external crate to avoid code duplication
 pub async fn start_chainstate_with_config(
@@ -408,6 +564,61 @@ async fn tx_too_big(#[case] seed: Seed) -> anyhow::Result<()> {
     Ok(())
 }
 
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn non_standard_script_hash_witness(#[case] seed: Seed) -> anyhow::Result<()> {
+    let mut rng = make_seedable_rng(seed);
+    let tf = TestFramework::builder(&mut rng).build();
+    let genesis = tf.genesis();
+    let mut mempool = setup_with_chainstate(tf.chainstate()).await;
+
+    let make_tx = |redeem_script: Vec<u8>, arguments: Vec<u8>| {
+        let spender = AuthorizedScriptHashSpend::new(redeem_script.into(), arguments.into());
+        TransactionBuilder::new()
+            .add_input(
+                TxInput::new(OutPointSourceId::BlockReward(genesis.get_id().into()), 0),
+                InputWitness::ScriptHash(spender),
+            )
+            .add_output(TxOutput::Transfer(
+                OutputValue::Coin(Amount::from_atoms(100)),
+                Destination::AnyoneCanSpend,
+            ))
+            .build()
+    };
+
+    // OP_TRUE repeated
+    let redeem_script = vec![0x51; MAX_STANDARD_REDEEM_SCRIPT_SIZE + 1];
+    assert_eq!(
+        mempool.add_transaction(make_tx(redeem_script, Vec::new())),
+        Err(MempoolPolicyError::NonStandardRedeemScriptSize {
+            input_index: 0,
+            size: MAX_STANDARD_REDEEM_SCRIPT_SIZE + 1,
+        }
+        .into())
+    );
+
+    let arguments = vec![0x51; MAX_STANDARD_SCRIPT_ARGUMENTS_SIZE + 1];
+    assert_eq!(
+        mempool.add_transaction(make_tx(vec![0x51], arguments)),
+        Err(MempoolPolicyError::NonStandardScriptArgumentsSize {
+            input_index: 0,
+            size: MAX_STANDARD_SCRIPT_ARGUMENTS_SIZE + 1,
+        }
+        .into())
+    );
+
+    // OP_1 OP_DUP
+    assert_eq!(
+        mempool.add_transaction(make_tx(vec![0x87], vec![0x51, 0x76])),
+        Err(MempoolPolicyError::NonStandardScriptArguments { input_index: 0 }.into())
+    );
+
+    mempool.store.assert_valid();
+    Ok(())
+}
+
 // To test our validation of BIP125 Rule#4 (replacement transaction pays for its own bandwidth), we need to know the necessary relay fee before creating the transaction. The relay fee depends on the size of the transaction. The usual way to get the size of a transaction is to call `tx.encoded_size` but we cannot do this until we have created the transaction itself. To get around this cycle, we have precomputed the size of all transaction created by `tx_spend_input`. This value will be the same for all transactions created by this function.
 const TX_SPEND_INPUT_SIZE: usize = 213;
 
@@ -972,9 +1183,10 @@ async fn rolling_fee(#[case] seed: Seed) -> anyhow::Result<()> {
         rolling_fee,
         (INCREMENTAL_RELAY_FEE_RATE
             + FeeRate::from_total_tx_fee(
-                child_0_fee,
+                *child_0_fee,
                 NonZeroUsize::new(child_0.encoded_size()).unwrap()
-            )?)
+            )
+            .unwrap())
         .unwrap()
     );
     assert_eq!(rolling_fee, FeeRate::new(Amount::from_atoms(3625)));
@@ -985,9 +1197,11 @@ async fn rolling_fee(#[case] seed: Seed) -> anyhow::Result<()> {
     assert_eq!(
         rolling_fee,
         (FeeRate::from_total_tx_fee(
-            try_get_fee(&mempool, &child_0).await,
+            *try_get_fee(&mempool, &child_0).await,
             NonZeroUsize::new(child_0.encoded_size()).unwrap()
-        )? + INCREMENTAL_RELAY_FEE_RATE)
+        )
+        .unwrap()
+            + INCREMENTAL_RELAY_FEE_RATE)
             .unwrap()
     );
 
@@ -1021,7 +1235,11 @@ async fn rolling_fee(#[case] seed: Seed) -> anyhow::Result<()> {
         &mempool,
         TxInput::new(outpoint_source_id, 2),
         InputWitness::NoSignature(Some(DUMMY_WITNESS_MSG.to_vec())),
-        mempool.get_minimum_rolling_fee().compute_fee(estimate_tx_size(1, 1)).unwrap(),
+        mempool
+            .get_minimum_rolling_fee()
+            .compute_fee(estimate_tx_size(1, 1))
+            .map(Fee::from)
+            .unwrap(),
         flags,
     )
     .await?;
@@ -1108,7 +1326,7 @@ async fn rolling_fee(#[case] seed: Seed) -> anyhow::Result<()> {
     );
     assert_eq!(
         mempool.get_minimum_rolling_fee(),
-        rolling_fee / NonZeroUsize::new(2).expect("nonzero")
+        FeeRate::new(Amount::from_atoms(rolling_fee.atoms_per_kb() / 2))
     );
 
     mock_time.store(
@@ -1123,7 +1341,7 @@ async fn rolling_fee(#[case] seed: Seed) -> anyhow::Result<()> {
     );
     assert_eq!(
         mempool.get_minimum_rolling_fee(),
-        rolling_fee / NonZeroUsize::new(4).expect("nonzero")
+        FeeRate::new(Amount::from_atoms(rolling_fee.atoms_per_kb() / 4))
     );
     log::debug!(
         "After successful addition of dummy, rolling fee rate is {:?}",
@@ -16,22 +16,91 @@
 //! Mempool subsystem RPC handler
 
 use common::{
-    chain::{GenBlock, SignedTransaction, Transaction},
-    primitives::Id,
+    chain::{Block, GenBlock, SignedTransaction, Transaction},
+    primitives::{BlockHeight, FeeRate, Id},
 };
+use rpc::subscription::{PendingSubscriptionSink, SubscriptionResult};
 use serialization::hex_encoded::HexEncoded;
 use utils::tap_error_log::LogError;
 
-#[rpc::rpc(server, namespace = "mempool")]
+use crate::{MempoolEvent, TxMempoolInfo};
+
+/// Mempool event as delivered to the RPC subscribers
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RpcMempoolEvent {
+    NewTip { id: Id<Block>, height: BlockHeight },
+    TransactionAdded { tx_id: Id<Transaction> },
+    TransactionRemoved { tx_id: Id<Transaction> },
+}
+
+/// Mempool memory usage, in bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RpcMemoryUsage {
+    pub usage: usize,
+    /// Transactions are evicted when the usage exceeds the limit
+    pub limit: usize,
+}
+
+impl From<MempoolEvent> for RpcMempoolEvent {
+    fn from(event: MempoolEvent) -> Self {
+        match event {
+            MempoolEvent::NewTip(id, height) => Self::NewTip { id, height },
+            MempoolEvent::TransactionAdded(tx_id) => Self::TransactionAdded { tx_id },
+            MempoolEvent::TransactionRemoved(tx_id) => Self::TransactionRemoved { tx_id },
+        }
+    }
+}
+
+#[rpc::rpc(server, client, namespace = "mempool")]
 trait MempoolRpc {
     #[method(name = "contains_tx")]
     async fn contains_tx(&self, tx_id: Id<Transaction>) -> rpc::Result<bool>;
 
+    #[method(name = "get_all_transactions")]
+    async fn get_all_transactions(&self) -> rpc::Result<Vec<HexEncoded<SignedTransaction>>>;
+
+    /// Fee, size and in-mempool relatives of all transactions
+    #[method(name = "get_all_transactions_info")]
+    async fn get_all_transactions_info(&self) -> rpc::Result<Vec<TxMempoolInfo>>;
+
+    #[method(name = "get_transaction")]
+    async fn get_transaction(
+        &self,
+        tx_id: Id<Transaction>,
+    ) -> rpc::Result<Option<HexEncoded<SignedTransaction>>>;
+
+    /// Fee, size and in-mempool relatives of a transaction
+    #[method(name = "transaction_info")]
+    async fn transaction_info(&self, tx_id: Id<Transaction>) -> rpc::Result<Option<TxMempoolInfo>>;
+
+    /// Remove a transaction and its descendants, returning the ids of the removed transactions
+    #[method(name = "evict_transaction")]
+    async fn evict_transaction(&self, tx_id: Id<Transaction>) -> rpc::Result<Vec<Id<Transaction>>>;
+
     #[method(name = "submit_transaction")]
     async fn submit_transaction(&self, tx: HexEncoded<SignedTransaction>) -> rpc::Result<()>;
 
     #[method(name = "local_best_block_id")]
     async fn local_best_block_id(&self) -> rpc::Result<Id<GenBlock>>;
+
+    #[method(name = "get_fee_rate")]
+    async fn get_fee_rate(&self) -> rpc::Result<FeeRate>;
+
+    #[method(name = "rolling_minimum_fee_rate")]
+    async fn rolling_minimum_fee_rate(&self) -> rpc::Result<FeeRate>;
+
+    #[method(name = "memory_usage")]
+    async fn memory_usage(&self) -> rpc::Result<RpcMemoryUsage>;
+
+    /// Subscribe to transactions entering and leaving the mempool and to new tips it has
+    /// processed. Only available over websocket.
+    #[subscription(
+        name = "subscribe_events",
+        unsubscribe = "unsubscribe_events",
+        item = RpcMempoolEvent,
+    )]
+    async fn subscribe_events(&self) -> SubscriptionResult;
 }
 
 #[async_trait::async_trait]
@@ -40,6 +109,40 @@ impl MempoolRpcServer for super::MempoolHandle {
         rpc::handle_result(self.call(move |this| this.contains_transaction(&tx_id)).await)
     }
 
+    async fn get_all_transactions(&self) -> rpc::Result<Vec<HexEncoded<SignedTransaction>>> {
+        rpc::handle_result(
+            self.call(|this| {
+                this.get_all()
+                    .map(|txs| txs.into_iter().map(HexEncoded::new).collect::<Vec<_>>())
+            })
+            .await,
+        )
+    }
+
+    async fn get_all_transactions_info(&self) -> rpc::Result<Vec<TxMempoolInfo>> {
+        rpc::handle_result(self.call(|this| this.get_all_info()).await)
+    }
+
+    async fn get_transaction(
+        &self,
+        tx_id: Id<Transaction>,
+    ) -> rpc::Result<Option<HexEncoded<SignedTransaction>>> {
+        rpc::handle_result(
+            self.call(move |this| this.transaction(&tx_id).map(|tx| tx.map(HexEncoded::new)))
+                .await,
+        )
+    }
+
+    async fn transaction_info(&self, tx_id: Id<Transaction>) -> rpc::Result<Option<TxMempoolInfo>> {
+        rpc::handle_result(self.call(move |this| this.transaction_info(&tx_id)).await)
+    }
+
+    async fn evict_transaction(&self, tx_id: Id<Transaction>) -> rpc::Result<Vec<Id<Transaction>>> {
+        rpc::handle_result(
+            self.call_mut(move |this| this.evict_transaction(&tx_id)).await.log_err(),
+        )
+    }
+
     async fn submit_transaction(&self, tx: HexEncoded<SignedTransaction>) -> rpc::Result<()> {
         rpc::handle_result(self.call_mut(|this| this.add_transaction(tx.take())).await.log_err())
     }
@@ -47,4 +150,27 @@ impl MempoolRpcServer for super::MempoolHandle {
     async fn local_best_block_id(&self) -> rpc::Result<Id<GenBlock>> {
         rpc::handle_result(self.call(|this| this.best_block_id()).await)
     }
+
+    async fn get_fee_rate(&self) -> rpc::Result<FeeRate> {
+        rpc::handle_result(self.call(|this| this.get_fee_rate()).await)
+    }
+
+    async fn rolling_minimum_fee_rate(&self) -> rpc::Result<FeeRate> {
+        rpc::handle_result(self.call(|this| this.rolling_minimum_fee_rate()).await)
+    }
+
+    async fn memory_usage(&self) -> rpc::Result<RpcMemoryUsage> {
+        rpc::handle_result(
+            self.call(|this| RpcMemoryUsage {
+                usage: this.memory_usage(),
+                limit: this.max_size(),
+            })
+            .await,
+        )
+    }
+
+    async fn subscribe_events(&self, pending: PendingSubscriptionSink) -> SubscriptionResult {
+        let receiver = self.call_mut(|this| this.subscribe_to_rpc_events()).await??;
+        rpc::subscription::pipe_from_receiver::<_, RpcMempoolEvent>(pending, receiver).await
+    }
 }
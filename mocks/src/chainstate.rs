@@ -26,11 +26,13 @@ use common::{
             GenBlock,
         },
         tokens::{RPCTokenInfo, TokenAuxiliaryData, TokenId},
-        ChainConfig, DelegationId, OutPoint, OutPointSourceId, PoolId, TxInput, TxMainChainIndex,
+        ChainConfig, DelegationId, OutPoint, OutPointSourceId, PoolId, Transaction, TxInput,
+        TxMainChainIndex,
     },
     primitives::{Amount, BlockHeight, Id},
 };
 use pos_accounting::PoolData;
+use tokio::sync::broadcast;
 use utils::eventhandler::EventHandler;
 use utxo::Utxo;
 
@@ -41,6 +43,7 @@ mockall::mock! {
 
     impl ChainstateInterface for ChainstateInterfaceMock {
         fn subscribe_to_events(&mut self, handler: Arc<dyn Fn(ChainstateEvent) + Send + Sync>);
+        fn subscribe_to_rpc_events(&mut self) -> broadcast::Receiver<ChainstateEvent>;
         fn process_block(&mut self, block: Block, source: BlockSource) -> Result<Option<BlockIndex>, ChainstateError>;
         fn preliminary_block_check(&self, block: Block) -> Result<Block, ChainstateError>;
         fn preliminary_header_check(&self, header: SignedBlockHeader) -> Result<(), ChainstateError>;
@@ -85,6 +88,10 @@ mockall::mock! {
             &self,
             tx_id: &OutPointSourceId,
         ) -> Result<Option<TxMainChainIndex>, ChainstateError>;
+        fn get_mainchain_tx(
+            &self,
+            tx_id: &Id<Transaction>,
+        ) -> Result<Option<(Transaction, Id<Block>)>, ChainstateError>;
         fn subscribers(&self) -> &Vec<EventHandler<ChainstateEvent>>;
         fn calculate_median_time_past(&self, starting_block: &Id<GenBlock>) -> Result<BlockTimestamp, ChainstateError>;
         fn is_already_an_orphan(&self, block_id: &Id<Block>) -> bool;
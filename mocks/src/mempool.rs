@@ -22,14 +22,15 @@ use std::sync::{
 
 use common::{
     chain::{GenBlock, SignedTransaction, Transaction},
-    primitives::Id,
+    primitives::{FeeRate, Id},
 };
 use mempool::{
     error::{Error, TxValidationError},
     tx_accumulator::TransactionAccumulator,
-    MempoolEvent, MempoolInterface, MempoolSubsystemInterface,
+    MempoolEvent, MempoolInterface, MempoolSubsystemInterface, TxMempoolInfo,
 };
 use subsystem::{subsystem::CallError, CallRequest, ShutdownRequest};
+use tokio::sync::broadcast;
 
 #[derive(Clone)]
 pub struct MempoolInterfaceMock {
@@ -111,10 +112,38 @@ impl MempoolInterface for MempoolInterfaceMock {
         unimplemented!()
     }
 
+    fn get_all_info(&self) -> Result<Vec<TxMempoolInfo>, Error> {
+        unimplemented!()
+    }
+
+    fn transaction_info(&self, _id: &Id<Transaction>) -> Result<Option<TxMempoolInfo>, Error> {
+        unimplemented!()
+    }
+
+    fn evict_transaction(&mut self, _id: &Id<Transaction>) -> Result<Vec<Id<Transaction>>, Error> {
+        unimplemented!()
+    }
+
     fn best_block_id(&self) -> Id<GenBlock> {
         unimplemented!()
     }
 
+    fn get_fee_rate(&self) -> FeeRate {
+        unimplemented!()
+    }
+
+    fn rolling_minimum_fee_rate(&self) -> FeeRate {
+        unimplemented!()
+    }
+
+    fn memory_usage(&self) -> usize {
+        unimplemented!()
+    }
+
+    fn max_size(&self) -> usize {
+        unimplemented!()
+    }
+
     fn collect_txs(
         &self,
         tx_accumulator: Box<dyn TransactionAccumulator + Send>,
@@ -140,6 +169,10 @@ impl MempoolInterface for MempoolInterfaceMock {
             Ok(())
         }
     }
+
+    fn subscribe_to_rpc_events(&mut self) -> Result<broadcast::Receiver<MempoolEvent>, Error> {
+        unimplemented!()
+    }
 }
 
 #[async_trait::async_trait]
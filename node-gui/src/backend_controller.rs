@@ -13,17 +13,106 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use common::chain::ChainConfig;
+use common::address::Address;
+use common::chain::{ChainConfig, GenBlock, OutPoint, TxOutput};
+use common::primitives::{Amount, BlockHeight, Id};
+use crypto::key::hdkd::u31::U31;
 use node_lib::node_controller::NodeController;
 use std::fmt::Debug;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::{oneshot, Mutex};
+use wallet_controller::{mnemonic, HandlesController, UtxoState, UtxoTypes, WalletHandlesClient};
+
+/// How often the wallet is synced with the node when no wallet is open or after an error
+const WALLET_SYNC_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(thiserror::Error, Debug, Clone)]
+pub enum BackendError {
+    #[error("No wallet is open")]
+    NoWallet,
+    #[error("A wallet is already open")]
+    WalletAlreadyOpen,
+    #[error("Invalid mnemonic: {0}")]
+    InvalidMnemonic(String),
+    #[error("Invalid address: {0}")]
+    InvalidAddress(String),
+    #[error("Invalid amount: {0}")]
+    InvalidAmount(String),
+    #[error("Node connection error: {0}")]
+    NodeConnection(String),
+    #[error("{0}")]
+    Wallet(String),
+}
+
+/// The state of a wallet account shown in the GUI
+#[derive(Debug, Clone)]
+pub struct AccountSummary {
+    pub account_index: U31,
+    pub name: Option<String>,
+    pub balance: Amount,
+    pub utxos: Vec<(OutPoint, TxOutput)>,
+}
+
+/// The state of the open wallet shown in the GUI
+#[derive(Debug, Clone)]
+pub struct WalletSummary {
+    pub file_path: PathBuf,
+    pub best_block: (Id<GenBlock>, BlockHeight),
+    pub accounts: Vec<AccountSummary>,
+    pub staking: bool,
+}
+
+struct OpenWallet {
+    file_path: PathBuf,
+    controller: HandlesController,
+}
+
+impl OpenWallet {
+    fn summary(&self) -> Result<WalletSummary, BackendError> {
+        let accounts = self
+            .controller
+            .account_names()
+            .map(|(account_index, name)| {
+                let (balance, _) = self
+                    .controller
+                    .get_balance(*account_index, UtxoState::Confirmed.into())
+                    .map_err(|e| BackendError::Wallet(e.to_string()))?;
+                let utxos = self
+                    .controller
+                    .get_utxos(*account_index, UtxoTypes::ALL, UtxoState::Confirmed.into())
+                    .map_err(|e| BackendError::Wallet(e.to_string()))?
+                    .into_iter()
+                    .collect();
+                Ok(AccountSummary {
+                    account_index: *account_index,
+                    name: name.clone(),
+                    balance,
+                    utxos,
+                })
+            })
+            .collect::<Result<Vec<_>, BackendError>>()?;
+
+        Ok(WalletSummary {
+            file_path: self.file_path.clone(),
+            best_block: self
+                .controller
+                .best_block()
+                .map_err(|e| BackendError::Wallet(e.to_string()))?,
+            accounts,
+            staking: self.controller.is_staking(),
+        })
+    }
+}
 
 #[derive(Clone)]
 pub struct NodeBackendController {
     chain_config: Arc<ChainConfig>,
     controller: NodeController,
     manager_join_handle: Option<Arc<tokio::sync::Mutex<tokio::task::JoinHandle<()>>>>,
+    wallet: Arc<Mutex<Option<OpenWallet>>>,
+    wallet_sync_task: Arc<tokio::task::JoinHandle<()>>,
 }
 
 impl Debug for NodeBackendController {
@@ -58,10 +147,15 @@ impl NodeBackendController {
             .await
             .expect("Chain config retrieval failed after node initialization");
 
+        let wallet = Arc::new(Mutex::new(None));
+        let wallet_sync_task = tokio::spawn(Self::run_wallet_sync(Arc::clone(&wallet)));
+
         let node_controller = NodeBackendController {
             chain_config,
             controller,
             manager_join_handle: Some(Arc::new(manager_join_handle.into())),
+            wallet,
+            wallet_sync_task: Arc::new(wallet_sync_task),
         };
 
         Ok(node_controller)
@@ -78,6 +172,7 @@ impl NodeBackendController {
         }
         logging::log::info!("Starting shutdown process...");
 
+        self.wallet_sync_task.abort();
         self.controller.shutdown_trigger.clone().initiate();
 
         self.manager_join_handle.take()
@@ -90,6 +185,190 @@ impl NodeBackendController {
     pub fn node(&self) -> &NodeController {
         &self.controller
     }
+
+    /// Sync the open wallet with the node and stake new blocks if staking is started
+    async fn run_wallet_sync(wallet: Arc<Mutex<Option<OpenWallet>>>) {
+        loop {
+            // `run_once` scans at most one block, the wallet is unlocked between the calls
+            // so that the GUI requests don't wait for the whole sync
+            let delay = match wallet.lock().await.as_mut() {
+                Some(open_wallet) => open_wallet.controller.run_once().await,
+                None => WALLET_SYNC_DELAY,
+            };
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    async fn make_wallet_controller(
+        &self,
+        file_path: PathBuf,
+        wallet: wallet::DefaultWallet,
+    ) -> Result<OpenWallet, BackendError> {
+        let node = &self.controller;
+        let client = WalletHandlesClient::new(
+            node.chainstate.clone(),
+            node.mempool.clone(),
+            node.block_prod.clone(),
+            node.p2p.clone(),
+        )
+        .await
+        .map_err(|e| BackendError::NodeConnection(e.to_string()))?;
+
+        Ok(OpenWallet {
+            file_path,
+            controller: HandlesController::new(Arc::clone(&self.chain_config), client, wallet),
+        })
+    }
+
+    /// Create a new wallet file from `mnemonic`, or from a new mnemonic if it's not specified.
+    /// Returns the mnemonic, which must be written down to be able to restore the wallet.
+    pub async fn create_wallet(
+        self,
+        file_path: PathBuf,
+        mnemonic: Option<String>,
+    ) -> Result<(String, WalletSummary), BackendError> {
+        let mut wallet_opt = self.wallet.lock().await;
+        utils::ensure!(wallet_opt.is_none(), BackendError::WalletAlreadyOpen);
+
+        // TODO: Support other languages
+        let language = mnemonic::Language::English;
+        let mnemonic = match mnemonic {
+            Some(mnemonic) => mnemonic::parse_mnemonic(language, &mnemonic)
+                .map_err(|e| BackendError::InvalidMnemonic(e.to_string()))?,
+            None => mnemonic::generate_new_mnemonic(language),
+        };
+
+        let wallet = HandlesController::create_wallet(
+            Arc::clone(&self.chain_config),
+            &file_path,
+            mnemonic.clone(),
+            None,
+        )
+        .map_err(|e| BackendError::Wallet(e.to_string()))?;
+        let open_wallet = self.make_wallet_controller(file_path, wallet).await?;
+        let summary = open_wallet.summary()?;
+        *wallet_opt = Some(open_wallet);
+
+        Ok((mnemonic.to_string(), summary))
+    }
+
+    pub async fn open_wallet(self, file_path: PathBuf) -> Result<WalletSummary, BackendError> {
+        let mut wallet_opt = self.wallet.lock().await;
+        utils::ensure!(wallet_opt.is_none(), BackendError::WalletAlreadyOpen);
+
+        let wallet = HandlesController::open_wallet(Arc::clone(&self.chain_config), &file_path)
+            .map_err(|e| BackendError::Wallet(e.to_string()))?;
+        let open_wallet = self.make_wallet_controller(file_path, wallet).await?;
+        let summary = open_wallet.summary()?;
+        *wallet_opt = Some(open_wallet);
+
+        Ok(summary)
+    }
+
+    pub async fn close_wallet(self) -> Result<(), BackendError> {
+        self.wallet.lock().await.take().ok_or(BackendError::NoWallet)?;
+        Ok(())
+    }
+
+    /// Call `f` with the open wallet and return the updated wallet state
+    async fn with_wallet<T>(
+        &self,
+        f: impl FnOnce(&mut HandlesController) -> Result<T, BackendError>,
+    ) -> Result<(T, WalletSummary), BackendError> {
+        let mut wallet_opt = self.wallet.lock().await;
+        let open_wallet = wallet_opt.as_mut().ok_or(BackendError::NoWallet)?;
+        let value = f(&mut open_wallet.controller)?;
+        Ok((value, open_wallet.summary()?))
+    }
+
+    pub async fn wallet_summary(self) -> Result<WalletSummary, BackendError> {
+        let (_, summary) = self.with_wallet(|_| Ok(())).await?;
+        Ok(summary)
+    }
+
+    /// Encrypt the private keys of the wallet with `password`, or remove the encryption if it's `None`
+    pub async fn encrypt_wallet(
+        self,
+        password: Option<String>,
+    ) -> Result<WalletSummary, BackendError> {
+        let (_, summary) = self
+            .with_wallet(|controller| {
+                controller
+                    .encrypt_wallet(&password)
+                    .map_err(|e| BackendError::Wallet(e.to_string()))
+            })
+            .await?;
+        Ok(summary)
+    }
+
+    pub async fn unlock_wallet(self, password: String) -> Result<WalletSummary, BackendError> {
+        let (_, summary) = self
+            .with_wallet(|controller| {
+                controller
+                    .unlock_wallet(&password)
+                    .map_err(|e| BackendError::Wallet(e.to_string()))
+            })
+            .await?;
+        Ok(summary)
+    }
+
+    pub async fn lock_wallet(self) -> Result<WalletSummary, BackendError> {
+        let (_, summary) = self
+            .with_wallet(|controller| {
+                controller.lock_wallet().map_err(|e| BackendError::Wallet(e.to_string()))
+            })
+            .await?;
+        Ok(summary)
+    }
+
+    pub async fn new_address(
+        self,
+        account_index: U31,
+    ) -> Result<(Address, WalletSummary), BackendError> {
+        self.with_wallet(|controller| {
+            controller
+                .new_address(account_index)
+                .map_err(|e| BackendError::Wallet(e.to_string()))
+        })
+        .await
+    }
+
+    /// Send `amount` coins to `address`, both as entered by the user
+    pub async fn send_to_address(
+        self,
+        account_index: U31,
+        address: String,
+        amount: String,
+    ) -> Result<WalletSummary, BackendError> {
+        let address = Address::from_str(&self.chain_config, address.trim())
+            .map_err(|_| BackendError::InvalidAddress(address))?;
+        let amount = Amount::from_fixedpoint_str(&amount, self.chain_config.coin_decimals())
+            .ok_or(BackendError::InvalidAmount(amount))?;
+
+        let mut wallet_opt = self.wallet.lock().await;
+        let open_wallet = wallet_opt.as_mut().ok_or(BackendError::NoWallet)?;
+        open_wallet
+            .controller
+            .send_to_address(account_index, address, amount, None)
+            .await
+            .map_err(|e| BackendError::Wallet(e.to_string()))?;
+        open_wallet.summary()
+    }
+
+    /// Start or stop staking with the open wallet
+    pub async fn set_staking(self, staking: bool) -> Result<WalletSummary, BackendError> {
+        let (_, summary) = self
+            .with_wallet(|controller| {
+                if staking {
+                    controller.start_staking()
+                } else {
+                    controller.stop_staking()
+                }
+                .map_err(|e| BackendError::Wallet(e.to_string()))
+            })
+            .await?;
+        Ok(summary)
+    }
 }
 
 // TODO: tests, especially startup and shutdown
@@ -15,6 +15,7 @@
 
 mod backend_controller;
 mod main_window;
+mod widgets;
 
 use std::ops::DerefMut;
 
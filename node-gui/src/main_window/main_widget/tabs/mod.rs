@@ -25,10 +25,12 @@ use crate::backend_controller::NodeBackendController;
 use self::{
     settings::{SettingsMessage, SettingsTab, TabBarPosition},
     summary::{SummaryMessage, SummaryTab},
+    wallet::{WalletMessage, WalletTab},
 };
 
 pub mod settings;
 pub mod summary;
+pub mod wallet;
 
 const TAB_PADDING: u16 = 16;
 
@@ -56,12 +58,14 @@ pub enum TabsMessage {
     Start,
     TabSelected(usize),
     Summary(SummaryMessage),
+    Wallet(WalletMessage),
     Settings(SettingsMessage),
 }
 
 pub struct TabsWidget {
     active_tab: usize,
     summary_tab: SummaryTab,
+    wallet_tab: WalletTab,
     settings_tab: SettingsTab,
 }
 
@@ -69,7 +73,8 @@ impl TabsWidget {
     pub fn new(backend_controller: NodeBackendController) -> Self {
         TabsWidget {
             active_tab: 0,
-            summary_tab: SummaryTab::new(backend_controller),
+            summary_tab: SummaryTab::new(backend_controller.clone()),
+            wallet_tab: WalletTab::new(backend_controller),
             settings_tab: SettingsTab::new(),
         }
     }
@@ -82,6 +87,7 @@ impl TabsWidget {
 
         Tabs::new(self.active_tab, TabsMessage::TabSelected)
             .push(self.summary_tab.tab_label(), self.summary_tab.view())
+            .push(self.wallet_tab.tab_label(), self.wallet_tab.view())
             .push(self.settings_tab.tab_label(), self.settings_tab.view())
             .icon_font(ICON_FONT)
             .tab_bar_position(match position {
@@ -94,6 +100,7 @@ impl TabsWidget {
     pub fn start() -> impl IntoIterator<Item = Command<TabsMessage>> {
         [
             iced::Command::perform(async {}, |_| TabsMessage::Summary(SummaryMessage::Start)),
+            iced::Command::perform(async {}, |_| TabsMessage::Wallet(WalletMessage::Start)),
             iced::Command::perform(async {}, |_| TabsMessage::Settings(SettingsMessage::Start)),
         ]
     }
@@ -108,6 +115,9 @@ impl TabsWidget {
             TabsMessage::Summary(message) => {
                 self.summary_tab.update(message).map(TabsMessage::Summary)
             }
+            TabsMessage::Wallet(message) => {
+                self.wallet_tab.update(message).map(TabsMessage::Wallet)
+            }
             TabsMessage::Settings(message) => {
                 self.settings_tab.update(message).map(TabsMessage::Settings)
             }
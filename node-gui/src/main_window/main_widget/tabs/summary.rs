@@ -120,6 +120,7 @@ impl SummaryTab {
                                     .send((block_id.into(), block_height))
                                     .log_err_pfx("Chainstate subscriber failed to send new tip");
                             }
+                            ChainstateEvent::Reorg(_) => {}
                         },
                     );
 
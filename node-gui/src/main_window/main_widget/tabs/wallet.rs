@@ -0,0 +1,455 @@
+// Copyright (c) 2021-2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{path::PathBuf, time::Duration};
+
+use common::{
+    address::Address,
+    chain::{tokens::OutputValue, OutPoint, OutPointSourceId, TxOutput},
+    primitives::Amount,
+};
+use crypto::key::hdkd::u31::U31;
+use iced::{
+    widget::{button, text_input, Button, Column, Radio, Row, Scrollable, Text},
+    Command, Element,
+};
+use iced_aw::{tab_bar::TabLabel, Card, Grid, Modal};
+use wallet_controller::DEFAULT_ACCOUNT_INDEX;
+
+use crate::{
+    backend_controller::{BackendError, NodeBackendController, WalletSummary},
+    widgets::qr_code::qr_code_image,
+};
+
+use super::{Tab, TabsMessage};
+
+/// How often the balances and the UTXOs of the open wallet are updated
+const REFRESH_DELAY: Duration = Duration::from_secs(5);
+
+const QR_CODE_SIZE: f32 = 200.0;
+
+#[derive(Debug, Clone)]
+pub enum WalletMessage {
+    Start,
+    WalletPathChanged(String),
+    MnemonicChanged(String),
+    PasswordChanged(String),
+    SendAddressChanged(String),
+    SendAmountChanged(String),
+    AccountSelected(U31),
+    CreateWallet,
+    OpenWallet,
+    CloseWallet,
+    EncryptWallet,
+    UnlockWallet,
+    LockWallet,
+    NewAddress,
+    Send,
+    SetStaking(bool),
+    Refresh,
+    WalletCreated(Result<(String, WalletSummary), BackendError>),
+    WalletUpdated(Result<WalletSummary, BackendError>),
+    WalletRefreshed(Result<WalletSummary, BackendError>),
+    AddressCreated(Result<(Address, WalletSummary), BackendError>),
+    WalletClosed(Result<(), BackendError>),
+    CloseDialog,
+}
+
+/// A message shown in a dialog over the tab
+struct Dialog {
+    title: String,
+    text: String,
+}
+
+pub struct WalletTab {
+    controller: NodeBackendController,
+    wallet_path: String,
+    mnemonic: String,
+    password: String,
+    send_address: String,
+    send_amount: String,
+    selected_account: U31,
+    summary: Option<WalletSummary>,
+    receive_address: Option<Address>,
+    refreshing: bool,
+    dialog: Option<Dialog>,
+}
+
+impl WalletTab {
+    pub fn new(controller: NodeBackendController) -> Self {
+        WalletTab {
+            controller,
+            wallet_path: String::new(),
+            mnemonic: String::new(),
+            password: String::new(),
+            send_address: String::new(),
+            send_amount: String::new(),
+            selected_account: DEFAULT_ACCOUNT_INDEX,
+            summary: None,
+            receive_address: None,
+            refreshing: false,
+            dialog: None,
+        }
+    }
+
+    pub fn start() -> impl IntoIterator<Item = Command<WalletMessage>> {
+        []
+    }
+
+    fn show_error(&mut self, error: BackendError) {
+        self.dialog = Some(Dialog {
+            title: "Error".to_owned(),
+            text: error.to_string(),
+        });
+    }
+
+    /// Show the new state of the wallet and keep it updated while the wallet is open
+    fn set_summary(&mut self, summary: WalletSummary) -> Command<WalletMessage> {
+        if !summary
+            .accounts
+            .iter()
+            .any(|account| account.account_index == self.selected_account)
+        {
+            self.selected_account = DEFAULT_ACCOUNT_INDEX;
+        }
+        self.summary = Some(summary);
+
+        if self.refreshing {
+            return Command::none();
+        }
+        self.refreshing = true;
+        Self::schedule_refresh()
+    }
+
+    fn schedule_refresh() -> Command<WalletMessage> {
+        Command::perform(tokio::time::sleep(REFRESH_DELAY), |_| {
+            WalletMessage::Refresh
+        })
+    }
+
+    pub fn update(&mut self, message: WalletMessage) -> Command<WalletMessage> {
+        let controller = self.controller.clone();
+        match message {
+            WalletMessage::Start => iced::Command::batch(Self::start()),
+            WalletMessage::WalletPathChanged(value) => {
+                self.wallet_path = value;
+                Command::none()
+            }
+            WalletMessage::MnemonicChanged(value) => {
+                self.mnemonic = value;
+                Command::none()
+            }
+            WalletMessage::PasswordChanged(value) => {
+                self.password = value;
+                Command::none()
+            }
+            WalletMessage::SendAddressChanged(value) => {
+                self.send_address = value;
+                Command::none()
+            }
+            WalletMessage::SendAmountChanged(value) => {
+                self.send_amount = value;
+                Command::none()
+            }
+            WalletMessage::AccountSelected(account_index) => {
+                self.selected_account = account_index;
+                self.receive_address = None;
+                Command::none()
+            }
+            WalletMessage::CreateWallet => {
+                let mnemonic = Some(self.mnemonic.trim().to_owned()).filter(|m| !m.is_empty());
+                Command::perform(
+                    controller.create_wallet(PathBuf::from(&self.wallet_path), mnemonic),
+                    WalletMessage::WalletCreated,
+                )
+            }
+            WalletMessage::OpenWallet => Command::perform(
+                controller.open_wallet(PathBuf::from(&self.wallet_path)),
+                WalletMessage::WalletUpdated,
+            ),
+            WalletMessage::CloseWallet => {
+                Command::perform(controller.close_wallet(), WalletMessage::WalletClosed)
+            }
+            WalletMessage::EncryptWallet => {
+                let password = std::mem::take(&mut self.password);
+                let password = Some(password).filter(|password| !password.is_empty());
+                Command::perform(
+                    controller.encrypt_wallet(password),
+                    WalletMessage::WalletUpdated,
+                )
+            }
+            WalletMessage::UnlockWallet => {
+                let password = std::mem::take(&mut self.password);
+                Command::perform(
+                    controller.unlock_wallet(password),
+                    WalletMessage::WalletUpdated,
+                )
+            }
+            WalletMessage::LockWallet => {
+                Command::perform(controller.lock_wallet(), WalletMessage::WalletUpdated)
+            }
+            WalletMessage::NewAddress => Command::perform(
+                controller.new_address(self.selected_account),
+                WalletMessage::AddressCreated,
+            ),
+            WalletMessage::Send => Command::perform(
+                controller.send_to_address(
+                    self.selected_account,
+                    self.send_address.clone(),
+                    self.send_amount.clone(),
+                ),
+                WalletMessage::WalletUpdated,
+            ),
+            WalletMessage::SetStaking(staking) => Command::perform(
+                controller.set_staking(staking),
+                WalletMessage::WalletUpdated,
+            ),
+            WalletMessage::Refresh => {
+                Command::perform(controller.wallet_summary(), WalletMessage::WalletRefreshed)
+            }
+            WalletMessage::WalletCreated(Ok((mnemonic, summary))) => {
+                self.mnemonic.clear();
+                self.dialog = Some(Dialog {
+                    title: "New wallet created".to_owned(),
+                    text: format!(
+                        "Your mnemonic: {mnemonic}\nPlease write it somewhere safe to be able to restore your wallet."
+                    ),
+                });
+                self.set_summary(summary)
+            }
+            WalletMessage::WalletUpdated(Ok(summary)) => self.set_summary(summary),
+            WalletMessage::WalletRefreshed(Ok(summary)) => {
+                self.summary = Some(summary);
+                Self::schedule_refresh()
+            }
+            WalletMessage::AddressCreated(Ok((address, summary))) => {
+                self.receive_address = Some(address);
+                self.set_summary(summary)
+            }
+            WalletMessage::WalletClosed(Ok(())) => {
+                self.summary = None;
+                self.receive_address = None;
+                Command::none()
+            }
+            WalletMessage::WalletRefreshed(Err(_)) => {
+                // The wallet is closed, the updates are started again when another one is open
+                self.refreshing = false;
+                Command::none()
+            }
+            WalletMessage::WalletCreated(Err(e))
+            | WalletMessage::WalletUpdated(Err(e))
+            | WalletMessage::AddressCreated(Err(e))
+            | WalletMessage::WalletClosed(Err(e)) => {
+                self.show_error(e);
+                Command::none()
+            }
+            WalletMessage::CloseDialog => {
+                self.dialog = None;
+                Command::none()
+            }
+        }
+    }
+
+    fn no_wallet_view(&self) -> Element<'_, WalletMessage> {
+        Column::new()
+            .spacing(10)
+            .max_width(600)
+            .push(text_input(
+                "Wallet file path",
+                &self.wallet_path,
+                WalletMessage::WalletPathChanged,
+            ))
+            .push(text_input(
+                "Mnemonic to restore, a new one is generated if empty",
+                &self.mnemonic,
+                WalletMessage::MnemonicChanged,
+            ))
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .push(button("Create wallet").on_press(WalletMessage::CreateWallet))
+                    .push(button("Open wallet").on_press(WalletMessage::OpenWallet)),
+            )
+            .into()
+    }
+
+    fn wallet_view<'a>(&'a self, summary: &'a WalletSummary) -> Element<'a, WalletMessage> {
+        let chain_config = self.controller.chain_config();
+        let print_amount =
+            |amount: Amount| amount.into_fixedpoint_str(chain_config.coin_decimals());
+
+        let (_, best_block_height) = summary.best_block;
+        let info = Grid::with_columns(2)
+            .push(Text::new("Wallet file "))
+            .push(Text::new(summary.file_path.display().to_string()))
+            .push(Text::new("Synced to height "))
+            .push(Text::new(best_block_height.to_string()))
+            .push(Text::new("Staking "))
+            .push(Text::new(if summary.staking {
+                "started"
+            } else {
+                "stopped"
+            }));
+
+        let encryption = Row::new()
+            .spacing(10)
+            .push(
+                text_input("Password", &self.password, WalletMessage::PasswordChanged)
+                    .password()
+                    .width(200),
+            )
+            .push(button("Encrypt").on_press(WalletMessage::EncryptWallet))
+            .push(button("Unlock").on_press(WalletMessage::UnlockWallet))
+            .push(button("Lock").on_press(WalletMessage::LockWallet))
+            .push(button("Close wallet").on_press(WalletMessage::CloseWallet));
+
+        let accounts = summary.accounts.iter().fold(
+            Column::new().spacing(5).push(Text::new("Accounts").size(20)),
+            |column, account| {
+                let label = format!(
+                    "Account {} {}: {}",
+                    account.account_index,
+                    account.name.as_deref().unwrap_or_default(),
+                    print_amount(account.balance)
+                );
+                column.push(
+                    Radio::new(
+                        account.account_index,
+                        label,
+                        Some(self.selected_account),
+                        WalletMessage::AccountSelected,
+                    )
+                    .size(16),
+                )
+            },
+        );
+
+        let staking_button: Button<'_, WalletMessage> = if summary.staking {
+            button("Stop staking").on_press(WalletMessage::SetStaking(false))
+        } else {
+            button("Start staking").on_press(WalletMessage::SetStaking(true))
+        };
+
+        let mut receive = Column::new()
+            .spacing(5)
+            .push(Text::new("Receive").size(20))
+            .push(button("New address").on_press(WalletMessage::NewAddress));
+        if let Some(address) = &self.receive_address {
+            receive = receive.push(Text::new(address.get()));
+            if let Ok(qr_code) = address.payment_request_qrcode(chain_config, None) {
+                receive = receive.push(qr_code_image(&qr_code, QR_CODE_SIZE));
+            }
+        }
+
+        let send = Column::new().spacing(5).push(Text::new("Send").size(20)).push(
+            Row::new()
+                .spacing(10)
+                .push(text_input(
+                    "Address",
+                    &self.send_address,
+                    WalletMessage::SendAddressChanged,
+                ))
+                .push(
+                    text_input(
+                        "Amount",
+                        &self.send_amount,
+                        WalletMessage::SendAmountChanged,
+                    )
+                    .width(150),
+                )
+                .push(button("Send").on_press(WalletMessage::Send)),
+        );
+
+        let utxos = summary
+            .accounts
+            .iter()
+            .filter(|account| account.account_index == self.selected_account)
+            .flat_map(|account| account.utxos.iter())
+            .fold(Grid::with_columns(2), |grid, (outpoint, output)| {
+                grid.push(Text::new(print_outpoint(outpoint)))
+                    .push(Text::new(print_output_value(output, &print_amount)))
+            });
+        let utxos = Column::new().spacing(5).push(Text::new("UTXOs").size(20)).push(utxos);
+
+        Column::new()
+            .spacing(15)
+            .push(info)
+            .push(encryption)
+            .push(accounts)
+            .push(staking_button)
+            .push(receive)
+            .push(send)
+            .push(utxos)
+            .into()
+    }
+}
+
+fn print_outpoint(outpoint: &OutPoint) -> String {
+    match outpoint.tx_id() {
+        OutPointSourceId::Transaction(id) => format!("tx:{id}:{}", outpoint.output_index()),
+        OutPointSourceId::BlockReward(id) => format!("block:{id}:{}", outpoint.output_index()),
+    }
+}
+
+fn print_output_value(output: &TxOutput, print_amount: &impl Fn(Amount) -> String) -> String {
+    match output {
+        TxOutput::Transfer(value, _) | TxOutput::LockThenTransfer(value, _, _) => match value {
+            OutputValue::Coin(amount) => print_amount(*amount),
+            OutputValue::Token(_) => "Tokens".to_owned(),
+        },
+        TxOutput::Burn(_)
+        | TxOutput::CreateStakePool(_, _)
+        | TxOutput::ProduceBlockFromStake(_, _)
+        | TxOutput::CreateDelegationId(_, _)
+        | TxOutput::DelegateStaking(_, _) => "Other".to_owned(),
+    }
+}
+
+impl Tab for WalletTab {
+    type Message = TabsMessage;
+
+    fn title(&self) -> String {
+        String::from("Wallet")
+    }
+
+    fn tab_label(&self) -> TabLabel {
+        TabLabel::Text(self.title())
+    }
+
+    fn content(&self) -> Element<'_, Self::Message> {
+        let underlay = Scrollable::new(match &self.summary {
+            Some(summary) => self.wallet_view(summary),
+            None => self.no_wallet_view(),
+        });
+
+        let content: Element<'_, WalletMessage> =
+            Modal::new(self.dialog.is_some(), underlay, || {
+                let (title, text) = match &self.dialog {
+                    Some(dialog) => (dialog.title.clone(), dialog.text.clone()),
+                    None => (String::new(), String::new()),
+                };
+                Card::new(Text::new(title), Text::new(text))
+                    .foot(button("Ok").on_press(WalletMessage::CloseDialog))
+                    .max_width(500.0)
+                    .on_close(WalletMessage::CloseDialog)
+                    .into()
+            })
+            .backdrop(WalletMessage::CloseDialog)
+            .on_esc(WalletMessage::CloseDialog)
+            .into();
+
+        content.map(TabsMessage::Wallet)
+    }
+}
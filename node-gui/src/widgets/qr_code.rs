@@ -0,0 +1,75 @@
+// Copyright (c) 2021-2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use iced::{
+    widget::canvas::{self, Canvas, Cursor, Frame, Geometry},
+    Color, Element, Length, Point, Rectangle, Size, Theme,
+};
+use utils::qrcode::QrCode;
+
+/// The number of empty modules around the QR code, required by the scanners to find it
+const QUIET_ZONE: usize = 2;
+
+/// A QR code drawn on a canvas, e.g. the payment request of a receive address
+pub struct QrCodeImage {
+    side_length: usize,
+    pixels: Vec<bool>,
+}
+
+impl QrCodeImage {
+    pub fn new(qr_code: &impl QrCode) -> Self {
+        Self {
+            side_length: qr_code.side_length(),
+            pixels: qr_code.as_vec(),
+        }
+    }
+}
+
+impl<Message> canvas::Program<Message> for QrCodeImage {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(bounds.size());
+        frame.fill_rectangle(Point::ORIGIN, bounds.size(), Color::WHITE);
+
+        let modules = (self.side_length + 2 * QUIET_ZONE) as f32;
+        let module_size = bounds.width.min(bounds.height) / modules;
+        for (index, _) in self.pixels.iter().enumerate().filter(|(_, filled)| **filled) {
+            let x = (index % self.side_length + QUIET_ZONE) as f32;
+            let y = (index / self.side_length + QUIET_ZONE) as f32;
+            frame.fill_rectangle(
+                Point::new(x * module_size, y * module_size),
+                Size::new(module_size, module_size),
+                Color::BLACK,
+            );
+        }
+
+        vec![frame.into_geometry()]
+    }
+}
+
+/// A square image of `qr_code` with the side of `size` pixels
+pub fn qr_code_image<'a, Message: 'a>(qr_code: &impl QrCode, size: f32) -> Element<'a, Message> {
+    Canvas::new(QrCodeImage::new(qr_code))
+        .width(Length::Fixed(size))
+        .height(Length::Fixed(size))
+        .into()
+}
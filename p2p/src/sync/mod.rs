@@ -244,6 +244,7 @@ pub async fn subscribe_to_new_tip(
                 chainstate::ChainstateEvent::NewTip(block_id, _) => {
                     let _ = sender.send(block_id).log_err_pfx("The new tip receiver closed");
                 }
+                chainstate::ChainstateEvent::Reorg(_) => {}
             },
         );
 
@@ -17,6 +17,7 @@ mod config;
 mod error;
 mod rpc_auth;
 pub mod rpc_creds;
+pub mod subscription;
 
 use std::net::SocketAddr;
 
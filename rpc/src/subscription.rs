@@ -0,0 +1,59 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Helpers for serving subscriptions over websocket
+
+use logging::log;
+use serde::Serialize;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+pub use jsonrpsee::{
+    core::SubscriptionResult,
+    server::{PendingSubscriptionSink, SubscriptionMessage},
+};
+
+/// Accept the subscription and forward the events received on the channel to the subscriber,
+/// converted to their RPC representation.
+///
+/// Returns once the subscriber goes away, falls too far behind or the channel is closed.
+/// The receiver is dropped then, so the subscriber is removed from the channel.
+pub async fn pipe_from_receiver<E: Clone, T: Serialize + From<E>>(
+    pending: PendingSubscriptionSink,
+    mut receiver: broadcast::Receiver<E>,
+) -> SubscriptionResult {
+    let sink = pending.accept().await?;
+
+    loop {
+        tokio::select! {
+            _ = sink.closed() => break,
+            event = receiver.recv() => {
+                let event = match event {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(skipped)) => {
+                        log::warn!("Closing the subscription that missed {skipped} events");
+                        break;
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+                let message = SubscriptionMessage::from_json(&T::from(event))?;
+                if sink.send(message).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
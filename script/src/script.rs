@@ -333,6 +333,15 @@ impl Script {
                 || opcodes::All::from(self.0[0]).classify() == opcodes::Class::IllegalOp)
     }
 
+    /// Checks whether the script consists of data and number pushes only
+    pub fn is_push_only(&self) -> bool {
+        self.instructions().all(|instr| match instr {
+            Ok(Instruction::PushBytes(_)) => true,
+            Ok(Instruction::Op(op)) => matches!(op.classify(), opcodes::Class::PushNum(_)),
+            Err(_) => false,
+        })
+    }
+
     /// Iterate over the script in the form of `Instruction`s, which are an enum covering
     /// opcodes, datapushes and errors. At most one error will be returned and then the
     /// iterator will end. To instead iterate over the script as sequence of bytes, treat
@@ -950,6 +959,26 @@ mod test {
         assert!(!hex_script!("").is_op_return());
     }
 
+    #[test]
+    fn push_only_test() {
+        assert!(hex_script!("").is_push_only());
+        assert!(Builder::new()
+            .push_int(0)
+            .push_int(16)
+            .push_int(-1)
+            .into_script()
+            .is_push_only());
+        assert!(Builder::new()
+            .push_slice(&[0xab; 80])
+            .push_int(1000)
+            .into_script()
+            .is_push_only());
+        // p2pkh
+        assert!(!hex_script!("76a91402306a7c23f3e8010de41e9e591348bb83f11daa88ac").is_push_only());
+        // truncated push
+        assert!(!hex_script!("4c").is_push_only());
+    }
+
     #[test]
     #[cfg(feature = "serde")]
     fn script_json_serialize() {
@@ -92,6 +92,17 @@ fn prefix_iteration() {
         assert_eq!(items, expected);
         dbtx.close();
 
+        // Iterate over the "foo" prefix keys
+        let dbtx = store.transaction_ro().unwrap();
+        let keys: Vec<_> = dbtx
+            .get::<Map2, _>()
+            .prefix_iter_keys(&("foo".into(),))
+            .unwrap()
+            .map(|(_, k)| k)
+            .collect();
+        assert_eq!(keys, vec![1, 2, 12]);
+        dbtx.close();
+
         // Iterate over all values
         let test_values_sorted = {
             let mut values = test_values;
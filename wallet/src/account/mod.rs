@@ -14,45 +14,156 @@
 // limitations under the License.
 
 mod output_cache;
+mod transaction_size;
+pub mod utxo_selector;
 
 use crate::key_chain::{
     make_path_to_vrf_key, vrf_from_private_key, AccountKeyChain, KeyChainError,
 };
-use crate::send_request::{make_address_output, make_stake_output};
+use crate::send_request::{
+    make_address_output, make_address_output_token, make_create_delegation_output,
+    make_issue_token_outputs, make_locked_address_output, make_stake_output,
+};
 use crate::{SendRequest, WalletError, WalletResult};
+use common::address::pubkeyhash::PublicKeyHash;
 use common::address::Address;
+use common::chain::block::timestamp::BlockTimestamp;
+use common::chain::classic_multisig::ClassicMultisigChallenge;
+use common::chain::signature::inputsig::arbitrary_message::ArbitraryMessageSignature;
+use common::chain::signature::inputsig::classical_multisig::authorize_classical_multisig::{
+    sign_classical_multisig_spending, AuthorizedClassicalMultisigSpend,
+};
 use common::chain::signature::inputsig::standard_signature::StandardInputSignature;
 use common::chain::signature::inputsig::InputWitness;
 use common::chain::signature::sighash::sighashtype::SigHashType;
-use common::chain::signature::TransactionSigError;
-use common::chain::tokens::{OutputValue, TokenData, TokenId};
+use common::chain::signature::sighash::signature_hash;
+use common::chain::timelock::OutputTimeLock;
+use common::chain::tokens::{
+    Metadata, NftIssuance, OutputValue, TokenData, TokenId, TokenIssuance,
+};
 use common::chain::{
-    Block, ChainConfig, Destination, GenBlock, OutPoint, SignedTransaction, TxInput, TxOutput,
+    Block, ChainConfig, DelegationId, Destination, GenBlock, OutPoint, OutPointSourceId, PoolId,
+    SignedTransaction, Transaction, TxInput, TxOutput,
 };
 use common::primitives::per_thousand::PerThousand;
-use common::primitives::{Amount, BlockHeight, Id};
+use common::primitives::signed_amount::SignedAmount;
+use common::primitives::{Amount, BlockHeight, FeeRate, Id, Idable, H256};
 use consensus::PoSGenerateBlockInputData;
+use crypto::key::extended::ExtendedPublicKey;
 use crypto::key::hdkd::u31::U31;
 use crypto::key::PublicKey;
 use crypto::vrf::{VRFPrivateKey, VRFPublicKey};
-use std::collections::BTreeMap;
+use pos_accounting::DelegationData;
+use serialization::Encode;
+use std::collections::{BTreeMap, BTreeSet};
 use std::ops::Add;
 use std::sync::Arc;
 use wallet_storage::{
     StoreTxRo, StoreTxRw, WalletStorageReadLocked, WalletStorageReadUnlocked,
     WalletStorageWriteLocked, WalletStorageWriteUnlocked,
 };
-use wallet_types::utxo_types::{get_utxo_type, UtxoType, UtxoTypes};
+use wallet_types::partially_signed_transaction::{
+    PartiallySignedTransaction, PartiallySignedTransactionError,
+};
+use wallet_types::tx_history::{TxHistoryEntry, TxHistoryFilter};
+use wallet_types::utxo_types::{
+    get_utxo_state, get_utxo_type, UtxoState, UtxoStates, UtxoType, UtxoTypes,
+};
 use wallet_types::wallet_tx::{BlockData, TxData, TxState};
-use wallet_types::{AccountId, AccountInfo, AccountWalletTxId, KeyPurpose, WalletTx};
+use wallet_types::{
+    AccountClassicMultisigId, AccountId, AccountInfo, AccountOutPointId, AccountWalletTxId,
+    KeyPurpose, WalletTx,
+};
 
 use self::output_cache::OutputCache;
+use self::utxo_selector::{select_coins, OutputGroup, UtxoSelectorError};
+
+/// What an output amount is denominated in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Currency {
+    Coin,
+    Token(TokenId),
+}
+
+/// The UTXOs that fund a transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FundsSource {
+    /// The UTXOs that the account can spend with its own keys
+    Account,
+    /// The UTXOs locked by a classic multisig watched by the account, the change goes back to it
+    ClassicMultisig(PublicKeyHash),
+}
+
+fn compute_fee(fee_rate: FeeRate, tx_size: usize) -> WalletResult<Amount> {
+    fee_rate.compute_fee(tx_size).ok_or(WalletError::FeeCalculationOverflow)
+}
+
+/// A change output used to estimate the transaction size before the change address is issued
+fn make_change_output(amount: Amount) -> TxOutput {
+    TxOutput::Transfer(
+        OutputValue::Coin(amount),
+        Destination::Address(PublicKeyHash::zero()),
+    )
+}
+
+/// The difference between the received and spent amounts
+fn signed_change(received: Amount, spent: Amount) -> WalletResult<SignedAmount> {
+    let received = received.into_signed().ok_or(WalletError::OutputAmountOverflow)?;
+    let spent = spent.into_signed().ok_or(WalletError::OutputAmountOverflow)?;
+    (received - spent).ok_or(WalletError::OutputAmountOverflow)
+}
+
+/// The block height or time after which an output locked with `lock` can be spent.
+///
+/// The relative locks are converted once the transaction that has created the output
+/// is confirmed, until then they are returned unchanged.
+fn unlock_point(lock: &OutputTimeLock, tx_state: &TxState) -> OutputTimeLock {
+    let absolute_lock = match (lock, tx_state) {
+        (OutputTimeLock::ForBlockCount(block_count), TxState::Confirmed(height, _)) => {
+            height.checked_add(*block_count).map(OutputTimeLock::UntilHeight)
+        }
+        (OutputTimeLock::ForSeconds(seconds), TxState::Confirmed(_, timestamp)) => {
+            timestamp.add_int_seconds(*seconds).map(OutputTimeLock::UntilTime)
+        }
+        (OutputTimeLock::UntilHeight(_) | OutputTimeLock::UntilTime(_), _)
+        | (
+            OutputTimeLock::ForBlockCount(_) | OutputTimeLock::ForSeconds(_),
+            TxState::InMempool
+            | TxState::Conflicted(_)
+            | TxState::Inactive
+            | TxState::Replaced(_)
+            | TxState::Abandoned,
+        ) => None,
+    };
+    absolute_lock.unwrap_or_else(|| lock.clone())
+}
+
+/// Check if an output with `unlock_point` can be spent in a block at `spend_height`
+/// with the median time past `median_time`, the same way the chainstate checks it
+fn is_unlocked(
+    unlock_point: &OutputTimeLock,
+    spend_height: BlockHeight,
+    median_time: BlockTimestamp,
+) -> bool {
+    match unlock_point {
+        OutputTimeLock::UntilHeight(height) => spend_height >= *height,
+        OutputTimeLock::UntilTime(time) => median_time >= *time,
+        // The output is not confirmed yet
+        OutputTimeLock::ForBlockCount(_) | OutputTimeLock::ForSeconds(_) => false,
+    }
+}
 
 pub struct Account {
     chain_config: Arc<ChainConfig>,
     key_chain: AccountKeyChain,
     output_cache: OutputCache,
     account_info: AccountInfo,
+    /// The name given to the account by the user, if any
+    name: Option<String>,
+    /// The classic multisig challenges watched by this account, by their hash
+    classic_multisigs: BTreeMap<PublicKeyHash, ClassicMultisigChallenge>,
+    /// The UTXOs that are skipped by the automatic input selection
+    frozen_utxos: BTreeSet<OutPoint>,
 }
 
 impl Account {
@@ -71,11 +182,28 @@ impl Account {
         let txs = db_tx.get_transactions(&key_chain.get_account_id())?;
         let output_cache = OutputCache::new(txs);
 
+        let name = db_tx.get_account_name(&key_chain.get_account_id())?;
+
+        let classic_multisigs = db_tx
+            .get_classic_multisig_challenges(&key_chain.get_account_id())?
+            .into_iter()
+            .map(|(id, challenge)| (id.into_item_id(), challenge))
+            .collect();
+
+        let frozen_utxos = db_tx
+            .get_frozen_utxos(&key_chain.get_account_id())?
+            .into_iter()
+            .map(AccountOutPointId::into_item_id)
+            .collect();
+
         Ok(Account {
             chain_config,
             key_chain,
             output_cache,
             account_info,
+            name,
+            classic_multisigs,
+            frozen_utxos,
         })
     }
 
@@ -84,6 +212,7 @@ impl Account {
         chain_config: Arc<ChainConfig>,
         db_tx: &mut impl WalletStorageWriteLocked,
         key_chain: AccountKeyChain,
+        name: Option<String>,
     ) -> WalletResult<Account> {
         let account_id = key_chain.get_account_id();
 
@@ -95,6 +224,9 @@ impl Account {
         );
 
         db_tx.set_account(&account_id, &account_info)?;
+        if let Some(name) = &name {
+            db_tx.set_account_name(&account_id, name)?;
+        }
 
         let output_cache = OutputCache::empty();
 
@@ -103,6 +235,9 @@ impl Account {
             key_chain,
             output_cache,
             account_info,
+            name,
+            classic_multisigs: BTreeMap::new(),
+            frozen_utxos: BTreeSet::new(),
         };
 
         account.scan_genesis(db_tx)?;
@@ -110,55 +245,403 @@ impl Account {
         Ok(account)
     }
 
+    /// Fill in the inputs (unless the request already has them) and the change outputs
+    /// so that the inputs cover the outputs and the fee at `fee_rate`, then sign the transaction.
+    ///
+    /// The time-locked UTXOs are selected if they can be spent in the next block,
+    /// whose median time past is `median_time`.
     pub fn process_send_request(
         &mut self,
         db_tx: &mut impl WalletStorageWriteUnlocked,
-        mut request: SendRequest,
+        request: SendRequest,
+        median_time: BlockTimestamp,
+        fee_rate: FeeRate,
     ) -> WalletResult<SignedTransaction> {
-        if request.utxos().is_empty() {
-            // TODO: Add support for LockThenTransfer
-            let utxos = self
-                .get_utxos(UtxoType::Transfer.into())
-                .into_iter()
-                .map(|(outpoint, txo)| (outpoint, txo.clone()));
+        let request = self.complete_send_request(
+            db_tx,
+            request,
+            FundsSource::Account,
+            median_time,
+            fee_rate,
+        )?;
+        self.sign_transaction(request, db_tx)
+    }
 
-            // TODO: Call coin selector
+    /// Same as `process_send_request`, but the transaction is not signed,
+    /// so that it can be signed later, possibly by another wallet
+    pub fn create_partially_signed_transaction(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        request: SendRequest,
+        median_time: BlockTimestamp,
+        fee_rate: FeeRate,
+    ) -> WalletResult<PartiallySignedTransaction> {
+        let request = self.complete_send_request(
+            db_tx,
+            request,
+            FundsSource::Account,
+            median_time,
+            fee_rate,
+        )?;
+        self.make_partially_signed_transaction(request)
+    }
 
-            request = request.with_inputs(utxos);
+    /// Create a transaction that spends the same inputs as the unconfirmed transaction `tx_id`
+    /// and pays a fee at `fee_rate`, so that it can replace it in the mempool.
+    ///
+    /// The fee increase is taken from the change output, which is removed if nothing is left.
+    /// It has to pay for the relay of the replacement at `relay_fee_rate`, the fee rate
+    /// of the node. The unconfirmed descendants of `tx_id` are replaced too,
+    /// so their fees are paid on top.
+    pub fn create_fee_bump_transaction(
+        &self,
+        db_tx: &impl WalletStorageReadUnlocked,
+        tx_id: Id<Transaction>,
+        fee_rate: FeeRate,
+        relay_fee_rate: FeeRate,
+    ) -> WalletResult<SignedTransaction> {
+        let wallet_tx_id = AccountWalletTxId::new(self.get_account_id(), tx_id.into());
+        let tx_data = match self.output_cache.txs().get(&wallet_tx_id) {
+            Some(WalletTx::Tx(tx_data)) => tx_data,
+            Some(WalletTx::Block(_)) | None => return Err(WalletError::NoTransactionFound(tx_id)),
+        };
+        match tx_data.state() {
+            TxState::InMempool | TxState::Inactive => {}
+            TxState::Confirmed(_, _)
+            | TxState::Conflicted(_)
+            | TxState::Replaced(_)
+            | TxState::Abandoned => return Err(WalletError::TransactionNotPending(tx_id)),
         }
+        let tx = tx_data.get_transaction();
+        utils::ensure!(
+            tx.is_replaceable(),
+            WalletError::TransactionNotReplaceable(tx_id)
+        );
 
-        let (input_coin_amount, input_tokens_amounts) =
-            Self::calculate_output_amounts(request.utxos().iter())?;
-
-        let (output_coin_amount, output_tokens_amounts) =
-            Self::calculate_output_amounts(request.outputs().iter())?;
+        let input_utxos = tx
+            .inputs()
+            .iter()
+            .map(|input| {
+                self.find_output(input.outpoint())
+                    .filter(|utxo| self.is_mine_or_watched(utxo))
+                    .cloned()
+                    .ok_or(WalletError::ForeignTransactionInputs(tx_id))
+            })
+            .collect::<WalletResult<Vec<_>>>()?;
 
-        // TODO: Implement tokens sending
+        let (change_index, change_amount, change_destination) = tx
+            .outputs()
+            .iter()
+            .enumerate()
+            .find_map(|(index, output)| match output {
+                TxOutput::Transfer(OutputValue::Coin(amount), destination)
+                    if self.is_change_destination(destination, &input_utxos) =>
+                {
+                    Some((index, *amount, destination.clone()))
+                }
+                _ => None,
+            })
+            .ok_or(WalletError::NoChangeOutput(tx_id))?;
+
+        let request = SendRequest::from_transaction(tx.clone(), input_utxos.clone());
+        let (input_coin_amount, _) = self.calculate_input_amounts(&request)?;
+        let (output_coin_amount, _) = Self::calculate_output_amounts(tx.outputs().iter())?;
+        let tx_fee =
+            (input_coin_amount - output_coin_amount).ok_or(WalletError::OutputAmountOverflow)?;
+        let current_fee = self
+            .output_cache
+            .find_unconfirmed_descendants(&wallet_tx_id)
+            .iter()
+            .filter_map(
+                |descendant_id| match self.output_cache.txs().get(descendant_id) {
+                    Some(WalletTx::Tx(tx_data)) => Some(tx_data.get_transaction()),
+                    Some(WalletTx::Block(_)) | None => None,
+                },
+            )
+            .try_fold(tx_fee, |total, descendant| {
+                let fee = self.unconfirmed_tx_fee(descendant)?;
+                (total + fee).ok_or(WalletError::OutputAmountOverflow)
+            })?;
+        let tx_size = self.estimate_signed_tx_size(&request)?;
+        let new_fee = compute_fee(fee_rate, tx_size)?;
+        let fee_increase = (new_fee - current_fee)
+            .filter(|fee_increase| *fee_increase > Amount::ZERO)
+            .ok_or(WalletError::FeeNotIncreased(current_fee, new_fee))?;
+        // The mempool only accepts a replacement whose additional fee pays for its own relay
+        let min_fee_increase = compute_fee(relay_fee_rate, tx_size)?;
         utils::ensure!(
-            input_tokens_amounts.is_empty() && output_tokens_amounts.is_empty(),
-            WalletError::NotImplemented("Token sending")
+            fee_increase >= min_fee_increase,
+            WalletError::InsufficientFeeIncrease(fee_increase, min_fee_increase)
         );
+        let new_change_amount = (change_amount - fee_increase)
+            .ok_or(WalletError::NotEnoughUtxo(change_amount, fee_increase))?;
+
+        let mut outputs = tx.outputs().to_vec();
+        if new_change_amount > Amount::ZERO {
+            outputs[change_index] =
+                TxOutput::Transfer(OutputValue::Coin(new_change_amount), change_destination);
+        } else {
+            outputs.remove(change_index);
+        }
+        let new_tx = Transaction::new(tx.flags(), tx.inputs().to_vec(), outputs)?;
+
+        self.sign_transaction(SendRequest::from_transaction(new_tx, input_utxos), db_tx)
+    }
+
+    /// The fee of an unconfirmed transaction, all of its inputs must be known to the account
+    fn unconfirmed_tx_fee(&self, tx: &Transaction) -> WalletResult<Amount> {
+        let input_utxos = tx
+            .inputs()
+            .iter()
+            .map(|input| {
+                let outpoint = input.outpoint();
+                self.find_output(outpoint)
+                    .map(|utxo| (outpoint.tx_id(), utxo))
+                    .ok_or(WalletError::UnknownDescendantFee(tx.get_id()))
+            })
+            .collect::<WalletResult<Vec<_>>>()?;
+        let (input_coins, _) = self.calculate_utxo_amounts(input_utxos.into_iter())?;
+        let (output_coins, _) = Self::calculate_output_amounts(tx.outputs().iter())?;
+        (input_coins - output_coins).ok_or(WalletError::OutputAmountOverflow)
+    }
+
+    fn complete_send_request(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        mut request: SendRequest,
+        source: FundsSource,
+        median_time: BlockTimestamp,
+        fee_rate: FeeRate,
+    ) -> WalletResult<SendRequest> {
+        let select_inputs = request.utxos().is_empty();
+
+        if select_inputs {
+            let utxos = self.select_token_inputs(&request, source, median_time)?;
+            request = request.with_inputs(utxos);
+        }
+
+        // The token change outputs are added before the coin inputs are selected,
+        // so that the selected coins pay for them too
+        request = self.add_token_change_outputs(db_tx, request, source)?;
+
+        if select_inputs {
+            let utxos =
+                self.select_inputs_for_send_request(&request, source, median_time, fee_rate)?;
+            request = request.with_inputs(utxos);
+        }
 
-        // TODO: Calculate network fee from fee rate and expected transaction size
-        let network_fee = Amount::from_atoms(10000);
+        let (input_coin_amount, _) = self.calculate_input_amounts(&request)?;
+        let (output_coin_amount, _) = Self::calculate_output_amounts(request.outputs().iter())?;
 
+        let network_fee = compute_fee(fee_rate, self.estimate_signed_tx_size(&request)?)?;
         let output_with_fee =
             (output_coin_amount + network_fee).ok_or(WalletError::OutputAmountOverflow)?;
 
-        let change_amount = (input_coin_amount - output_with_fee).ok_or(
+        let excess_amount = (input_coin_amount - output_with_fee).ok_or(
             WalletError::NotEnoughUtxo(input_coin_amount, output_with_fee),
         )?;
+
+        // The change output makes the transaction bigger, so the fee has to be recalculated.
+        // If what is left does not pay for the change output, it is added to the fee instead.
+        let request_with_change = request.clone().with_outputs([make_change_output(excess_amount)]);
+        let fee_with_change = compute_fee(
+            fee_rate,
+            self.estimate_signed_tx_size(&request_with_change)?,
+        )?;
+        let change_amount = (input_coin_amount - output_coin_amount)
+            .and_then(|amount| amount - fee_with_change)
+            .unwrap_or(Amount::ZERO);
+
         if change_amount > Amount::ZERO {
-            let change_address = self.get_new_address(db_tx, KeyPurpose::Change)?;
+            let change_address = self.get_change_address(db_tx, source)?;
             let change_output = make_address_output(change_address, change_amount)?;
             request = request.with_outputs([change_output]);
         }
 
         // TODO: Randomize inputs and outputs
 
-        let tx = self.sign_transaction(request, db_tx)?;
+        Ok(request)
+    }
+
+    /// Select the token UTXOs that cover the token outputs of `request`
+    fn select_token_inputs(
+        &self,
+        request: &SendRequest,
+        source: FundsSource,
+        median_time: BlockTimestamp,
+    ) -> WalletResult<Vec<(OutPoint, TxOutput)>> {
+        let (_, output_tokens_amounts) = Self::calculate_output_amounts(request.outputs().iter())?;
+        if output_tokens_amounts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let utxos = self.get_selectable_utxos(source, median_time);
+
+        let mut selected = Vec::new();
+        for (token_id, output_amount) in output_tokens_amounts {
+            let mut utxo_pool = Vec::new();
+            for (outpoint, txo) in &utxos {
+                let issued_token_id = self.output_cache.issued_token_id(&outpoint.tx_id());
+                if let Some((Currency::Token(utxo_token_id), amount)) =
+                    Self::get_output_amount(txo, issued_token_id)?
+                {
+                    if utxo_token_id == token_id {
+                        utxo_pool.push(OutputGroup::new(outpoint.clone(), (*txo).clone(), amount));
+                    }
+                }
+            }
+
+            // The fee is paid in coins, so there is no cost of change to trade against here
+            let selection =
+                select_coins(utxo_pool, output_amount, Amount::ZERO).map_err(|err| match err {
+                    UtxoSelectorError::NotEnoughFunds(available, required) => {
+                        WalletError::NotEnoughTokens(token_id, available, required)
+                    }
+                    UtxoSelectorError::AmountOverflow => err.into(),
+                })?;
+            selected.extend(selection.into_output_pairs());
+        }
 
-        Ok(tx)
+        Ok(selected)
+    }
+
+    /// Add an output that returns the unspent part of each token input of `request` to `source`
+    fn add_token_change_outputs(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        mut request: SendRequest,
+        source: FundsSource,
+    ) -> WalletResult<SendRequest> {
+        let (_, input_tokens_amounts) = self.calculate_input_amounts(&request)?;
+        let (_, output_tokens_amounts) = Self::calculate_output_amounts(request.outputs().iter())?;
+
+        for (token_id, output_amount) in &output_tokens_amounts {
+            let input_amount = input_tokens_amounts.get(token_id).copied().unwrap_or(Amount::ZERO);
+            utils::ensure!(
+                input_amount >= *output_amount,
+                WalletError::NotEnoughTokens(*token_id, input_amount, *output_amount)
+            );
+        }
+
+        for (token_id, input_amount) in input_tokens_amounts {
+            let output_amount =
+                output_tokens_amounts.get(&token_id).copied().unwrap_or(Amount::ZERO);
+            let change_amount = (input_amount - output_amount).expect("checked above");
+            if change_amount > Amount::ZERO {
+                let change_address = self.get_change_address(db_tx, source)?;
+                let change_output =
+                    make_address_output_token(change_address, token_id, change_amount)?;
+                request = request.with_outputs([change_output]);
+            }
+        }
+
+        Ok(request)
+    }
+
+    /// Select the coin UTXOs that pay for the outputs of `request` and the fee at `fee_rate`
+    fn select_inputs_for_send_request(
+        &self,
+        request: &SendRequest,
+        source: FundsSource,
+        median_time: BlockTimestamp,
+        fee_rate: FeeRate,
+    ) -> WalletResult<Vec<(OutPoint, TxOutput)>> {
+        let (output_coin_amount, _) = Self::calculate_output_amounts(request.outputs().iter())?;
+
+        // The fee of each input is accounted for in its effective value during the selection,
+        // so only the fee of the rest of the transaction is added to the target
+        let base_fee = compute_fee(fee_rate, self.estimate_signed_tx_size(request)?)?;
+        let cost_of_change = compute_fee(fee_rate, make_change_output(Amount::MAX).encoded_size())?;
+
+        let mut selection_target =
+            (output_coin_amount + base_fee).ok_or(WalletError::OutputAmountOverflow)?;
+
+        loop {
+            let utxos = self.select_coin_inputs(
+                selection_target,
+                cost_of_change,
+                source,
+                median_time,
+                fee_rate,
+            )?;
+
+            let candidate = request.clone().with_inputs(utxos.clone());
+            let (input_coin_amount, _) = self.calculate_input_amounts(&candidate)?;
+            let network_fee = compute_fee(fee_rate, self.estimate_signed_tx_size(&candidate)?)?;
+            let output_with_fee =
+                (output_coin_amount + network_fee).ok_or(WalletError::OutputAmountOverflow)?;
+
+            match output_with_fee - input_coin_amount {
+                Some(missing_amount) if missing_amount > Amount::ZERO => {
+                    // The inputs are not enough to pay the exact fee (e.g. the encoded length
+                    // of the inputs got longer), try again with a bigger target
+                    selection_target = (selection_target + missing_amount)
+                        .ok_or(WalletError::OutputAmountOverflow)?;
+                }
+                _ => return Ok(utxos),
+            }
+        }
+    }
+
+    /// Select the coin UTXOs with a total effective value of at least `selection_target`.
+    ///
+    /// The effective value of a UTXO is its value minus the fee for spending it at `fee_rate`.
+    fn select_coin_inputs(
+        &self,
+        selection_target: Amount,
+        cost_of_change: Amount,
+        source: FundsSource,
+        median_time: BlockTimestamp,
+        fee_rate: FeeRate,
+    ) -> WalletResult<Vec<(OutPoint, TxOutput)>> {
+        let mut utxo_pool = Vec::new();
+        for (outpoint, txo) in self.get_selectable_utxos(source, median_time) {
+            let amount = match txo {
+                TxOutput::Transfer(OutputValue::Coin(amount), _)
+                | TxOutput::LockThenTransfer(OutputValue::Coin(amount), _, _) => *amount,
+                _ => continue,
+            };
+
+            let input_size = TxInput::new(outpoint.tx_id(), outpoint.output_index()).encoded_size()
+                + self.input_witness_size(txo)?;
+            let input_fee = compute_fee(fee_rate, input_size)?;
+
+            // Skip the UTXOs that cost more to spend than they are worth
+            if let Some(effective_value) = (amount - input_fee).filter(|v| *v > Amount::ZERO) {
+                utxo_pool.push(OutputGroup::new(outpoint, txo.clone(), effective_value));
+            }
+        }
+
+        let selection = select_coins(utxo_pool, selection_target, cost_of_change)?;
+
+        Ok(selection.into_output_pairs())
+    }
+
+    /// Estimate the size of the transaction created from `request` after it is signed
+    fn estimate_signed_tx_size(&self, request: &SendRequest) -> WalletResult<usize> {
+        let witness_sizes = request
+            .utxos()
+            .iter()
+            .map(|utxo| self.input_witness_size(utxo))
+            .collect::<WalletResult<Vec<_>>>()?;
+        let (tx, _) = request.clone().into_transaction_and_utxos()?;
+        Ok(transaction_size::signed_tx_size(&tx, &witness_sizes))
+    }
+
+    fn input_witness_size(&self, utxo: &TxOutput) -> WalletResult<usize> {
+        let destination = self
+            .get_spending_destination(utxo)
+            .ok_or_else(|| WalletError::UnsupportedTransactionOutput(Box::new(utxo.clone())))?;
+        let challenge = match destination {
+            Destination::ClassicMultisig(challenge_hash) => {
+                self.classic_multisigs.get(challenge_hash)
+            }
+            Destination::AnyoneCanSpend
+            | Destination::Address(_)
+            | Destination::PublicKey(_)
+            | Destination::ScriptHash(_) => None,
+        };
+        transaction_size::input_witness_size(destination, challenge)
     }
 
     fn get_vrf_key(
@@ -183,44 +666,290 @@ impl Account {
         &mut self,
         db_tx: &mut impl WalletStorageWriteUnlocked,
         amount: Amount,
+        median_time: BlockTimestamp,
+        fee_rate: FeeRate,
     ) -> WalletResult<SignedTransaction> {
-        // process_send_request can fill UTXOs, but the first UTXO is needed in advance to calculate pool_id
+        // TODO: Use other accounts here
+        let staker = self.key_chain.issue_key(db_tx, KeyPurpose::ReceiveFunds)?.into_public_key();
+        let decommission_key =
+            self.key_chain.issue_key(db_tx, KeyPurpose::ReceiveFunds)?.into_public_key();
+        let (_vrf_private_key, vrf_public_key) = self.get_vrf_key(db_tx)?;
 
-        // TODO: Add support for LockThenTransfer
-        let utxos = self
-            .get_utxos(UtxoType::Transfer.into())
-            .into_iter()
-            .map(|(outpoint, txo)| (outpoint, txo.clone()))
-            .collect::<Vec<(OutPoint, TxOutput)>>();
+        let make_request = |pool_id| -> WalletResult<SendRequest> {
+            let stake_output = make_stake_output(
+                pool_id,
+                amount,
+                staker.clone(),
+                decommission_key.clone(),
+                vrf_public_key.clone(),
+                PerThousand::new(1000).expect("must not fail"),
+                Amount::ZERO,
+            )?;
+            Ok(SendRequest::new().with_outputs([stake_output]))
+        };
+
+        // process_send_request can fill UTXOs, but the first UTXO is needed in advance to calculate pool_id.
+        // The pool id has a fixed size, so a placeholder can be used to select the inputs.
+        let placeholder_request = make_request(PoolId::new(H256::zero()))?;
+        let utxos = self.select_inputs_for_send_request(
+            &placeholder_request,
+            FundsSource::Account,
+            median_time,
+            fee_rate,
+        )?;
 
         let input0 = utxos.get(0).ok_or(WalletError::NoUtxos)?;
         let pool_id = pos_accounting::make_pool_id(&input0.0);
 
-        // TODO: Use other accounts here
-        let staker = self.key_chain.issue_key(db_tx, KeyPurpose::ReceiveFunds)?;
-        let decommission_key = self.key_chain.issue_key(db_tx, KeyPurpose::ReceiveFunds)?;
-        let (_vrf_private_key, vrf_public_key) = self.get_vrf_key(db_tx)?;
+        let request = make_request(pool_id)?.with_inputs(utxos);
+
+        self.process_send_request(db_tx, request, median_time, fee_rate)
+    }
+
+    /// Issue new fungible tokens to `address`, paying the issuance fee from the coins of this account
+    pub fn issue_new_token(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteUnlocked,
+        address: Address,
+        token_issuance: TokenIssuance,
+        median_time: BlockTimestamp,
+        fee_rate: FeeRate,
+    ) -> WalletResult<SignedTransaction> {
+        let token_data = TokenData::TokenIssuance(Box::new(token_issuance));
+        let outputs = make_issue_token_outputs(address, token_data, &self.chain_config)?;
+        self.process_send_request(
+            db_tx,
+            SendRequest::new().with_outputs(outputs),
+            median_time,
+            fee_rate,
+        )
+    }
+
+    /// Issue a new NFT to `address`, paying the issuance fee from the coins of this account
+    pub fn issue_new_nft(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteUnlocked,
+        address: Address,
+        metadata: Metadata,
+        median_time: BlockTimestamp,
+        fee_rate: FeeRate,
+    ) -> WalletResult<SignedTransaction> {
+        let token_data = TokenData::NftIssuance(Box::new(NftIssuance { metadata }));
+        let outputs = make_issue_token_outputs(address, token_data, &self.chain_config)?;
+        self.process_send_request(
+            db_tx,
+            SendRequest::new().with_outputs(outputs),
+            median_time,
+            fee_rate,
+        )
+    }
+
+    /// Create a new delegation to `pool_id`, the delegated coins can be withdrawn to `address`
+    pub fn create_delegation(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteUnlocked,
+        address: Address,
+        pool_id: PoolId,
+        median_time: BlockTimestamp,
+        fee_rate: FeeRate,
+    ) -> WalletResult<SignedTransaction> {
+        let output = make_create_delegation_output(address, pool_id)?;
+        self.process_send_request(
+            db_tx,
+            SendRequest::new().with_outputs([output]),
+            median_time,
+            fee_rate,
+        )
+    }
 
-        let stake_output = make_stake_output(
-            pool_id,
-            amount,
-            staker.into_public_key(),
-            decommission_key.into_public_key(),
-            vrf_public_key,
-            PerThousand::new(1000).expect("must not fail"),
-            Amount::ZERO,
+    /// Add `amount` coins of this account to the stake of a delegation
+    pub fn delegate_staking(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteUnlocked,
+        amount: Amount,
+        delegation_id: DelegationId,
+        median_time: BlockTimestamp,
+        fee_rate: FeeRate,
+    ) -> WalletResult<SignedTransaction> {
+        let output = TxOutput::DelegateStaking(amount, delegation_id);
+        self.process_send_request(
+            db_tx,
+            SendRequest::new().with_outputs([output]),
+            median_time,
+            fee_rate,
+        )
+    }
+
+    /// Withdraw `amount` coins from a delegation of this account to `address`.
+    ///
+    /// Only a single delegation UTXO can be spent by a transaction, the fee is paid from it
+    /// and the rest is delegated again. The withdrawn coins are locked for
+    /// `spend_share_maturity_distance` blocks.
+    pub fn withdraw_from_delegation(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteUnlocked,
+        address: Address,
+        amount: Amount,
+        delegation_id: DelegationId,
+        fee_rate: FeeRate,
+    ) -> WalletResult<SignedTransaction> {
+        utils::ensure!(
+            self.get_delegations().any(|(id, _)| *id == delegation_id),
+            WalletError::DelegationNotFound(delegation_id)
+        );
+
+        let current_block_height = self.account_info.best_block_height().next_height();
+        let maturity_distance: i64 =
+            self.chain_config.spend_share_maturity_distance(current_block_height).into();
+        let lock = OutputTimeLock::ForBlockCount(
+            maturity_distance.try_into().expect("Maturity distance must not be negative"),
+        );
+        let withdraw_output = make_locked_address_output(address, amount, lock)?;
+
+        let mut utxos = self
+            .get_utxos(
+                UtxoType::DelegateStaking.into(),
+                Self::spendable_utxo_states(),
+            )
+            .into_iter()
+            .filter_map(|(outpoint, utxo)| match utxo {
+                TxOutput::DelegateStaking(value, id) if *id == delegation_id => {
+                    Some((*value, outpoint, utxo.clone()))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        utxos.sort_by_key(|(value, _, _)| *value);
+        let (largest_value, largest_outpoint, largest_utxo) =
+            utxos.last().cloned().ok_or(WalletError::NotEnoughUtxo(Amount::ZERO, amount))?;
+
+        // The remaining coins are delegated again, which makes the transaction bigger.
+        // The size doesn't depend much on the chosen UTXO, so the fee is estimated
+        // with the largest UTXO and the largest possible remaining amount.
+        let request_with_remaining = SendRequest::new()
+            .with_inputs([(largest_outpoint, largest_utxo)])
+            .with_outputs([
+                withdraw_output.clone(),
+                TxOutput::DelegateStaking(largest_value, delegation_id),
+            ]);
+        let fee = compute_fee(
+            fee_rate,
+            self.estimate_signed_tx_size(&request_with_remaining)?,
         )?;
+        let required = (amount + fee).ok_or(WalletError::OutputAmountOverflow)?;
 
-        let request = SendRequest::new().with_inputs(utxos).with_outputs([stake_output]);
+        // Use the smallest delegation UTXO that covers the amount and the fee
+        let (value, outpoint, utxo) = utxos
+            .into_iter()
+            .find(|(value, _, _)| *value >= required)
+            .ok_or(WalletError::NotEnoughUtxo(largest_value, required))?;
+
+        let mut request = SendRequest::new()
+            .with_inputs([(outpoint, utxo)])
+            .with_outputs([withdraw_output]);
+        let remaining = (value - required).expect("the UTXO covers the required amount");
+        if remaining > Amount::ZERO {
+            request = request.with_outputs([TxOutput::DelegateStaking(remaining, delegation_id)]);
+        }
 
-        self.process_send_request(db_tx, request)
+        self.sign_transaction(request, db_tx)
+    }
+
+    /// Decommission a stake pool of this account, the pledge minus the fee is transferred to
+    /// a new address of this account and locked for `decommission_pool_maturity_distance` blocks.
+    ///
+    /// The pool is decommissioned by spending its latest UTXO, which is either the output that
+    /// has created the pool or the reward of the latest block produced by it.
+    pub fn decommission_stake_pool(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteUnlocked,
+        pool_id: PoolId,
+        pool_pledge: Amount,
+        fee_rate: FeeRate,
+    ) -> WalletResult<SignedTransaction> {
+        let (outpoint, utxo) = self
+            .get_utxos(
+                UtxoType::CreateStakePool | UtxoType::ProduceBlockFromStake,
+                Self::spendable_utxo_states(),
+            )
+            .into_iter()
+            .find(|(_, utxo)| match utxo {
+                TxOutput::CreateStakePool(id, _) | TxOutput::ProduceBlockFromStake(_, id) => {
+                    *id == pool_id
+                }
+                TxOutput::Transfer(_, _)
+                | TxOutput::LockThenTransfer(_, _, _)
+                | TxOutput::Burn(_)
+                | TxOutput::CreateDelegationId(_, _)
+                | TxOutput::DelegateStaking(_, _) => false,
+            })
+            .ok_or(WalletError::UnknownPoolId(pool_id))?;
+        let utxo = utxo.clone();
+
+        let current_block_height = self.account_info.best_block_height().next_height();
+        let maturity_distance: i64 = self
+            .chain_config
+            .decommission_pool_maturity_distance(current_block_height)
+            .into();
+        let lock = OutputTimeLock::ForBlockCount(
+            maturity_distance.try_into().expect("Maturity distance must not be negative"),
+        );
+        let address = self.key_chain.issue_address(db_tx, KeyPurpose::ReceiveFunds)?;
+
+        let make_request = |amount| -> WalletResult<SendRequest> {
+            let output = make_locked_address_output(address.clone(), amount, lock.clone())?;
+            Ok(SendRequest::new()
+                .with_inputs([(outpoint.clone(), utxo.clone())])
+                .with_outputs([output]))
+        };
+
+        // The fee is paid from the pledge, the output amount doesn't change the transaction size much
+        let fee = compute_fee(
+            fee_rate,
+            self.estimate_signed_tx_size(&make_request(pool_pledge)?)?,
+        )?;
+        let amount = (pool_pledge - fee).ok_or(WalletError::NotEnoughUtxo(pool_pledge, fee))?;
+
+        self.sign_transaction(make_request(amount)?, db_tx)
+    }
+
+    /// The ids of the stake pools that are staked by this account and not decommissioned yet
+    pub fn get_pool_ids(&self) -> Vec<PoolId> {
+        self.get_utxos(
+            UtxoType::CreateStakePool | UtxoType::ProduceBlockFromStake,
+            UtxoState::Confirmed.into(),
+        )
+        .into_values()
+        .filter_map(|utxo| match utxo {
+            TxOutput::CreateStakePool(pool_id, _) | TxOutput::ProduceBlockFromStake(_, pool_id) => {
+                Some(*pool_id)
+            }
+            TxOutput::Transfer(_, _)
+            | TxOutput::LockThenTransfer(_, _, _)
+            | TxOutput::Burn(_)
+            | TxOutput::CreateDelegationId(_, _)
+            | TxOutput::DelegateStaking(_, _) => None,
+        })
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+    }
+
+    /// The delegations that can be withdrawn by this account
+    pub fn get_delegations(&self) -> impl Iterator<Item = (&DelegationId, &DelegationData)> {
+        self.output_cache.delegations().iter().filter(|(_, delegation_data)| {
+            self.is_destination_mine_or_watched(delegation_data.spend_destination())
+        })
     }
 
     pub fn get_pos_gen_block_data(
         &self,
         db_tx: &impl WalletStorageReadUnlocked,
     ) -> WalletResult<PoSGenerateBlockInputData> {
-        let utxos = self.get_utxos(UtxoType::CreateStakePool | UtxoType::ProduceBlockFromStake);
+        let utxos = self.get_utxos(
+            UtxoType::CreateStakePool | UtxoType::ProduceBlockFromStake,
+            UtxoState::Confirmed.into(),
+        );
         // TODO: Select by pool_id if there is more than one UTXO
         let (kernel_input_outpoint, kernel_input_utxo) =
             utxos.into_iter().next().ok_or(WalletError::NoUtxos)?;
@@ -257,111 +986,386 @@ impl Account {
         Ok(data)
     }
 
-    /// Calculate the output amount for coins and tokens
-    fn calculate_output_amounts<'a>(
-        outputs: impl Iterator<Item = &'a TxOutput>,
+    /// Get the coin or token amount of an output.
+    ///
+    /// The issuance outputs create new tokens, they are only counted if the id of the issued
+    /// tokens is known, i.e. when they are spent as UTXOs.
+    fn get_output_amount(
+        output: &TxOutput,
+        issued_token_id: Option<TokenId>,
+    ) -> WalletResult<Option<(Currency, Amount)>> {
+        // Get the supported output value
+        let output_value = match output {
+            TxOutput::Transfer(v, _) | TxOutput::LockThenTransfer(v, _, _) | TxOutput::Burn(v) => {
+                v.clone()
+            }
+            TxOutput::CreateStakePool(_, stake) => OutputValue::Coin(stake.value()),
+            TxOutput::DelegateStaking(amount, _) => OutputValue::Coin(*amount),
+            // These outputs don't carry any value
+            TxOutput::ProduceBlockFromStake(_, _) | TxOutput::CreateDelegationId(_, _) => {
+                return Ok(None)
+            }
+        };
+
+        let amount = match output_value {
+            OutputValue::Coin(amount) => Some((Currency::Coin, amount)),
+            OutputValue::Token(token_data) => match token_data.as_ref() {
+                TokenData::TokenTransfer(token_transfer) => Some((
+                    Currency::Token(token_transfer.token_id),
+                    token_transfer.amount,
+                )),
+                TokenData::TokenIssuance(token_issuance) => issued_token_id
+                    .map(|token_id| (Currency::Token(token_id), token_issuance.amount_to_issue)),
+                TokenData::NftIssuance(_) => issued_token_id
+                    .map(|token_id| (Currency::Token(token_id), Amount::from_atoms(1))),
+            },
+        };
+        Ok(amount)
+    }
+
+    /// Calculate the coin and tokens amounts of outputs paired with the ids of the tokens
+    /// they issue, if any
+    fn calculate_amounts<'a>(
+        outputs: impl Iterator<Item = (&'a TxOutput, Option<TokenId>)>,
     ) -> WalletResult<(Amount, BTreeMap<TokenId, Amount>)> {
         let mut coin_amount = Amount::ZERO;
         let mut tokens_amounts: BTreeMap<TokenId, Amount> = BTreeMap::new();
 
         // Iterate over all outputs and calculate the coin and tokens amounts
-        for output in outputs {
-            // Get the supported output value
-            let output_value = match output {
-                TxOutput::Transfer(v, _)
-                | TxOutput::LockThenTransfer(v, _, _)
-                | TxOutput::Burn(v) => v.clone(),
-                TxOutput::CreateStakePool(_, stake) => OutputValue::Coin(stake.value()),
-                TxOutput::ProduceBlockFromStake(_, _)
-                | TxOutput::CreateDelegationId(_, _)
-                | TxOutput::DelegateStaking(_, _) => {
-                    return Err(WalletError::UnsupportedTransactionOutput(Box::new(
-                        output.clone(),
-                    )))
-                }
-            };
-
-            match output_value {
-                OutputValue::Coin(output_amount) => {
+        for (output, issued_token_id) in outputs {
+            match Self::get_output_amount(output, issued_token_id)? {
+                Some((Currency::Coin, output_amount)) => {
                     coin_amount =
                         coin_amount.add(output_amount).ok_or(WalletError::OutputAmountOverflow)?
                 }
-                OutputValue::Token(token_data) => {
-                    let token_data = token_data.as_ref();
-                    match token_data {
-                        TokenData::TokenTransfer(token_transfer) => {
-                            let total_token_amount = tokens_amounts
-                                .entry(token_transfer.token_id)
-                                .or_insert(Amount::ZERO);
-                            *total_token_amount = total_token_amount
-                                .add(token_transfer.amount)
-                                .ok_or(WalletError::OutputAmountOverflow)?;
-                        }
-                        TokenData::TokenIssuance(_) | TokenData::NftIssuance(_) => {
-                            return Err(WalletError::UnsupportedTransactionOutput(Box::new(
-                                output.clone(),
-                            )))
-                        }
-                    }
+                Some((Currency::Token(token_id), output_amount)) => {
+                    let total_token_amount = tokens_amounts.entry(token_id).or_insert(Amount::ZERO);
+                    *total_token_amount = total_token_amount
+                        .add(output_amount)
+                        .ok_or(WalletError::OutputAmountOverflow)?;
                 }
+                None => {}
             }
         }
         Ok((coin_amount, tokens_amounts))
     }
 
-    // TODO: Use a different type to support partially signed transactions
+    /// Calculate the output amount for coins and tokens, the newly issued tokens are not counted
+    fn calculate_output_amounts<'a>(
+        outputs: impl Iterator<Item = &'a TxOutput>,
+    ) -> WalletResult<(Amount, BTreeMap<TokenId, Amount>)> {
+        Self::calculate_amounts(outputs.map(|output| (output, None)))
+    }
+
+    /// Calculate the coin and tokens amounts of UTXOs, including the tokens issued by them
+    fn calculate_utxo_amounts<'a>(
+        &self,
+        utxos: impl Iterator<Item = (OutPointSourceId, &'a TxOutput)>,
+    ) -> WalletResult<(Amount, BTreeMap<TokenId, Amount>)> {
+        Self::calculate_amounts(
+            utxos.map(|(tx_id, utxo)| (utxo, self.output_cache.issued_token_id(&tx_id))),
+        )
+    }
+
+    /// Calculate the coin and tokens amounts of the inputs of `request`
+    fn calculate_input_amounts(
+        &self,
+        request: &SendRequest,
+    ) -> WalletResult<(Amount, BTreeMap<TokenId, Amount>)> {
+        let tx_ids = request.inputs().iter().map(|input| input.outpoint().tx_id());
+        self.calculate_utxo_amounts(tx_ids.zip(request.utxos().iter()))
+    }
+
+    /// Find an output of a transaction or block reward stored by this account
+    fn find_output(&self, outpoint: &OutPoint) -> Option<&TxOutput> {
+        let tx_id = AccountWalletTxId::new(self.get_account_id(), outpoint.tx_id());
+        self.output_cache
+            .txs()
+            .get(&tx_id)
+            .and_then(|tx| tx.outputs().get(outpoint.output_index() as usize))
+    }
+
+    fn make_history_entry(&self, tx: &WalletTx) -> WalletResult<TxHistoryEntry> {
+        let tx_id = tx.id();
+        let issued_token_id = self.output_cache.issued_token_id(&tx_id);
+
+        let input_utxos = match tx {
+            WalletTx::Tx(tx_data) => tx_data
+                .get_transaction()
+                .inputs()
+                .iter()
+                .map(|input| {
+                    let outpoint = input.outpoint();
+                    self.find_output(outpoint).map(|utxo| (outpoint.tx_id(), utxo))
+                })
+                .collect::<Vec<_>>(),
+            // The kernel inputs of a block only move the stake of the pool to the next block
+            WalletTx::Block(_) => Vec::new(),
+        };
+        let own_input_utxos =
+            input_utxos.iter().flatten().filter(|(_, utxo)| self.is_mine_or_watched(utxo));
+        let (spent_coins, spent_tokens) = self.calculate_utxo_amounts(own_input_utxos.cloned())?;
+
+        let own_outputs = tx.outputs().iter().filter(|output| self.is_mine_or_watched(output));
+        let (received_coins, received_tokens) =
+            Self::calculate_amounts(own_outputs.map(|output| (output, issued_token_id)))?;
+
+        let coin_change = signed_change(received_coins, spent_coins)?;
+        let token_ids = spent_tokens.keys().chain(received_tokens.keys()).collect::<BTreeSet<_>>();
+        let token_changes = token_ids
+            .into_iter()
+            .map(|token_id| {
+                let received = received_tokens.get(token_id).copied().unwrap_or(Amount::ZERO);
+                let spent = spent_tokens.get(token_id).copied().unwrap_or(Amount::ZERO);
+                Ok((*token_id, signed_change(received, spent)?))
+            })
+            .collect::<WalletResult<BTreeMap<_, _>>>()?;
+
+        // The fee can only be calculated for transactions with all the inputs known
+        let fee = match tx {
+            WalletTx::Tx(_) if input_utxos.iter().all(Option::is_some) => {
+                let (input_coins, _) =
+                    self.calculate_utxo_amounts(input_utxos.into_iter().flatten())?;
+                let (output_coins, _) = Self::calculate_output_amounts(tx.outputs().iter())?;
+                input_coins - output_coins
+            }
+            WalletTx::Tx(_) | WalletTx::Block(_) => None,
+        };
+
+        let counterparties = if spent_coins == Amount::ZERO && spent_tokens.is_empty() {
+            Vec::new()
+        } else {
+            tx.outputs()
+                .iter()
+                .filter(|output| !self.is_mine_or_watched(output))
+                .filter_map(|output| self.get_output_destination(output))
+                .cloned()
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect()
+        };
+
+        let confirmations = match tx.state() {
+            TxState::Confirmed(height, _) => {
+                let best_block_height = self.account_info.best_block_height();
+                best_block_height.into_int().saturating_sub(height.into_int()) + 1
+            }
+            TxState::InMempool
+            | TxState::Conflicted(_)
+            | TxState::Inactive
+            | TxState::Replaced(_)
+            | TxState::Abandoned => 0,
+        };
+
+        Ok(TxHistoryEntry {
+            tx_id,
+            state: tx.state(),
+            confirmations,
+            coin_change,
+            token_changes,
+            fee,
+            counterparties,
+        })
+    }
+
+    /// Get the transactions and block rewards of this account, the most recent ones first
+    pub fn get_transaction_history(
+        &self,
+        filter: &TxHistoryFilter,
+    ) -> WalletResult<Vec<TxHistoryEntry>> {
+        let mut txs = self
+            .output_cache
+            .txs()
+            .values()
+            .filter(|tx| filter.contains(&tx.state()))
+            .collect::<Vec<_>>();
+        // The unconfirmed transactions go first
+        txs.sort_by_key(|tx| {
+            std::cmp::Reverse(match tx.state() {
+                TxState::Confirmed(height, _) => height,
+                TxState::InMempool
+                | TxState::Conflicted(_)
+                | TxState::Inactive
+                | TxState::Replaced(_)
+                | TxState::Abandoned => BlockHeight::max(),
+            })
+        });
+
+        txs.into_iter()
+            .skip(filter.skip)
+            .take(filter.limit)
+            .map(|tx| self.make_history_entry(tx))
+            .collect()
+    }
+
+    fn make_partially_signed_transaction(
+        &self,
+        req: SendRequest,
+    ) -> WalletResult<PartiallySignedTransaction> {
+        let (tx, utxos) = req.into_transaction_and_utxos()?;
+        let destinations =
+            utxos.iter().map(|utxo| self.get_spending_destination(utxo).cloned()).collect();
+        let witnesses = vec![None; utxos.len()];
+        let mut ptx = PartiallySignedTransaction::new(tx, witnesses, utxos, destinations)?;
+
+        // The cosigners of the watched classic multisig inputs can sign them right away
+        for input_index in 0..ptx.destinations().len() {
+            if let Some(Destination::ClassicMultisig(challenge_hash)) =
+                &ptx.destinations()[input_index]
+            {
+                if let Some(challenge) = self.classic_multisigs.get(challenge_hash) {
+                    ptx.add_multisig_challenge(input_index, challenge.clone())?;
+                }
+            }
+        }
+
+        Ok(ptx)
+    }
+
     fn sign_transaction(
         &self,
         req: SendRequest,
         db_tx: &impl WalletStorageReadUnlocked,
     ) -> WalletResult<SignedTransaction> {
-        let (tx, utxos) = req.into_transaction_and_utxos()?;
-        let inputs = tx.inputs();
-        let input_utxos = utxos.iter().collect::<Vec<_>>();
-        if utxos.len() != inputs.len() {
-            return Err(
-                TransactionSigError::InvalidUtxoCountVsInputs(utxos.len(), inputs.len()).into(),
-            );
+        let ptx = self.make_partially_signed_transaction(req)?;
+        if let Some(input_index) = ptx.destinations().iter().position(Option::is_none) {
+            return Err(WalletError::UnsupportedTransactionOutput(Box::new(
+                ptx.input_utxos()[input_index].clone(),
+            )));
         }
 
-        let witnesses = utxos
-            .iter()
-            .enumerate()
-            .map(|(i, utxo)| {
-                // Get the destination from this utxo
-                let destination = Self::get_tx_output_destination(utxo).ok_or_else(|| {
-                    WalletError::UnsupportedTransactionOutput(Box::new(utxo.clone()))
-                })?;
+        let ptx = self.sign_partially_signed_transaction(ptx, db_tx)?;
+        utils::ensure!(
+            ptx.witnesses().iter().all(Option::is_some),
+            WalletError::KeyChainError(KeyChainError::NoPrivateKeyFound)
+        );
 
-                if *destination == Destination::AnyoneCanSpend {
-                    Ok(InputWitness::NoSignature(None))
-                } else {
-                    let private_key = self
-                        .key_chain
-                        .get_private_key_for_destination(destination, db_tx)?
-                        .ok_or(WalletError::KeyChainError(KeyChainError::NoPrivateKeyFound))?
-                        .private_key();
+        Ok(ptx.into_signed_tx(&self.chain_config)?)
+    }
 
-                    let sighash_type =
-                        SigHashType::try_from(SigHashType::ALL).expect("Should not fail");
+    /// Add the signatures this account can make to `ptx`.
+    ///
+    /// The inputs that are already signed or that can't be signed by this account are left as they are.
+    /// Classic multisig inputs are signed only if their challenge has been added to `ptx`,
+    /// one signature is added for each key of the challenge that belongs to this account.
+    pub fn sign_partially_signed_transaction(
+        &self,
+        mut ptx: PartiallySignedTransaction,
+        db_tx: &impl WalletStorageReadUnlocked,
+    ) -> WalletResult<PartiallySignedTransaction> {
+        let sighash_type = SigHashType::try_from(SigHashType::ALL).expect("Should not fail");
+
+        for input_index in 0..ptx.tx().inputs().len() {
+            let invalid_input_index =
+                || PartiallySignedTransactionError::InvalidInputIndex(input_index);
+            let destination = match ptx.destinations().get(input_index) {
+                Some(Some(destination)) => destination.clone(),
+                Some(None) => continue,
+                None => return Err(invalid_input_index().into()),
+            };
+            let is_signed =
+                ptx.witnesses().get(input_index).ok_or_else(invalid_input_index)?.is_some();
 
-                    StandardInputSignature::produce_uniparty_signature_for_input(
+            match destination {
+                Destination::AnyoneCanSpend => {
+                    if !is_signed {
+                        ptx.set_witness(input_index, InputWitness::NoSignature(None))?;
+                    }
+                }
+                Destination::Address(_) | Destination::PublicKey(_) => {
+                    if is_signed {
+                        continue;
+                    }
+                    let private_key = match self
+                        .key_chain
+                        .get_private_key_for_destination(&destination, db_tx)?
+                    {
+                        Some(private_key) => private_key.private_key(),
+                        None => continue,
+                    };
+                    let input_utxos = ptx.input_utxos().iter().collect::<Vec<_>>();
+                    let signature = StandardInputSignature::produce_uniparty_signature_for_input(
                         &private_key,
                         sighash_type,
-                        destination.clone(),
-                        &tx,
+                        destination,
+                        ptx.tx(),
                         &input_utxos,
-                        i,
-                    )
-                    .map(InputWitness::Standard)
-                    .map_err(WalletError::TransactionSig)
+                        input_index,
+                    )?;
+                    ptx.set_witness(input_index, InputWitness::Standard(signature))?;
                 }
-            })
-            .collect::<Result<Vec<InputWitness>, _>>()?;
+                Destination::ClassicMultisig(_) => {
+                    if let Some(spend) = ptx.multisig_spend(input_index) {
+                        let spend = self.sign_multisig_input(&ptx, input_index, spend, db_tx)?;
+                        ptx.set_multisig_spend(input_index, &spend)?;
+                    }
+                }
+                Destination::ScriptHash(_) => {}
+            }
+        }
+
+        Ok(ptx)
+    }
 
-        let tx = SignedTransaction::new(tx, witnesses)?;
+    /// Add the signatures of the keys of this account to a classic multisig input,
+    /// until the required number of signatures is reached
+    fn sign_multisig_input(
+        &self,
+        ptx: &PartiallySignedTransaction,
+        input_index: usize,
+        mut spend: AuthorizedClassicalMultisigSpend,
+        db_tx: &impl WalletStorageReadUnlocked,
+    ) -> WalletResult<AuthorizedClassicalMultisigSpend> {
+        let sighash_type = SigHashType::try_from(SigHashType::ALL).expect("Should not fail");
+        let input_utxos = ptx.input_utxos().iter().collect::<Vec<_>>();
+        let sighash = signature_hash(sighash_type, ptx.tx(), &input_utxos, input_index)?;
+        let challenge = spend.challenge().clone();
+        let required_signatures = challenge.min_required_signatures() as usize;
+
+        for (key_index, public_key) in challenge.public_keys().iter().enumerate() {
+            if spend.available_signatures_count() >= required_signatures {
+                break;
+            }
+            let key_index = key_index as u8;
+            if spend.signatures().contains_key(&key_index) {
+                continue;
+            }
+            let destination = Destination::PublicKey(public_key.clone());
+            if let Some(private_key) =
+                self.key_chain.get_private_key_for_destination(&destination, db_tx)?
+            {
+                spend = sign_classical_multisig_spending(
+                    &self.chain_config,
+                    key_index,
+                    &private_key.private_key(),
+                    &challenge,
+                    &sighash,
+                    spend,
+                )?
+                .take();
+            }
+        }
 
-        Ok(tx)
+        Ok(spend)
+    }
+
+    /// Sign `message` with the key of `destination` to prove that it belongs to this account
+    pub fn sign_message(
+        &self,
+        destination: &Destination,
+        message: &[u8],
+        db_tx: &impl WalletStorageReadUnlocked,
+    ) -> WalletResult<ArbitraryMessageSignature> {
+        let private_key = self
+            .key_chain
+            .get_private_key_for_destination(destination, db_tx)?
+            .ok_or(WalletError::KeyChainError(KeyChainError::NoPrivateKeyFound))?
+            .private_key();
+        Ok(ArbitraryMessageSignature::produce_uniparty_signature(
+            &private_key,
+            destination,
+            message,
+        )?)
     }
 
     pub fn account_index(&self) -> U31 {
@@ -373,6 +1377,31 @@ impl Account {
         self.key_chain.get_account_id()
     }
 
+    pub fn account_public_key(&self) -> &ExtendedPublicKey {
+        self.key_chain.account_public_key()
+    }
+
+    pub fn name(&self) -> &Option<String> {
+        &self.name
+    }
+
+    pub fn lookahead_size(&self) -> u32 {
+        self.account_info.lookahead_size()
+    }
+
+    pub fn set_name(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        name: Option<String>,
+    ) -> WalletResult<()> {
+        match &name {
+            Some(name) => db_tx.set_account_name(&self.key_chain.get_account_id(), name)?,
+            None => db_tx.del_account_name(&self.key_chain.get_account_id())?,
+        }
+        self.name = name;
+        Ok(())
+    }
+
     /// Get a new address that hasn't been used before
     pub fn get_new_address(
         &mut self,
@@ -382,6 +1411,88 @@ impl Account {
         Ok(self.key_chain.issue_address(db_tx, purpose)?)
     }
 
+    /// Get the address that receives the change of the transactions funded by `source`
+    fn get_change_address(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        source: FundsSource,
+    ) -> WalletResult<Address> {
+        match source {
+            FundsSource::Account => self.get_new_address(db_tx, KeyPurpose::Change),
+            FundsSource::ClassicMultisig(challenge_hash) => Ok(
+                Address::from_classic_multisig_hash(&self.chain_config, &challenge_hash)?,
+            ),
+        }
+    }
+
+    /// Start watching a classic multisig and return its address.
+    ///
+    /// Only the funds received after this call are found, the wallet has to be rescanned
+    /// to find the older ones.
+    pub fn add_classic_multisig(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        challenge: ClassicMultisigChallenge,
+    ) -> WalletResult<Address> {
+        let challenge_hash = PublicKeyHash::from(&challenge);
+        let id = AccountClassicMultisigId::new(self.get_account_id(), challenge_hash);
+        db_tx.set_classic_multisig_challenge(&id, &challenge)?;
+        self.classic_multisigs.insert(challenge_hash, challenge);
+        Ok(Address::from_classic_multisig_hash(
+            &self.chain_config,
+            &challenge_hash,
+        )?)
+    }
+
+    /// The classic multisigs watched by this account with their addresses
+    /// and their confirmed coin balances
+    pub fn get_classic_multisigs(
+        &self,
+    ) -> WalletResult<Vec<(Address, ClassicMultisigChallenge, Amount)>> {
+        let utxos = self.get_utxos(UtxoTypes::ALL, UtxoState::Confirmed.into());
+        self.classic_multisigs
+            .iter()
+            .map(|(challenge_hash, challenge)| {
+                let multisig_utxos = utxos.iter().filter(|(_, utxo)| {
+                    Self::is_from_source(utxo, FundsSource::ClassicMultisig(*challenge_hash))
+                });
+                let (balance, _) = self.calculate_utxo_amounts(
+                    multisig_utxos.map(|(outpoint, utxo)| (outpoint.tx_id(), *utxo)),
+                )?;
+                let address =
+                    Address::from_classic_multisig_hash(&self.chain_config, challenge_hash)?;
+                Ok((address, challenge.clone(), balance))
+            })
+            .collect()
+    }
+
+    /// Create a transaction that spends the funds of a watched classic multisig,
+    /// the change goes back to the multisig.
+    ///
+    /// The transaction is not signed, the multisig challenge is added to its inputs,
+    /// so that it can be signed by the owners of the multisig keys.
+    pub fn create_classic_multisig_spend(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        challenge_hash: PublicKeyHash,
+        request: SendRequest,
+        median_time: BlockTimestamp,
+        fee_rate: FeeRate,
+    ) -> WalletResult<PartiallySignedTransaction> {
+        utils::ensure!(
+            self.classic_multisigs.contains_key(&challenge_hash),
+            WalletError::UnknownClassicMultisig(challenge_hash)
+        );
+        let request = self.complete_send_request(
+            db_tx,
+            request,
+            FundsSource::ClassicMultisig(challenge_hash),
+            median_time,
+            fee_rate,
+        )?;
+        self.make_partially_signed_transaction(request)
+    }
+
     /// Get a new public key that hasn't been used before
     pub fn get_new_public_key<B: storage::Backend>(
         &mut self,
@@ -403,16 +1514,81 @@ impl Account {
         }
     }
 
+    /// The destination that can spend `txo`, the delegation outputs are spent by the destination
+    /// that has created the delegation
+    fn get_output_destination<'a>(&'a self, txo: &'a TxOutput) -> Option<&'a Destination> {
+        match txo {
+            TxOutput::DelegateStaking(_, delegation_id) => self
+                .output_cache
+                .delegation_data(delegation_id)
+                .map(|delegation_data| delegation_data.spend_destination()),
+            TxOutput::Transfer(_, _)
+            | TxOutput::LockThenTransfer(_, _, _)
+            | TxOutput::Burn(_)
+            | TxOutput::CreateStakePool(_, _)
+            | TxOutput::ProduceBlockFromStake(_, _)
+            | TxOutput::CreateDelegationId(_, _) => Self::get_tx_output_destination(txo),
+        }
+    }
+
+    /// The destination that must sign the inputs spending `txo`.
+    ///
+    /// The stake pool outputs are owned by the staker, but can only be spent by the decommission key.
+    fn get_spending_destination<'a>(&'a self, txo: &'a TxOutput) -> Option<&'a Destination> {
+        match txo {
+            TxOutput::CreateStakePool(_, data) => Some(data.decommission_key()),
+            TxOutput::ProduceBlockFromStake(_, pool_id) => self
+                .output_cache
+                .pool_data(pool_id)
+                .map(|pool_data| pool_data.decommission_key()),
+            TxOutput::Transfer(_, _)
+            | TxOutput::LockThenTransfer(_, _, _)
+            | TxOutput::Burn(_)
+            | TxOutput::CreateDelegationId(_, _)
+            | TxOutput::DelegateStaking(_, _) => self.get_output_destination(txo),
+        }
+    }
+
     /// Return true if this transaction output is can be spent by this account or if it is being
     /// watched.
     fn is_mine_or_watched(&self, txo: &TxOutput) -> bool {
+        self.get_output_destination(txo)
+            .map_or(false, |d| self.is_destination_mine_or_watched(d))
+    }
+
+    /// Return true if `destination` is where the account sends the change of a transaction
+    /// that spends `input_utxos`
+    fn is_change_destination(
+        &self,
+        destination: &Destination,
+        input_utxos: &[(OutPoint, TxOutput)],
+    ) -> bool {
+        match destination {
+            Destination::Address(_) | Destination::PublicKey(_) => self
+                .key_chain
+                .get_leaf_key_chain(KeyPurpose::Change)
+                .is_destination_mine(destination),
+            // The change of the funds locked by a classic multisig goes back to it
+            Destination::ClassicMultisig(_) => input_utxos
+                .iter()
+                .all(|(_, utxo)| self.get_output_destination(utxo) == Some(destination)),
+            Destination::AnyoneCanSpend | Destination::ScriptHash(_) => false,
+        }
+    }
+
+    /// Return true if the funds sent to `destination` can be spent by this account
+    /// or if they are being watched
+    pub fn is_destination_mine_or_watched(&self, destination: &Destination) -> bool {
         // TODO: Should we really report `AnyoneCanSpend` as own?
-        Self::get_tx_output_destination(txo).map_or(false, |d| match d {
+        match destination {
             Destination::Address(pkh) => self.key_chain.is_public_key_hash_mine(pkh),
             Destination::PublicKey(pk) => self.key_chain.is_public_key_mine(pk),
             Destination::AnyoneCanSpend => true,
-            Destination::ScriptHash(_) | Destination::ClassicMultisig(_) => false,
-        })
+            Destination::ClassicMultisig(challenge_hash) => {
+                self.classic_multisigs.contains_key(challenge_hash)
+            }
+            Destination::ScriptHash(_) => false,
+        }
     }
 
     fn mark_outputs_as_seen(
@@ -433,8 +1609,8 @@ impl Account {
         db_tx: &mut impl WalletStorageWriteLocked,
         output: &TxOutput,
     ) -> WalletResult<bool> {
-        if let Some(d) = Self::get_tx_output_destination(output) {
-            match d {
+        if let Some(d) = self.get_output_destination(output).cloned() {
+            match &d {
                 Destination::Address(pkh) => {
                     let found = self.key_chain.mark_public_key_hash_as_used(db_tx, pkh)?;
                     if found {
@@ -448,7 +1624,12 @@ impl Account {
                     }
                 }
                 Destination::AnyoneCanSpend => return Ok(true),
-                Destination::ClassicMultisig(_) | Destination::ScriptHash(_) => {}
+                Destination::ClassicMultisig(challenge_hash) => {
+                    if self.classic_multisigs.contains_key(challenge_hash) {
+                        return Ok(true);
+                    }
+                }
+                Destination::ScriptHash(_) => {}
             }
         }
         Ok(false)
@@ -457,18 +1638,207 @@ impl Account {
     pub fn get_balance(
         &self,
         utxo_types: UtxoTypes,
+        utxo_states: UtxoStates,
     ) -> WalletResult<(Amount, BTreeMap<TokenId, Amount>)> {
-        let utxos = self.get_utxos(utxo_types);
-        let balances = Self::calculate_output_amounts(utxos.into_values())?;
+        // The classic multisig funds can't be spent by this account alone
+        let utxos = self
+            .get_utxos(utxo_types, utxo_states)
+            .into_iter()
+            .filter(|(_, utxo)| Self::is_from_source(utxo, FundsSource::Account));
+        let balances =
+            self.calculate_utxo_amounts(utxos.map(|(outpoint, utxo)| (outpoint.tx_id(), utxo)))?;
         Ok(balances)
     }
 
-    pub fn get_utxos(&self, utxo_types: UtxoTypes) -> BTreeMap<OutPoint, &TxOutput> {
-        let mut all_outputs = self.output_cache.utxos();
-        all_outputs.retain(|_outpoint, txo| {
-            self.is_mine_or_watched(txo) && utxo_types.contains(get_utxo_type(txo))
-        });
-        all_outputs
+    pub fn get_utxos(
+        &self,
+        utxo_types: UtxoTypes,
+        utxo_states: UtxoStates,
+    ) -> BTreeMap<OutPoint, &TxOutput> {
+        self.output_cache
+            .utxos()
+            .into_iter()
+            .filter(|(_outpoint, (txo, tx_state))| {
+                self.is_mine_or_watched(txo)
+                    && utxo_types.contains(get_utxo_type(txo))
+                    && utxo_states.contains(get_utxo_state(tx_state))
+            })
+            .map(|(outpoint, (txo, _tx_state))| (outpoint, txo))
+            .collect()
+    }
+
+    /// Check if the funds of `txo` come from `source`
+    fn is_from_source(txo: &TxOutput, source: FundsSource) -> bool {
+        let multisig_hash = match Self::get_tx_output_destination(txo) {
+            Some(Destination::ClassicMultisig(challenge_hash)) => Some(*challenge_hash),
+            Some(
+                Destination::AnyoneCanSpend
+                | Destination::Address(_)
+                | Destination::PublicKey(_)
+                | Destination::ScriptHash(_),
+            )
+            | None => None,
+        };
+        match source {
+            FundsSource::Account => multisig_hash.is_none(),
+            FundsSource::ClassicMultisig(source_hash) => multisig_hash == Some(source_hash),
+        }
+    }
+
+    /// The transfer UTXOs from `source` that can be spent in the next block,
+    /// including the time-locked ones whose lock has expired
+    fn get_unlocked_transfer_utxos(
+        &self,
+        source: FundsSource,
+        median_time: BlockTimestamp,
+    ) -> BTreeMap<OutPoint, &TxOutput> {
+        let spend_height = self.account_info.best_block_height().next_height();
+        self.output_cache
+            .utxos()
+            .into_iter()
+            .filter(|(_outpoint, (txo, tx_state))| {
+                let unlocked = match txo {
+                    TxOutput::Transfer(_, _) => true,
+                    TxOutput::LockThenTransfer(_, _, lock) => {
+                        is_unlocked(&unlock_point(lock, tx_state), spend_height, median_time)
+                    }
+                    TxOutput::Burn(_)
+                    | TxOutput::CreateStakePool(_, _)
+                    | TxOutput::ProduceBlockFromStake(_, _)
+                    | TxOutput::CreateDelegationId(_, _)
+                    | TxOutput::DelegateStaking(_, _) => false,
+                };
+                unlocked
+                    && Self::is_from_source(txo, source)
+                    && self.is_mine_or_watched(txo)
+                    && Self::spendable_utxo_states().contains(get_utxo_state(tx_state))
+            })
+            .map(|(outpoint, (txo, _tx_state))| (outpoint, txo))
+            .collect()
+    }
+
+    /// The UTXOs that the automatic input selection can choose from,
+    /// i.e. the unlocked transfer UTXOs that are not frozen
+    fn get_selectable_utxos(
+        &self,
+        source: FundsSource,
+        median_time: BlockTimestamp,
+    ) -> BTreeMap<OutPoint, &TxOutput> {
+        let mut utxos = self.get_unlocked_transfer_utxos(source, median_time);
+        utxos.retain(|outpoint, _txo| !self.frozen_utxos.contains(outpoint));
+        utxos
+    }
+
+    /// Look up the UTXOs of `outpoints` to be used as the inputs of a transaction,
+    /// all of them must be transfer UTXOs of the account that can be spent in the next block.
+    ///
+    /// The frozen UTXOs can be spent this way.
+    pub fn get_utxos_to_spend(
+        &self,
+        outpoints: impl IntoIterator<Item = OutPoint>,
+        median_time: BlockTimestamp,
+    ) -> WalletResult<Vec<(OutPoint, TxOutput)>> {
+        let mut utxos = self.get_unlocked_transfer_utxos(FundsSource::Account, median_time);
+        let selected = outpoints
+            .into_iter()
+            .map(|outpoint| {
+                // Removing the UTXO also rejects the duplicate outpoints
+                let txo = utxos
+                    .remove(&outpoint)
+                    .ok_or_else(|| WalletError::UtxoNotSpendable(outpoint.clone()))?;
+                Ok((outpoint, txo.clone()))
+            })
+            .collect::<WalletResult<Vec<_>>>()?;
+        utils::ensure!(!selected.is_empty(), WalletError::NoUtxos);
+        Ok(selected)
+    }
+
+    /// Exclude an unspent output of the account from the automatic input selection
+    pub fn freeze_utxo(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        outpoint: OutPoint,
+    ) -> WalletResult<()> {
+        let is_known_utxo = self
+            .output_cache
+            .utxos()
+            .get(&outpoint)
+            .map_or(false, |(txo, _tx_state)| self.is_mine_or_watched(txo));
+        utils::ensure!(is_known_utxo, WalletError::UtxoNotFound(outpoint));
+
+        db_tx.set_frozen_utxo(&AccountOutPointId::new(
+            self.get_account_id(),
+            outpoint.clone(),
+        ))?;
+        self.frozen_utxos.insert(outpoint);
+        Ok(())
+    }
+
+    /// Return a frozen output to the automatic input selection
+    pub fn unfreeze_utxo(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        outpoint: OutPoint,
+    ) -> WalletResult<()> {
+        utils::ensure!(
+            self.frozen_utxos.contains(&outpoint),
+            WalletError::UtxoNotFrozen(outpoint)
+        );
+
+        db_tx.del_frozen_utxo(&AccountOutPointId::new(
+            self.get_account_id(),
+            outpoint.clone(),
+        ))?;
+        self.frozen_utxos.remove(&outpoint);
+        Ok(())
+    }
+
+    /// The frozen outputs that are not spent yet
+    pub fn get_frozen_utxos(&self) -> BTreeMap<OutPoint, &TxOutput> {
+        self.output_cache
+            .utxos()
+            .into_iter()
+            .filter(|(outpoint, (txo, _tx_state))| {
+                self.frozen_utxos.contains(outpoint) && self.is_mine_or_watched(txo)
+            })
+            .map(|(outpoint, (txo, _tx_state))| (outpoint, txo))
+            .collect()
+    }
+
+    /// The time-locked UTXOs that can't be spent in the next block yet, with the block height
+    /// or time after which they can be spent
+    pub fn get_locked_utxos(
+        &self,
+        utxo_states: UtxoStates,
+        median_time: BlockTimestamp,
+    ) -> BTreeMap<OutPoint, (&TxOutput, OutputTimeLock)> {
+        let spend_height = self.account_info.best_block_height().next_height();
+        self.output_cache
+            .utxos()
+            .into_iter()
+            .filter(|(_outpoint, (txo, tx_state))| {
+                self.is_mine_or_watched(txo) && utxo_states.contains(get_utxo_state(tx_state))
+            })
+            .filter_map(|(outpoint, (txo, tx_state))| match txo {
+                TxOutput::LockThenTransfer(_, _, lock) => {
+                    let unlock_point = unlock_point(lock, &tx_state);
+                    (!is_unlocked(&unlock_point, spend_height, median_time))
+                        .then_some((outpoint, (txo, unlock_point)))
+                }
+                TxOutput::Transfer(_, _)
+                | TxOutput::Burn(_)
+                | TxOutput::CreateStakePool(_, _)
+                | TxOutput::ProduceBlockFromStake(_, _)
+                | TxOutput::CreateDelegationId(_, _)
+                | TxOutput::DelegateStaking(_, _) => None,
+            })
+            .collect()
+    }
+
+    /// The UTXOs that can be spent by new transactions: the confirmed ones and the ones
+    /// from the mempool, which allows chaining payments
+    fn spendable_utxo_states() -> UtxoStates {
+        UtxoState::Confirmed | UtxoState::InMempool
     }
 
     fn reset_to_height<B: storage::Backend>(
@@ -481,14 +1851,18 @@ impl Account {
             .txs()
             .iter()
             .filter_map(|(id, tx)| match tx.state() {
-                TxState::Confirmed(height) => {
+                TxState::Confirmed(height, _) => {
                     if height > common_block_height {
                         Some(id.clone())
                     } else {
                         None
                     }
                 }
-                TxState::Inactive | TxState::Conflicted(_) | TxState::InMempool => None,
+                TxState::Inactive
+                | TxState::Conflicted(_)
+                | TxState::InMempool
+                | TxState::Replaced(_)
+                | TxState::Abandoned => None,
             })
             .collect::<Vec<_>>();
 
@@ -497,6 +1871,46 @@ impl Account {
             self.output_cache.remove_tx(&tx_id);
         }
 
+        // The transactions that conflicted with the revoked ones can be confirmed again
+        for tx_id in self.output_cache.find_resolved_conflicts() {
+            self.update_tx_state(db_tx, tx_id, TxState::Inactive)?;
+        }
+
+        Ok(())
+    }
+
+    /// Change the state of an unconfirmed transaction
+    fn update_tx_state(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        tx_id: AccountWalletTxId,
+        state: TxState,
+    ) -> WalletResult<()> {
+        let tx = match self.output_cache.txs().get(&tx_id) {
+            Some(WalletTx::Tx(tx_data)) => tx_data.get_transaction().clone(),
+            Some(WalletTx::Block(_)) | None => return Ok(()),
+        };
+
+        let wallet_tx = WalletTx::Tx(TxData::new(tx.into(), state));
+        db_tx.set_transaction(&tx_id, &wallet_tx)?;
+        // Re-add the tx, because the state determines whether its inputs are consumed
+        self.output_cache.remove_tx(&tx_id);
+        self.output_cache.add_tx(tx_id, wallet_tx);
+
+        Ok(())
+    }
+
+    /// Mark the unconfirmed transactions that spend the same outputs as the confirmed `tx`
+    /// (and their descendants) as conflicted with the block that contains `tx`
+    fn mark_conflicting_txs(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        tx: &Transaction,
+        block_id: Id<GenBlock>,
+    ) -> WalletResult<()> {
+        for tx_id in self.output_cache.find_conflicting_txs(tx) {
+            self.update_tx_state(db_tx, tx_id, TxState::Conflicted(block_id))?;
+        }
         Ok(())
     }
 
@@ -506,10 +1920,13 @@ impl Account {
         db_tx: &mut impl WalletStorageWriteLocked,
         tx: WalletTx,
     ) -> WalletResult<()> {
-        let relevant_inputs = tx
-            .inputs()
-            .iter()
-            .any(|input| self.output_cache.outpoints().contains(input.outpoint()));
+        let relevant_inputs = tx.inputs().iter().any(|input| {
+            self.output_cache.outpoints().contains(input.outpoint())
+                || self.output_cache.txs().contains_key(&AccountWalletTxId::new(
+                    self.get_account_id(),
+                    input.outpoint().tx_id(),
+                ))
+        });
         let relevant_outputs = self.mark_outputs_as_seen(db_tx, tx.outputs())?;
         if relevant_inputs || relevant_outputs {
             let id = AccountWalletTxId::new(self.get_account_id(), tx.id());
@@ -548,12 +1965,15 @@ impl Account {
 
         for (index, block) in blocks.iter().enumerate() {
             let block_height = BlockHeight::new(common_block_height.into_int() + index as u64 + 1);
-            let tx_state = TxState::Confirmed(block_height);
+            let tx_state = TxState::Confirmed(block_height, block.timestamp());
 
             let wallet_tx = WalletTx::Block(BlockData::from_block(block, block_height));
             self.add_wallet_tx_if_relevant(db_tx, wallet_tx)?;
 
+            let block_id = block.header().block_id().into();
             for signed_tx in block.transactions() {
+                self.mark_conflicting_txs(db_tx, signed_tx.transaction(), block_id)?;
+
                 let wallet_tx = WalletTx::Tx(TxData::new(
                     signed_tx.transaction().clone().into(),
                     tx_state,
@@ -572,12 +1992,148 @@ impl Account {
         Ok(())
     }
 
+    /// Update the unconfirmed transactions of this account from the current mempool contents.
+    ///
+    /// The relevant mempool transactions are added as `InMempool`, while the transactions
+    /// that have left the mempool without being confirmed become `Inactive`.
+    pub fn scan_mempool(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        transactions: &[SignedTransaction],
+    ) -> WalletResult<()> {
+        let mempool_tx_ids = transactions
+            .iter()
+            .map(|tx| OutPointSourceId::from(tx.transaction().get_id()))
+            .collect::<BTreeSet<_>>();
+
+        let dropped_txs = self
+            .output_cache
+            .txs()
+            .iter()
+            .filter(|(_, tx)| {
+                tx.state() == TxState::InMempool && !mempool_tx_ids.contains(&tx.id())
+            })
+            .map(|(tx_id, _)| tx_id.clone())
+            .collect::<Vec<_>>();
+        for tx_id in dropped_txs {
+            self.update_tx_state(db_tx, tx_id, TxState::Inactive)?;
+        }
+
+        for signed_tx in transactions {
+            self.add_unconfirmed_tx(db_tx, signed_tx, TxState::InMempool)?;
+        }
+
+        Ok(())
+    }
+
+    /// Abandon the unconfirmed transaction `tx_id` that is not in the mempool, together with
+    /// its descendants, so that the outputs spent by them can be used by other transactions
+    pub fn abandon_transaction(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        tx_id: Id<Transaction>,
+    ) -> WalletResult<()> {
+        let wallet_tx_id = AccountWalletTxId::new(self.get_account_id(), tx_id.into());
+        match self.output_cache.txs().get(&wallet_tx_id).map(WalletTx::state) {
+            Some(TxState::Inactive) => {}
+            Some(
+                TxState::Confirmed(_, _)
+                | TxState::InMempool
+                | TxState::Conflicted(_)
+                | TxState::Replaced(_)
+                | TxState::Abandoned,
+            ) => return Err(WalletError::CannotAbandonTransaction(tx_id)),
+            None => return Err(WalletError::NoTransactionFound(tx_id)),
+        }
+
+        for descendant_id in self.output_cache.find_unconfirmed_descendants(&wallet_tx_id) {
+            self.update_tx_state(db_tx, descendant_id, TxState::Abandoned)?;
+        }
+        self.update_tx_state(db_tx, wallet_tx_id, TxState::Abandoned)
+    }
+
+    /// Store a transaction that is not confirmed yet, e.g. one that has been created by this wallet,
+    /// if it is relevant to this account.
+    ///
+    /// The unconfirmed transactions that spend the same outputs as a transaction accepted
+    /// by the mempool (and their descendants) are marked as replaced by it.
+    pub fn add_unconfirmed_tx(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        tx: &SignedTransaction,
+        state: TxState,
+    ) -> WalletResult<()> {
+        let tx_id = AccountWalletTxId::new(self.get_account_id(), tx.transaction().get_id().into());
+        match self.output_cache.txs().get(&tx_id).map(WalletTx::state) {
+            // The state of the confirmed and conflicted txs is updated when blocks are scanned
+            Some(TxState::Confirmed(_, _) | TxState::Conflicted(_) | TxState::Replaced(_)) => {
+                Ok(())
+            }
+            // The abandoned txs that are found in the mempool are no longer abandoned
+            Some(TxState::InMempool | TxState::Inactive | TxState::Abandoned) | None => {
+                if state == TxState::InMempool {
+                    // The replaced txs have to stop consuming their inputs before the new tx is added
+                    let replaced_state = TxState::Replaced(tx.transaction().get_id());
+                    for replaced_tx_id in self.output_cache.find_conflicting_txs(tx.transaction()) {
+                        self.update_tx_state(db_tx, replaced_tx_id, replaced_state)?;
+                    }
+                }
+                let wallet_tx = WalletTx::Tx(TxData::new(tx.transaction().clone().into(), state));
+                self.add_wallet_tx_if_relevant(db_tx, wallet_tx)
+            }
+        }
+    }
+
     pub fn best_block(&self) -> (Id<GenBlock>, BlockHeight) {
         (
             self.account_info.best_block_id(),
             self.account_info.best_block_height(),
         )
     }
+
+    /// Skip the blocks up to the given one, they must not contain anything relevant to the account
+    pub fn set_best_block(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        best_block_height: BlockHeight,
+        best_block_id: Id<GenBlock>,
+    ) -> WalletResult<()> {
+        self.account_info.update_best_block(best_block_height, best_block_id);
+        db_tx.set_account(&self.key_chain.get_account_id(), &self.account_info)?;
+        Ok(())
+    }
+
+    /// Whether any block or transaction relevant to the account has been found
+    pub fn has_transactions(&self) -> bool {
+        !self.output_cache.txs().is_empty()
+    }
+
+    /// Remove the account with its keys and transactions from the database
+    pub fn delete(&self, db_tx: &mut impl WalletStorageWriteLocked) -> WalletResult<()> {
+        let account_id = self.get_account_id();
+
+        for tx_id in db_tx.get_transactions(&account_id)?.into_keys() {
+            db_tx.del_transaction(&tx_id)?;
+        }
+        for address_id in db_tx.get_addresses(&account_id)?.into_keys() {
+            db_tx.del_address(&address_id)?;
+        }
+        for public_key_id in db_tx.get_public_keys(&account_id)?.into_keys() {
+            db_tx.det_public_key(&public_key_id)?;
+        }
+        for purpose_id in db_tx.get_keychain_usage_states(&account_id)?.into_keys() {
+            db_tx.del_keychain_usage_state(&purpose_id)?;
+        }
+        for multisig_id in db_tx.get_classic_multisig_challenges(&account_id)?.into_keys() {
+            db_tx.del_classic_multisig_challenge(&multisig_id)?;
+        }
+        for outpoint_id in db_tx.get_frozen_utxos(&account_id)? {
+            db_tx.del_frozen_utxo(&outpoint_id)?;
+        }
+        db_tx.del_account(&account_id)?;
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -15,7 +15,12 @@
 
 use std::collections::{BTreeMap, BTreeSet};
 
-use common::chain::{OutPoint, TxOutput};
+use common::chain::stakelock::StakePoolData;
+use common::chain::tokens::{get_tokens_issuance_count, token_id, TokenId};
+use common::chain::{DelegationId, OutPoint, OutPointSourceId, PoolId, Transaction, TxOutput};
+use common::primitives::Idable;
+use pos_accounting::{make_delegation_id, DelegationData};
+use wallet_types::wallet_tx::TxState;
 use wallet_types::{AccountWalletTxId, WalletTx};
 
 /// A helper structure for the UTXO search.
@@ -28,10 +33,18 @@ use wallet_types::{AccountWalletTxId, WalletTx};
 /// transactions and blocks. Then we filter the outputs that are from our wallet
 /// (can be signed) to get the final UTXO list that is ready to use.
 /// In case of reorg, top blocks (and the transactions they contain) are simply removed from the DB/cache.
+/// Unconfirmed transactions that conflict with a confirmed transaction are kept, but they neither
+/// consume nor produce any outputs.
 /// A similar approach is used by the Bitcoin Core wallet.
 pub struct OutputCache {
     txs: BTreeMap<AccountWalletTxId, WalletTx>,
     consumed: BTreeSet<OutPoint>,
+    /// The ids of the tokens issued by the cached transactions
+    issued_token_ids: BTreeMap<OutPointSourceId, TokenId>,
+    /// The delegations created by the cached transactions
+    delegations: BTreeMap<DelegationId, DelegationData>,
+    /// The stake pools created by the cached transactions and blocks
+    pools: BTreeMap<PoolId, StakePoolData>,
 }
 
 impl OutputCache {
@@ -39,6 +52,9 @@ impl OutputCache {
         Self {
             txs: BTreeMap::new(),
             consumed: BTreeSet::new(),
+            issued_token_ids: BTreeMap::new(),
+            delegations: BTreeMap::new(),
+            pools: BTreeMap::new(),
         }
     }
 
@@ -59,8 +75,24 @@ impl OutputCache {
     }
 
     pub fn add_tx(&mut self, tx_id: AccountWalletTxId, tx: WalletTx) {
-        for input in tx.inputs() {
-            self.consumed.insert(input.outpoint().clone());
+        if !is_dropped(&tx) {
+            for input in tx.inputs() {
+                self.consumed.insert(input.outpoint().clone());
+            }
+        }
+        if let WalletTx::Tx(tx_data) = &tx {
+            let transaction = tx_data.get_transaction();
+            if get_tokens_issuance_count(transaction.outputs()) > 0 {
+                if let Some(token_id) = token_id(transaction) {
+                    self.issued_token_ids.insert(tx.id(), token_id);
+                }
+            }
+            if let Some((delegation_id, delegation_data)) = created_delegation(transaction) {
+                self.delegations.insert(delegation_id, delegation_data);
+            }
+        }
+        for (pool_id, pool_data) in created_pools(tx.outputs()) {
+            self.pools.insert(*pool_id, pool_data.clone());
         }
         self.txs.insert(tx_id, tx);
     }
@@ -68,28 +100,229 @@ impl OutputCache {
     pub fn remove_tx(&mut self, tx_id: &AccountWalletTxId) {
         let tx_opt = self.txs.remove(tx_id);
         if let Some(tx) = tx_opt {
-            for input in tx.inputs() {
-                self.consumed.remove(input.outpoint());
+            if !is_dropped(&tx) {
+                for input in tx.inputs() {
+                    self.consumed.remove(input.outpoint());
+                }
+            }
+            self.issued_token_ids.remove(&tx.id());
+            if let WalletTx::Tx(tx_data) = &tx {
+                if let Some((delegation_id, _)) = created_delegation(tx_data.get_transaction()) {
+                    self.delegations.remove(&delegation_id);
+                }
+            }
+            for (pool_id, _) in created_pools(tx.outputs()) {
+                self.pools.remove(pool_id);
             }
         }
     }
 
+    /// The id of the tokens issued by the transaction `tx_id`, if it has issuance outputs
+    pub fn issued_token_id(&self, tx_id: &OutPointSourceId) -> Option<TokenId> {
+        self.issued_token_ids.get(tx_id).copied()
+    }
+
+    pub fn delegation_data(&self, delegation_id: &DelegationId) -> Option<&DelegationData> {
+        self.delegations.get(delegation_id)
+    }
+
+    pub fn delegations(&self) -> &BTreeMap<DelegationId, DelegationData> {
+        &self.delegations
+    }
+
+    pub fn pool_data(&self, pool_id: &PoolId) -> Option<&StakePoolData> {
+        self.pools.get(pool_id)
+    }
+
     fn valid_utxo(&self, outpoint: &OutPoint) -> bool {
         !self.consumed.contains(outpoint)
     }
 
-    pub fn utxos(&self) -> BTreeMap<OutPoint, &TxOutput> {
+    /// All the unspent outputs together with the state of the transactions that created them
+    pub fn utxos(&self) -> BTreeMap<OutPoint, (&TxOutput, TxState)> {
         let mut utxos = BTreeMap::new();
 
-        for tx in self.txs.values() {
+        for tx in self.txs.values().filter(|tx| !is_dropped(tx)) {
             for (index, output) in tx.outputs().iter().enumerate() {
                 let outpoint = OutPoint::new(tx.id(), index as u32);
                 if self.valid_utxo(&outpoint) {
-                    utxos.insert(outpoint, output);
+                    utxos.insert(outpoint, (output, tx.state()));
                 }
             }
         }
 
         utxos
     }
+
+    /// Find the other unconfirmed transactions that spend any of the inputs of `tx`
+    /// and all of their descendants, they can't be confirmed together with `tx`
+    pub fn find_conflicting_txs(&self, tx: &Transaction) -> Vec<AccountWalletTxId> {
+        // Only the txs that spend the same outputs as `tx` can conflict with it
+        let spends_consumed =
+            tx.inputs().iter().any(|input| self.consumed.contains(input.outpoint()));
+        if !spends_consumed {
+            return Vec::new();
+        }
+
+        let tx_id: OutPointSourceId = tx.get_id().into();
+        let spent_by_tx = tx.inputs().iter().map(|input| input.outpoint()).collect::<BTreeSet<_>>();
+
+        let mut conflicting_tx_ids = BTreeSet::new();
+        let mut conflicting = Vec::new();
+        // Repeat until no more descendants are found, as the txs are not ordered by dependency
+        loop {
+            let found = self
+                .txs
+                .iter()
+                .filter(|(id, wallet_tx)| {
+                    is_unconfirmed(wallet_tx)
+                        && wallet_tx.id() != tx_id
+                        && !conflicting.contains(*id)
+                        && wallet_tx.inputs().iter().any(|input| {
+                            spent_by_tx.contains(input.outpoint())
+                                || conflicting_tx_ids.contains(&input.outpoint().tx_id())
+                        })
+                })
+                .map(|(id, wallet_tx)| (id.clone(), wallet_tx.id()))
+                .collect::<Vec<_>>();
+
+            if found.is_empty() {
+                return conflicting;
+            }
+
+            for (id, wallet_tx_id) in found {
+                conflicting.push(id);
+                conflicting_tx_ids.insert(wallet_tx_id);
+            }
+        }
+    }
+
+    /// Find the unconfirmed transactions that spend the outputs of `tx_id`,
+    /// directly or through other unconfirmed transactions
+    pub fn find_unconfirmed_descendants(
+        &self,
+        tx_id: &AccountWalletTxId,
+    ) -> Vec<AccountWalletTxId> {
+        let mut ancestor_tx_ids = match self.txs.get(tx_id) {
+            Some(tx) => BTreeSet::from([tx.id()]),
+            None => return Vec::new(),
+        };
+
+        let mut descendants = Vec::new();
+        // Repeat until no more descendants are found, as the txs are not ordered by dependency
+        loop {
+            let found = self
+                .txs
+                .iter()
+                .filter(|(id, wallet_tx)| {
+                    is_unconfirmed(wallet_tx)
+                        && !ancestor_tx_ids.contains(&wallet_tx.id())
+                        && wallet_tx
+                            .inputs()
+                            .iter()
+                            .any(|input| ancestor_tx_ids.contains(&input.outpoint().tx_id()))
+                        && !descendants.contains(*id)
+                })
+                .map(|(id, wallet_tx)| (id.clone(), wallet_tx.id()))
+                .collect::<Vec<_>>();
+
+            if found.is_empty() {
+                return descendants;
+            }
+
+            for (id, wallet_tx_id) in found {
+                descendants.push(id);
+                ancestor_tx_ids.insert(wallet_tx_id);
+            }
+        }
+    }
+
+    /// Find the conflicted transactions whose conflicting transactions are no longer confirmed,
+    /// e.g. because of a reorg
+    pub fn find_resolved_conflicts(&self) -> Vec<AccountWalletTxId> {
+        let spent_by_confirmed = self
+            .txs
+            .values()
+            .filter(|tx| matches!(tx.state(), TxState::Confirmed(_, _)))
+            .flat_map(|tx| tx.inputs().iter().map(|input| input.outpoint()))
+            .collect::<BTreeSet<_>>();
+
+        let mut conflicted =
+            self.txs.iter().filter(|(_, tx)| is_conflicted(tx)).collect::<BTreeMap<_, _>>();
+
+        // A transaction stays conflicted if any of its inputs is spent by a confirmed transaction
+        // or it spends an output of another conflicted transaction
+        loop {
+            let conflicted_tx_ids = conflicted.values().map(|tx| tx.id()).collect::<BTreeSet<_>>();
+            let conflicted_count = conflicted.len();
+            conflicted.retain(|_, tx| {
+                tx.inputs().iter().any(|input| {
+                    spent_by_confirmed.contains(input.outpoint())
+                        || conflicted_tx_ids.contains(&input.outpoint().tx_id())
+                })
+            });
+            if conflicted.len() == conflicted_count {
+                break;
+            }
+        }
+
+        self.txs
+            .iter()
+            .filter(|(id, tx)| is_conflicted(tx) && !conflicted.contains_key(id))
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+}
+
+/// The delegation created by `tx`, if any
+fn created_delegation(tx: &Transaction) -> Option<(DelegationId, DelegationData)> {
+    let input0 = tx.inputs().get(0)?;
+    tx.outputs().iter().find_map(|output| match output {
+        TxOutput::CreateDelegationId(spend_destination, pool_id) => Some((
+            make_delegation_id(input0.outpoint()),
+            DelegationData::new(*pool_id, spend_destination.clone()),
+        )),
+        TxOutput::Transfer(_, _)
+        | TxOutput::LockThenTransfer(_, _, _)
+        | TxOutput::Burn(_)
+        | TxOutput::CreateStakePool(_, _)
+        | TxOutput::ProduceBlockFromStake(_, _)
+        | TxOutput::DelegateStaking(_, _) => None,
+    })
+}
+
+/// The stake pools created by `outputs`
+fn created_pools(outputs: &[TxOutput]) -> impl Iterator<Item = (&PoolId, &StakePoolData)> {
+    outputs.iter().filter_map(|output| match output {
+        TxOutput::CreateStakePool(pool_id, pool_data) => Some((pool_id, pool_data.as_ref())),
+        TxOutput::Transfer(_, _)
+        | TxOutput::LockThenTransfer(_, _, _)
+        | TxOutput::Burn(_)
+        | TxOutput::ProduceBlockFromStake(_, _)
+        | TxOutput::CreateDelegationId(_, _)
+        | TxOutput::DelegateStaking(_, _) => None,
+    })
+}
+
+fn is_conflicted(tx: &WalletTx) -> bool {
+    matches!(tx.state(), TxState::Conflicted(_))
+}
+
+/// The conflicted, replaced and abandoned transactions don't consume their inputs
+/// and don't create spendable outputs
+fn is_dropped(tx: &WalletTx) -> bool {
+    matches!(
+        tx.state(),
+        TxState::Conflicted(_) | TxState::Replaced(_) | TxState::Abandoned
+    )
+}
+
+fn is_unconfirmed(tx: &WalletTx) -> bool {
+    match tx.state() {
+        TxState::InMempool | TxState::Inactive => true,
+        TxState::Confirmed(_, _)
+        | TxState::Conflicted(_)
+        | TxState::Replaced(_)
+        | TxState::Abandoned => false,
+    }
 }
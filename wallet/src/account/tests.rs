@@ -14,7 +14,7 @@
 // limitations under the License.
 
 use super::*;
-use crate::key_chain::MasterKeyChain;
+use crate::key_chain::{MasterKeyChain, LOOKAHEAD_SIZE};
 use common::address::pubkeyhash::PublicKeyHash;
 use common::chain::config::create_regtest;
 use common::chain::signature::verify_signature;
@@ -46,10 +46,10 @@ fn account_addresses() {
         MasterKeyChain::new_from_mnemonic(config.clone(), &mut db_tx, MNEMONIC, None).unwrap();
 
     let key_chain = master_key_chain
-        .create_account_key_chain(&mut db_tx, DEFAULT_ACCOUNT_INDEX)
+        .create_account_key_chain(&mut db_tx, DEFAULT_ACCOUNT_INDEX, LOOKAHEAD_SIZE)
         .unwrap();
 
-    let mut account = Account::new(config, &mut db_tx, key_chain).unwrap();
+    let mut account = Account::new(config, &mut db_tx, key_chain, None).unwrap();
     db_tx.commit().unwrap();
 
     let test_vec = vec![
@@ -75,9 +75,9 @@ fn account_addresses_lookahead() {
         MasterKeyChain::new_from_mnemonic(config.clone(), &mut db_tx, MNEMONIC, None).unwrap();
 
     let key_chain = master_key_chain
-        .create_account_key_chain(&mut db_tx, DEFAULT_ACCOUNT_INDEX)
+        .create_account_key_chain(&mut db_tx, DEFAULT_ACCOUNT_INDEX, LOOKAHEAD_SIZE)
         .unwrap();
-    let mut account = Account::new(config, &mut db_tx, key_chain).unwrap();
+    let mut account = Account::new(config, &mut db_tx, key_chain, None).unwrap();
 
     assert_eq!(
         account.key_chain.get_leaf_key_chain(ReceiveFunds).last_issued(),
@@ -116,9 +116,9 @@ fn sign_transaction(#[case] seed: Seed) {
         MasterKeyChain::new_from_mnemonic(config.clone(), &mut db_tx, MNEMONIC, None).unwrap();
 
     let key_chain = master_key_chain
-        .create_account_key_chain(&mut db_tx, DEFAULT_ACCOUNT_INDEX)
+        .create_account_key_chain(&mut db_tx, DEFAULT_ACCOUNT_INDEX, LOOKAHEAD_SIZE)
         .unwrap();
-    let mut account = Account::new(config.clone(), &mut db_tx, key_chain).unwrap();
+    let mut account = Account::new(config.clone(), &mut db_tx, key_chain, None).unwrap();
 
     let amounts: Vec<Amount> = (0..(2 + rng.next_u32() % 5))
         .map(|_| Amount::from_atoms(rng.next_u32() as UnsignedIntType))
@@ -0,0 +1,81 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Estimation of the encoded size of signed transactions before they are signed.
+
+use common::chain::classic_multisig::ClassicMultisigChallenge;
+use common::chain::signature::inputsig::standard_signature::StandardInputSignature;
+use common::chain::signature::inputsig::InputWitness;
+use common::chain::signature::sighash::sighashtype::SigHashType;
+use common::chain::{Destination, Transaction};
+use serialization::{Compact, Encode};
+
+use crate::{WalletError, WalletResult};
+
+/// Encoded size of a Schnorr signature: the signature kind byte followed by 64 bytes of data
+const SIGNATURE_SIZE: usize = 65;
+
+/// Encoded size of a compressed secp256k1 public key: the key kind byte followed by 33 bytes of data
+const PUBLIC_KEY_SIZE: usize = 34;
+
+fn compact_len_size(len: usize) -> usize {
+    Compact::<u32>(len as u32).encoded_size()
+}
+
+fn standard_witness_size(raw_signature_size: usize) -> usize {
+    let sighash_type = SigHashType::try_from(SigHashType::ALL).expect("Should not fail");
+    InputWitness::Standard(StandardInputSignature::new(
+        sighash_type,
+        vec![0; raw_signature_size],
+    ))
+    .encoded_size()
+}
+
+/// Size of the witness of a fully signed classic multisig input
+pub fn classic_multisig_witness_size(challenge: &ClassicMultisigChallenge) -> usize {
+    let signatures_count = challenge.min_required_signatures() as usize;
+    // The signatures are stored in a map from the public key index (u8) to the signature
+    let signatures_size =
+        compact_len_size(signatures_count) + signatures_count * (1 + SIGNATURE_SIZE);
+    standard_witness_size(signatures_size + challenge.encoded_size())
+}
+
+/// Size of the witness needed to spend an output locked to `destination`.
+///
+/// Spending a classic multisig output requires the challenge, which can't be recovered from the destination.
+pub fn input_witness_size(
+    destination: &Destination,
+    multisig_challenge: Option<&ClassicMultisigChallenge>,
+) -> WalletResult<usize> {
+    match destination {
+        Destination::AnyoneCanSpend => Ok(InputWitness::NoSignature(None).encoded_size()),
+        Destination::PublicKey(_) => Ok(standard_witness_size(SIGNATURE_SIZE)),
+        Destination::Address(_) => Ok(standard_witness_size(PUBLIC_KEY_SIZE + SIGNATURE_SIZE)),
+        Destination::ClassicMultisig(_) => multisig_challenge
+            .map(classic_multisig_witness_size)
+            .ok_or_else(|| WalletError::UnsupportedInputDestination(destination.clone())),
+        Destination::ScriptHash(_) => Err(WalletError::UnsupportedInputDestination(
+            destination.clone(),
+        )),
+    }
+}
+
+/// Size of `tx` once it is signed with witnesses of the given sizes, one per input
+pub fn signed_tx_size(tx: &Transaction, witness_sizes: &[usize]) -> usize {
+    tx.encoded_size() + compact_len_size(witness_sizes.len()) + witness_sizes.iter().sum::<usize>()
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,108 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::num::NonZeroU8;
+
+use super::*;
+use common::address::pubkeyhash::PublicKeyHash;
+use common::chain::config::create_regtest;
+use common::chain::signature::inputsig::authorize_pubkey_spend::sign_pubkey_spending;
+use common::chain::signature::inputsig::authorize_pubkeyhash_spend::sign_address_spending;
+use common::chain::signature::inputsig::classical_multisig::authorize_classical_multisig::AuthorizedClassicalMultisigSpend;
+use common::primitives::H256;
+use crypto::key::{KeyKind, PrivateKey, PublicKey};
+use crypto::random::Rng;
+use rstest::rstest;
+use test_utils::random::{make_seedable_rng, Seed};
+
+fn standard_witness(raw_signature: Vec<u8>) -> InputWitness {
+    let sighash_type = SigHashType::try_from(SigHashType::ALL).unwrap();
+    InputWitness::Standard(StandardInputSignature::new(sighash_type, raw_signature))
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn single_key_witness_sizes(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let (private_key, public_key) = PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+    let sighash = H256::random_using(&mut rng);
+
+    let pkh = PublicKeyHash::from(&public_key);
+    let witness =
+        standard_witness(sign_address_spending(&private_key, &pkh, &sighash).unwrap().encode());
+    assert_eq!(
+        input_witness_size(&Destination::Address(pkh), None).unwrap(),
+        witness.encoded_size()
+    );
+
+    let witness = standard_witness(
+        sign_pubkey_spending(&private_key, &public_key, &sighash).unwrap().encode(),
+    );
+    assert_eq!(
+        input_witness_size(&Destination::PublicKey(public_key), None).unwrap(),
+        witness.encoded_size()
+    );
+
+    assert_eq!(
+        input_witness_size(&Destination::AnyoneCanSpend, None).unwrap(),
+        InputWitness::NoSignature(None).encoded_size()
+    );
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn classic_multisig_witness_sizes(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = create_regtest();
+
+    let keys_count = rng.gen_range(1..=chain_config.max_classic_multisig_public_keys_count());
+    let min_required_signatures = rng.gen_range(1..=keys_count);
+    let private_keys = (0..keys_count)
+        .map(|_| PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr).0)
+        .collect::<Vec<_>>();
+    let challenge = ClassicMultisigChallenge::new(
+        &chain_config,
+        NonZeroU8::new(min_required_signatures as u8).unwrap(),
+        private_keys.iter().map(PublicKey::from_private_key).collect(),
+    )
+    .unwrap();
+    let destination = Destination::ClassicMultisig(PublicKeyHash::from(&challenge));
+
+    assert_eq!(
+        input_witness_size(&destination, None),
+        Err(WalletError::UnsupportedInputDestination(
+            destination.clone()
+        ))
+    );
+
+    let message = H256::random_using(&mut rng).encode();
+    let signatures = private_keys
+        .iter()
+        .take(min_required_signatures)
+        .enumerate()
+        .map(|(index, key)| (index as u8, key.sign_message(&message).unwrap()))
+        .collect::<BTreeMap<_, _>>();
+    let witness = standard_witness(
+        AuthorizedClassicalMultisigSpend::new(signatures, challenge.clone()).encode(),
+    );
+
+    assert_eq!(
+        input_witness_size(&destination, Some(&challenge)).unwrap(),
+        witness.encoded_size()
+    );
+}
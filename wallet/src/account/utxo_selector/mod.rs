@@ -0,0 +1,268 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Coin selection for the wallet.
+//!
+//! Two strategies are tried in order:
+//! 1. Branch and bound, which looks for an input set whose value falls in the window
+//!    `[target, target + cost_of_change]`, so that no change output is needed.
+//! 2. A knapsack fallback, which either takes the smallest single UTXO that covers the target
+//!    or accumulates the UTXOs smaller than the target largest-first and then drops the
+//!    ones that are not needed, whichever leaves less change.
+
+use common::chain::{OutPoint, TxOutput};
+use common::primitives::Amount;
+
+/// The maximum number of branches explored by the branch and bound search
+const TOTAL_TRIES: usize = 100_000;
+
+#[derive(thiserror::Error, Debug, Eq, PartialEq)]
+pub enum UtxoSelectorError {
+    #[error("Not enough funds, available: {0:?}, required: {1:?}")]
+    NotEnoughFunds(Amount, Amount),
+    #[error("Amount overflow in coin selection")]
+    AmountOverflow,
+}
+
+/// A UTXO that can be used as a transaction input together with the value it contributes
+#[derive(Debug, Clone)]
+pub struct OutputGroup {
+    outpoint: OutPoint,
+    output: TxOutput,
+    value: Amount,
+}
+
+impl OutputGroup {
+    pub fn new(outpoint: OutPoint, output: TxOutput, value: Amount) -> Self {
+        Self {
+            outpoint,
+            output,
+            value,
+        }
+    }
+
+    pub fn value(&self) -> Amount {
+        self.value
+    }
+}
+
+/// The outcome of a successful coin selection
+#[derive(Debug, Clone)]
+pub struct SelectionResult {
+    outputs: Vec<OutputGroup>,
+    total_value: Amount,
+    target: Amount,
+}
+
+impl SelectionResult {
+    fn new(outputs: Vec<OutputGroup>, target: Amount) -> Result<Self, UtxoSelectorError> {
+        let total_value = sum_values(outputs.iter())?;
+        debug_assert!(total_value >= target);
+        Ok(Self {
+            outputs,
+            total_value,
+            target,
+        })
+    }
+
+    pub fn outputs(&self) -> &[OutputGroup] {
+        &self.outputs
+    }
+
+    pub fn total_value(&self) -> Amount {
+        self.total_value
+    }
+
+    /// The value of the selected inputs that exceeds the selection target
+    pub fn excess(&self) -> Amount {
+        (self.total_value - self.target).expect("total value is not less than the target")
+    }
+
+    pub fn into_output_pairs(self) -> Vec<(OutPoint, TxOutput)> {
+        self.outputs.into_iter().map(|group| (group.outpoint, group.output)).collect()
+    }
+}
+
+fn sum_values<'a>(
+    groups: impl Iterator<Item = &'a OutputGroup>,
+) -> Result<Amount, UtxoSelectorError> {
+    groups
+        .map(|group| group.value)
+        .sum::<Option<Amount>>()
+        .ok_or(UtxoSelectorError::AmountOverflow)
+}
+
+/// Select a set of UTXOs from `utxo_pool` that covers `target`.
+///
+/// `cost_of_change` is the amount the caller is willing to lose to fees instead of
+/// creating a change output; it is only used by the branch and bound search.
+pub fn select_coins(
+    utxo_pool: Vec<OutputGroup>,
+    target: Amount,
+    cost_of_change: Amount,
+) -> Result<SelectionResult, UtxoSelectorError> {
+    let total_available = sum_values(utxo_pool.iter())?;
+    if total_available < target {
+        return Err(UtxoSelectorError::NotEnoughFunds(total_available, target));
+    }
+
+    if target == Amount::ZERO {
+        return SelectionResult::new(Vec::new(), target);
+    }
+
+    let mut utxo_pool = utxo_pool;
+    // Largest first exploration for both strategies
+    utxo_pool.sort_by(|a, b| b.value.cmp(&a.value));
+
+    let selected = match select_coins_bnb(&utxo_pool, target, cost_of_change, total_available)? {
+        Some(selected) => selected,
+        None => select_coins_knapsack(&utxo_pool, target)?,
+    };
+
+    let selected = selected.into_iter().map(|index| utxo_pool[index].clone()).collect();
+    SelectionResult::new(selected, target)
+}
+
+/// Depth first search for an input set whose value is in `[target, target + cost_of_change]`.
+///
+/// `utxo_pool` must be sorted by value in descending order.
+/// Returns the indexes of the selected UTXOs with the least excess value, if any were found.
+fn select_coins_bnb(
+    utxo_pool: &[OutputGroup],
+    target: Amount,
+    cost_of_change: Amount,
+    total_available: Amount,
+) -> Result<Option<Vec<usize>>, UtxoSelectorError> {
+    let upper_bound = (target + cost_of_change).ok_or(UtxoSelectorError::AmountOverflow)?;
+
+    let mut curr_value = Amount::ZERO;
+    let mut curr_available = total_available;
+    let mut curr_selection: Vec<usize> = Vec::new();
+    let mut best_selection: Option<Vec<usize>> = None;
+    let mut best_excess = Amount::MAX;
+
+    let mut utxo_pool_index = 0;
+    for _ in 0..TOTAL_TRIES {
+        let reachable = (curr_value + curr_available).ok_or(UtxoSelectorError::AmountOverflow)?;
+
+        let backtrack = if reachable < target || curr_value > upper_bound {
+            // Either this branch can't reach the target or it has already exceeded the window
+            true
+        } else if curr_value >= target {
+            // A valid solution, remember it if it wastes less than the best one so far
+            let excess = (curr_value - target).expect("checked above");
+            if excess <= best_excess {
+                best_excess = excess;
+                best_selection = Some(curr_selection.clone());
+            }
+            true
+        } else {
+            false
+        };
+
+        if backtrack {
+            let last_included = match curr_selection.last() {
+                Some(index) => *index,
+                // Nothing left to explore
+                None => break,
+            };
+
+            // Add the omitted UTXOs back before exploring the branch that excludes the last included one
+            utxo_pool_index -= 1;
+            while utxo_pool_index > last_included {
+                curr_available = (curr_available + utxo_pool[utxo_pool_index].value)
+                    .ok_or(UtxoSelectorError::AmountOverflow)?;
+                utxo_pool_index -= 1;
+            }
+
+            // The UTXO was included on the previous iterations, try excluding it now
+            curr_value = (curr_value - utxo_pool[utxo_pool_index].value)
+                .expect("the value was added before");
+            curr_selection.pop();
+        } else {
+            let utxo = &utxo_pool[utxo_pool_index];
+            curr_available = (curr_available - utxo.value).expect("the value was added before");
+
+            // Skip the inclusion branch if the previous UTXO has the same value and was excluded,
+            // because that branch has already been explored.
+            let previous_excluded_with_same_value = !curr_selection.is_empty()
+                && curr_selection.last() != Some(&(utxo_pool_index - 1))
+                && utxo_pool[utxo_pool_index - 1].value == utxo.value;
+
+            if !previous_excluded_with_same_value {
+                curr_selection.push(utxo_pool_index);
+                curr_value = (curr_value + utxo.value).ok_or(UtxoSelectorError::AmountOverflow)?;
+            }
+        }
+
+        utxo_pool_index += 1;
+    }
+
+    Ok(best_selection)
+}
+
+/// Fallback selection used when no changeless solution is found.
+///
+/// `utxo_pool` must be sorted by value in descending order, and its total value must cover the target.
+fn select_coins_knapsack(
+    utxo_pool: &[OutputGroup],
+    target: Amount,
+) -> Result<Vec<usize>, UtxoSelectorError> {
+    // The smallest UTXO that covers the target on its own
+    let lowest_larger = utxo_pool.iter().rposition(|utxo| utxo.value >= target);
+
+    if let Some(index) = lowest_larger {
+        if utxo_pool[index].value == target {
+            return Ok(vec![index]);
+        }
+    }
+
+    // Accumulate the UTXOs smaller than the target, largest first
+    let mut accumulated = Vec::new();
+    let mut accumulated_value = Amount::ZERO;
+    for (index, utxo) in utxo_pool.iter().enumerate().filter(|(_, utxo)| utxo.value < target) {
+        if accumulated_value >= target {
+            break;
+        }
+        accumulated.push(index);
+        accumulated_value =
+            (accumulated_value + utxo.value).ok_or(UtxoSelectorError::AmountOverflow)?;
+    }
+
+    if accumulated_value < target {
+        // The smaller UTXOs are not enough, so there must be a larger one
+        return Ok(vec![lowest_larger.expect("total value covers the target")]);
+    }
+
+    // Drop the UTXOs that are not needed to reach the target, smallest first
+    let mut position = accumulated.len();
+    while position > 0 {
+        position -= 1;
+        let value = utxo_pool[accumulated[position]].value;
+        let without = (accumulated_value - value).expect("value is part of the sum");
+        if without >= target {
+            accumulated.remove(position);
+            accumulated_value = without;
+        }
+    }
+
+    match lowest_larger {
+        Some(index) if utxo_pool[index].value <= accumulated_value => Ok(vec![index]),
+        _ => Ok(accumulated),
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,129 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use common::chain::tokens::OutputValue;
+use common::chain::{Destination, OutPointSourceId, Transaction};
+use common::primitives::amount::UnsignedIntType;
+use common::primitives::{Id, H256};
+use crypto::random::Rng;
+use rstest::rstest;
+use test_utils::random::{make_seedable_rng, Seed};
+
+fn make_pool(amounts: &[UnsignedIntType]) -> Vec<OutputGroup> {
+    let source_id: OutPointSourceId = Id::<Transaction>::new(H256::zero()).into();
+    amounts
+        .iter()
+        .enumerate()
+        .map(|(index, atoms)| {
+            let amount = Amount::from_atoms(*atoms);
+            OutputGroup::new(
+                OutPoint::new(source_id.clone(), index as u32),
+                TxOutput::Transfer(OutputValue::Coin(amount), Destination::AnyoneCanSpend),
+                amount,
+            )
+        })
+        .collect()
+}
+
+fn selected_amounts(result: &SelectionResult) -> Vec<UnsignedIntType> {
+    let mut amounts: Vec<_> = result.outputs().iter().map(|g| g.value().into_atoms()).collect();
+    amounts.sort();
+    amounts
+}
+
+#[test]
+fn not_enough_funds() {
+    let pool = make_pool(&[1, 2, 3]);
+    assert_eq!(
+        select_coins(pool, Amount::from_atoms(7), Amount::ZERO).unwrap_err(),
+        UtxoSelectorError::NotEnoughFunds(Amount::from_atoms(6), Amount::from_atoms(7))
+    );
+
+    assert_eq!(
+        select_coins(Vec::new(), Amount::from_atoms(1), Amount::ZERO).unwrap_err(),
+        UtxoSelectorError::NotEnoughFunds(Amount::ZERO, Amount::from_atoms(1))
+    );
+}
+
+#[test]
+fn bnb_finds_exact_match() {
+    let pool = make_pool(&[1, 2, 5, 10, 20, 50]);
+    let result = select_coins(pool, Amount::from_atoms(27), Amount::ZERO).unwrap();
+    assert_eq!(selected_amounts(&result), vec![2, 5, 20]);
+    assert_eq!(result.excess(), Amount::ZERO);
+}
+
+#[test]
+fn bnb_uses_cost_of_change_window() {
+    let pool = make_pool(&[4, 9, 30]);
+    let result = select_coins(pool, Amount::from_atoms(12), Amount::from_atoms(1)).unwrap();
+    assert_eq!(selected_amounts(&result), vec![4, 9]);
+    assert_eq!(result.excess(), Amount::from_atoms(1));
+}
+
+#[test]
+fn knapsack_prefers_lowest_larger() {
+    let pool = make_pool(&[1, 2, 3, 100, 1000]);
+    let result = select_coins(pool, Amount::from_atoms(50), Amount::ZERO).unwrap();
+    assert_eq!(selected_amounts(&result), vec![100]);
+}
+
+#[test]
+fn knapsack_prunes_unneeded_inputs() {
+    let pool = make_pool(&[7, 6, 4, 1000]);
+    let result = select_coins(pool, Amount::from_atoms(12), Amount::ZERO).unwrap();
+    assert_eq!(selected_amounts(&result), vec![6, 7]);
+    assert_eq!(result.excess(), Amount::from_atoms(1));
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn random_selection_covers_target(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+
+    let amounts: Vec<UnsignedIntType> =
+        (0..rng.gen_range(1..100)).map(|_| rng.gen_range(1..1_000_000)).collect();
+    let total: UnsignedIntType = amounts.iter().sum();
+    let target = Amount::from_atoms(rng.gen_range(1..=total));
+    let cost_of_change = Amount::from_atoms(rng.gen_range(0..1000));
+
+    let result = select_coins(make_pool(&amounts), target, cost_of_change).unwrap();
+
+    assert!(result.total_value() >= target);
+    let sum = result
+        .outputs()
+        .iter()
+        .map(|group| group.value())
+        .sum::<Option<Amount>>()
+        .unwrap();
+    assert_eq!(sum, result.total_value());
+
+    // A changeless solution stays within the cost of change and every selected input is needed
+    let mut pool = make_pool(&amounts);
+    pool.sort_by(|a, b| b.value().cmp(&a.value()));
+    let total_available = Amount::from_atoms(total);
+    if let Some(selected) =
+        select_coins_bnb(&pool, target, cost_of_change, total_available).unwrap()
+    {
+        let selected_value = sum_values(selected.iter().map(|index| &pool[*index])).unwrap();
+        assert!(selected_value >= target);
+        assert!(selected_value <= (target + cost_of_change).unwrap());
+        for index in &selected {
+            assert!((selected_value - pool[*index].value()).unwrap() < target);
+        }
+    }
+}
@@ -0,0 +1,107 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Encrypted wallet backups.
+//!
+//! A backup contains the root key of the wallet and the metadata that can't be found
+//! by scanning the blockchain. The wallet is restored from it by creating the accounts again
+//! and rescanning them.
+
+use std::collections::BTreeMap;
+
+use common::address::Address;
+use common::chain::classic_multisig::ClassicMultisigChallenge;
+use common::chain::{DelegationId, PoolId};
+use crypto::kdf::KdfChallenge;
+use crypto::key::extended::ExtendedPublicKey;
+use crypto::key::hdkd::u31::U31;
+use crypto::random::make_true_rng;
+use serialization::{Decode, DecodeAll, Encode};
+use wallet_storage::{challenge_to_sym_key, password_to_sym_key};
+use wallet_types::RootKeyContent;
+
+use crate::{WalletError, WalletResult};
+
+pub const WALLET_BACKUP_VERSION_V1: u32 = 1;
+
+/// The metadata of an account stored in a wallet backup
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct AccountBackup {
+    pub account_index: U31,
+    /// The account is watch-only if this key is not derived from the root key of the backup
+    pub account_pubkey: ExtendedPublicKey,
+    pub name: Option<String>,
+    pub lookahead_size: u32,
+    /// The classic multisigs watched by the account, their funds are found by the rescan
+    pub classic_multisigs: Vec<ClassicMultisigChallenge>,
+    /// The stake pools of the account when the backup was made
+    pub pool_ids: Vec<PoolId>,
+    /// The delegations of the account when the backup was made
+    pub delegation_ids: Vec<DelegationId>,
+}
+
+/// The content of a wallet backup before it is encrypted
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct WalletBackup {
+    /// Watch-only wallets have no root key
+    pub root_key: Option<RootKeyContent>,
+    pub accounts: Vec<AccountBackup>,
+    pub address_labels: BTreeMap<Address, String>,
+    pub address_book: BTreeMap<Address, String>,
+}
+
+/// The encoded backup file
+#[derive(Debug, Clone, Encode, Decode)]
+struct EncryptedWalletBackup {
+    version: u32,
+    kdf_challenge: KdfChallenge,
+    encrypted_content: Vec<u8>,
+}
+
+impl WalletBackup {
+    /// Encrypt the backup with a key derived from `password` and encode it
+    pub fn encrypt(&self, password: &String) -> WalletResult<Vec<u8>> {
+        let (sym_key, kdf_challenge) = password_to_sym_key(password)?;
+        let encrypted_content = sym_key
+            .encrypt(&self.encode(), &mut make_true_rng(), None)
+            .map_err(WalletError::BackupEncryption)?;
+        let backup = EncryptedWalletBackup {
+            version: WALLET_BACKUP_VERSION_V1,
+            kdf_challenge,
+            encrypted_content,
+        };
+        Ok(backup.encode())
+    }
+
+    /// Decode and decrypt a backup made by `encrypt`
+    pub fn decrypt(data: &[u8], password: &String) -> WalletResult<Self> {
+        let backup = EncryptedWalletBackup::decode_all(&mut &data[..])
+            .map_err(|_| WalletError::InvalidBackup)?;
+        utils::ensure!(
+            backup.version == WALLET_BACKUP_VERSION_V1,
+            WalletError::UnsupportedBackupVersion(backup.version)
+        );
+
+        let sym_key = challenge_to_sym_key(password, backup.kdf_challenge)?;
+        // The authenticated decryption fails if the password is wrong
+        let content = sym_key
+            .decrypt(&backup.encrypted_content, None)
+            .map_err(|_| WalletError::BackupDecryption)?;
+        Self::decode_all(&mut content.as_slice()).map_err(|_| WalletError::InvalidBackup)
+    }
+}
+
+#[cfg(test)]
+mod tests;
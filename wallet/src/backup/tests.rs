@@ -0,0 +1,75 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use crypto::key::extended::{ExtendedKeyKind, ExtendedPrivateKey};
+
+fn make_backup() -> WalletBackup {
+    let (root_key, _) = ExtendedPrivateKey::new_from_entropy(ExtendedKeyKind::Secp256k1Schnorr);
+    let (_, account_pubkey) =
+        ExtendedPrivateKey::new_from_entropy(ExtendedKeyKind::Secp256k1Schnorr);
+    WalletBackup {
+        root_key: Some(root_key.into()),
+        accounts: vec![AccountBackup {
+            account_index: U31::ZERO,
+            account_pubkey,
+            name: Some("savings".to_owned()),
+            lookahead_size: 20,
+            classic_multisigs: Vec::new(),
+            pool_ids: Vec::new(),
+            delegation_ids: Vec::new(),
+        }],
+        address_labels: BTreeMap::new(),
+        address_book: BTreeMap::new(),
+    }
+}
+
+#[test]
+fn encrypt_decrypt() {
+    let password = "password".to_owned();
+    let backup = make_backup();
+    let data = backup.encrypt(&password).unwrap();
+
+    assert_eq!(WalletBackup::decrypt(&data, &password).unwrap(), backup);
+    assert_eq!(
+        WalletBackup::decrypt(&data, &"wrong password".to_owned()),
+        Err(WalletError::BackupDecryption)
+    );
+    assert_eq!(
+        WalletBackup::decrypt(&data[..data.len() - 1], &password),
+        Err(WalletError::InvalidBackup)
+    );
+    assert_eq!(
+        make_backup().encrypt(&String::new()),
+        Err(WalletError::DatabaseError(
+            wallet_storage::Error::WalletEmptyPassword
+        ))
+    );
+}
+
+#[test]
+fn unsupported_version() {
+    let password = "password".to_owned();
+    let data = make_backup().encrypt(&password).unwrap();
+    let mut backup = EncryptedWalletBackup::decode_all(&mut data.as_slice()).unwrap();
+    backup.version = WALLET_BACKUP_VERSION_V1 + 1;
+
+    assert_eq!(
+        WalletBackup::decrypt(&backup.encode(), &password),
+        Err(WalletError::UnsupportedBackupVersion(
+            WALLET_BACKUP_VERSION_V1 + 1
+        ))
+    );
+}
@@ -26,6 +26,7 @@ use crypto::key::hdkd::u31::U31;
 use crypto::key::PublicKey;
 use std::sync::Arc;
 use utils::const_value::ConstValue;
+use utils::ensure;
 use wallet_storage::{StoreTxRo, WalletStorageReadUnlocked, WalletStorageWriteLocked};
 use wallet_types::keys::KeyPurpose;
 use wallet_types::{AccountId, AccountInfo};
@@ -62,6 +63,26 @@ impl AccountKeyChain {
 
         let account_pubkey = account_privkey.to_public_key();
 
+        Self::new_from_account_key(
+            chain_config,
+            db_tx,
+            account_pubkey,
+            account_index,
+            lookahead_size,
+        )
+    }
+
+    /// Create a key chain from the account-level extended public key only.
+    ///
+    /// The private keys are derived from the root key of the wallet when needed, so if the
+    /// account key doesn't belong to the root key of the wallet, the account is watch-only.
+    pub fn new_from_account_key(
+        chain_config: Arc<ChainConfig>,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        account_pubkey: ExtendedPublicKey,
+        account_index: U31,
+        lookahead_size: u32,
+    ) -> KeyChainResult<AccountKeyChain> {
         let account_id = AccountId::new_from_xpub(&account_pubkey);
 
         let receiving_key_chain = LeafKeySoftChain::new_empty(
@@ -105,8 +126,13 @@ impl AccountKeyChain {
     ) -> KeyChainResult<ExtendedPrivateKey> {
         let account_path = make_account_path(&self.chain_config, self.account_index);
 
-        let root_key = MasterKeyChain::load_root_key(db_tx)?.derive_absolute_path(&account_path)?;
-        Ok(root_key)
+        let account_key =
+            MasterKeyChain::load_root_key(db_tx)?.derive_absolute_path(&account_path)?;
+        ensure!(
+            account_key.to_public_key() == *self.account_public_key(),
+            KeyChainError::WatchOnlyAccount
+        );
+        Ok(account_key)
     }
 
     /// Load the key chain from the database
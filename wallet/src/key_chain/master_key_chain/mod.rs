@@ -14,9 +14,9 @@
 // limitations under the License.
 
 use crate::key_chain::account_key_chain::AccountKeyChain;
-use crate::key_chain::{KeyChainError, KeyChainResult, DEFAULT_KEY_KIND, LOOKAHEAD_SIZE};
+use crate::key_chain::{KeyChainError, KeyChainResult, DEFAULT_KEY_KIND};
 use common::chain::ChainConfig;
-use crypto::key::extended::ExtendedPrivateKey;
+use crypto::key::extended::{ExtendedPrivateKey, ExtendedPublicKey};
 use crypto::key::hdkd::derivable::Derivable;
 use crypto::key::hdkd::u31::U31;
 use itertools::Itertools;
@@ -24,7 +24,7 @@ use std::sync::Arc;
 use utils::ensure;
 use wallet_storage::{
     StoreTxRwUnlocked, WalletStorageReadLocked, WalletStorageReadUnlocked,
-    WalletStorageWriteUnlocked,
+    WalletStorageWriteLocked, WalletStorageWriteUnlocked,
 };
 use wallet_types::{RootKeyContent, RootKeyId};
 use zeroize::Zeroize;
@@ -81,8 +81,9 @@ impl MasterKeyChain {
     pub fn load_root_key(
         db_tx: &impl WalletStorageReadUnlocked,
     ) -> KeyChainResult<ExtendedPrivateKey> {
-        let key = db_tx
-            .get_all_root_keys()?
+        let root_keys = db_tx.get_all_root_keys()?;
+        ensure!(!root_keys.is_empty(), KeyChainError::NoRootKey);
+        let key = root_keys
             .into_values()
             .exactly_one()
             .map_err(|_| KeyChainError::OnlyOneRootKeyIsSupported)?
@@ -91,23 +92,55 @@ impl MasterKeyChain {
         Ok(key)
     }
 
+    /// Creates a Master key chain without a root key, the wallet can only have watch-only accounts
+    pub fn new_watch_only(
+        chain_config: Arc<ChainConfig>,
+        db_tx: &mut impl WalletStorageWriteLocked,
+    ) -> KeyChainResult<Self> {
+        db_tx.set_watch_only(true)?;
+        Ok(MasterKeyChain { chain_config })
+    }
+
     /// Creates a Master key chain, checks the database for an existing one
     pub fn new_from_existing_database(
         chain_config: Arc<ChainConfig>,
         db_tx: &impl WalletStorageReadLocked,
     ) -> KeyChainResult<Self> {
-        ensure!(
-            db_tx.exactly_one_root_key()?,
-            KeyChainError::OnlyOneRootKeyIsSupported
-        );
-        // The current format supports a single root key
+        // The current format supports a single root key, watch-only wallets have none
+        if !db_tx.is_watch_only()? {
+            let root_keys_count = db_tx.root_keys_count()?;
+            ensure!(root_keys_count > 0, KeyChainError::NoRootKey);
+            ensure!(
+                root_keys_count == 1,
+                KeyChainError::OnlyOneRootKeyIsSupported
+            );
+        }
         Ok(MasterKeyChain { chain_config })
     }
 
+    /// Create an account key chain from the account-level extended public key,
+    /// the account is watch-only if the key is not derived from the root key
+    pub fn create_account_key_chain_from_public_key(
+        &self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        account_index: U31,
+        account_pubkey: ExtendedPublicKey,
+        lookahead_size: u32,
+    ) -> KeyChainResult<AccountKeyChain> {
+        AccountKeyChain::new_from_account_key(
+            self.chain_config.clone(),
+            db_tx,
+            account_pubkey,
+            account_index,
+            lookahead_size,
+        )
+    }
+
     pub fn create_account_key_chain(
         &self,
         db_tx: &mut impl WalletStorageWriteUnlocked,
         account_index: U31,
+        lookahead_size: u32,
     ) -> KeyChainResult<AccountKeyChain> {
         let root_key = Self::load_root_key(db_tx)?;
         AccountKeyChain::new_from_root_key(
@@ -115,7 +148,7 @@ impl MasterKeyChain {
             db_tx,
             root_key,
             account_index,
-            LOOKAHEAD_SIZE,
+            lookahead_size,
         )
     }
 }
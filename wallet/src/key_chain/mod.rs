@@ -97,6 +97,10 @@ pub enum KeyChainError {
     KeyNotRoot,
     #[error("No private key found")]
     NoPrivateKeyFound,
+    #[error("The wallet has no root key, it can only watch accounts")]
+    NoRootKey,
+    #[error("The account is watch-only, its private keys are not in the wallet")]
+    WatchOnlyAccount,
 }
 
 /// Result type used for the key chain
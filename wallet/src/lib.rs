@@ -14,6 +14,7 @@
 // limitations under the License.
 
 pub mod account;
+pub mod backup;
 mod key_chain;
 pub mod send_request;
 pub mod wallet;
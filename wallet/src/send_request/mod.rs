@@ -13,12 +13,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use common::address::pubkeyhash::PublicKeyHash;
 use common::address::Address;
 use common::chain::stakelock::StakePoolData;
-use common::chain::tokens::OutputValue;
+use common::chain::timelock::OutputTimeLock;
+use common::chain::tokens::{OutputValue, TokenData, TokenId, TokenTransfer};
 use common::chain::{
-    Destination, OutPoint, PoolId, Transaction, TransactionCreationError, TxInput, TxOutput,
+    ChainConfig, Destination, OutPoint, PoolId, Transaction, TransactionCreationError, TxInput,
+    TxOutput,
 };
 use common::primitives::per_thousand::PerThousand;
 use common::primitives::Amount;
@@ -41,15 +42,68 @@ pub struct SendRequest {
     outputs: Vec<TxOutput>,
 }
 
-pub fn make_address_output(address: Address, amount: Amount) -> WalletResult<TxOutput> {
-    let pub_key_hash = PublicKeyHash::try_from(&address)
-        .map_err(|e| WalletError::InvalidAddress(address.get().to_owned(), e))?;
+fn address_destination(address: &Address) -> WalletResult<Destination> {
+    address
+        .destination()
+        .map_err(|e| WalletError::InvalidAddress(address.get().to_owned(), e))
+}
 
-    let destination = Destination::Address(pub_key_hash);
+pub fn make_address_output(address: Address, amount: Amount) -> WalletResult<TxOutput> {
+    let destination = address_destination(&address)?;
 
     Ok(TxOutput::Transfer(OutputValue::Coin(amount), destination))
 }
 
+pub fn make_locked_address_output(
+    address: Address,
+    amount: Amount,
+    lock: OutputTimeLock,
+) -> WalletResult<TxOutput> {
+    let destination = address_destination(&address)?;
+
+    Ok(TxOutput::LockThenTransfer(
+        OutputValue::Coin(amount),
+        destination,
+        lock,
+    ))
+}
+
+pub fn make_address_output_token(
+    address: Address,
+    token_id: TokenId,
+    amount: Amount,
+) -> WalletResult<TxOutput> {
+    let destination = address_destination(&address)?;
+    let token_data = TokenData::TokenTransfer(TokenTransfer { token_id, amount });
+
+    Ok(TxOutput::Transfer(
+        OutputValue::Token(Box::new(token_data)),
+        destination,
+    ))
+}
+
+/// Make the outputs that issue new tokens (fungible or NFT) to `address`.
+///
+/// The issuance fee is paid by burning coins.
+pub fn make_issue_token_outputs(
+    address: Address,
+    token_data: TokenData,
+    chain_config: &ChainConfig,
+) -> WalletResult<Vec<TxOutput>> {
+    let destination = address_destination(&address)?;
+
+    let issuance_output = TxOutput::Transfer(OutputValue::Token(Box::new(token_data)), destination);
+    let fee_output = TxOutput::Burn(OutputValue::Coin(chain_config.token_min_issuance_fee()));
+
+    Ok(vec![issuance_output, fee_output])
+}
+
+pub fn make_create_delegation_output(address: Address, pool_id: PoolId) -> WalletResult<TxOutput> {
+    let destination = address_destination(&address)?;
+
+    Ok(TxOutput::CreateDelegationId(destination, pool_id))
+}
+
 pub fn make_stake_output(
     pool_id: PoolId,
     amount: Amount,
@@ -73,10 +127,14 @@ pub fn make_stake_output(
     Ok(TxOutput::CreateStakePool(pool_id, stake_data.into()))
 }
 
+/// The transactions created by the wallet signal that they can be replaced while they are
+/// not confirmed, so that their fee can be bumped
+const REPLACEABLE_TX_FLAGS: u128 = 1;
+
 impl SendRequest {
     pub fn new() -> Self {
         Self {
-            flags: 0,
+            flags: REPLACEABLE_TX_FLAGS,
             utxos: Vec::new(),
             inputs: Vec::new(),
             outputs: Vec::new(),
@@ -14,22 +14,33 @@
 // limitations under the License.
 
 use std::collections::BTreeMap;
+use std::num::NonZeroU8;
 use std::path::Path;
 use std::sync::Arc;
 
-use crate::key_chain::{KeyChainError, MasterKeyChain};
+use crate::account::utxo_selector::UtxoSelectorError;
+use crate::backup::{AccountBackup, WalletBackup};
+use crate::key_chain::{KeyChainError, MasterKeyChain, LOOKAHEAD_SIZE};
 use crate::{Account, SendRequest};
 pub use bip39::{Language, Mnemonic};
-use common::address::pubkeyhash::PublicKeyHashError;
-use common::address::Address;
+use common::address::pubkeyhash::PublicKeyHash;
+use common::address::{Address, AddressError};
+use common::chain::block::timestamp::BlockTimestamp;
+use common::chain::classic_multisig::{ClassicMultisigChallenge, ClassicMultisigChallengeError};
+use common::chain::signature::inputsig::arbitrary_message::{
+    ArbitraryMessageSignature, SignArbitraryMessageError,
+};
+use common::chain::signature::inputsig::classical_multisig::authorize_classical_multisig::ClassicalMultisigSigningError;
 use common::chain::signature::TransactionSigError;
-use common::chain::tokens::TokenId;
+use common::chain::timelock::OutputTimeLock;
+use common::chain::tokens::{Metadata, TokenId, TokenIssuance};
 use common::chain::{
-    Block, ChainConfig, GenBlock, OutPoint, SignedTransaction, Transaction,
-    TransactionCreationError, TxOutput,
+    Block, ChainConfig, DelegationId, Destination, GenBlock, OutPoint, PoolId, SignedTransaction,
+    Transaction, TransactionCreationError, TxOutput,
 };
-use common::primitives::{Amount, BlockHeight, Id};
+use common::primitives::{Amount, BlockHeight, FeeRate, Id};
 use consensus::PoSGenerateBlockInputData;
+use crypto::key::extended::ExtendedPublicKey;
 use crypto::key::hdkd::u31::U31;
 use crypto::key::PublicKey;
 use crypto::vrf::VRFPublicKey;
@@ -39,18 +50,26 @@ use wallet_storage::{
     WalletStorageReadLocked, WalletStorageWriteLocked,
 };
 use wallet_storage::{StoreTxRwUnlocked, TransactionRwUnlocked};
-use wallet_types::utxo_types::UtxoTypes;
+use wallet_types::partially_signed_transaction::{
+    PartiallySignedTransaction, PartiallySignedTransactionError,
+};
+use wallet_types::tx_history::{TxHistoryEntry, TxHistoryFilter};
+use wallet_types::utxo_types::{UtxoStates, UtxoTypes};
+use wallet_types::wallet_tx::TxState;
 use wallet_types::{AccountId, KeyPurpose};
 
 pub const WALLET_VERSION_UNINITIALIZED: u32 = 0;
 pub const WALLET_VERSION_V1: u32 = 1;
-pub const CURRENT_WALLET_VERSION: u32 = WALLET_VERSION_V1;
+pub const WALLET_VERSION_V2: u32 = 2;
+pub const CURRENT_WALLET_VERSION: u32 = WALLET_VERSION_V2;
 
 /// Wallet errors
 #[derive(thiserror::Error, Debug, Eq, PartialEq)]
 pub enum WalletError {
     #[error("Wallet is not initialized")]
     WalletNotInitialized,
+    #[error("Unsupported wallet version: {0}")]
+    UnsupportedWalletVersion(u32),
     #[error("Wallet database error: {0}")]
     DatabaseError(#[from] wallet_storage::Error),
     #[error("Transaction already present: {0}")]
@@ -77,12 +96,95 @@ pub enum WalletError {
     TransactionCreation(#[from] TransactionCreationError),
     #[error("Transaction signing error: {0}")]
     TransactionSig(#[from] TransactionSigError),
+    #[error("Message signing error: {0}")]
+    SignArbitraryMessage(#[from] SignArbitraryMessageError),
     #[error("Not enough UTXOs amount: {0:?}, required: {1:?}")]
     NotEnoughUtxo(Amount, Amount),
+    #[error("Not enough tokens {0}, amount: {1:?}, required: {2:?}")]
+    NotEnoughTokens(TokenId, Amount, Amount),
     #[error("Invalid address {0}: {1}")]
-    InvalidAddress(String, PublicKeyHashError),
+    InvalidAddress(String, AddressError),
     #[error("No UTXOs")]
     NoUtxos,
+    #[error("Coin selection error: {0}")]
+    CoinSelectionError(UtxoSelectorError),
+    #[error("Unsupported input destination")] // TODO implement display for Destination
+    UnsupportedInputDestination(Destination),
+    #[error("Fee calculation overflow")]
+    FeeCalculationOverflow,
+    #[error("Delegation not found: {0}")]
+    DelegationNotFound(DelegationId),
+    #[error("Unknown pool id: {0}")]
+    UnknownPoolId(PoolId),
+    #[error("Classic multisig signing error: {0}")]
+    ClassicalMultisigSigning(#[from] ClassicalMultisigSigningError),
+    #[error("Partially signed transaction error: {0}")]
+    PartiallySignedTransaction(#[from] PartiallySignedTransactionError),
+    #[error("Invalid lookahead size: {0}")]
+    InvalidLookaheadSize(u32),
+    #[error("Cannot create a new account when the last account {0} has no transactions")]
+    EmptyLastAccount(U31),
+    #[error("Cannot create an account after the account {0}, the maximum index is reached")]
+    AbsoluteMaxNumAccountsExceeded(U31),
+    #[error("Address error: {0}")]
+    Address(#[from] AddressError),
+    #[error("Invalid classic multisig: {0}")]
+    ClassicMultisigChallenge(#[from] ClassicMultisigChallengeError),
+    #[error("Address {0} is not a classic multisig address")]
+    NotClassicMultisigAddress(String),
+    #[error("Unknown classic multisig {0}")]
+    UnknownClassicMultisig(PublicKeyHash),
+    #[error("Address {0} does not belong to the wallet")]
+    AddressNotOwned(String),
+    #[error("Address {0} belongs to the wallet and can't be added to the address book")]
+    OwnAddressInAddressBook(String),
+    #[error("Labels and contact names can't be empty")]
+    EmptyLabel,
+    #[error("The name {0} is already used by another contact")]
+    DuplicateContactName(String),
+    #[error("Contact {0} not found")]
+    ContactNotFound(String),
+    #[error("UTXO {0:?} not found")]
+    UtxoNotFound(OutPoint),
+    #[error("UTXO {0:?} is not frozen")]
+    UtxoNotFrozen(OutPoint),
+    #[error("UTXO {0:?} can't be spent by the account in the next block or is used twice")]
+    UtxoNotSpendable(OutPoint),
+    #[error("Transaction {0} is not pending")]
+    TransactionNotPending(Id<Transaction>),
+    #[error("Transaction {0} can't be replaced")]
+    TransactionNotReplaceable(Id<Transaction>),
+    #[error("Transaction {0} spends outputs that don't belong to the account")]
+    ForeignTransactionInputs(Id<Transaction>),
+    #[error("Transaction {0} has no change output to pay the higher fee")]
+    NoChangeOutput(Id<Transaction>),
+    #[error("The fee of the unconfirmed descendant {0} of the replaced transaction is unknown")]
+    UnknownDescendantFee(Id<Transaction>),
+    #[error("The new fee {1:?} is not higher than the current fee {0:?}")]
+    FeeNotIncreased(Amount, Amount),
+    #[error("The fee increase {0:?} doesn't pay for the relay of the replacement, at least {1:?} is required")]
+    InsufficientFeeIncrease(Amount, Amount),
+    #[error("Wallet backup encryption error: {0}")]
+    BackupEncryption(crypto::symkey::Error),
+    #[error("Invalid password or corrupted wallet backup")]
+    BackupDecryption,
+    #[error("Invalid wallet backup format")]
+    InvalidBackup,
+    #[error("Unsupported wallet backup version: {0}")]
+    UnsupportedBackupVersion(u32),
+    #[error("Transaction {0} can't be abandoned, it is in the mempool or not pending")]
+    CannotAbandonTransaction(Id<Transaction>),
+}
+
+impl From<UtxoSelectorError> for WalletError {
+    fn from(err: UtxoSelectorError) -> Self {
+        match err {
+            UtxoSelectorError::NotEnoughFunds(available, required) => {
+                WalletError::NotEnoughUtxo(available, required)
+            }
+            UtxoSelectorError::AmountOverflow => WalletError::CoinSelectionError(err),
+        }
+    }
 }
 
 /// Result type used for the wallet
@@ -133,15 +235,70 @@ impl<B: storage::Backend> Wallet<B> {
         })
     }
 
-    pub fn load_wallet(chain_config: Arc<ChainConfig>, db: Store<B>) -> WalletResult<Self> {
-        // Please continue to use read-only transaction here.
-        // Some unit tests expect that loading the wallet does not change the DB.
-        let db_tx = db.transaction_ro()?;
+    /// Create a wallet without a root key, it can only watch accounts created from their
+    /// extended public keys and create unsigned transactions for them
+    pub fn new_watch_only_wallet(
+        chain_config: Arc<ChainConfig>,
+        db: Store<B>,
+    ) -> WalletResult<Self> {
+        let mut db_tx = db.transaction_rw(None)?;
+        let key_chain = MasterKeyChain::new_watch_only(chain_config.clone(), &mut db_tx)?;
+        db_tx.set_storage_version(CURRENT_WALLET_VERSION)?;
+        db_tx.commit()?;
+
+        Ok(Wallet {
+            chain_config,
+            db,
+            key_chain,
+            accounts: BTreeMap::new(),
+        })
+    }
 
+    /// Upgrade the data stored by an older version of the wallet to the current format
+    fn migrate_if_needed(chain_config: &ChainConfig, db: &Store<B>) -> WalletResult<()> {
+        let db_tx = db.transaction_ro()?;
         let version = db_tx.get_storage_version()?;
-        if version == WALLET_VERSION_UNINITIALIZED {
-            return Err(WalletError::WalletNotInitialized);
+        db_tx.close();
+
+        match version {
+            WALLET_VERSION_UNINITIALIZED => Err(WalletError::WalletNotInitialized),
+            WALLET_VERSION_V1 => {
+                let mut db_tx = db.transaction_rw(None)?;
+                Self::migration_v2(chain_config, &mut db_tx)?;
+                db_tx.commit()?;
+                Ok(())
+            }
+            CURRENT_WALLET_VERSION => Ok(()),
+            _ => Err(WalletError::UnsupportedWalletVersion(version)),
         }
+    }
+
+    /// The version 2 stores the timestamps of the blocks and of the confirmed transactions.
+    /// The old entries can't be converted without the blocks, so all of them are dropped
+    /// and the accounts are rescanned from the genesis by the next sync.
+    /// The pending transactions come back with the mempool scan.
+    fn migration_v2(
+        chain_config: &ChainConfig,
+        db_tx: &mut impl WalletStorageWriteLocked,
+    ) -> WalletResult<()> {
+        db_tx.clear_transactions()?;
+
+        for (account_id, mut account_info) in db_tx.get_accounts_info()? {
+            account_info.update_best_block(BlockHeight::zero(), chain_config.genesis_block_id());
+            db_tx.set_account(&account_id, &account_info)?;
+        }
+
+        db_tx.set_storage_version(WALLET_VERSION_V2)?;
+
+        Ok(())
+    }
+
+    pub fn load_wallet(chain_config: Arc<ChainConfig>, db: Store<B>) -> WalletResult<Self> {
+        Self::migrate_if_needed(&chain_config, &db)?;
+
+        // Please continue to use read-only transaction here.
+        // Some unit tests expect that loading an up to date wallet does not change the DB.
+        let db_tx = db.transaction_ro()?;
 
         let key_chain = MasterKeyChain::new_from_existing_database(chain_config.clone(), &db_tx)?;
 
@@ -167,6 +324,119 @@ impl<B: storage::Backend> Wallet<B> {
         })
     }
 
+    /// Create a wallet from a decrypted backup. The restored accounts have no transactions,
+    /// they must be rescanned from the birthday of the wallet.
+    pub fn restore_from_backup(
+        chain_config: Arc<ChainConfig>,
+        db: Store<B>,
+        backup: &WalletBackup,
+    ) -> WalletResult<Self> {
+        let mut db_tx = db.transaction_rw_unlocked(None)?;
+
+        let key_chain = match &backup.root_key {
+            Some(root_key) => MasterKeyChain::new_from_root_key(
+                chain_config.clone(),
+                &mut db_tx,
+                root_key.as_key().clone(),
+            )?,
+            None => MasterKeyChain::new_watch_only(chain_config.clone(), &mut db_tx)?,
+        };
+
+        db_tx.set_storage_version(CURRENT_WALLET_VERSION)?;
+
+        for (address, label) in &backup.address_labels {
+            db_tx.set_address_label(address, label)?;
+        }
+        for (address, name) in &backup.address_book {
+            db_tx.set_address_book_entry(address, name)?;
+        }
+
+        let mut accounts = BTreeMap::new();
+        for account_backup in &backup.accounts {
+            let account_index = account_backup.account_index;
+            ensure!(
+                !accounts.contains_key(&account_index),
+                WalletError::AccountAlreadyExists(account_index)
+            );
+            ensure!(
+                account_backup.lookahead_size > 0,
+                WalletError::InvalidLookaheadSize(account_backup.lookahead_size)
+            );
+
+            let account_key_chain = key_chain.create_account_key_chain_from_public_key(
+                &mut db_tx,
+                account_index,
+                account_backup.account_pubkey.clone(),
+                account_backup.lookahead_size,
+            )?;
+            let mut account = Account::new(
+                Arc::clone(&chain_config),
+                &mut db_tx,
+                account_key_chain,
+                account_backup.name.clone(),
+            )?;
+            for challenge in &account_backup.classic_multisigs {
+                account.add_classic_multisig(&mut db_tx, challenge.clone())?;
+            }
+
+            accounts.insert(account_index, account);
+        }
+
+        db_tx.commit()?;
+
+        Ok(Wallet {
+            chain_config,
+            db,
+            key_chain,
+            accounts,
+        })
+    }
+
+    /// Export the root key and the metadata of the wallet that can't be found by scanning
+    /// the blockchain, encrypted with a key derived from `password`
+    pub fn export_backup(&self, password: &String) -> WalletResult<Vec<u8>> {
+        let db_tx = self.db.transaction_ro_unlocked()?;
+
+        let root_key = if db_tx.root_keys_count()? > 0 {
+            Some(MasterKeyChain::load_root_key(&db_tx)?.into())
+        } else {
+            None
+        };
+
+        let accounts = self
+            .accounts
+            .values()
+            .map(|account| {
+                let classic_multisigs = account
+                    .get_classic_multisigs()?
+                    .into_iter()
+                    .map(|(_, challenge, _)| challenge)
+                    .collect();
+                Ok(AccountBackup {
+                    account_index: account.account_index(),
+                    account_pubkey: account.account_public_key().clone(),
+                    name: account.name().clone(),
+                    lookahead_size: account.lookahead_size(),
+                    classic_multisigs,
+                    pool_ids: account.get_pool_ids(),
+                    delegation_ids: account
+                        .get_delegations()
+                        .map(|(delegation_id, _)| *delegation_id)
+                        .collect(),
+                })
+            })
+            .collect::<WalletResult<Vec<_>>>()?;
+
+        let backup = WalletBackup {
+            root_key,
+            accounts,
+            address_labels: db_tx.get_address_labels()?,
+            address_book: db_tx.get_address_book()?,
+        };
+
+        backup.encrypt(password)
+    }
+
     pub fn encrypt_wallet(&mut self, password: &Option<String>) -> WalletResult<()> {
         self.db.encrypt_private_keys(password).map_err(WalletError::from)
     }
@@ -187,20 +457,36 @@ impl<B: storage::Backend> Wallet<B> {
     // and a new next account should be rejected if the previously created one has no transactions
     // associated with it
     pub fn create_account(&mut self, account_index: U31) -> WalletResult<()> {
+        self.create_account_with_lookahead_size(account_index, LOOKAHEAD_SIZE)
+    }
+
+    /// Create an account that derives `lookahead_size` addresses past the last used one,
+    /// so that the transactions to any of them are found when scanning the blocks
+    pub fn create_account_with_lookahead_size(
+        &mut self,
+        account_index: U31,
+        lookahead_size: u32,
+    ) -> WalletResult<()> {
         ensure!(
             !self.accounts.contains_key(&account_index),
             WalletError::AccountAlreadyExists(account_index)
         );
+        ensure!(
+            lookahead_size > 0,
+            WalletError::InvalidLookaheadSize(lookahead_size)
+        );
 
         let mut db_tx = self.db.transaction_rw_unlocked(None)?;
 
         let account_key_chain =
-            self.key_chain.create_account_key_chain(&mut db_tx, account_index)?;
+            self.key_chain
+                .create_account_key_chain(&mut db_tx, account_index, lookahead_size)?;
 
         let account = Account::new(
             Arc::clone(&self.chain_config),
             &mut db_tx,
             account_key_chain,
+            None,
         )?;
 
         db_tx.commit()?;
@@ -212,6 +498,59 @@ impl<B: storage::Backend> Wallet<B> {
         Ok(())
     }
 
+    /// Create the account after the last one and return its index.
+    /// As specified by BIP44, the last account must have transactions,
+    /// otherwise the new account would not be found when the wallet is recovered.
+    pub fn create_next_account(&mut self, name: Option<String>) -> WalletResult<U31> {
+        let (last_index, last_account) =
+            self.accounts.iter().next_back().ok_or(WalletError::WalletNotInitialized)?;
+        ensure!(
+            last_account.has_transactions(),
+            WalletError::EmptyLastAccount(*last_index)
+        );
+        let account_index = last_index
+            .plus_one()
+            .map_err(|_| WalletError::AbsoluteMaxNumAccountsExceeded(*last_index))?;
+        let (best_block_id, best_block_height) = last_account.best_block();
+
+        let mut db_tx = self.db.transaction_rw_unlocked(None)?;
+
+        let account_key_chain =
+            self.key_chain
+                .create_account_key_chain(&mut db_tx, account_index, LOOKAHEAD_SIZE)?;
+
+        let mut account = Account::new(
+            Arc::clone(&self.chain_config),
+            &mut db_tx,
+            account_key_chain,
+            name,
+        )?;
+        // The keys of the new account can't be used in the blocks scanned before
+        account.set_best_block(&mut db_tx, best_block_height, best_block_id)?;
+
+        db_tx.commit()?;
+
+        self.accounts.insert(account_index, account);
+
+        Ok(account_index)
+    }
+
+    pub fn account_names(&self) -> impl Iterator<Item = (&U31, &Option<String>)> {
+        self.accounts
+            .iter()
+            .map(|(account_index, account)| (account_index, account.name()))
+    }
+
+    pub fn set_account_name(
+        &mut self,
+        account_index: U31,
+        name: Option<String>,
+    ) -> WalletResult<()> {
+        self.for_account_rw(account_index, |account, db_tx| {
+            account.set_name(db_tx, name)
+        })
+    }
+
     pub fn database(&self) -> &Store<B> {
         &self.db
     }
@@ -246,31 +585,241 @@ impl<B: storage::Backend> Wallet<B> {
         Ok(value)
     }
 
+    /// Remove the account with its keys and transactions from the wallet,
+    /// it can be created again from the root key
+    pub fn remove_account(&mut self, account_index: U31) -> WalletResult<()> {
+        let account = self
+            .accounts
+            .get(&account_index)
+            .ok_or(WalletError::NoAccountFoundWithIndex(account_index))?;
+
+        let mut db_tx = self.db.transaction_rw(None)?;
+        account.delete(&mut db_tx)?;
+        db_tx.commit()?;
+
+        self.accounts.remove(&account_index);
+
+        Ok(())
+    }
+
+    /// Create an account that watches the addresses derived from `account_pubkey`,
+    /// the account-level extended public key of another wallet
+    pub fn create_watch_only_account(
+        &mut self,
+        account_index: U31,
+        account_pubkey: ExtendedPublicKey,
+    ) -> WalletResult<()> {
+        ensure!(
+            !self.accounts.contains_key(&account_index),
+            WalletError::AccountAlreadyExists(account_index)
+        );
+
+        let mut db_tx = self.db.transaction_rw(None)?;
+
+        let account_key_chain = self.key_chain.create_account_key_chain_from_public_key(
+            &mut db_tx,
+            account_index,
+            account_pubkey,
+            LOOKAHEAD_SIZE,
+        )?;
+
+        let account = Account::new(
+            Arc::clone(&self.chain_config),
+            &mut db_tx,
+            account_key_chain,
+            None,
+        )?;
+
+        db_tx.commit()?;
+
+        self.accounts.insert(account.account_index(), account);
+
+        Ok(())
+    }
+
+    /// The account-level extended public key, which can be used to create a watch-only account
+    pub fn get_account_public_key(&self, account_index: U31) -> WalletResult<ExtendedPublicKey> {
+        let account_pubkey = self
+            .accounts
+            .get(&account_index)
+            .ok_or(WalletError::NoAccountFoundWithIndex(account_index))?
+            .account_public_key()
+            .clone();
+        Ok(account_pubkey)
+    }
+
+    fn is_address_owned(&self, address: &Address) -> WalletResult<bool> {
+        let destination = address.destination()?;
+        Ok(self
+            .accounts
+            .values()
+            .any(|account| account.is_destination_mine_or_watched(&destination)))
+    }
+
+    /// Set the label of an address that belongs to the wallet, or remove it if `label` is `None`
+    pub fn set_address_label(
+        &mut self,
+        address: &Address,
+        label: Option<String>,
+    ) -> WalletResult<()> {
+        ensure!(
+            self.is_address_owned(address)?,
+            WalletError::AddressNotOwned(address.get().to_owned())
+        );
+
+        let mut db_tx = self.db.transaction_rw(None)?;
+        match label {
+            Some(label) => {
+                ensure!(!label.trim().is_empty(), WalletError::EmptyLabel);
+                db_tx.set_address_label(address, label.trim())?;
+            }
+            None => db_tx.del_address_label(address)?,
+        }
+        db_tx.commit()?;
+        Ok(())
+    }
+
+    /// The labels of the addresses that belong to the wallet
+    pub fn get_address_labels(&self) -> WalletResult<BTreeMap<Address, String>> {
+        Ok(self.db.transaction_ro()?.get_address_labels()?)
+    }
+
+    /// Add an external address to the address book under a unique name,
+    /// or rename it if it's already there
+    pub fn add_contact(&mut self, name: String, address: &Address) -> WalletResult<()> {
+        let name = name.trim();
+        ensure!(!name.is_empty(), WalletError::EmptyLabel);
+        ensure!(
+            !self.is_address_owned(address)?,
+            WalletError::OwnAddressInAddressBook(address.get().to_owned())
+        );
+
+        let mut db_tx = self.db.transaction_rw(None)?;
+        let name_used = db_tx.get_address_book()?.iter().any(|(contact_address, contact_name)| {
+            contact_address != address && contact_name == name
+        });
+        ensure!(
+            !name_used,
+            WalletError::DuplicateContactName(name.to_owned())
+        );
+        db_tx.set_address_book_entry(address, name)?;
+        db_tx.commit()?;
+        Ok(())
+    }
+
+    /// Remove a contact from the address book
+    pub fn remove_contact(&mut self, name: &str) -> WalletResult<()> {
+        let address = self
+            .find_contact(name)?
+            .ok_or_else(|| WalletError::ContactNotFound(name.to_owned()))?;
+        let mut db_tx = self.db.transaction_rw(None)?;
+        db_tx.del_address_book_entry(&address)?;
+        db_tx.commit()?;
+        Ok(())
+    }
+
+    /// The external addresses in the address book with their names
+    pub fn get_address_book(&self) -> WalletResult<BTreeMap<Address, String>> {
+        Ok(self.db.transaction_ro()?.get_address_book()?)
+    }
+
+    /// Find the address of a contact by its name
+    pub fn find_contact(&self, name: &str) -> WalletResult<Option<Address>> {
+        let address = self
+            .get_address_book()?
+            .into_iter()
+            .find_map(|(address, contact_name)| (contact_name == name.trim()).then_some(address));
+        Ok(address)
+    }
+
     pub fn get_balance(
         &self,
         account_index: U31,
         utxo_types: UtxoTypes,
+        utxo_states: UtxoStates,
     ) -> WalletResult<(Amount, BTreeMap<TokenId, Amount>)> {
         self.accounts
             .get(&account_index)
             .ok_or(WalletError::NoAccountFoundWithIndex(account_index))?
-            .get_balance(utxo_types)
+            .get_balance(utxo_types, utxo_states)
     }
 
     pub fn get_utxos(
         &self,
         account_index: U31,
         utxo_types: UtxoTypes,
+        utxo_states: UtxoStates,
     ) -> WalletResult<BTreeMap<OutPoint, TxOutput>> {
         let account = self
             .accounts
             .get(&account_index)
             .ok_or(WalletError::NoAccountFoundWithIndex(account_index))?;
-        let utxos = account.get_utxos(utxo_types);
+        let utxos = account.get_utxos(utxo_types, utxo_states);
         let utxos = utxos.into_iter().map(|(outpoint, txo)| (outpoint, txo.clone())).collect();
         Ok(utxos)
     }
 
+    /// The time-locked UTXOs of the account that can't be spent in the next block yet,
+    /// with the block height or time after which they can be spent
+    pub fn get_locked_utxos(
+        &self,
+        account_index: U31,
+        utxo_states: UtxoStates,
+        median_time: BlockTimestamp,
+    ) -> WalletResult<BTreeMap<OutPoint, (TxOutput, OutputTimeLock)>> {
+        let account = self
+            .accounts
+            .get(&account_index)
+            .ok_or(WalletError::NoAccountFoundWithIndex(account_index))?;
+        let utxos = account
+            .get_locked_utxos(utxo_states, median_time)
+            .into_iter()
+            .map(|(outpoint, (txo, unlock_point))| (outpoint, (txo.clone(), unlock_point)))
+            .collect();
+        Ok(utxos)
+    }
+
+    /// Exclude an unspent output from the automatic input selection of the account
+    pub fn freeze_utxo(&mut self, account_index: U31, outpoint: OutPoint) -> WalletResult<()> {
+        self.for_account_rw(account_index, |account, db_tx| {
+            account.freeze_utxo(db_tx, outpoint)
+        })
+    }
+
+    pub fn unfreeze_utxo(&mut self, account_index: U31, outpoint: OutPoint) -> WalletResult<()> {
+        self.for_account_rw(account_index, |account, db_tx| {
+            account.unfreeze_utxo(db_tx, outpoint)
+        })
+    }
+
+    /// The frozen outputs of the account that are not spent yet
+    pub fn get_frozen_utxos(
+        &self,
+        account_index: U31,
+    ) -> WalletResult<BTreeMap<OutPoint, TxOutput>> {
+        let account = self
+            .accounts
+            .get(&account_index)
+            .ok_or(WalletError::NoAccountFoundWithIndex(account_index))?;
+        let utxos = account
+            .get_frozen_utxos()
+            .into_iter()
+            .map(|(outpoint, txo)| (outpoint, txo.clone()))
+            .collect();
+        Ok(utxos)
+    }
+
+    pub fn get_transaction_history(
+        &self,
+        account_index: U31,
+        filter: &TxHistoryFilter,
+    ) -> WalletResult<Vec<TxHistoryEntry>> {
+        self.accounts
+            .get(&account_index)
+            .ok_or(WalletError::NoAccountFoundWithIndex(account_index))?
+            .get_transaction_history(filter)
+    }
+
     pub fn get_new_address(&mut self, account_index: U31) -> WalletResult<Address> {
         self.for_account_rw(account_index, |account, db_tx| {
             account.get_new_address(db_tx, KeyPurpose::ReceiveFunds)
@@ -295,10 +844,151 @@ impl<B: storage::Backend> Wallet<B> {
         &mut self,
         account_index: U31,
         outputs: impl IntoIterator<Item = TxOutput>,
+        median_time: BlockTimestamp,
+        fee_rate: FeeRate,
     ) -> WalletResult<SignedTransaction> {
         let request = SendRequest::new().with_outputs(outputs);
         self.for_account_rw_unlocked(account_index, |account, db_tx| {
-            account.process_send_request(db_tx, request)
+            account.process_send_request(db_tx, request, median_time, fee_rate)
+        })
+    }
+
+    /// Create a transaction to `outputs` that spends exactly the UTXOs of `inputs`,
+    /// the coins and tokens that are left go to change outputs
+    pub fn create_transaction_with_inputs(
+        &mut self,
+        account_index: U31,
+        inputs: Vec<OutPoint>,
+        outputs: impl IntoIterator<Item = TxOutput>,
+        median_time: BlockTimestamp,
+        fee_rate: FeeRate,
+    ) -> WalletResult<SignedTransaction> {
+        self.for_account_rw_unlocked(account_index, |account, db_tx| {
+            let utxos = account.get_utxos_to_spend(inputs, median_time)?;
+            let request = SendRequest::new().with_inputs(utxos).with_outputs(outputs);
+            account.process_send_request(db_tx, request, median_time, fee_rate)
+        })
+    }
+
+    /// Create a transaction to `outputs` funded by the account without signing it
+    pub fn create_partially_signed_transaction(
+        &mut self,
+        account_index: U31,
+        outputs: impl IntoIterator<Item = TxOutput>,
+        median_time: BlockTimestamp,
+        fee_rate: FeeRate,
+    ) -> WalletResult<PartiallySignedTransaction> {
+        let request = SendRequest::new().with_outputs(outputs);
+        self.for_account_rw(account_index, |account, db_tx| {
+            account.create_partially_signed_transaction(db_tx, request, median_time, fee_rate)
+        })
+    }
+
+    /// Add the signatures the account can make to a partially signed transaction
+    pub fn sign_partially_signed_transaction(
+        &self,
+        account_index: U31,
+        ptx: PartiallySignedTransaction,
+    ) -> WalletResult<PartiallySignedTransaction> {
+        let db_tx = self.db.transaction_ro_unlocked()?;
+        self.accounts
+            .get(&account_index)
+            .ok_or(WalletError::NoAccountFoundWithIndex(account_index))?
+            .sign_partially_signed_transaction(ptx, &db_tx)
+    }
+
+    /// Create a transaction that replaces the unconfirmed transaction `tx_id` of the account
+    /// and pays a fee at `fee_rate`, the fee increase has to pay for its relay at `relay_fee_rate`.
+    ///
+    /// The original transaction is marked as replaced when the new one is added to the wallet
+    /// after it is accepted by the mempool.
+    pub fn create_fee_bump_transaction(
+        &self,
+        account_index: U31,
+        tx_id: Id<Transaction>,
+        fee_rate: FeeRate,
+        relay_fee_rate: FeeRate,
+    ) -> WalletResult<SignedTransaction> {
+        let db_tx = self.db.transaction_ro_unlocked()?;
+        self.accounts
+            .get(&account_index)
+            .ok_or(WalletError::NoAccountFoundWithIndex(account_index))?
+            .create_fee_bump_transaction(&db_tx, tx_id, fee_rate, relay_fee_rate)
+    }
+
+    /// Sign `message` with the key of `address` to prove its ownership
+    pub fn sign_message(
+        &self,
+        account_index: U31,
+        address: &Address,
+        message: &[u8],
+    ) -> WalletResult<ArbitraryMessageSignature> {
+        let destination = address.destination()?;
+        let db_tx = self.db.transaction_ro_unlocked()?;
+        self.accounts
+            .get(&account_index)
+            .ok_or(WalletError::NoAccountFoundWithIndex(account_index))?
+            .sign_message(&destination, message, &db_tx)
+    }
+
+    /// Create a classic multisig of `public_keys` that needs `min_required_signatures` of them,
+    /// and watch its funds in the account
+    pub fn add_classic_multisig(
+        &mut self,
+        account_index: U31,
+        min_required_signatures: NonZeroU8,
+        public_keys: Vec<PublicKey>,
+    ) -> WalletResult<Address> {
+        let challenge = ClassicMultisigChallenge::new(
+            &self.chain_config,
+            min_required_signatures,
+            public_keys,
+        )?;
+        self.for_account_rw(account_index, |account, db_tx| {
+            account.add_classic_multisig(db_tx, challenge)
+        })
+    }
+
+    pub fn get_classic_multisigs(
+        &self,
+        account_index: U31,
+    ) -> WalletResult<Vec<(Address, ClassicMultisigChallenge, Amount)>> {
+        self.accounts
+            .get(&account_index)
+            .ok_or(WalletError::NoAccountFoundWithIndex(account_index))?
+            .get_classic_multisigs()
+    }
+
+    /// Create an unsigned transaction that spends the funds of a classic multisig
+    /// watched by the account
+    pub fn create_classic_multisig_spend(
+        &mut self,
+        account_index: U31,
+        multisig_address: &Address,
+        outputs: impl IntoIterator<Item = TxOutput>,
+        median_time: BlockTimestamp,
+        fee_rate: FeeRate,
+    ) -> WalletResult<PartiallySignedTransaction> {
+        let challenge_hash = match multisig_address.destination()? {
+            Destination::ClassicMultisig(challenge_hash) => challenge_hash,
+            Destination::AnyoneCanSpend
+            | Destination::Address(_)
+            | Destination::PublicKey(_)
+            | Destination::ScriptHash(_) => {
+                return Err(WalletError::NotClassicMultisigAddress(
+                    multisig_address.get().to_owned(),
+                ))
+            }
+        };
+        let request = SendRequest::new().with_outputs(outputs);
+        self.for_account_rw(account_index, |account, db_tx| {
+            account.create_classic_multisig_spend(
+                db_tx,
+                challenge_hash,
+                request,
+                median_time,
+                fee_rate,
+            )
         })
     }
 
@@ -306,9 +996,112 @@ impl<B: storage::Backend> Wallet<B> {
         &mut self,
         account_index: U31,
         amount: Amount,
+        median_time: BlockTimestamp,
+        fee_rate: FeeRate,
+    ) -> WalletResult<SignedTransaction> {
+        self.for_account_rw_unlocked(account_index, |account, db_tx| {
+            account.create_stake_pool_tx(db_tx, amount, median_time, fee_rate)
+        })
+    }
+
+    pub fn decommission_stake_pool(
+        &mut self,
+        account_index: U31,
+        pool_id: PoolId,
+        pool_pledge: Amount,
+        fee_rate: FeeRate,
+    ) -> WalletResult<SignedTransaction> {
+        self.for_account_rw_unlocked(account_index, |account, db_tx| {
+            account.decommission_stake_pool(db_tx, pool_id, pool_pledge, fee_rate)
+        })
+    }
+
+    /// The ids of the stake pools staked by the account
+    pub fn get_pool_ids(&self, account_index: U31) -> WalletResult<Vec<PoolId>> {
+        let pool_ids = self
+            .accounts
+            .get(&account_index)
+            .ok_or(WalletError::NoAccountFoundWithIndex(account_index))?
+            .get_pool_ids();
+        Ok(pool_ids)
+    }
+
+    pub fn create_delegation(
+        &mut self,
+        account_index: U31,
+        address: Address,
+        pool_id: PoolId,
+        median_time: BlockTimestamp,
+        fee_rate: FeeRate,
+    ) -> WalletResult<SignedTransaction> {
+        self.for_account_rw_unlocked(account_index, |account, db_tx| {
+            account.create_delegation(db_tx, address, pool_id, median_time, fee_rate)
+        })
+    }
+
+    pub fn delegate_staking(
+        &mut self,
+        account_index: U31,
+        amount: Amount,
+        delegation_id: DelegationId,
+        median_time: BlockTimestamp,
+        fee_rate: FeeRate,
+    ) -> WalletResult<SignedTransaction> {
+        self.for_account_rw_unlocked(account_index, |account, db_tx| {
+            account.delegate_staking(db_tx, amount, delegation_id, median_time, fee_rate)
+        })
+    }
+
+    pub fn withdraw_from_delegation(
+        &mut self,
+        account_index: U31,
+        address: Address,
+        amount: Amount,
+        delegation_id: DelegationId,
+        fee_rate: FeeRate,
+    ) -> WalletResult<SignedTransaction> {
+        self.for_account_rw_unlocked(account_index, |account, db_tx| {
+            account.withdraw_from_delegation(db_tx, address, amount, delegation_id, fee_rate)
+        })
+    }
+
+    /// The delegations of the account together with the pools they delegate to
+    pub fn get_delegations(&self, account_index: U31) -> WalletResult<Vec<(DelegationId, PoolId)>> {
+        let delegations = self
+            .accounts
+            .get(&account_index)
+            .ok_or(WalletError::NoAccountFoundWithIndex(account_index))?
+            .get_delegations()
+            .map(|(delegation_id, delegation_data)| {
+                (*delegation_id, *delegation_data.source_pool())
+            })
+            .collect();
+        Ok(delegations)
+    }
+
+    pub fn issue_new_token(
+        &mut self,
+        account_index: U31,
+        address: Address,
+        token_issuance: TokenIssuance,
+        median_time: BlockTimestamp,
+        fee_rate: FeeRate,
+    ) -> WalletResult<SignedTransaction> {
+        self.for_account_rw_unlocked(account_index, |account, db_tx| {
+            account.issue_new_token(db_tx, address, token_issuance, median_time, fee_rate)
+        })
+    }
+
+    pub fn issue_new_nft(
+        &mut self,
+        account_index: U31,
+        address: Address,
+        metadata: Metadata,
+        median_time: BlockTimestamp,
+        fee_rate: FeeRate,
     ) -> WalletResult<SignedTransaction> {
         self.for_account_rw_unlocked(account_index, |account, db_tx| {
-            account.create_stake_pool_tx(db_tx, amount)
+            account.issue_new_nft(db_tx, address, metadata, median_time, fee_rate)
         })
     }
 
@@ -332,6 +1125,28 @@ impl<B: storage::Backend> Wallet<B> {
         Ok(account.best_block())
     }
 
+    /// Whether any block or transaction relevant to the account has been found
+    pub fn account_has_transactions(&self, account_index: U31) -> WalletResult<bool> {
+        let account = self
+            .accounts
+            .get(&account_index)
+            .ok_or(WalletError::NoAccountFoundWithIndex(account_index))?;
+        Ok(account.has_transactions())
+    }
+
+    /// Start scanning the blocks of an account after the given block, the blocks up to it
+    /// must not contain anything relevant to the account (e.g. they are older than the account)
+    pub fn set_account_best_block(
+        &mut self,
+        account_index: U31,
+        best_block_height: BlockHeight,
+        best_block_id: Id<GenBlock>,
+    ) -> WalletResult<()> {
+        self.for_account_rw(account_index, |account, db_tx| {
+            account.set_best_block(db_tx, best_block_height, best_block_id)
+        })
+    }
+
     /// Scan new blocks and update best block hash/height.
     /// New block may reset the chain of previously scanned blocks.
     ///
@@ -353,9 +1168,56 @@ impl<B: storage::Backend> Wallet<B> {
         Ok(())
     }
 
+    /// Scan new blocks for a single account, used to bring a new account up to date
+    /// with the rest of the wallet
+    pub fn scan_new_blocks_for_account(
+        &mut self,
+        account_index: U31,
+        common_block_height: BlockHeight,
+        blocks: Vec<Block>,
+    ) -> WalletResult<()> {
+        self.for_account_rw(account_index, |account, db_tx| {
+            account.scan_new_blocks(db_tx, common_block_height, &blocks)
+        })
+    }
+
     /// Rescan mempool for unconfirmed transactions and UTXOs
-    pub fn scan_mempool(&mut self, _transactions: Vec<SignedTransaction>) -> WalletResult<()> {
-        Err(WalletError::NotImplemented("scan_mempool"))
+    pub fn scan_mempool(&mut self, transactions: &[SignedTransaction]) -> WalletResult<()> {
+        let mut db_tx = self.db.transaction_rw(None)?;
+
+        for account in self.accounts.values_mut() {
+            account.scan_mempool(&mut db_tx, transactions)?;
+        }
+
+        db_tx.commit()?;
+
+        Ok(())
+    }
+
+    /// Store a transaction created by this wallet after it has been submitted to the mempool,
+    /// so that its outputs can be spent before it is confirmed
+    pub fn add_unconfirmed_tx(&mut self, transaction: &SignedTransaction) -> WalletResult<()> {
+        let mut db_tx = self.db.transaction_rw(None)?;
+
+        for account in self.accounts.values_mut() {
+            account.add_unconfirmed_tx(&mut db_tx, transaction, TxState::InMempool)?;
+        }
+
+        db_tx.commit()?;
+
+        Ok(())
+    }
+
+    /// Abandon an unconfirmed transaction that is no longer in the mempool and its descendants,
+    /// so that the outputs they spend can be used again
+    pub fn abandon_transaction(
+        &mut self,
+        account_index: U31,
+        tx_id: Id<Transaction>,
+    ) -> WalletResult<()> {
+        self.for_account_rw(account_index, |account, db_tx| {
+            account.abandon_transaction(db_tx, tx_id)
+        })
     }
 }
 
@@ -13,38 +13,57 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::num::NonZeroU8;
+
 use crate::{
     key_chain::{make_account_path, LOOKAHEAD_SIZE},
-    send_request::make_address_output,
+    send_request::{make_address_output, make_address_output_token, make_locked_address_output},
     DefaultWallet,
 };
 
 use super::*;
 use common::{
+    address::pubkeyhash::PublicKeyHash,
     chain::{
         block::{timestamp::BlockTimestamp, BlockReward, ConsensusData},
+        classic_multisig::ClassicMultisigChallenge,
         config::{create_mainnet, create_regtest, Builder, ChainType},
         signature::inputsig::InputWitness,
         timelock::OutputTimeLock,
-        tokens::OutputValue,
-        Destination, Genesis, OutPointSourceId, TxInput,
+        tokens::{token_id, OutputValue, TokenData, TokenIssuance, TokenTransfer},
+        DelegationId, Destination, Genesis, OutPointSourceId, PoolId, TxInput,
     },
-    primitives::Idable,
+    primitives::{Idable, H256},
 };
 use crypto::{
     key::hdkd::{child_number::ChildNumber, derivable::Derivable, derivation_path::DerivationPath},
     random::{CryptoRng, Rng},
 };
 use rstest::rstest;
+use serialization::{DecodeAll, Encode};
 use test_utils::random::{make_seedable_rng, Seed};
-use wallet_types::{account_info::DEFAULT_ACCOUNT_INDEX, utxo_types::UtxoType};
+use wallet_storage::{WalletStorageReadUnlocked, WalletStorageWriteUnlocked};
+use wallet_types::{
+    account_info::DEFAULT_ACCOUNT_INDEX,
+    tx_history::TxHistoryFilter,
+    utxo_types::{UtxoState, UtxoStates, UtxoType},
+};
 
 // TODO: Many of these tests require randomization...
 
 const MNEMONIC: &str =
     "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
 
-const NETWORK_FEE: u128 = 10000;
+const MNEMONIC2: &str =
+    "legal winner thank year wave sausage worth useful legal winner thank yellow";
+
+const FEE_RATE: FeeRate = FeeRate::new(Amount::from_atoms(1000));
+
+// The median time past of the chain when the test transactions are created
+const MEDIAN_TIME: BlockTimestamp = BlockTimestamp::from_int_seconds(1639975460);
+
+// Enough to pay the fee of a small transaction at FEE_RATE
+const MAX_NETWORK_FEE: u128 = 10000;
 
 fn gen_random_password(rng: &mut (impl Rng + CryptoRng)) -> String {
     (0..rng.gen_range(1..100)).map(|_| rng.gen::<char>()).collect()
@@ -81,6 +100,7 @@ fn verify_wallet_balance(
         .get_balance(
             DEFAULT_ACCOUNT_INDEX,
             UtxoType::Transfer | UtxoType::LockThenTransfer,
+            UtxoState::Confirmed.into(),
         )
         .unwrap();
     assert_eq!(coin_balance, expected_balance);
@@ -92,6 +112,7 @@ fn verify_wallet_balance(
         .get_balance(
             DEFAULT_ACCOUNT_INDEX,
             UtxoType::Transfer | UtxoType::LockThenTransfer,
+            UtxoState::Confirmed.into(),
         )
         .unwrap();
     // Check that the loaded wallet has the same balance
@@ -139,6 +160,72 @@ fn wallet_creation_in_memory() {
     let _wallet = Wallet::load_wallet(chain_config, initialized_db).unwrap();
 }
 
+#[test]
+fn wallet_migration_to_v2() {
+    let chain_config = Arc::new(create_mainnet());
+
+    let db = create_wallet_in_memory().unwrap();
+    let mut wallet = Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC, None).unwrap();
+    wallet.create_account(DEFAULT_ACCOUNT_INDEX).unwrap();
+
+    let block1_amount = Amount::from_atoms(10000);
+    let address = get_address(
+        &chain_config,
+        MNEMONIC,
+        DEFAULT_ACCOUNT_INDEX,
+        KeyPurpose::ReceiveFunds,
+        0.try_into().unwrap(),
+    );
+    let block1 = Block::new(
+        vec![],
+        chain_config.genesis_block_id(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(vec![make_address_output(address, block1_amount).unwrap()]),
+    )
+    .unwrap();
+    let block1_id = block1.header().block_id();
+    wallet.scan_new_blocks(BlockHeight::new(0), vec![block1.clone()]).unwrap();
+    verify_wallet_balance(&chain_config, &wallet, block1_amount);
+
+    // Mark the stored data as written by the first version of the wallet
+    let db = wallet.db;
+    let mut db_tx = db.transaction_rw(None).unwrap();
+    db_tx.set_storage_version(WALLET_VERSION_V1).unwrap();
+    db_tx.commit().unwrap();
+
+    // The migration drops the stored blocks and resets the best block for a rescan
+    let mut wallet = Wallet::load_wallet(Arc::clone(&chain_config), db.clone()).unwrap();
+    assert_eq!(
+        db.transaction_ro().unwrap().get_storage_version().unwrap(),
+        CURRENT_WALLET_VERSION
+    );
+    assert_eq!(
+        wallet.get_best_block().unwrap(),
+        (chain_config.genesis_block_id(), BlockHeight::new(0))
+    );
+    verify_wallet_balance(&chain_config, &wallet, Amount::ZERO);
+
+    // The rescan restores the balance
+    wallet.scan_new_blocks(BlockHeight::new(0), vec![block1]).unwrap();
+    assert_eq!(
+        wallet.get_best_block().unwrap(),
+        (block1_id.into(), BlockHeight::new(1))
+    );
+    verify_wallet_balance(&chain_config, &wallet, block1_amount);
+
+    // A wallet written by a newer version can't be loaded
+    let mut db_tx = db.transaction_rw(None).unwrap();
+    db_tx.set_storage_version(CURRENT_WALLET_VERSION + 1).unwrap();
+    db_tx.commit().unwrap();
+    assert_eq!(
+        Wallet::load_wallet(Arc::clone(&chain_config), db).err(),
+        Some(WalletError::UnsupportedWalletVersion(
+            CURRENT_WALLET_VERSION + 1
+        ))
+    );
+}
+
 #[test]
 fn wallet_balance_genesis() {
     let chain_type = ChainType::Mainnet;
@@ -215,6 +302,7 @@ fn locked_wallet_balance_works(#[case] seed: Seed) {
         .get_balance(
             DEFAULT_ACCOUNT_INDEX,
             UtxoType::Transfer | UtxoType::LockThenTransfer,
+            UtxoState::Confirmed.into(),
         )
         .unwrap();
     assert_eq!(coin_balance, genesis_amount);
@@ -227,6 +315,7 @@ fn locked_wallet_balance_works(#[case] seed: Seed) {
         .get_balance(
             DEFAULT_ACCOUNT_INDEX,
             UtxoType::Transfer | UtxoType::LockThenTransfer,
+            UtxoState::Confirmed.into(),
         )
         .unwrap();
     assert_eq!(coin_balance, genesis_amount);
@@ -244,6 +333,7 @@ fn wallet_balance_block_reward() {
         .get_balance(
             DEFAULT_ACCOUNT_INDEX,
             UtxoType::Transfer | UtxoType::LockThenTransfer,
+            UtxoState::Confirmed.into(),
         )
         .unwrap();
     assert_eq!(coin_balance, Amount::ZERO);
@@ -438,96 +528,259 @@ fn wallet_balance_parent_child_transactions() {
     verify_wallet_balance(&chain_config, &wallet, tx_amount2);
 }
 
-#[track_caller]
-fn test_wallet_accounts(
-    chain_config: &Arc<ChainConfig>,
-    wallet: &DefaultWallet,
-    expected_accounts: Vec<U31>,
-) {
-    let accounts = wallet.account_indexes().cloned().collect::<Vec<_>>();
-    assert_eq!(accounts, expected_accounts);
-
-    let db_copy = wallet.db.clone();
-    let mut wallet = Wallet::load_wallet(Arc::clone(chain_config), db_copy).unwrap();
-    let accounts = wallet.account_indexes().cloned().collect::<Vec<_>>();
-    assert_eq!(accounts, expected_accounts);
-
-    for account_index in expected_accounts {
-        wallet.create_account(account_index).expect_err("Account should already exist");
-    }
-}
-
 #[test]
-fn wallet_accounts_creation() {
+fn wallet_coin_selection() {
     let chain_config = Arc::new(create_mainnet());
 
     let db = create_wallet_in_memory().unwrap();
     let mut wallet = Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC, None).unwrap();
-    test_wallet_accounts(&chain_config, &wallet, vec![]);
-
     wallet.create_account(DEFAULT_ACCOUNT_INDEX).unwrap();
-    test_wallet_accounts(&chain_config, &wallet, vec![DEFAULT_ACCOUNT_INDEX]);
 
-    wallet.create_account(1.try_into().unwrap()).unwrap();
-    test_wallet_accounts(
+    let address = get_address(
         &chain_config,
-        &wallet,
-        vec![DEFAULT_ACCOUNT_INDEX, 1.try_into().unwrap()],
+        MNEMONIC,
+        DEFAULT_ACCOUNT_INDEX,
+        KeyPurpose::ReceiveFunds,
+        0.try_into().unwrap(),
     );
+    let reward_amounts = [10_000, 20_000, 100_000, 1_000_000].map(Amount::from_atoms);
+    let total_amount = reward_amounts.iter().copied().sum::<Option<Amount>>().unwrap();
+    let block1 = Block::new(
+        vec![],
+        chain_config.genesis_block_id(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(
+            reward_amounts
+                .iter()
+                .map(|amount| make_address_output(address.clone(), *amount).unwrap())
+                .collect(),
+        ),
+    )
+    .unwrap();
+    wallet.scan_new_blocks(BlockHeight::new(0), vec![block1]).unwrap();
+    verify_wallet_balance(&chain_config, &wallet, total_amount);
 
-    let db = create_wallet_in_memory().unwrap();
-    let mut wallet = Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC, None).unwrap();
-    wallet.create_account(123.try_into().unwrap()).unwrap();
-    test_wallet_accounts(&chain_config, &wallet, vec![123.try_into().unwrap()]);
+    let utxos = wallet
+        .get_utxos(
+            DEFAULT_ACCOUNT_INDEX,
+            UtxoType::Transfer.into(),
+            UtxoState::Confirmed.into(),
+        )
+        .unwrap();
+    let coin_amount = |output: &TxOutput| match output {
+        TxOutput::Transfer(OutputValue::Coin(amount), _) => *amount,
+        _ => panic!("unexpected output"),
+    };
+    let paid_fee = |tx: &SignedTransaction| {
+        let inputs_amount = tx
+            .inputs()
+            .iter()
+            .map(|input| coin_amount(&utxos[input.outpoint()]))
+            .sum::<Option<Amount>>()
+            .unwrap();
+        let outputs_amount = tx.outputs().iter().map(coin_amount).sum::<Option<Amount>>().unwrap();
+        (inputs_amount - outputs_amount).unwrap()
+    };
+
+    // Not all of the UTXOs are needed to pay a small amount and the change gets everything above the fee
+    let new_output = TxOutput::Transfer(
+        OutputValue::Coin(Amount::from_atoms(15_000)),
+        Destination::AnyoneCanSpend,
+    );
+    let tx = wallet
+        .create_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            vec![new_output],
+            MEDIAN_TIME,
+            FEE_RATE,
+        )
+        .unwrap();
+    assert!(tx.inputs().len() < reward_amounts.len());
+    assert_eq!(tx.outputs().len(), 2);
+    assert_eq!(
+        paid_fee(&tx),
+        FEE_RATE.compute_fee(tx.encoded_size()).unwrap()
+    );
+
+    // Without the change output, a single UTXO covers the amount plus the fee exactly
+    let new_output = TxOutput::Transfer(
+        OutputValue::Coin(Amount::from_atoms(99_000)),
+        Destination::AnyoneCanSpend,
+    );
+    let tx = wallet
+        .create_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            vec![new_output],
+            MEDIAN_TIME,
+            FEE_RATE,
+        )
+        .unwrap();
+    assert_eq!(tx.inputs().len(), 1);
+    assert_eq!(tx.outputs().len(), 2);
+    let changeless_fee = FEE_RATE
+        .compute_fee(tx.encoded_size() - tx.outputs()[1].encoded_size())
+        .unwrap();
+
+    let new_output = TxOutput::Transfer(
+        OutputValue::Coin((Amount::from_atoms(100_000) - changeless_fee).unwrap()),
+        Destination::AnyoneCanSpend,
+    );
+    let tx = wallet
+        .create_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            vec![new_output],
+            MEDIAN_TIME,
+            FEE_RATE,
+        )
+        .unwrap();
+    assert_eq!(tx.inputs().len(), 1);
+    assert_eq!(tx.outputs().len(), 1);
+    assert_eq!(paid_fee(&tx), changeless_fee);
+
+    // Spending everything leaves nothing for the fee
+    let new_output =
+        TxOutput::Transfer(OutputValue::Coin(total_amount), Destination::AnyoneCanSpend);
+    assert!(matches!(
+        wallet.create_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            vec![new_output],
+            MEDIAN_TIME,
+            FEE_RATE
+        ),
+        Err(WalletError::NotEnoughUtxo(_, _))
+    ));
 }
 
-#[rstest]
-#[trace]
-#[case(Seed::from_entropy())]
-fn locked_wallet_accounts_creation_fail(#[case] seed: Seed) {
-    let mut rng = make_seedable_rng(seed);
+#[test]
+fn wallet_frozen_utxos() {
     let chain_config = Arc::new(create_mainnet());
 
     let db = create_wallet_in_memory().unwrap();
     let mut wallet = Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC, None).unwrap();
-    let password = Some(gen_random_password(&mut rng));
-    wallet.encrypt_wallet(&password).unwrap();
-    wallet.lock_wallet().unwrap();
+    wallet.create_account(DEFAULT_ACCOUNT_INDEX).unwrap();
 
-    let err = wallet.create_account(rng.gen_range(0..1 << 31).try_into().unwrap());
+    let address = get_address(
+        &chain_config,
+        MNEMONIC,
+        DEFAULT_ACCOUNT_INDEX,
+        KeyPurpose::ReceiveFunds,
+        0.try_into().unwrap(),
+    );
+    let small_amount = Amount::from_atoms(10_000);
+    let big_amount = Amount::from_atoms(1_000_000);
+    let block1 = Block::new(
+        vec![],
+        chain_config.genesis_block_id(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(vec![
+            make_address_output(address.clone(), small_amount).unwrap(),
+            make_address_output(address.clone(), big_amount).unwrap(),
+        ]),
+    )
+    .unwrap();
+    let small_outpoint = OutPoint::new(block1.get_id().into(), 0);
+    let big_outpoint = OutPoint::new(block1.get_id().into(), 1);
+    wallet.scan_new_blocks(BlockHeight::new(0), vec![block1]).unwrap();
+
+    // Only the UTXOs of the account can be frozen
+    let unknown_outpoint = OutPoint::new(chain_config.genesis_block_id().into(), 100);
     assert_eq!(
-        err,
-        Err(WalletError::DatabaseError(
-            wallet_storage::Error::WalletLocked
-        ))
+        wallet.freeze_utxo(DEFAULT_ACCOUNT_INDEX, unknown_outpoint.clone()),
+        Err(WalletError::UtxoNotFound(unknown_outpoint))
     );
+    wallet.freeze_utxo(DEFAULT_ACCOUNT_INDEX, big_outpoint.clone()).unwrap();
 
-    // success after unlock
-    wallet.unlock_wallet(&password.unwrap()).unwrap();
-    wallet.create_account(rng.gen_range(0..1 << 31).try_into().unwrap()).unwrap();
+    // The frozen UTXOs are stored in the DB
+    let wallet_copy = Wallet::load_wallet(Arc::clone(&chain_config), wallet.db.clone()).unwrap();
+    assert_eq!(
+        wallet_copy
+            .get_frozen_utxos(DEFAULT_ACCOUNT_INDEX)
+            .unwrap()
+            .into_keys()
+            .collect::<Vec<_>>(),
+        vec![big_outpoint.clone()]
+    );
+
+    // The frozen UTXO is skipped by the automatic input selection
+    let new_output = TxOutput::Transfer(
+        OutputValue::Coin(Amount::from_atoms(500_000)),
+        Destination::AnyoneCanSpend,
+    );
+    assert!(matches!(
+        wallet.create_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            vec![new_output.clone()],
+            MEDIAN_TIME,
+            FEE_RATE
+        ),
+        Err(WalletError::NotEnoughUtxo(_, _))
+    ));
+
+    // But it can be spent explicitly, the rest goes to the change output
+    let tx = wallet
+        .create_transaction_with_inputs(
+            DEFAULT_ACCOUNT_INDEX,
+            vec![big_outpoint.clone()],
+            vec![new_output.clone()],
+            MEDIAN_TIME,
+            FEE_RATE,
+        )
+        .unwrap();
+    assert_eq!(
+        tx.inputs().iter().map(|input| input.outpoint().clone()).collect::<Vec<_>>(),
+        vec![big_outpoint.clone()]
+    );
+    assert_eq!(tx.outputs().len(), 2);
+    assert_eq!(tx.outputs()[0], new_output);
+
+    // The explicit inputs must be spendable UTXOs of the account, each used once
+    assert_eq!(
+        wallet.create_transaction_with_inputs(
+            DEFAULT_ACCOUNT_INDEX,
+            vec![small_outpoint.clone(), small_outpoint.clone()],
+            vec![new_output.clone()],
+            MEDIAN_TIME,
+            FEE_RATE,
+        ),
+        Err(WalletError::UtxoNotSpendable(small_outpoint.clone()))
+    );
+    assert!(matches!(
+        wallet.create_transaction_with_inputs(
+            DEFAULT_ACCOUNT_INDEX,
+            vec![small_outpoint],
+            vec![new_output.clone()],
+            MEDIAN_TIME,
+            FEE_RATE,
+        ),
+        Err(WalletError::NotEnoughUtxo(_, _))
+    ));
+
+    wallet.unfreeze_utxo(DEFAULT_ACCOUNT_INDEX, big_outpoint.clone()).unwrap();
+    assert!(wallet.get_frozen_utxos(DEFAULT_ACCOUNT_INDEX).unwrap().is_empty());
+    assert_eq!(
+        wallet.unfreeze_utxo(DEFAULT_ACCOUNT_INDEX, big_outpoint.clone()),
+        Err(WalletError::UtxoNotFrozen(big_outpoint))
+    );
+    wallet
+        .create_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            vec![new_output],
+            MEDIAN_TIME,
+            FEE_RATE,
+        )
+        .unwrap();
 }
 
-#[rstest]
-#[trace]
-#[case(Seed::from_entropy())]
-fn locked_wallet_cant_sign_transaction(#[case] seed: Seed) {
-    let mut rng = make_seedable_rng(seed);
+#[test]
+fn wallet_time_locked_outputs() {
     let chain_config = Arc::new(create_mainnet());
 
     let db = create_wallet_in_memory().unwrap();
     let mut wallet = Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC, None).unwrap();
     wallet.create_account(DEFAULT_ACCOUNT_INDEX).unwrap();
 
-    let (coin_balance, _) = wallet
-        .get_balance(
-            DEFAULT_ACCOUNT_INDEX,
-            UtxoType::Transfer | UtxoType::LockThenTransfer,
-        )
-        .unwrap();
-    assert_eq!(coin_balance, Amount::ZERO);
-
-    // Generate a new block which sends reward to the wallet
-    let block1_amount = Amount::from_atoms(rng.gen_range(NETWORK_FEE + 1..NETWORK_FEE + 10000));
     let address = get_address(
         &chain_config,
         MNEMONIC,
@@ -535,47 +788,2286 @@ fn locked_wallet_cant_sign_transaction(#[case] seed: Seed) {
         KeyPurpose::ReceiveFunds,
         0.try_into().unwrap(),
     );
+    let block_timestamp = chain_config.genesis_block().timestamp();
+    let unlock_time = block_timestamp.add_int_seconds(600).unwrap();
+    let locks = [
+        OutputTimeLock::UntilHeight(BlockHeight::new(3)),
+        OutputTimeLock::ForBlockCount(2),
+        OutputTimeLock::UntilTime(unlock_time),
+        OutputTimeLock::ForSeconds(600),
+    ];
+    let locked_amount = Amount::from_atoms(100_000);
     let block1 = Block::new(
         vec![],
         chain_config.genesis_block_id(),
-        chain_config.genesis_block().timestamp(),
+        block_timestamp,
         ConsensusData::None,
-        BlockReward::new(vec![make_address_output(address, block1_amount).unwrap()]),
+        BlockReward::new(
+            locks
+                .iter()
+                .map(|lock| {
+                    make_locked_address_output(address.clone(), locked_amount, lock.clone())
+                        .unwrap()
+                })
+                .collect(),
+        ),
     )
     .unwrap();
-
+    let block1_id = block1.header().block_id();
     wallet.scan_new_blocks(BlockHeight::new(0), vec![block1]).unwrap();
 
-    let password = Some(gen_random_password(&mut rng));
-    wallet.encrypt_wallet(&password).unwrap();
-    wallet.lock_wallet().unwrap();
+    // The relative locks are converted to the block height or time after which
+    // the outputs can be spent
+    let locked_utxos = wallet
+        .get_locked_utxos(
+            DEFAULT_ACCOUNT_INDEX,
+            UtxoState::Confirmed.into(),
+            block_timestamp,
+        )
+        .unwrap();
+    let unlock_points = locked_utxos.values().map(|(_, lock)| lock.clone()).collect::<Vec<_>>();
+    assert_eq!(
+        unlock_points,
+        vec![
+            OutputTimeLock::UntilHeight(BlockHeight::new(3)),
+            OutputTimeLock::UntilHeight(BlockHeight::new(3)),
+            OutputTimeLock::UntilTime(unlock_time),
+            OutputTimeLock::UntilTime(unlock_time),
+        ]
+    );
 
-    let (coin_balance, _) = wallet
-        .get_balance(
+    // None of the outputs can be spent in the next block
+    let new_output = TxOutput::Transfer(
+        OutputValue::Coin(Amount::from_atoms(50_000)),
+        Destination::AnyoneCanSpend,
+    );
+    let err = wallet
+        .create_transaction_to_addresses(
             DEFAULT_ACCOUNT_INDEX,
-            UtxoType::Transfer | UtxoType::LockThenTransfer,
+            vec![new_output],
+            block_timestamp,
+            FEE_RATE,
+        )
+        .unwrap_err();
+    assert!(matches!(err, WalletError::NotEnoughUtxo(_, _)));
+
+    // The height locks expire at the block after the next one
+    let block2 = Block::new(
+        vec![],
+        block1_id.into(),
+        block_timestamp,
+        ConsensusData::None,
+        BlockReward::new(vec![]),
+    )
+    .unwrap();
+    wallet.scan_new_blocks(BlockHeight::new(1), vec![block2]).unwrap();
+
+    let locked_utxos = wallet
+        .get_locked_utxos(
+            DEFAULT_ACCOUNT_INDEX,
+            UtxoState::Confirmed.into(),
+            block_timestamp,
         )
         .unwrap();
-    assert_eq!(coin_balance, block1_amount);
+    assert_eq!(locked_utxos.len(), 2);
 
     let new_output = TxOutput::Transfer(
-        OutputValue::Coin(Amount::from_atoms(
-            rng.gen_range(1..block1_amount.into_atoms() - NETWORK_FEE),
-        )),
+        OutputValue::Coin(Amount::from_atoms(150_000)),
         Destination::AnyoneCanSpend,
     );
+    let tx = wallet
+        .create_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            vec![new_output.clone()],
+            block_timestamp,
+            FEE_RATE,
+        )
+        .unwrap();
+    assert_eq!(tx.inputs().len(), 2);
+    assert!(tx.inputs().iter().all(|input| !locked_utxos.contains_key(input.outpoint())));
 
-    assert_eq!(
-        wallet.create_transaction_to_addresses(DEFAULT_ACCOUNT_INDEX, vec![new_output.clone()]),
-        Err(WalletError::DatabaseError(
-            wallet_storage::Error::WalletLocked
-        ))
+    // The time locks expire once the median time past reaches the unlock time
+    let locked_utxos = wallet
+        .get_locked_utxos(
+            DEFAULT_ACCOUNT_INDEX,
+            UtxoState::Confirmed.into(),
+            unlock_time,
+        )
+        .unwrap();
+    assert!(locked_utxos.is_empty());
+
+    let new_output = TxOutput::Transfer(
+        OutputValue::Coin(Amount::from_atoms(350_000)),
+        Destination::AnyoneCanSpend,
     );
+    let tx = wallet
+        .create_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            vec![new_output],
+            unlock_time,
+            FEE_RATE,
+        )
+        .unwrap();
+    assert_eq!(tx.inputs().len(), locks.len());
+}
 
-    // success after unlock
-    wallet.unlock_wallet(&password.unwrap()).unwrap();
-    wallet
-        .create_transaction_to_addresses(DEFAULT_ACCOUNT_INDEX, vec![new_output])
+#[test]
+fn wallet_token_issuance_and_transfer() {
+    let chain_config = Arc::new(create_mainnet());
+
+    let db = create_wallet_in_memory().unwrap();
+    let mut wallet = Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC, None).unwrap();
+    wallet.create_account(DEFAULT_ACCOUNT_INDEX).unwrap();
+
+    let address = get_address(
+        &chain_config,
+        MNEMONIC,
+        DEFAULT_ACCOUNT_INDEX,
+        KeyPurpose::ReceiveFunds,
+        0.try_into().unwrap(),
+    );
+    let issuance_fee = chain_config.token_min_issuance_fee();
+    let block1_amount = (issuance_fee * 2).unwrap();
+    let block1 = Block::new(
+        vec![],
+        chain_config.genesis_block_id(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(vec![make_address_output(address, block1_amount).unwrap()]),
+    )
+    .unwrap();
+    let block1_id = block1.header().block_id();
+    wallet.scan_new_blocks(BlockHeight::new(0), vec![block1]).unwrap();
+
+    // Issue new tokens to the wallet, the issuance fee is burned from the coins
+    let amount_to_issue = Amount::from_atoms(1000);
+    let token_issuance = TokenIssuance {
+        token_ticker: b"XXXX".to_vec(),
+        amount_to_issue,
+        number_of_decimals: 2,
+        metadata_uri: b"http://uri".to_vec(),
+    };
+    let token_address = wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
+    let issuance_tx = wallet
+        .issue_new_token(
+            DEFAULT_ACCOUNT_INDEX,
+            token_address,
+            token_issuance.clone(),
+            MEDIAN_TIME,
+            FEE_RATE,
+        )
+        .unwrap();
+    let token_id = token_id(issuance_tx.transaction()).unwrap();
+    assert!(issuance_tx.outputs().contains(&TxOutput::Burn(OutputValue::Coin(issuance_fee))));
+    assert!(issuance_tx.outputs().iter().any(|output| matches!(
+        output,
+        TxOutput::Transfer(OutputValue::Token(token_data), _)
+            if **token_data == TokenData::TokenIssuance(Box::new(token_issuance.clone()))
+    )));
+
+    let block2 = Block::new(
+        vec![issuance_tx],
+        block1_id.into(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(Vec::new()),
+    )
+    .unwrap();
+    let block2_id = block2.header().block_id();
+    wallet.scan_new_blocks(BlockHeight::new(1), vec![block2]).unwrap();
+
+    let (coin_balance, tokens_balance) = wallet
+        .get_balance(
+            DEFAULT_ACCOUNT_INDEX,
+            UtxoType::Transfer | UtxoType::LockThenTransfer,
+            UtxoState::Confirmed.into(),
+        )
+        .unwrap();
+    let balance_without_network_fee = (block1_amount - issuance_fee).unwrap();
+    assert!(coin_balance < balance_without_network_fee);
+    assert!(
+        coin_balance > (balance_without_network_fee - Amount::from_atoms(MAX_NETWORK_FEE)).unwrap()
+    );
+    assert_eq!(
+        tokens_balance,
+        BTreeMap::from([(token_id, amount_to_issue)])
+    );
+
+    // Send a part of the issued tokens, the rest comes back as token change
+    let sent_amount = Amount::from_atoms(400);
+    let token_output = TxOutput::Transfer(
+        OutputValue::Token(Box::new(TokenData::TokenTransfer(TokenTransfer {
+            token_id,
+            amount: sent_amount,
+        }))),
+        Destination::Address(PublicKeyHash::zero()),
+    );
+    let transfer_tx = wallet
+        .create_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            vec![token_output],
+            MEDIAN_TIME,
+            FEE_RATE,
+        )
+        .unwrap();
+    // The token output, the token change and the coin change
+    assert_eq!(transfer_tx.outputs().len(), 3);
+
+    let block3 = Block::new(
+        vec![transfer_tx],
+        block2_id.into(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(Vec::new()),
+    )
+    .unwrap();
+    wallet.scan_new_blocks(BlockHeight::new(2), vec![block3]).unwrap();
+
+    let (_, tokens_balance) = wallet
+        .get_balance(
+            DEFAULT_ACCOUNT_INDEX,
+            UtxoType::Transfer | UtxoType::LockThenTransfer,
+            UtxoState::Confirmed.into(),
+        )
+        .unwrap();
+    let change_amount = (amount_to_issue - sent_amount).unwrap();
+    assert_eq!(tokens_balance, BTreeMap::from([(token_id, change_amount)]));
+
+    // The token change can be spent again, but not more than that
+    let token_address = wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
+    let token_output = make_address_output_token(
+        token_address,
+        token_id,
+        (change_amount + Amount::from_atoms(1)).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        wallet.create_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            vec![token_output],
+            MEDIAN_TIME,
+            FEE_RATE
+        ),
+        Err(WalletError::NotEnoughTokens(
+            token_id,
+            change_amount,
+            (change_amount + Amount::from_atoms(1)).unwrap()
+        ))
+    );
+}
+
+#[test]
+fn wallet_unconfirmed_transactions() {
+    let chain_config = Arc::new(create_mainnet());
+
+    let db = create_wallet_in_memory().unwrap();
+    let mut wallet = Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC, None).unwrap();
+    wallet.create_account(DEFAULT_ACCOUNT_INDEX).unwrap();
+
+    let address = get_address(
+        &chain_config,
+        MNEMONIC,
+        DEFAULT_ACCOUNT_INDEX,
+        KeyPurpose::ReceiveFunds,
+        0.try_into().unwrap(),
+    );
+    let block1_amount = Amount::from_atoms(100_000);
+    let block1 = Block::new(
+        vec![],
+        chain_config.genesis_block_id(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(vec![make_address_output(address, block1_amount).unwrap()]),
+    )
+    .unwrap();
+    let block1_id = block1.header().block_id();
+    wallet.scan_new_blocks(BlockHeight::new(0), vec![block1]).unwrap();
+
+    let balance = |wallet: &DefaultWallet, utxo_states: UtxoStates| {
+        wallet
+            .get_balance(
+                DEFAULT_ACCOUNT_INDEX,
+                UtxoType::Transfer | UtxoType::LockThenTransfer,
+                utxo_states,
+            )
+            .unwrap()
+            .0
+    };
+    let external_output = |amount| {
+        TxOutput::Transfer(
+            OutputValue::Coin(Amount::from_atoms(amount)),
+            Destination::Address(PublicKeyHash::zero()),
+        )
+    };
+    let change_amount = |tx: &SignedTransaction| match &tx.outputs()[1] {
+        TxOutput::Transfer(OutputValue::Coin(amount), _) => *amount,
+        _ => panic!("unexpected output"),
+    };
+
+    // The change of a sent transaction is pending until the transaction is confirmed
+    let tx1 = wallet
+        .create_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            vec![external_output(10_000)],
+            MEDIAN_TIME,
+            FEE_RATE,
+        )
+        .unwrap();
+    wallet.add_unconfirmed_tx(&tx1).unwrap();
+    assert_eq!(balance(&wallet, UtxoState::Confirmed.into()), Amount::ZERO);
+    assert_eq!(
+        balance(&wallet, UtxoState::InMempool.into()),
+        change_amount(&tx1)
+    );
+
+    // The next payment spends the unconfirmed change
+    let tx2 = wallet
+        .create_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            vec![external_output(5_000)],
+            MEDIAN_TIME,
+            FEE_RATE,
+        )
+        .unwrap();
+    assert_eq!(tx2.inputs().len(), 1);
+    assert_eq!(
+        tx2.inputs()[0].outpoint().tx_id(),
+        OutPointSourceId::Transaction(tx1.transaction().get_id())
+    );
+    wallet.add_unconfirmed_tx(&tx2).unwrap();
+    assert_eq!(
+        balance(&wallet, UtxoState::InMempool.into()),
+        change_amount(&tx2)
+    );
+
+    // The transactions that have left the mempool become inactive
+    wallet.scan_mempool(&[tx1.clone()]).unwrap();
+    assert_eq!(balance(&wallet, UtxoState::InMempool.into()), Amount::ZERO);
+    assert_eq!(
+        balance(&wallet, UtxoState::Inactive.into()),
+        change_amount(&tx2)
+    );
+    wallet.scan_mempool(&[tx1.clone(), tx2.clone()]).unwrap();
+    assert_eq!(
+        balance(&wallet, UtxoState::InMempool.into()),
+        change_amount(&tx2)
+    );
+
+    // A block that double spends the input of the first transaction conflicts with both of them
+    let double_spend = SignedTransaction::new(
+        Transaction::new(
+            0,
+            vec![TxInput::new(OutPointSourceId::BlockReward(block1_id.into()), 0)],
+            vec![external_output(90_000)],
+        )
+        .unwrap(),
+        vec![InputWitness::NoSignature(None)],
+    )
+    .unwrap();
+    let block2 = Block::new(
+        vec![double_spend],
+        block1_id.into(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(Vec::new()),
+    )
+    .unwrap();
+    wallet.scan_new_blocks(BlockHeight::new(1), vec![block2]).unwrap();
+    assert_eq!(balance(&wallet, UtxoStates::ALL), Amount::ZERO);
+
+    // After a reorg the conflicted transactions can be confirmed again
+    let block2 = Block::new(
+        vec![],
+        block1_id.into(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(Vec::new()),
+    )
+    .unwrap();
+    wallet.scan_new_blocks(BlockHeight::new(1), vec![block2]).unwrap();
+    assert_eq!(balance(&wallet, UtxoState::Confirmed.into()), Amount::ZERO);
+    assert_eq!(
+        balance(&wallet, UtxoState::Inactive.into()),
+        change_amount(&tx2)
+    );
+}
+
+#[test]
+fn wallet_abandon_transaction() {
+    let chain_config = Arc::new(create_mainnet());
+
+    let db = create_wallet_in_memory().unwrap();
+    let mut wallet = Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC, None).unwrap();
+    wallet.create_account(DEFAULT_ACCOUNT_INDEX).unwrap();
+
+    let address = get_address(
+        &chain_config,
+        MNEMONIC,
+        DEFAULT_ACCOUNT_INDEX,
+        KeyPurpose::ReceiveFunds,
+        0.try_into().unwrap(),
+    );
+    let block1_amount = Amount::from_atoms(100_000);
+    let block1 = Block::new(
+        vec![],
+        chain_config.genesis_block_id(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(vec![make_address_output(address, block1_amount).unwrap()]),
+    )
+    .unwrap();
+    let block1_id = block1.header().block_id();
+    wallet.scan_new_blocks(BlockHeight::new(0), vec![block1]).unwrap();
+
+    let balance = |wallet: &DefaultWallet, utxo_states: UtxoStates| {
+        wallet
+            .get_balance(
+                DEFAULT_ACCOUNT_INDEX,
+                UtxoType::Transfer | UtxoType::LockThenTransfer,
+                utxo_states,
+            )
+            .unwrap()
+            .0
+    };
+    let external_output = |amount| {
+        TxOutput::Transfer(
+            OutputValue::Coin(Amount::from_atoms(amount)),
+            Destination::Address(PublicKeyHash::zero()),
+        )
+    };
+
+    let tx1 = wallet
+        .create_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            vec![external_output(10_000)],
+            MEDIAN_TIME,
+            FEE_RATE,
+        )
+        .unwrap();
+    let tx1_id = tx1.transaction().get_id();
+    wallet.add_unconfirmed_tx(&tx1).unwrap();
+    let tx2 = wallet
+        .create_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            vec![external_output(5_000)],
+            MEDIAN_TIME,
+            FEE_RATE,
+        )
+        .unwrap();
+    wallet.add_unconfirmed_tx(&tx2).unwrap();
+
+    // The transactions in the mempool can't be abandoned
+    assert_eq!(
+        wallet.abandon_transaction(DEFAULT_ACCOUNT_INDEX, tx1_id),
+        Err(WalletError::CannotAbandonTransaction(tx1_id))
+    );
+
+    // The inputs of the transactions that have left the mempool stay spent
+    wallet.scan_mempool(&[]).unwrap();
+    assert_eq!(balance(&wallet, UtxoState::Confirmed.into()), Amount::ZERO);
+    assert_ne!(balance(&wallet, UtxoState::Inactive.into()), Amount::ZERO);
+
+    let unknown_tx_id = Id::<Transaction>::new(H256::zero());
+    assert_eq!(
+        wallet.abandon_transaction(DEFAULT_ACCOUNT_INDEX, unknown_tx_id),
+        Err(WalletError::NoTransactionFound(unknown_tx_id))
+    );
+
+    // Abandoning the first transaction abandons the second one too and frees the block reward
+    wallet.abandon_transaction(DEFAULT_ACCOUNT_INDEX, tx1_id).unwrap();
+    assert_eq!(balance(&wallet, UtxoState::Confirmed.into()), block1_amount);
+    assert_eq!(balance(&wallet, UtxoState::Inactive.into()), Amount::ZERO);
+    assert_eq!(
+        wallet.abandon_transaction(DEFAULT_ACCOUNT_INDEX, tx1_id),
+        Err(WalletError::CannotAbandonTransaction(tx1_id))
+    );
+
+    let tx3 = wallet
+        .create_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            vec![external_output(20_000)],
+            MEDIAN_TIME,
+            FEE_RATE,
+        )
+        .unwrap();
+    assert_eq!(
+        tx3.inputs()[0].outpoint().tx_id(),
+        OutPointSourceId::BlockReward(block1_id.into())
+    );
+}
+
+#[test]
+fn wallet_bump_fee() {
+    let chain_config = Arc::new(create_mainnet());
+
+    let db = create_wallet_in_memory().unwrap();
+    let mut wallet = Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC, None).unwrap();
+    wallet.create_account(DEFAULT_ACCOUNT_INDEX).unwrap();
+
+    let address = get_address(
+        &chain_config,
+        MNEMONIC,
+        DEFAULT_ACCOUNT_INDEX,
+        KeyPurpose::ReceiveFunds,
+        0.try_into().unwrap(),
+    );
+    let block1 = Block::new(
+        vec![],
+        chain_config.genesis_block_id(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(vec![make_address_output(
+            address,
+            Amount::from_atoms(100_000),
+        )
+        .unwrap()]),
+    )
+    .unwrap();
+    wallet.scan_new_blocks(BlockHeight::new(0), vec![block1]).unwrap();
+
+    let external_output = TxOutput::Transfer(
+        OutputValue::Coin(Amount::from_atoms(10_000)),
+        Destination::Address(PublicKeyHash::zero()),
+    );
+    let tx1 = wallet
+        .create_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            vec![external_output.clone()],
+            MEDIAN_TIME,
+            FEE_RATE,
+        )
+        .unwrap();
+    assert!(tx1.is_replaceable());
+    let tx1_id = tx1.transaction().get_id();
+
+    // Only the transactions stored by the wallet can be replaced
+    assert_eq!(
+        wallet.create_fee_bump_transaction(DEFAULT_ACCOUNT_INDEX, tx1_id, FEE_RATE, FEE_RATE),
+        Err(WalletError::NoTransactionFound(tx1_id))
+    );
+    wallet.add_unconfirmed_tx(&tx1).unwrap();
+
+    // The fee has to be increased
+    assert!(matches!(
+        wallet.create_fee_bump_transaction(DEFAULT_ACCOUNT_INDEX, tx1_id, FEE_RATE, FEE_RATE),
+        Err(WalletError::FeeNotIncreased(_, _))
+    ));
+
+    // The fee increase has to pay for the relay of the replacement
+    let low_fee_rate = FeeRate::new(Amount::from_atoms(1500));
+    assert!(matches!(
+        wallet.create_fee_bump_transaction(DEFAULT_ACCOUNT_INDEX, tx1_id, low_fee_rate, FEE_RATE),
+        Err(WalletError::InsufficientFeeIncrease(_, _))
+    ));
+
+    // The replacement spends the same inputs and pays more from the change
+    let higher_fee_rate = FeeRate::new(Amount::from_atoms(2000));
+    let tx2 = wallet
+        .create_fee_bump_transaction(DEFAULT_ACCOUNT_INDEX, tx1_id, higher_fee_rate, FEE_RATE)
+        .unwrap();
+    assert_eq!(tx2.inputs(), tx1.inputs());
+    assert_eq!(tx2.outputs()[0], external_output);
+    let change = |tx: &SignedTransaction| match &tx.outputs()[1] {
+        TxOutput::Transfer(OutputValue::Coin(amount), destination) => {
+            (*amount, destination.clone())
+        }
+        _ => panic!("unexpected output"),
+    };
+    let (tx1_change, tx1_change_destination) = change(&tx1);
+    let (tx2_change, tx2_change_destination) = change(&tx2);
+    assert!(tx2_change < tx1_change);
+    assert_eq!(tx2_change_destination, tx1_change_destination);
+
+    // The original transaction is marked as replaced once the replacement is added
+    let tx2_id = tx2.transaction().get_id();
+    wallet.add_unconfirmed_tx(&tx2).unwrap();
+    let history = wallet
+        .get_transaction_history(
+            DEFAULT_ACCOUNT_INDEX,
+            &TxHistoryFilter {
+                limit: usize::MAX,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    let tx_state = |tx_id: Id<Transaction>| {
+        history
+            .iter()
+            .find(|entry| entry.tx_id == OutPointSourceId::from(tx_id))
+            .map(|entry| entry.state)
+            .unwrap()
+    };
+    assert_eq!(tx_state(tx1_id), TxState::Replaced(tx2_id));
+    assert_eq!(tx_state(tx2_id), TxState::InMempool);
+    let pending_utxos = wallet
+        .get_utxos(
+            DEFAULT_ACCOUNT_INDEX,
+            UtxoTypes::ALL,
+            UtxoState::InMempool | UtxoState::Replaced,
+        )
+        .unwrap();
+    assert_eq!(
+        pending_utxos.into_keys().collect::<Vec<_>>(),
+        vec![OutPoint::new(tx2_id.into(), 1)]
+    );
+
+    assert_eq!(
+        wallet.create_fee_bump_transaction(
+            DEFAULT_ACCOUNT_INDEX,
+            tx1_id,
+            higher_fee_rate,
+            FEE_RATE
+        ),
+        Err(WalletError::TransactionNotPending(tx1_id))
+    );
+}
+
+#[test]
+fn wallet_bump_fee_pays_for_descendants() {
+    let chain_config = Arc::new(create_mainnet());
+
+    let db = create_wallet_in_memory().unwrap();
+    let mut wallet = Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC, None).unwrap();
+    wallet.create_account(DEFAULT_ACCOUNT_INDEX).unwrap();
+
+    let address = get_address(
+        &chain_config,
+        MNEMONIC,
+        DEFAULT_ACCOUNT_INDEX,
+        KeyPurpose::ReceiveFunds,
+        0.try_into().unwrap(),
+    );
+    let block1 = Block::new(
+        vec![],
+        chain_config.genesis_block_id(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(vec![make_address_output(
+            address,
+            Amount::from_atoms(100_000),
+        )
+        .unwrap()]),
+    )
+    .unwrap();
+    wallet.scan_new_blocks(BlockHeight::new(0), vec![block1]).unwrap();
+
+    let external_output = TxOutput::Transfer(
+        OutputValue::Coin(Amount::from_atoms(10_000)),
+        Destination::Address(PublicKeyHash::zero()),
+    );
+    let tx1 = wallet
+        .create_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            vec![external_output],
+            MEDIAN_TIME,
+            FEE_RATE,
+        )
+        .unwrap();
+    let tx1_id = tx1.transaction().get_id();
+    wallet.add_unconfirmed_tx(&tx1).unwrap();
+    let tx1_change = match &tx1.outputs()[1] {
+        TxOutput::Transfer(OutputValue::Coin(amount), _) => *amount,
+        _ => panic!("unexpected output"),
+    };
+
+    // A child of the transaction that pays a high fee from the change
+    let own_address = get_address(
+        &chain_config,
+        MNEMONIC,
+        DEFAULT_ACCOUNT_INDEX,
+        KeyPurpose::ReceiveFunds,
+        1.try_into().unwrap(),
+    );
+    let child_fee = Amount::from_atoms(1000);
+    let child = SignedTransaction::new(
+        Transaction::new(
+            1,
+            vec![TxInput::new(tx1_id.into(), 1)],
+            vec![make_address_output(own_address, (tx1_change - child_fee).unwrap()).unwrap()],
+        )
+        .unwrap(),
+        vec![InputWitness::NoSignature(None)],
+    )
+    .unwrap();
+    let child_id = child.transaction().get_id();
+    wallet.add_unconfirmed_tx(&child).unwrap();
+
+    // The replacement has to pay more than the transaction and its child together
+    let higher_fee_rate = FeeRate::new(Amount::from_atoms(2000));
+    let err = wallet
+        .create_fee_bump_transaction(DEFAULT_ACCOUNT_INDEX, tx1_id, higher_fee_rate, FEE_RATE)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        WalletError::FeeNotIncreased(current_fee, _) if current_fee > child_fee
+    ));
+    let much_higher_fee_rate = FeeRate::new(Amount::from_atoms(20_000));
+    let tx2 = wallet
+        .create_fee_bump_transaction(
+            DEFAULT_ACCOUNT_INDEX,
+            tx1_id,
+            much_higher_fee_rate,
+            FEE_RATE,
+        )
+        .unwrap();
+    assert_eq!(tx2.inputs(), tx1.inputs());
+
+    // The fee of a descendant with unknown inputs can't be paid for
+    let grandchild = SignedTransaction::new(
+        Transaction::new(
+            1,
+            vec![
+                TxInput::new(child_id.into(), 0),
+                TxInput::new(Id::<Transaction>::new(H256::zero()).into(), 0),
+            ],
+            vec![TxOutput::Transfer(
+                OutputValue::Coin(Amount::from_atoms(1)),
+                Destination::Address(PublicKeyHash::zero()),
+            )],
+        )
+        .unwrap(),
+        vec![InputWitness::NoSignature(None), InputWitness::NoSignature(None)],
+    )
+    .unwrap();
+    wallet.add_unconfirmed_tx(&grandchild).unwrap();
+    assert_eq!(
+        wallet.create_fee_bump_transaction(
+            DEFAULT_ACCOUNT_INDEX,
+            tx1_id,
+            much_higher_fee_rate,
+            FEE_RATE
+        ),
+        Err(WalletError::UnknownDescendantFee(
+            grandchild.transaction().get_id()
+        ))
+    );
+}
+
+#[test]
+fn wallet_bump_fee_keeps_self_payment() {
+    let chain_config = Arc::new(create_mainnet());
+
+    let db = create_wallet_in_memory().unwrap();
+    let mut wallet = Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC, None).unwrap();
+    wallet.create_account(DEFAULT_ACCOUNT_INDEX).unwrap();
+
+    let address = get_address(
+        &chain_config,
+        MNEMONIC,
+        DEFAULT_ACCOUNT_INDEX,
+        KeyPurpose::ReceiveFunds,
+        0.try_into().unwrap(),
+    );
+    let block1 = Block::new(
+        vec![],
+        chain_config.genesis_block_id(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(vec![make_address_output(
+            address,
+            Amount::from_atoms(100_000),
+        )
+        .unwrap()]),
+    )
+    .unwrap();
+    let block1_id = block1.header().block_id();
+    wallet.scan_new_blocks(BlockHeight::new(0), vec![block1]).unwrap();
+
+    // A consolidation to a receive address of the wallet has no change output
+    let own_address = get_address(
+        &chain_config,
+        MNEMONIC,
+        DEFAULT_ACCOUNT_INDEX,
+        KeyPurpose::ReceiveFunds,
+        1.try_into().unwrap(),
+    );
+    let consolidation = SignedTransaction::new(
+        Transaction::new(
+            1,
+            vec![TxInput::new(OutPointSourceId::BlockReward(block1_id.into()), 0)],
+            vec![make_address_output(own_address, Amount::from_atoms(99_000)).unwrap()],
+        )
+        .unwrap(),
+        vec![InputWitness::NoSignature(None)],
+    )
+    .unwrap();
+    assert!(consolidation.is_replaceable());
+    let consolidation_id = consolidation.transaction().get_id();
+    wallet.add_unconfirmed_tx(&consolidation).unwrap();
+
+    let higher_fee_rate = FeeRate::new(Amount::from_atoms(2000));
+    assert_eq!(
+        wallet.create_fee_bump_transaction(
+            DEFAULT_ACCOUNT_INDEX,
+            consolidation_id,
+            higher_fee_rate,
+            FEE_RATE
+        ),
+        Err(WalletError::NoChangeOutput(consolidation_id))
+    );
+
+    // The payment to a receive address is kept when there is change to pay the higher fee
+    let self_payment = make_address_output(
+        get_address(
+            &chain_config,
+            MNEMONIC,
+            DEFAULT_ACCOUNT_INDEX,
+            KeyPurpose::ReceiveFunds,
+            2.try_into().unwrap(),
+        ),
+        Amount::from_atoms(10_000),
+    )
+    .unwrap();
+    let tx = wallet
+        .create_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            vec![self_payment.clone()],
+            MEDIAN_TIME,
+            FEE_RATE,
+        )
+        .unwrap();
+    let tx_id = tx.transaction().get_id();
+    wallet.add_unconfirmed_tx(&tx).unwrap();
+    let replacement = wallet
+        .create_fee_bump_transaction(DEFAULT_ACCOUNT_INDEX, tx_id, higher_fee_rate, FEE_RATE)
+        .unwrap();
+    assert_eq!(replacement.outputs()[0], self_payment);
+    assert_eq!(replacement.outputs().len(), tx.outputs().len());
+}
+
+#[test]
+fn wallet_transaction_history() {
+    let chain_config = Arc::new(create_mainnet());
+
+    let db = create_wallet_in_memory().unwrap();
+    let mut wallet = Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC, None).unwrap();
+    wallet.create_account(DEFAULT_ACCOUNT_INDEX).unwrap();
+
+    let address = get_address(
+        &chain_config,
+        MNEMONIC,
+        DEFAULT_ACCOUNT_INDEX,
+        KeyPurpose::ReceiveFunds,
+        0.try_into().unwrap(),
+    );
+    let block1_amount = Amount::from_atoms(100_000);
+    let block1_timestamp = chain_config.genesis_block().timestamp().add_int_seconds(60).unwrap();
+    let block1 = Block::new(
+        vec![],
+        chain_config.genesis_block_id(),
+        block1_timestamp,
+        ConsensusData::None,
+        BlockReward::new(vec![make_address_output(address, block1_amount).unwrap()]),
+    )
+    .unwrap();
+    let block1_id = block1.header().block_id();
+    wallet.scan_new_blocks(BlockHeight::new(0), vec![block1]).unwrap();
+
+    let history = |wallet: &DefaultWallet, filter: TxHistoryFilter| {
+        wallet.get_transaction_history(DEFAULT_ACCOUNT_INDEX, &filter).unwrap()
+    };
+    let all = TxHistoryFilter {
+        limit: usize::MAX,
+        ..Default::default()
+    };
+    let signed = |amount: Amount| amount.into_signed().unwrap();
+
+    let send_amount = Amount::from_atoms(10_000);
+    let external_destination = Destination::Address(PublicKeyHash::zero());
+    let tx = wallet
+        .create_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            vec![TxOutput::Transfer(OutputValue::Coin(send_amount), external_destination.clone())],
+            MEDIAN_TIME,
+            FEE_RATE,
+        )
+        .unwrap();
+    wallet.add_unconfirmed_tx(&tx).unwrap();
+    let change = match &tx.outputs()[1] {
+        TxOutput::Transfer(OutputValue::Coin(amount), _) => *amount,
+        _ => panic!("unexpected output"),
+    };
+    let fee = ((block1_amount - send_amount).unwrap() - change).unwrap();
+
+    let entries = history(&wallet, all);
+    assert_eq!(entries.len(), 2);
+
+    // The unconfirmed transaction goes first
+    let tx_entry = &entries[0];
+    assert_eq!(
+        tx_entry.tx_id,
+        OutPointSourceId::Transaction(tx.transaction().get_id())
+    );
+    assert_eq!(tx_entry.state, TxState::InMempool);
+    assert_eq!(tx_entry.confirmations, 0);
+    assert_eq!(
+        tx_entry.coin_change,
+        (signed(change) - signed(block1_amount)).unwrap()
+    );
+    assert_eq!(tx_entry.fee, Some(fee));
+    assert_eq!(tx_entry.counterparties, vec![external_destination]);
+
+    let reward_entry = &entries[1];
+    assert_eq!(
+        reward_entry.tx_id,
+        OutPointSourceId::BlockReward(block1_id.into())
+    );
+    assert_eq!(reward_entry.block_height(), Some(BlockHeight::new(1)));
+    assert_eq!(reward_entry.timestamp(), Some(block1_timestamp));
+    assert_eq!(reward_entry.confirmations, 1);
+    assert_eq!(reward_entry.coin_change, signed(block1_amount));
+    assert!(reward_entry.token_changes.is_empty());
+    assert_eq!(reward_entry.fee, None);
+    assert!(reward_entry.counterparties.is_empty());
+
+    let block2 = Block::new(
+        vec![tx],
+        block1_id.into(),
+        block1_timestamp,
+        ConsensusData::None,
+        BlockReward::new(Vec::new()),
+    )
+    .unwrap();
+    wallet.scan_new_blocks(BlockHeight::new(1), vec![block2]).unwrap();
+
+    let entries = history(&wallet, all);
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].block_height(), Some(BlockHeight::new(2)));
+    assert_eq!(entries[0].confirmations, 1);
+    assert_eq!(entries[1].confirmations, 2);
+
+    // Filtering by height
+    let entries = history(
+        &wallet,
+        TxHistoryFilter {
+            min_height: Some(BlockHeight::new(2)),
+            ..all
+        },
+    );
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].block_height(), Some(BlockHeight::new(2)));
+    let entries = history(
+        &wallet,
+        TxHistoryFilter {
+            max_height: Some(BlockHeight::new(1)),
+            ..all
+        },
+    );
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].block_height(), Some(BlockHeight::new(1)));
+
+    // Pagination
+    let entries = history(
+        &wallet,
+        TxHistoryFilter {
+            skip: 1,
+            limit: 1,
+            ..Default::default()
+        },
+    );
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].block_height(), Some(BlockHeight::new(1)));
+}
+
+#[test]
+fn wallet_transaction_history_delegation_outputs() {
+    let chain_config = Arc::new(create_mainnet());
+
+    let db = create_wallet_in_memory().unwrap();
+    let mut wallet = Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC, None).unwrap();
+    wallet.create_account(DEFAULT_ACCOUNT_INDEX).unwrap();
+
+    let address = get_address(
+        &chain_config,
+        MNEMONIC,
+        DEFAULT_ACCOUNT_INDEX,
+        KeyPurpose::ReceiveFunds,
+        0.try_into().unwrap(),
+    );
+    let block1_amount = Amount::from_atoms(100_000);
+    let block1 = Block::new(
+        vec![],
+        chain_config.genesis_block_id(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(vec![make_address_output(address, block1_amount).unwrap()]),
+    )
+    .unwrap();
+    wallet.scan_new_blocks(BlockHeight::new(0), vec![block1]).unwrap();
+
+    // Creating a delegation moves no coins, delegating spends the delegated amount
+    let delegate_amount = Amount::from_atoms(10_000);
+    let tx = wallet
+        .create_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            vec![
+                TxOutput::CreateDelegationId(
+                    Destination::Address(PublicKeyHash::zero()),
+                    PoolId::new(H256::zero()),
+                ),
+                TxOutput::DelegateStaking(delegate_amount, DelegationId::new(H256::zero())),
+            ],
+            MEDIAN_TIME,
+            FEE_RATE,
+        )
+        .unwrap();
+    wallet.add_unconfirmed_tx(&tx).unwrap();
+    let change = match &tx.outputs()[2] {
+        TxOutput::Transfer(OutputValue::Coin(amount), _) => *amount,
+        _ => panic!("unexpected output"),
+    };
+    let fee = ((block1_amount - delegate_amount).unwrap() - change).unwrap();
+    assert!(fee > Amount::ZERO);
+
+    let (balance, _) = wallet
+        .get_balance(
+            DEFAULT_ACCOUNT_INDEX,
+            UtxoType::Transfer.into(),
+            UtxoState::Confirmed | UtxoState::InMempool,
+        )
+        .unwrap();
+    assert_eq!(balance, change);
+
+    let entries = wallet
+        .get_transaction_history(
+            DEFAULT_ACCOUNT_INDEX,
+            &TxHistoryFilter {
+                limit: usize::MAX,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    assert_eq!(entries.len(), 2);
+    let tx_entry = &entries[0];
+    assert_eq!(
+        tx_entry.tx_id,
+        OutPointSourceId::Transaction(tx.transaction().get_id())
+    );
+    assert_eq!(
+        tx_entry.coin_change,
+        (change.into_signed().unwrap() - block1_amount.into_signed().unwrap()).unwrap()
+    );
+    assert_eq!(tx_entry.fee, Some(fee));
+}
+
+#[test]
+fn wallet_delegations() {
+    let chain_config = Arc::new(create_mainnet());
+
+    let db = create_wallet_in_memory().unwrap();
+    let mut wallet = Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC, None).unwrap();
+    wallet.create_account(DEFAULT_ACCOUNT_INDEX).unwrap();
+
+    let address = get_address(
+        &chain_config,
+        MNEMONIC,
+        DEFAULT_ACCOUNT_INDEX,
+        KeyPurpose::ReceiveFunds,
+        0.try_into().unwrap(),
+    );
+    let block1 = Block::new(
+        vec![],
+        chain_config.genesis_block_id(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(vec![make_address_output(
+            address.clone(),
+            Amount::from_atoms(100_000),
+        )
+        .unwrap()]),
+    )
+    .unwrap();
+    let mut prev_block_id = block1.header().block_id();
+    wallet.scan_new_blocks(BlockHeight::new(0), vec![block1]).unwrap();
+
+    let mut add_block = |wallet: &mut DefaultWallet, height: u64, tx: SignedTransaction| {
+        let block = Block::new(
+            vec![tx],
+            prev_block_id.into(),
+            chain_config.genesis_block().timestamp(),
+            ConsensusData::None,
+            BlockReward::new(Vec::new()),
+        )
+        .unwrap();
+        prev_block_id = block.header().block_id();
+        wallet.scan_new_blocks(BlockHeight::new(height), vec![block]).unwrap();
+    };
+
+    let pool_id = PoolId::new(H256::zero());
+    let tx = wallet
+        .create_delegation(
+            DEFAULT_ACCOUNT_INDEX,
+            address.clone(),
+            pool_id,
+            MEDIAN_TIME,
+            FEE_RATE,
+        )
+        .unwrap();
+    let delegation_id = pos_accounting::make_delegation_id(tx.inputs()[0].outpoint());
+    add_block(&mut wallet, 1, tx);
+    assert_eq!(
+        wallet.get_delegations(DEFAULT_ACCOUNT_INDEX).unwrap(),
+        vec![(delegation_id, pool_id)]
+    );
+
+    let delegated_amount = Amount::from_atoms(50_000);
+    let tx = wallet
+        .delegate_staking(
+            DEFAULT_ACCOUNT_INDEX,
+            delegated_amount,
+            delegation_id,
+            MEDIAN_TIME,
+            FEE_RATE,
+        )
+        .unwrap();
+    assert!(tx
+        .outputs()
+        .contains(&TxOutput::DelegateStaking(delegated_amount, delegation_id)));
+    add_block(&mut wallet, 2, tx);
+    let delegation_utxos = wallet
+        .get_utxos(
+            DEFAULT_ACCOUNT_INDEX,
+            UtxoType::DelegateStaking.into(),
+            UtxoState::Confirmed.into(),
+        )
+        .unwrap();
+    assert_eq!(delegation_utxos.len(), 1);
+
+    // A smaller delegation UTXO that can't pay the fee on top of the withdrawn amount
+    let small_delegated_amount = Amount::from_atoms(20_000);
+    let tx = wallet
+        .delegate_staking(
+            DEFAULT_ACCOUNT_INDEX,
+            small_delegated_amount,
+            delegation_id,
+            MEDIAN_TIME,
+            FEE_RATE,
+        )
+        .unwrap();
+    add_block(&mut wallet, 3, tx);
+
+    // Withdrawing more than the delegated amount fails, the fee is part of the required amount
+    let err = wallet
+        .withdraw_from_delegation(
+            DEFAULT_ACCOUNT_INDEX,
+            address.clone(),
+            Amount::from_atoms(60_000),
+            delegation_id,
+            FEE_RATE,
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        WalletError::NotEnoughUtxo(available, required)
+            if available == delegated_amount && required > Amount::from_atoms(60_000)
+    ));
+
+    let unknown_delegation_id = DelegationId::new(H256::zero());
+    let err = wallet
+        .withdraw_from_delegation(
+            DEFAULT_ACCOUNT_INDEX,
+            address.clone(),
+            Amount::from_atoms(20_000),
+            unknown_delegation_id,
+            FEE_RATE,
+        )
+        .unwrap_err();
+    assert_eq!(err, WalletError::DelegationNotFound(unknown_delegation_id));
+
+    // The withdrawn coins are locked and the rest minus the fee is delegated again
+    let withdrawn_amount = (small_delegated_amount - Amount::from_atoms(1)).unwrap();
+    let tx = wallet
+        .withdraw_from_delegation(
+            DEFAULT_ACCOUNT_INDEX,
+            address,
+            withdrawn_amount,
+            delegation_id,
+            FEE_RATE,
+        )
+        .unwrap();
+    assert_eq!(tx.inputs().len(), 1);
+    assert_eq!(
+        tx.inputs()[0].outpoint(),
+        delegation_utxos.keys().next().unwrap()
+    );
+    let maturity: i64 = chain_config.spend_share_maturity_distance(BlockHeight::new(4)).into();
+    match &tx.outputs()[0] {
+        TxOutput::LockThenTransfer(OutputValue::Coin(amount), _, lock) => {
+            assert_eq!(*amount, withdrawn_amount);
+            assert_eq!(*lock, OutputTimeLock::ForBlockCount(maturity as u64));
+        }
+        _ => panic!("unexpected output"),
+    }
+    match &tx.outputs()[1] {
+        TxOutput::DelegateStaking(amount, id) => {
+            assert_eq!(*id, delegation_id);
+            assert!(*amount < (delegated_amount - withdrawn_amount).unwrap());
+        }
+        _ => panic!("unexpected output"),
+    }
+}
+
+#[test]
+fn wallet_decommission_stake_pool() {
+    let chain_config = Arc::new(create_mainnet());
+
+    let db = create_wallet_in_memory().unwrap();
+    let mut wallet = Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC, None).unwrap();
+    wallet.create_account(DEFAULT_ACCOUNT_INDEX).unwrap();
+
+    let address = get_address(
+        &chain_config,
+        MNEMONIC,
+        DEFAULT_ACCOUNT_INDEX,
+        KeyPurpose::ReceiveFunds,
+        0.try_into().unwrap(),
+    );
+    let block1 = Block::new(
+        vec![],
+        chain_config.genesis_block_id(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(vec![make_address_output(
+            address,
+            Amount::from_atoms(100_000),
+        )
+        .unwrap()]),
+    )
+    .unwrap();
+    let block1_id = block1.header().block_id();
+    wallet.scan_new_blocks(BlockHeight::new(0), vec![block1]).unwrap();
+
+    let pledge = Amount::from_atoms(50_000);
+    let tx = wallet
+        .create_stake_pool_tx(DEFAULT_ACCOUNT_INDEX, pledge, MEDIAN_TIME, FEE_RATE)
+        .unwrap();
+    let pool_id = pos_accounting::make_pool_id(tx.inputs()[0].outpoint());
+    let pool_outpoint = tx
+        .outputs()
+        .iter()
+        .position(|output| matches!(output, TxOutput::CreateStakePool(_, _)))
+        .map(|index| OutPoint::new(tx.transaction().get_id().into(), index as u32))
+        .unwrap();
+    let block2 = Block::new(
+        vec![tx],
+        block1_id.into(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(Vec::new()),
+    )
+    .unwrap();
+    wallet.scan_new_blocks(BlockHeight::new(1), vec![block2]).unwrap();
+    assert_eq!(
+        wallet.get_pool_ids(DEFAULT_ACCOUNT_INDEX).unwrap(),
+        vec![pool_id]
+    );
+
+    let unknown_pool_id = PoolId::new(H256::zero());
+    let err = wallet
+        .decommission_stake_pool(DEFAULT_ACCOUNT_INDEX, unknown_pool_id, pledge, FEE_RATE)
+        .unwrap_err();
+    assert_eq!(err, WalletError::UnknownPoolId(unknown_pool_id));
+
+    // The pledge minus the fee is returned to the wallet and locked
+    let tx = wallet
+        .decommission_stake_pool(DEFAULT_ACCOUNT_INDEX, pool_id, pledge, FEE_RATE)
+        .unwrap();
+    assert_eq!(tx.inputs().len(), 1);
+    assert_eq!(tx.inputs()[0].outpoint(), &pool_outpoint);
+    assert_eq!(tx.outputs().len(), 1);
+    let maturity: i64 =
+        chain_config.decommission_pool_maturity_distance(BlockHeight::new(2)).into();
+    match &tx.outputs()[0] {
+        TxOutput::LockThenTransfer(OutputValue::Coin(amount), _, lock) => {
+            assert!(*amount < pledge);
+            assert!((pledge - *amount).unwrap() <= Amount::from_atoms(MAX_NETWORK_FEE));
+            assert_eq!(*lock, OutputTimeLock::ForBlockCount(maturity as u64));
+        }
+        _ => panic!("unexpected output"),
+    }
+
+    // The decommissioned pool is no longer listed and the locked coins belong to the wallet
+    wallet.add_unconfirmed_tx(&tx).unwrap();
+    assert!(wallet.get_pool_ids(DEFAULT_ACCOUNT_INDEX).unwrap().is_empty());
+    let locked_utxos = wallet
+        .get_utxos(
+            DEFAULT_ACCOUNT_INDEX,
+            UtxoType::LockThenTransfer.into(),
+            UtxoState::InMempool.into(),
+        )
+        .unwrap();
+    assert_eq!(locked_utxos.len(), 1);
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn locked_wallet_creates_partially_signed_transaction(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_mainnet());
+
+    let db = create_wallet_in_memory().unwrap();
+    let mut wallet = Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC, None).unwrap();
+    wallet.create_account(DEFAULT_ACCOUNT_INDEX).unwrap();
+
+    let block1_amount =
+        Amount::from_atoms(rng.gen_range(MAX_NETWORK_FEE + 1..MAX_NETWORK_FEE + 10000));
+    let address = get_address(
+        &chain_config,
+        MNEMONIC,
+        DEFAULT_ACCOUNT_INDEX,
+        KeyPurpose::ReceiveFunds,
+        0.try_into().unwrap(),
+    );
+    let block1 = Block::new(
+        vec![],
+        chain_config.genesis_block_id(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(vec![make_address_output(address, block1_amount).unwrap()]),
+    )
+    .unwrap();
+    wallet.scan_new_blocks(BlockHeight::new(0), vec![block1]).unwrap();
+
+    let password = Some(gen_random_password(&mut rng));
+    wallet.encrypt_wallet(&password).unwrap();
+    wallet.lock_wallet().unwrap();
+
+    // The transaction can be created without the private keys
+    let new_output = TxOutput::Transfer(
+        OutputValue::Coin(Amount::from_atoms(
+            rng.gen_range(1..block1_amount.into_atoms() - MAX_NETWORK_FEE),
+        )),
+        Destination::AnyoneCanSpend,
+    );
+    let ptx = wallet
+        .create_partially_signed_transaction(
+            DEFAULT_ACCOUNT_INDEX,
+            [new_output],
+            MEDIAN_TIME,
+            FEE_RATE,
+        )
+        .unwrap();
+    assert_eq!(ptx.signed_inputs_count(&chain_config), 0);
+    assert!(!ptx.is_fully_signed(&chain_config));
+    assert_eq!(
+        ptx.clone().into_signed_tx(&chain_config),
+        Err(PartiallySignedTransactionError::MissingWitness(0))
+    );
+    assert_eq!(
+        wallet.sign_partially_signed_transaction(DEFAULT_ACCOUNT_INDEX, ptx.clone()),
+        Err(WalletError::DatabaseError(
+            wallet_storage::Error::WalletLocked
+        ))
+    );
+
+    // The decoding checks that there is a witness for every input
+    let invalid_ptx = (
+        ptx.tx(),
+        &ptx.witnesses()[1..],
+        ptx.input_utxos(),
+        ptx.destinations(),
+    );
+    assert!(PartiallySignedTransaction::decode_all(&mut invalid_ptx.encode().as_slice()).is_err());
+
+    // The encoded transaction can be signed later
+    let ptx = PartiallySignedTransaction::decode_all(&mut ptx.encode().as_slice()).unwrap();
+    wallet.unlock_wallet(&password.unwrap()).unwrap();
+    let signed_ptx = wallet
+        .sign_partially_signed_transaction(DEFAULT_ACCOUNT_INDEX, ptx.clone())
+        .unwrap();
+    assert!(signed_ptx.is_fully_signed(&chain_config));
+
+    // Signing again changes nothing
+    assert_eq!(
+        wallet
+            .sign_partially_signed_transaction(DEFAULT_ACCOUNT_INDEX, signed_ptx.clone())
+            .unwrap(),
+        signed_ptx
+    );
+    let combined = ptx.combine(signed_ptx.clone()).unwrap();
+    assert_eq!(combined, signed_ptx);
+
+    let tx = signed_ptx.into_signed_tx(&chain_config).unwrap();
+    assert_eq!(tx.transaction(), combined.tx());
+}
+
+#[test]
+fn classic_multisig_partial_signatures() {
+    let chain_config = Arc::new(create_mainnet());
+
+    let mut wallets = [MNEMONIC, MNEMONIC2].map(|mnemonic| {
+        let db = create_wallet_in_memory().unwrap();
+        let mut wallet = Wallet::new_wallet(Arc::clone(&chain_config), db, mnemonic, None).unwrap();
+        wallet.create_account(DEFAULT_ACCOUNT_INDEX).unwrap();
+        wallet
+    });
+    let public_keys = wallets
+        .iter_mut()
+        .map(|wallet| wallet.get_new_public_key(DEFAULT_ACCOUNT_INDEX).unwrap())
+        .collect();
+    let challenge =
+        ClassicMultisigChallenge::new(&chain_config, NonZeroU8::new(2).unwrap(), public_keys)
+            .unwrap();
+    let destination = Destination::ClassicMultisig(PublicKeyHash::from(&challenge));
+
+    let utxo = TxOutput::Transfer(
+        OutputValue::Coin(Amount::from_atoms(10_000)),
+        destination.clone(),
+    );
+    let tx = Transaction::new(
+        0,
+        vec![TxInput::new(OutPointSourceId::Transaction(Id::new(H256::zero())), 0)],
+        vec![TxOutput::Transfer(
+            OutputValue::Coin(Amount::from_atoms(9_000)),
+            Destination::AnyoneCanSpend,
+        )],
+    )
+    .unwrap();
+    let ptx = PartiallySignedTransaction::new(tx, vec![None], vec![utxo], vec![Some(destination)])
+        .unwrap();
+
+    // Nothing can be signed without the challenge
+    let unsigned_ptx = wallets[0]
+        .sign_partially_signed_transaction(DEFAULT_ACCOUNT_INDEX, ptx.clone())
+        .unwrap();
+    assert_eq!(unsigned_ptx, ptx);
+
+    let mut ptx = ptx;
+    ptx.add_multisig_challenge(0, challenge).unwrap();
+
+    // Each wallet adds one signature
+    let signed_ptxs = wallets
+        .iter()
+        .map(|wallet| {
+            wallet
+                .sign_partially_signed_transaction(DEFAULT_ACCOUNT_INDEX, ptx.clone())
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+    for signed_ptx in &signed_ptxs {
+        assert_eq!(
+            signed_ptx.multisig_spend(0).unwrap().available_signatures_count(),
+            1
+        );
+        assert!(!signed_ptx.is_fully_signed(&chain_config));
+    }
+
+    let combined = signed_ptxs[0].clone().combine(signed_ptxs[1].clone()).unwrap();
+    assert_eq!(
+        combined.multisig_spend(0).unwrap().available_signatures_count(),
+        2
+    );
+    assert!(combined.is_fully_signed(&chain_config));
+    combined.into_signed_tx(&chain_config).unwrap();
+
+    // The second wallet can also complete the signatures of the first one
+    let completed = wallets[1]
+        .sign_partially_signed_transaction(DEFAULT_ACCOUNT_INDEX, signed_ptxs[0].clone())
+        .unwrap();
+    assert!(completed.is_fully_signed(&chain_config));
+}
+
+#[test]
+fn wallet_sign_message() {
+    let chain_config = Arc::new(create_mainnet());
+
+    let db = create_wallet_in_memory().unwrap();
+    let mut wallet = Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC, None).unwrap();
+    wallet.create_account(DEFAULT_ACCOUNT_INDEX).unwrap();
+
+    let address = wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
+    let message = b"I own this address";
+    let signature = wallet.sign_message(DEFAULT_ACCOUNT_INDEX, &address, message).unwrap();
+    signature
+        .verify_signature_for_address(&chain_config, address.get(), message)
+        .unwrap();
+    assert!(signature
+        .verify_signature_for_address(&chain_config, address.get(), b"another message")
+        .is_err());
+
+    // The signature can't be made for an address of another wallet
+    let other_address = get_address(
+        &chain_config,
+        MNEMONIC2,
+        DEFAULT_ACCOUNT_INDEX,
+        KeyPurpose::ReceiveFunds,
+        0.try_into().unwrap(),
+    );
+    assert_eq!(
+        wallet.sign_message(DEFAULT_ACCOUNT_INDEX, &other_address, message),
+        Err(WalletError::KeyChainError(KeyChainError::NoPrivateKeyFound))
+    );
+}
+
+#[test]
+fn wallet_address_labels_and_contacts() {
+    let chain_config = Arc::new(create_mainnet());
+
+    let db = create_wallet_in_memory().unwrap();
+    let mut wallet = Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC, None).unwrap();
+    wallet.create_account(DEFAULT_ACCOUNT_INDEX).unwrap();
+
+    let own_address = wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
+    let other_address = get_address(
+        &chain_config,
+        MNEMONIC2,
+        DEFAULT_ACCOUNT_INDEX,
+        KeyPurpose::ReceiveFunds,
+        0.try_into().unwrap(),
+    );
+
+    // Only the wallet addresses can be labeled
+    wallet.set_address_label(&own_address, Some(" savings ".to_owned())).unwrap();
+    assert_eq!(
+        wallet.set_address_label(&other_address, Some("other".to_owned())),
+        Err(WalletError::AddressNotOwned(other_address.get().to_owned()))
+    );
+    assert_eq!(
+        wallet.set_address_label(&own_address, Some(" ".to_owned())),
+        Err(WalletError::EmptyLabel)
+    );
+    assert_eq!(
+        wallet.get_address_labels().unwrap(),
+        BTreeMap::from([(own_address.clone(), "savings".to_owned())])
+    );
+
+    // Only the external addresses can be contacts
+    wallet.add_contact("alice".to_owned(), &other_address).unwrap();
+    assert_eq!(
+        wallet.add_contact("me".to_owned(), &own_address),
+        Err(WalletError::OwnAddressInAddressBook(
+            own_address.get().to_owned()
+        ))
+    );
+    assert_eq!(
+        wallet.find_contact("alice").unwrap(),
+        Some(other_address.clone())
+    );
+    assert_eq!(wallet.find_contact("bob").unwrap(), None);
+
+    // The labels and the contacts are stored in the DB
+    let wallet_copy = Wallet::load_wallet(Arc::clone(&chain_config), wallet.db.clone()).unwrap();
+    assert_eq!(
+        wallet_copy.get_address_labels().unwrap(),
+        BTreeMap::from([(own_address.clone(), "savings".to_owned())])
+    );
+    assert_eq!(
+        wallet_copy.get_address_book().unwrap(),
+        BTreeMap::from([(other_address.clone(), "alice".to_owned())])
+    );
+
+    // The contact names are unique
+    let another_address = get_address(
+        &chain_config,
+        MNEMONIC2,
+        DEFAULT_ACCOUNT_INDEX,
+        KeyPurpose::ReceiveFunds,
+        1.try_into().unwrap(),
+    );
+    assert_eq!(
+        wallet.add_contact("alice".to_owned(), &another_address),
+        Err(WalletError::DuplicateContactName("alice".to_owned()))
+    );
+    wallet.add_contact("alice2".to_owned(), &other_address).unwrap();
+    assert_eq!(wallet.find_contact("alice").unwrap(), None);
+
+    wallet.set_address_label(&own_address, None).unwrap();
+    assert!(wallet.get_address_labels().unwrap().is_empty());
+    wallet.remove_contact("alice2").unwrap();
+    assert!(wallet.get_address_book().unwrap().is_empty());
+    assert_eq!(
+        wallet.remove_contact("alice2"),
+        Err(WalletError::ContactNotFound("alice2".to_owned()))
+    );
+}
+
+#[test]
+fn wallet_classic_multisig_address() {
+    let chain_config = Arc::new(create_mainnet());
+
+    let mut wallets = [MNEMONIC, MNEMONIC2].map(|mnemonic| {
+        let db = create_wallet_in_memory().unwrap();
+        let mut wallet = Wallet::new_wallet(Arc::clone(&chain_config), db, mnemonic, None).unwrap();
+        wallet.create_account(DEFAULT_ACCOUNT_INDEX).unwrap();
+        wallet
+    });
+    let public_keys = wallets
+        .iter_mut()
+        .map(|wallet| wallet.get_new_public_key(DEFAULT_ACCOUNT_INDEX).unwrap())
+        .collect::<Vec<_>>();
+    let min_required_signatures = NonZeroU8::new(2).unwrap();
+
+    // Both owners get the same address
+    let multisig_addresses = wallets
+        .iter_mut()
+        .map(|wallet| {
+            wallet
+                .add_classic_multisig(
+                    DEFAULT_ACCOUNT_INDEX,
+                    min_required_signatures,
+                    public_keys.clone(),
+                )
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(multisig_addresses[0], multisig_addresses[1]);
+    let multisig_address = multisig_addresses[0].clone();
+    let challenge =
+        ClassicMultisigChallenge::new(&chain_config, min_required_signatures, public_keys).unwrap();
+    assert_eq!(
+        multisig_address.destination().unwrap(),
+        Destination::ClassicMultisig(PublicKeyHash::from(&challenge))
+    );
+
+    // A single key address is not a multisig address
+    let address = get_address(
+        &chain_config,
+        MNEMONIC2,
+        DEFAULT_ACCOUNT_INDEX,
+        KeyPurpose::ReceiveFunds,
+        0.try_into().unwrap(),
+    );
+    let send_amount = Amount::from_atoms(10_000);
+    let outputs = [make_address_output(address.clone(), send_amount).unwrap()];
+    assert_eq!(
+        wallets[0].create_classic_multisig_spend(
+            DEFAULT_ACCOUNT_INDEX,
+            &address,
+            outputs.clone(),
+            MEDIAN_TIME,
+            FEE_RATE
+        ),
+        Err(WalletError::NotClassicMultisigAddress(
+            address.get().to_owned()
+        ))
+    );
+
+    let multisig_amount = Amount::from_atoms(100_000);
+    let block1 = Block::new(
+        vec![],
+        chain_config.genesis_block_id(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(vec![make_address_output(
+            multisig_address.clone(),
+            multisig_amount,
+        )
+        .unwrap()]),
+    )
+    .unwrap();
+    for wallet in wallets.iter_mut() {
+        wallet.scan_new_blocks(BlockHeight::new(0), vec![block1.clone()]).unwrap();
+
+        // The multisig funds are watched, but can't be spent by the account alone
+        assert_eq!(
+            wallet.get_classic_multisigs(DEFAULT_ACCOUNT_INDEX).unwrap(),
+            vec![(multisig_address.clone(), challenge.clone(), multisig_amount)]
+        );
+        verify_wallet_balance(&chain_config, wallet, Amount::ZERO);
+    }
+
+    // The watched multisigs are loaded from the DB
+    let loaded_wallet =
+        Wallet::load_wallet(Arc::clone(&chain_config), wallets[0].db.clone()).unwrap();
+    assert_eq!(
+        loaded_wallet.get_classic_multisigs(DEFAULT_ACCOUNT_INDEX).unwrap(),
+        vec![(multisig_address.clone(), challenge.clone(), multisig_amount)]
+    );
+
+    let ptx = wallets[0]
+        .create_classic_multisig_spend(
+            DEFAULT_ACCOUNT_INDEX,
+            &multisig_address,
+            outputs,
+            MEDIAN_TIME,
+            FEE_RATE,
+        )
+        .unwrap();
+
+    // The change goes back to the multisig
+    assert_eq!(ptx.tx().outputs().len(), 2);
+    let (change_amount, change_destination) = match &ptx.tx().outputs()[1] {
+        TxOutput::Transfer(OutputValue::Coin(amount), destination) => {
+            (*amount, destination.clone())
+        }
+        _ => panic!("unexpected change output"),
+    };
+    assert_eq!(change_destination, multisig_address.destination().unwrap());
+    assert_eq!(
+        ptx.multisig_spend(0).unwrap().challenge(),
+        &challenge,
+        "the challenge is added to the multisig input"
+    );
+
+    let signed_ptxs = wallets
+        .iter()
+        .map(|wallet| {
+            wallet
+                .sign_partially_signed_transaction(DEFAULT_ACCOUNT_INDEX, ptx.clone())
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+    assert!(!signed_ptxs[0].is_fully_signed(&chain_config));
+    let combined = signed_ptxs[0].clone().combine(signed_ptxs[1].clone()).unwrap();
+    let tx = combined.into_signed_tx(&chain_config).unwrap();
+
+    // Once the transaction is confirmed, only the change is left in the multisig
+    let block2 = Block::new(
+        vec![tx.clone()],
+        block1.get_id().into(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(vec![]),
+    )
+    .unwrap();
+    wallets[0].scan_new_blocks(BlockHeight::new(1), vec![block2]).unwrap();
+    assert_eq!(
+        wallets[0].get_classic_multisigs(DEFAULT_ACCOUNT_INDEX).unwrap(),
+        vec![(multisig_address, challenge, change_amount)]
+    );
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn watch_only_wallet(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_mainnet());
+
+    let db = create_wallet_in_memory().unwrap();
+    let mut wallet = Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC, None).unwrap();
+    wallet.create_account(DEFAULT_ACCOUNT_INDEX).unwrap();
+    let account_pubkey = wallet.get_account_public_key(DEFAULT_ACCOUNT_INDEX).unwrap();
+
+    let db = create_wallet_in_memory().unwrap();
+    let mut watch_only_wallet =
+        Wallet::new_watch_only_wallet(Arc::clone(&chain_config), db).unwrap();
+    watch_only_wallet
+        .create_watch_only_account(DEFAULT_ACCOUNT_INDEX, account_pubkey.clone())
+        .unwrap();
+    assert_eq!(
+        watch_only_wallet.get_account_public_key(DEFAULT_ACCOUNT_INDEX).unwrap(),
+        account_pubkey
+    );
+
+    // Both wallets derive the same addresses
+    assert_eq!(
+        watch_only_wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap(),
+        wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap()
+    );
+
+    let block1_amount =
+        Amount::from_atoms(rng.gen_range(MAX_NETWORK_FEE + 1..MAX_NETWORK_FEE + 10000));
+    let address = get_address(
+        &chain_config,
+        MNEMONIC,
+        DEFAULT_ACCOUNT_INDEX,
+        KeyPurpose::ReceiveFunds,
+        0.try_into().unwrap(),
+    );
+    let block1 = Block::new(
+        vec![],
+        chain_config.genesis_block_id(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(vec![make_address_output(address, block1_amount).unwrap()]),
+    )
+    .unwrap();
+    wallet.scan_new_blocks(BlockHeight::new(0), vec![block1.clone()]).unwrap();
+    watch_only_wallet.scan_new_blocks(BlockHeight::new(0), vec![block1]).unwrap();
+    verify_wallet_balance(&chain_config, &watch_only_wallet, block1_amount);
+
+    // The watch-only wallet can create the transaction, but only the other wallet can sign it
+    let new_output = TxOutput::Transfer(
+        OutputValue::Coin(Amount::from_atoms(
+            rng.gen_range(1..block1_amount.into_atoms() - MAX_NETWORK_FEE),
+        )),
+        Destination::AnyoneCanSpend,
+    );
+    let ptx = watch_only_wallet
+        .create_partially_signed_transaction(
+            DEFAULT_ACCOUNT_INDEX,
+            [new_output],
+            MEDIAN_TIME,
+            FEE_RATE,
+        )
+        .unwrap();
+    assert_eq!(
+        watch_only_wallet.sign_partially_signed_transaction(DEFAULT_ACCOUNT_INDEX, ptx.clone()),
+        Err(WalletError::KeyChainError(KeyChainError::NoRootKey))
+    );
+    assert_eq!(
+        watch_only_wallet.create_account(DEFAULT_ACCOUNT_INDEX.plus_one().unwrap()),
+        Err(WalletError::KeyChainError(KeyChainError::NoRootKey))
+    );
+
+    let signed_ptx = wallet.sign_partially_signed_transaction(DEFAULT_ACCOUNT_INDEX, ptx).unwrap();
+    assert!(signed_ptx.is_fully_signed(&chain_config));
+
+    // Only the wallets created as watch-only can be loaded without a root key
+    Wallet::load_wallet(Arc::clone(&chain_config), watch_only_wallet.db).unwrap();
+    let db = wallet.db;
+    let mut db_tx = db.transaction_rw_unlocked(None).unwrap();
+    for root_key_id in db_tx.get_all_root_keys().unwrap().into_keys() {
+        db_tx.del_root_key(&root_key_id).unwrap();
+    }
+    db_tx.commit().unwrap();
+    assert_eq!(
+        Wallet::load_wallet(Arc::clone(&chain_config), db).err(),
+        Some(WalletError::KeyChainError(KeyChainError::NoRootKey))
+    );
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn wallet_recovery(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_mainnet());
+    let account1_index = DEFAULT_ACCOUNT_INDEX.plus_one().unwrap();
+    let account2_index = account1_index.plus_one().unwrap();
+
+    // An address beyond the default lookahead of the first account and an address of the second account
+    let address_index = rng.gen_range(LOOKAHEAD_SIZE..LOOKAHEAD_SIZE * 2);
+    let address0 = get_address(
+        &chain_config,
+        MNEMONIC,
+        DEFAULT_ACCOUNT_INDEX,
+        KeyPurpose::ReceiveFunds,
+        address_index.try_into().unwrap(),
+    );
+    let address1 = get_address(
+        &chain_config,
+        MNEMONIC,
+        account1_index,
+        KeyPurpose::Change,
+        0.try_into().unwrap(),
+    );
+    let amount0 = Amount::from_atoms(rng.gen_range(1..10000));
+    let amount1 = Amount::from_atoms(rng.gen_range(1..10000));
+    let block1 = Block::new(
+        vec![],
+        chain_config.genesis_block_id(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(vec![
+            make_address_output(address0, amount0).unwrap(),
+            make_address_output(address1, amount1).unwrap(),
+        ]),
+    )
+    .unwrap();
+
+    let get_coin_balance = |wallet: &DefaultWallet, account_index: U31| {
+        wallet
+            .get_balance(
+                account_index,
+                UtxoType::Transfer | UtxoType::LockThenTransfer,
+                UtxoState::Confirmed.into(),
+            )
+            .unwrap()
+            .0
+    };
+
+    // The address is not found with the default lookahead size
+    let db = create_wallet_in_memory().unwrap();
+    let mut wallet = Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC, None).unwrap();
+    wallet.create_account(DEFAULT_ACCOUNT_INDEX).unwrap();
+    wallet.scan_new_blocks(BlockHeight::new(0), vec![block1.clone()]).unwrap();
+    assert!(!wallet.account_has_transactions(DEFAULT_ACCOUNT_INDEX).unwrap());
+
+    let db = create_wallet_in_memory().unwrap();
+    let mut wallet = Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC, None).unwrap();
+    assert_eq!(
+        wallet.create_account_with_lookahead_size(DEFAULT_ACCOUNT_INDEX, 0),
+        Err(WalletError::InvalidLookaheadSize(0))
+    );
+
+    // Scan the accounts one by one until an unused one is found
+    let lookahead_size = LOOKAHEAD_SIZE * 2;
+    for account_index in [DEFAULT_ACCOUNT_INDEX, account1_index, account2_index] {
+        wallet
+            .create_account_with_lookahead_size(account_index, lookahead_size)
+            .unwrap();
+        wallet
+            .scan_new_blocks_for_account(account_index, BlockHeight::new(0), vec![block1.clone()])
+            .unwrap();
+    }
+    assert!(wallet.account_has_transactions(DEFAULT_ACCOUNT_INDEX).unwrap());
+    assert!(wallet.account_has_transactions(account1_index).unwrap());
+    assert!(!wallet.account_has_transactions(account2_index).unwrap());
+    wallet.remove_account(account2_index).unwrap();
+    assert_eq!(
+        wallet.remove_account(account2_index),
+        Err(WalletError::NoAccountFoundWithIndex(account2_index))
+    );
+
+    assert_eq!(get_coin_balance(&wallet, DEFAULT_ACCOUNT_INDEX), amount0);
+    assert_eq!(get_coin_balance(&wallet, account1_index), amount1);
+
+    // The removed account is not loaded again and can be recreated
+    let mut wallet = Wallet::load_wallet(Arc::clone(&chain_config), wallet.db).unwrap();
+    assert_eq!(
+        wallet.account_indexes().cloned().collect::<Vec<_>>(),
+        vec![DEFAULT_ACCOUNT_INDEX, account1_index]
+    );
+    assert_eq!(get_coin_balance(&wallet, DEFAULT_ACCOUNT_INDEX), amount0);
+    assert_eq!(get_coin_balance(&wallet, account1_index), amount1);
+    wallet.create_account(account2_index).unwrap();
+
+    // The blocks before the account's best block are skipped
+    wallet
+        .set_account_best_block(account2_index, BlockHeight::new(1), block1.get_id().into())
+        .unwrap();
+    let block2 = Block::new(
+        vec![],
+        block1.get_id().into(),
+        block1.timestamp(),
+        ConsensusData::None,
+        BlockReward::new(vec![]),
+    )
+    .unwrap();
+    wallet
+        .scan_new_blocks_for_account(account2_index, BlockHeight::new(1), vec![block2])
+        .unwrap();
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn wallet_backup(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_mainnet());
+    let account1_index = DEFAULT_ACCOUNT_INDEX.plus_one().unwrap();
+    let watch_only_index = account1_index.plus_one().unwrap();
+    let lookahead_size = LOOKAHEAD_SIZE * 2;
+
+    let db = create_wallet_in_memory().unwrap();
+    let mut wallet = Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC, None).unwrap();
+    wallet.create_account(DEFAULT_ACCOUNT_INDEX).unwrap();
+    wallet.set_account_name(DEFAULT_ACCOUNT_INDEX, Some("main".to_owned())).unwrap();
+    wallet
+        .create_account_with_lookahead_size(account1_index, lookahead_size)
+        .unwrap();
+
+    let db2 = create_wallet_in_memory().unwrap();
+    let mut wallet2 = Wallet::new_wallet(Arc::clone(&chain_config), db2, MNEMONIC2, None).unwrap();
+    wallet2.create_account(DEFAULT_ACCOUNT_INDEX).unwrap();
+    let watched_pubkey = wallet2.get_account_public_key(DEFAULT_ACCOUNT_INDEX).unwrap();
+    wallet
+        .create_watch_only_account(watch_only_index, watched_pubkey.clone())
+        .unwrap();
+
+    let own_address = wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
+    wallet.set_address_label(&own_address, Some("savings".to_owned())).unwrap();
+    let contact_address = wallet2.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
+    wallet.add_contact("alice".to_owned(), &contact_address).unwrap();
+    let public_keys = vec![
+        wallet.get_new_public_key(DEFAULT_ACCOUNT_INDEX).unwrap(),
+        wallet2.get_new_public_key(DEFAULT_ACCOUNT_INDEX).unwrap(),
+    ];
+    let multisig_address = wallet
+        .add_classic_multisig(
+            DEFAULT_ACCOUNT_INDEX,
+            NonZeroU8::new(2).unwrap(),
+            public_keys,
+        )
+        .unwrap();
+
+    let password = "backup password".to_owned();
+    let data = wallet.export_backup(&password).unwrap();
+    assert_eq!(
+        WalletBackup::decrypt(&data, &"wrong password".to_owned()),
+        Err(WalletError::BackupDecryption)
+    );
+    let backup = WalletBackup::decrypt(&data, &password).unwrap();
+
+    let db = create_wallet_in_memory().unwrap();
+    let mut restored = Wallet::restore_from_backup(Arc::clone(&chain_config), db, &backup).unwrap();
+
+    assert_eq!(
+        restored.account_names().collect::<Vec<_>>(),
+        wallet.account_names().collect::<Vec<_>>()
+    );
+    for account_index in [DEFAULT_ACCOUNT_INDEX, account1_index, watch_only_index] {
+        assert_eq!(
+            restored.get_account_public_key(account_index).unwrap(),
+            wallet.get_account_public_key(account_index).unwrap()
+        );
+    }
+    assert_eq!(
+        restored.accounts[&account1_index].lookahead_size(),
+        lookahead_size
+    );
+    assert_eq!(
+        restored.get_address_labels().unwrap(),
+        BTreeMap::from([(own_address.clone(), "savings".to_owned())])
+    );
+    assert_eq!(
+        restored.get_address_book().unwrap(),
+        BTreeMap::from([(contact_address, "alice".to_owned())])
+    );
+    assert_eq!(
+        restored
+            .get_classic_multisigs(DEFAULT_ACCOUNT_INDEX)
+            .unwrap()
+            .into_iter()
+            .map(|(address, _, _)| address)
+            .collect::<Vec<_>>(),
+        vec![multisig_address]
+    );
+
+    // The restored accounts find their funds when the blocks are scanned again
+    let address_index = rng.gen_range(LOOKAHEAD_SIZE..lookahead_size);
+    let address = get_address(
+        &chain_config,
+        MNEMONIC,
+        account1_index,
+        KeyPurpose::ReceiveFunds,
+        address_index.try_into().unwrap(),
+    );
+    let amount = Amount::from_atoms(rng.gen_range(1..10000));
+    let block1 = Block::new(
+        vec![],
+        chain_config.genesis_block_id(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(vec![make_address_output(address, amount).unwrap()]),
+    )
+    .unwrap();
+    restored.scan_new_blocks(BlockHeight::new(0), vec![block1]).unwrap();
+    let (balance, _) = restored
+        .get_balance(
+            account1_index,
+            UtxoType::Transfer | UtxoType::LockThenTransfer,
+            UtxoState::Confirmed.into(),
+        )
+        .unwrap();
+    assert_eq!(balance, amount);
+
+    // A watch-only wallet can be restored without a root key
+    let db = create_wallet_in_memory().unwrap();
+    let mut watch_only_wallet =
+        Wallet::new_watch_only_wallet(Arc::clone(&chain_config), db).unwrap();
+    watch_only_wallet
+        .create_watch_only_account(DEFAULT_ACCOUNT_INDEX, watched_pubkey)
+        .unwrap();
+    let data = watch_only_wallet.export_backup(&password).unwrap();
+    let backup = WalletBackup::decrypt(&data, &password).unwrap();
+    assert_eq!(backup.root_key, None);
+    let db = create_wallet_in_memory().unwrap();
+    let restored = Wallet::restore_from_backup(Arc::clone(&chain_config), db, &backup).unwrap();
+    assert_eq!(
+        restored.account_indexes().cloned().collect::<Vec<_>>(),
+        vec![DEFAULT_ACCOUNT_INDEX]
+    );
+}
+
+#[track_caller]
+fn test_wallet_accounts(
+    chain_config: &Arc<ChainConfig>,
+    wallet: &DefaultWallet,
+    expected_accounts: Vec<U31>,
+) {
+    let accounts = wallet.account_indexes().cloned().collect::<Vec<_>>();
+    assert_eq!(accounts, expected_accounts);
+
+    let db_copy = wallet.db.clone();
+    let mut wallet = Wallet::load_wallet(Arc::clone(chain_config), db_copy).unwrap();
+    let accounts = wallet.account_indexes().cloned().collect::<Vec<_>>();
+    assert_eq!(accounts, expected_accounts);
+
+    for account_index in expected_accounts {
+        wallet.create_account(account_index).expect_err("Account should already exist");
+    }
+}
+
+#[test]
+fn wallet_accounts_creation() {
+    let chain_config = Arc::new(create_mainnet());
+
+    let db = create_wallet_in_memory().unwrap();
+    let mut wallet = Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC, None).unwrap();
+    test_wallet_accounts(&chain_config, &wallet, vec![]);
+
+    wallet.create_account(DEFAULT_ACCOUNT_INDEX).unwrap();
+    test_wallet_accounts(&chain_config, &wallet, vec![DEFAULT_ACCOUNT_INDEX]);
+
+    wallet.create_account(1.try_into().unwrap()).unwrap();
+    test_wallet_accounts(
+        &chain_config,
+        &wallet,
+        vec![DEFAULT_ACCOUNT_INDEX, 1.try_into().unwrap()],
+    );
+
+    let db = create_wallet_in_memory().unwrap();
+    let mut wallet = Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC, None).unwrap();
+    wallet.create_account(123.try_into().unwrap()).unwrap();
+    test_wallet_accounts(&chain_config, &wallet, vec![123.try_into().unwrap()]);
+}
+
+#[test]
+fn wallet_next_account_creation() {
+    let chain_config = Arc::new(create_mainnet());
+    let account1_index = DEFAULT_ACCOUNT_INDEX.plus_one().unwrap();
+
+    let db = create_wallet_in_memory().unwrap();
+    let mut wallet = Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC, None).unwrap();
+    wallet.create_account(DEFAULT_ACCOUNT_INDEX).unwrap();
+
+    // The last account must be used first
+    assert_eq!(
+        wallet.create_next_account(Some("Savings".to_owned())),
+        Err(WalletError::EmptyLastAccount(DEFAULT_ACCOUNT_INDEX))
+    );
+
+    let address = wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
+    let block1 = Block::new(
+        vec![],
+        chain_config.genesis_block_id(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(vec![
+            make_address_output(address, Amount::from_atoms(1)).unwrap()
+        ]),
+    )
+    .unwrap();
+    wallet.scan_new_blocks(BlockHeight::new(0), vec![block1]).unwrap();
+
+    assert_eq!(
+        wallet.create_next_account(Some("Savings".to_owned())),
+        Ok(account1_index)
+    );
+    assert_eq!(
+        wallet.create_next_account(None),
+        Err(WalletError::EmptyLastAccount(account1_index))
+    );
+
+    wallet.set_account_name(DEFAULT_ACCOUNT_INDEX, Some("Main".to_owned())).unwrap();
+    wallet.set_account_name(account1_index, None).unwrap();
+
+    let account_names = |wallet: &DefaultWallet| {
+        wallet
+            .account_names()
+            .map(|(account_index, name)| (*account_index, name.clone()))
+            .collect::<Vec<_>>()
+    };
+    let expected_names =
+        vec![(DEFAULT_ACCOUNT_INDEX, Some("Main".to_owned())), (account1_index, None)];
+    assert_eq!(account_names(&wallet), expected_names);
+
+    let wallet = Wallet::load_wallet(Arc::clone(&chain_config), wallet.db).unwrap();
+    assert_eq!(account_names(&wallet), expected_names);
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn locked_wallet_accounts_creation_fail(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_mainnet());
+
+    let db = create_wallet_in_memory().unwrap();
+    let mut wallet = Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC, None).unwrap();
+    let password = Some(gen_random_password(&mut rng));
+    wallet.encrypt_wallet(&password).unwrap();
+    wallet.lock_wallet().unwrap();
+
+    let err = wallet.create_account(rng.gen_range(0..1 << 31).try_into().unwrap());
+    assert_eq!(
+        err,
+        Err(WalletError::DatabaseError(
+            wallet_storage::Error::WalletLocked
+        ))
+    );
+
+    // success after unlock
+    wallet.unlock_wallet(&password.unwrap()).unwrap();
+    wallet.create_account(rng.gen_range(0..1 << 31).try_into().unwrap()).unwrap();
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn locked_wallet_cant_sign_transaction(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_mainnet());
+
+    let db = create_wallet_in_memory().unwrap();
+    let mut wallet = Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC, None).unwrap();
+    wallet.create_account(DEFAULT_ACCOUNT_INDEX).unwrap();
+
+    let (coin_balance, _) = wallet
+        .get_balance(
+            DEFAULT_ACCOUNT_INDEX,
+            UtxoType::Transfer | UtxoType::LockThenTransfer,
+            UtxoState::Confirmed.into(),
+        )
+        .unwrap();
+    assert_eq!(coin_balance, Amount::ZERO);
+
+    // Generate a new block which sends reward to the wallet
+    let block1_amount =
+        Amount::from_atoms(rng.gen_range(MAX_NETWORK_FEE + 1..MAX_NETWORK_FEE + 10000));
+    let address = get_address(
+        &chain_config,
+        MNEMONIC,
+        DEFAULT_ACCOUNT_INDEX,
+        KeyPurpose::ReceiveFunds,
+        0.try_into().unwrap(),
+    );
+    let block1 = Block::new(
+        vec![],
+        chain_config.genesis_block_id(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(vec![make_address_output(address, block1_amount).unwrap()]),
+    )
+    .unwrap();
+
+    wallet.scan_new_blocks(BlockHeight::new(0), vec![block1]).unwrap();
+
+    let password = Some(gen_random_password(&mut rng));
+    wallet.encrypt_wallet(&password).unwrap();
+    wallet.lock_wallet().unwrap();
+
+    let (coin_balance, _) = wallet
+        .get_balance(
+            DEFAULT_ACCOUNT_INDEX,
+            UtxoType::Transfer | UtxoType::LockThenTransfer,
+            UtxoState::Confirmed.into(),
+        )
+        .unwrap();
+    assert_eq!(coin_balance, block1_amount);
+
+    let new_output = TxOutput::Transfer(
+        OutputValue::Coin(Amount::from_atoms(
+            rng.gen_range(1..block1_amount.into_atoms() - MAX_NETWORK_FEE),
+        )),
+        Destination::AnyoneCanSpend,
+    );
+
+    assert_eq!(
+        wallet.create_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            vec![new_output.clone()],
+            MEDIAN_TIME,
+            FEE_RATE
+        ),
+        Err(WalletError::DatabaseError(
+            wallet_storage::Error::WalletLocked
+        ))
+    );
+
+    // success after unlock
+    wallet.unlock_wallet(&password.unwrap()).unwrap();
+    wallet
+        .create_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            vec![new_output],
+            MEDIAN_TIME,
+            FEE_RATE,
+        )
         .unwrap();
 }
 
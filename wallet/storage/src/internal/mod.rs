@@ -13,9 +13,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use common::address::Address;
+use common::chain::classic_multisig::ClassicMultisigChallenge;
 use crypto::key::extended::ExtendedPublicKey;
 
 use crate::{
@@ -25,13 +26,13 @@ use crate::{
 };
 
 mod password;
-use password::{challenge_to_sym_key, password_to_sym_key};
+pub use password::{challenge_to_sym_key, password_to_sym_key};
 
 mod store_tx;
 pub use store_tx::{StoreTxRo, StoreTxRoUnlocked, StoreTxRw, StoreTxRwUnlocked};
 use wallet_types::{
-    wallet_tx::WalletTx, AccountDerivationPathId, AccountId, AccountInfo, AccountKeyPurposeId,
-    AccountWalletTxId, KeychainUsageState,
+    wallet_tx::WalletTx, AccountClassicMultisigId, AccountDerivationPathId, AccountId, AccountInfo,
+    AccountKeyPurposeId, AccountWalletTxId, KeychainUsageState,
 };
 
 use self::store_tx::EncryptionState;
@@ -213,24 +214,32 @@ macro_rules! delegate_to_transaction {
 impl<B: storage::Backend> WalletStorageReadLocked for Store<B> {
     delegate_to_transaction! {
         fn get_storage_version(&self) -> crate::Result<u32>;
+        fn is_watch_only(&self) -> crate::Result<bool>;
         fn get_transaction(&self, id: &AccountWalletTxId) -> crate::Result<Option<WalletTx>>;
         fn get_transactions(&self, account_id: &AccountId) -> crate::Result<BTreeMap<AccountWalletTxId, WalletTx>>;
         fn get_accounts_info(&self) -> crate::Result<BTreeMap<AccountId, AccountInfo>>;
         fn get_address(&self, id: &AccountDerivationPathId) -> crate::Result<Option<Address>>;
         fn get_addresses(&self, account_id: &AccountId) -> crate::Result<BTreeMap<AccountDerivationPathId, Address>>;
-        fn exactly_one_root_key(&self) -> crate::Result<bool>;
+        fn root_keys_count(&self) -> crate::Result<usize>;
         fn get_keychain_usage_state(&self, id: &AccountKeyPurposeId) -> crate::Result<Option<KeychainUsageState>>;
         fn get_keychain_usage_states(&self, account_id: &AccountId) -> crate::Result<BTreeMap<AccountKeyPurposeId, KeychainUsageState>>;
         fn get_public_key(&self, id: &AccountDerivationPathId) -> crate::Result<Option<ExtendedPublicKey>>;
         fn get_public_keys(&self, account_id: &AccountId) -> crate::Result<BTreeMap<AccountDerivationPathId, ExtendedPublicKey>>;
+        fn get_classic_multisig_challenges(&self, account_id: &AccountId) -> crate::Result<BTreeMap<AccountClassicMultisigId, ClassicMultisigChallenge>>;
+        fn get_frozen_utxos(&self, account_id: &AccountId) -> crate::Result<BTreeSet<AccountOutPointId>>;
+        fn get_address_labels(&self) -> crate::Result<BTreeMap<Address, String>>;
+        fn get_address_book(&self) -> crate::Result<BTreeMap<Address, String>>;
+        fn get_account_name(&self, id: &AccountId) -> crate::Result<Option<String>>;
     }
 }
 
 impl<B: storage::Backend> WalletStorageWriteLocked for Store<B> {
     delegate_to_transaction! {
         fn set_storage_version(&mut self, version: u32) -> crate::Result<()>;
+        fn set_watch_only(&mut self, watch_only: bool) -> crate::Result<()>;
         fn set_transaction(&mut self, id: &AccountWalletTxId, tx: &WalletTx) -> crate::Result<()>;
         fn del_transaction(&mut self, id: &AccountWalletTxId) -> crate::Result<()>;
+        fn clear_transactions(&mut self) -> crate::Result<()>;
         fn set_account(&mut self, id: &AccountId, content: &AccountInfo) -> crate::Result<()>;
         fn del_account(&mut self, id: &AccountId) -> crate::Result<()>;
         fn set_address(&mut self, id: &AccountDerivationPathId, address: &Address) -> crate::Result<()>;
@@ -239,6 +248,16 @@ impl<B: storage::Backend> WalletStorageWriteLocked for Store<B> {
         fn del_keychain_usage_state(&mut self, id: &AccountKeyPurposeId) -> crate::Result<()>;
         fn set_public_key(&mut self, id: &AccountDerivationPathId, content: &ExtendedPublicKey) -> crate::Result<()>;
         fn det_public_key(&mut self, id: &AccountDerivationPathId) -> crate::Result<()>;
+        fn set_classic_multisig_challenge(&mut self, id: &AccountClassicMultisigId, challenge: &ClassicMultisigChallenge) -> crate::Result<()>;
+        fn del_classic_multisig_challenge(&mut self, id: &AccountClassicMultisigId) -> crate::Result<()>;
+        fn set_frozen_utxo(&mut self, id: &AccountOutPointId) -> crate::Result<()>;
+        fn del_frozen_utxo(&mut self, id: &AccountOutPointId) -> crate::Result<()>;
+        fn set_address_label(&mut self, address: &Address, label: &str) -> crate::Result<()>;
+        fn del_address_label(&mut self, address: &Address) -> crate::Result<()>;
+        fn set_address_book_entry(&mut self, address: &Address, name: &str) -> crate::Result<()>;
+        fn del_address_book_entry(&mut self, address: &Address) -> crate::Result<()>;
+        fn set_account_name(&mut self, id: &AccountId, name: &str) -> crate::Result<()>;
+        fn del_account_name(&mut self, id: &AccountId) -> crate::Result<()>;
     }
 }
 
@@ -13,16 +13,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use common::address::Address;
+use common::chain::classic_multisig::ClassicMultisigChallenge;
 use crypto::{kdf::KdfChallenge, key::extended::ExtendedPublicKey, symkey::SymmetricKey};
 use serialization::{Codec, DecodeAll, Encode, EncodeLike};
 use storage::schema;
 use utils::maybe_encrypted::{MaybeEncrypted, MaybeEncryptedError};
 use wallet_types::{
-    AccountDerivationPathId, AccountId, AccountInfo, AccountKeyPurposeId, AccountWalletTxId,
-    KeychainUsageState, RootKeyContent, RootKeyId, WalletTx,
+    AccountClassicMultisigId, AccountDerivationPathId, AccountId, AccountInfo, AccountKeyPurposeId,
+    AccountOutPointId, AccountWalletTxId, KeychainUsageState, RootKeyContent, RootKeyId, WalletTx,
 };
 
 use crate::{
@@ -56,6 +57,7 @@ mod well_known {
 
     declare_entry!(StoreVersion: u32);
     declare_entry!(EncryptionKeyKdfChallenge: KdfChallenge);
+    declare_entry!(WatchOnly: bool);
 }
 
 #[derive(PartialEq, Clone)]
@@ -133,6 +135,10 @@ macro_rules! impl_read_ops {
                 self.read_value::<well_known::StoreVersion>().map(|v| v.unwrap_or_default())
             }
 
+            fn is_watch_only(&self) -> crate::Result<bool> {
+                self.read_value::<well_known::WatchOnly>().map(|v| v.unwrap_or_default())
+            }
+
             fn get_transaction(&self, id: &AccountWalletTxId) -> crate::Result<Option<WalletTx>> {
                 self.read::<db::DBTxs, _, _>(id)
             }
@@ -156,13 +162,12 @@ macro_rules! impl_read_ops {
                     .map(Iterator::collect)
             }
 
-            fn exactly_one_root_key(&self) -> crate::Result<bool> {
+            fn root_keys_count(&self) -> crate::Result<usize> {
                 self.storage
                     .get::<db::DBRootKeys, _>()
                     .prefix_iter_decoded(&())
                     .map_err(crate::Error::from)
                     .map(Iterator::count)
-                    .map(|count| count == 1)
             }
 
             /// Collect and return all transactions from the storage
@@ -212,6 +217,48 @@ macro_rules! impl_read_ops {
                     .map_err(crate::Error::from)
                     .map(Iterator::collect)
             }
+
+            fn get_classic_multisig_challenges(
+                &self,
+                account_id: &AccountId,
+            ) -> crate::Result<BTreeMap<AccountClassicMultisigId, ClassicMultisigChallenge>> {
+                self.storage
+                    .get::<db::DBClassicMultisigChallenges, _>()
+                    .prefix_iter_decoded(account_id)
+                    .map_err(crate::Error::from)
+                    .map(Iterator::collect)
+            }
+
+            fn get_frozen_utxos(
+                &self,
+                account_id: &AccountId,
+            ) -> crate::Result<BTreeSet<AccountOutPointId>> {
+                self.storage
+                    .get::<db::DBFrozenUtxos, _>()
+                    .prefix_iter_decoded(account_id)
+                    .map_err(crate::Error::from)
+                    .map(|items| items.map(|(id, ())| id).collect())
+            }
+
+            fn get_address_labels(&self) -> crate::Result<BTreeMap<Address, String>> {
+                self.storage
+                    .get::<db::DBAddressLabels, _>()
+                    .prefix_iter_decoded(&())
+                    .map_err(crate::Error::from)
+                    .map(Iterator::collect)
+            }
+
+            fn get_address_book(&self) -> crate::Result<BTreeMap<Address, String>> {
+                self.storage
+                    .get::<db::DBAddressBook, _>()
+                    .prefix_iter_decoded(&())
+                    .map_err(crate::Error::from)
+                    .map(Iterator::collect)
+            }
+
+            fn get_account_name(&self, id: &AccountId) -> crate::Result<Option<String>> {
+                self.read::<db::DBAccountNames, _, _>(id)
+            }
         }
 
         impl<'st, B: storage::Backend> $TxType<'st, B> {
@@ -314,6 +361,10 @@ macro_rules! impl_write_ops {
                 self.write_value::<well_known::StoreVersion>(&version)
             }
 
+            fn set_watch_only(&mut self, watch_only: bool) -> crate::Result<()> {
+                self.write_value::<well_known::WatchOnly>(&watch_only)
+            }
+
             fn set_transaction(
                 &mut self,
                 id: &AccountWalletTxId,
@@ -326,6 +377,16 @@ macro_rules! impl_write_ops {
                 self.storage.get_mut::<db::DBTxs, _>().del(id).map_err(Into::into)
             }
 
+            fn clear_transactions(&mut self) -> crate::Result<()> {
+                let ids =
+                    self.storage.get::<db::DBTxs, _>().prefix_iter_keys(&())?.collect::<Vec<_>>();
+                let mut txs = self.storage.get_mut::<db::DBTxs, _>();
+                for id in ids {
+                    txs.del(id)?;
+                }
+                Ok(())
+            }
+
             // fn set_block(
             //     &mut self,
             //     block_height: &AccountBlockHeight,
@@ -391,6 +452,63 @@ macro_rules! impl_write_ops {
             fn det_public_key(&mut self, id: &AccountDerivationPathId) -> crate::Result<()> {
                 self.storage.get_mut::<db::DBPubKeys, _>().del(id).map_err(Into::into)
             }
+
+            fn set_classic_multisig_challenge(
+                &mut self,
+                id: &AccountClassicMultisigId,
+                challenge: &ClassicMultisigChallenge,
+            ) -> crate::Result<()> {
+                self.write::<db::DBClassicMultisigChallenges, _, _, _>(id, challenge)
+            }
+
+            fn del_classic_multisig_challenge(
+                &mut self,
+                id: &AccountClassicMultisigId,
+            ) -> crate::Result<()> {
+                self.storage
+                    .get_mut::<db::DBClassicMultisigChallenges, _>()
+                    .del(id)
+                    .map_err(Into::into)
+            }
+
+            fn set_frozen_utxo(&mut self, id: &AccountOutPointId) -> crate::Result<()> {
+                self.write::<db::DBFrozenUtxos, _, _, _>(id, ())
+            }
+
+            fn del_frozen_utxo(&mut self, id: &AccountOutPointId) -> crate::Result<()> {
+                self.storage.get_mut::<db::DBFrozenUtxos, _>().del(id).map_err(Into::into)
+            }
+
+            fn set_address_label(&mut self, address: &Address, label: &str) -> crate::Result<()> {
+                self.write::<db::DBAddressLabels, _, _, _>(address, label)
+            }
+
+            fn del_address_label(&mut self, address: &Address) -> crate::Result<()> {
+                self.storage
+                    .get_mut::<db::DBAddressLabels, _>()
+                    .del(address)
+                    .map_err(Into::into)
+            }
+
+            fn set_address_book_entry(
+                &mut self,
+                address: &Address,
+                name: &str,
+            ) -> crate::Result<()> {
+                self.write::<db::DBAddressBook, _, _, _>(address, name)
+            }
+
+            fn del_address_book_entry(&mut self, address: &Address) -> crate::Result<()> {
+                self.storage.get_mut::<db::DBAddressBook, _>().del(address).map_err(Into::into)
+            }
+
+            fn set_account_name(&mut self, id: &AccountId, name: &str) -> crate::Result<()> {
+                self.write::<db::DBAccountNames, _, _, _>(id, name)
+            }
+
+            fn del_account_name(&mut self, id: &AccountId) -> crate::Result<()> {
+                self.storage.get_mut::<db::DBAccountNames, _>().del(id).map_err(Into::into)
+            }
         }
 
         impl<'st, B: storage::Backend> $TxType<'st, B> {
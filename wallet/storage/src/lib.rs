@@ -20,13 +20,17 @@ mod is_transaction_seal;
 pub mod schema;
 
 use common::address::Address;
+use common::chain::classic_multisig::ClassicMultisigChallenge;
 use crypto::{kdf::KdfChallenge, key::extended::ExtendedPublicKey, symkey::SymmetricKey};
-pub use internal::{Store, StoreTxRo, StoreTxRoUnlocked, StoreTxRw, StoreTxRwUnlocked};
-use std::collections::BTreeMap;
+pub use internal::{
+    challenge_to_sym_key, password_to_sym_key, Store, StoreTxRo, StoreTxRoUnlocked, StoreTxRw,
+    StoreTxRwUnlocked,
+};
+use std::collections::{BTreeMap, BTreeSet};
 
 use wallet_types::{
-    AccountDerivationPathId, AccountId, AccountInfo, AccountKeyPurposeId, AccountWalletTxId,
-    KeychainUsageState, RootKeyContent, RootKeyId, WalletTx,
+    AccountClassicMultisigId, AccountDerivationPathId, AccountId, AccountInfo, AccountKeyPurposeId,
+    AccountOutPointId, AccountWalletTxId, KeychainUsageState, RootKeyContent, RootKeyId, WalletTx,
 };
 
 /// Wallet Errors
@@ -53,6 +57,8 @@ pub type Result<T> = std::result::Result<T, Error>;
 pub trait WalletStorageReadLocked {
     /// Get storage version
     fn get_storage_version(&self) -> Result<u32>;
+    /// Check if the wallet has been created without a root key
+    fn is_watch_only(&self) -> Result<bool>;
     fn get_transaction(&self, id: &AccountWalletTxId) -> Result<Option<WalletTx>>;
     fn get_transactions(
         &self,
@@ -64,7 +70,7 @@ pub trait WalletStorageReadLocked {
         &self,
         account_id: &AccountId,
     ) -> Result<BTreeMap<AccountDerivationPathId, Address>>;
-    fn exactly_one_root_key(&self) -> Result<bool>;
+    fn root_keys_count(&self) -> Result<usize>;
     fn get_keychain_usage_state(
         &self,
         id: &AccountKeyPurposeId,
@@ -78,6 +84,14 @@ pub trait WalletStorageReadLocked {
         &self,
         account_id: &AccountId,
     ) -> Result<BTreeMap<AccountDerivationPathId, ExtendedPublicKey>>;
+    fn get_classic_multisig_challenges(
+        &self,
+        account_id: &AccountId,
+    ) -> Result<BTreeMap<AccountClassicMultisigId, ClassicMultisigChallenge>>;
+    fn get_frozen_utxos(&self, account_id: &AccountId) -> Result<BTreeSet<AccountOutPointId>>;
+    fn get_address_labels(&self) -> Result<BTreeMap<Address, String>>;
+    fn get_address_book(&self) -> Result<BTreeMap<Address, String>>;
+    fn get_account_name(&self, id: &AccountId) -> Result<Option<String>>;
 }
 
 /// Queries on persistent wallet data with access to encrypted data
@@ -96,8 +110,11 @@ pub trait WalletStorageEncryptionRead {
 pub trait WalletStorageWriteLocked: WalletStorageReadLocked {
     /// Set storage version
     fn set_storage_version(&mut self, version: u32) -> Result<()>;
+    fn set_watch_only(&mut self, watch_only: bool) -> Result<()>;
     fn set_transaction(&mut self, id: &AccountWalletTxId, tx: &WalletTx) -> Result<()>;
     fn del_transaction(&mut self, id: &AccountWalletTxId) -> Result<()>;
+    /// Delete the transactions of all the accounts, the stored values are not decoded
+    fn clear_transactions(&mut self) -> Result<()>;
     fn set_account(&mut self, id: &AccountId, content: &AccountInfo) -> Result<()>;
     fn del_account(&mut self, id: &AccountId) -> Result<()>;
     fn set_address(&mut self, id: &AccountDerivationPathId, address: &Address) -> Result<()>;
@@ -114,6 +131,20 @@ pub trait WalletStorageWriteLocked: WalletStorageReadLocked {
         content: &ExtendedPublicKey,
     ) -> Result<()>;
     fn det_public_key(&mut self, id: &AccountDerivationPathId) -> Result<()>;
+    fn set_classic_multisig_challenge(
+        &mut self,
+        id: &AccountClassicMultisigId,
+        challenge: &ClassicMultisigChallenge,
+    ) -> Result<()>;
+    fn del_classic_multisig_challenge(&mut self, id: &AccountClassicMultisigId) -> Result<()>;
+    fn set_frozen_utxo(&mut self, id: &AccountOutPointId) -> Result<()>;
+    fn del_frozen_utxo(&mut self, id: &AccountOutPointId) -> Result<()>;
+    fn set_address_label(&mut self, address: &Address, label: &str) -> Result<()>;
+    fn del_address_label(&mut self, address: &Address) -> Result<()>;
+    fn set_address_book_entry(&mut self, address: &Address, name: &str) -> Result<()>;
+    fn del_address_book_entry(&mut self, address: &Address) -> Result<()>;
+    fn set_account_name(&mut self, id: &AccountId, name: &str) -> Result<()>;
+    fn del_account_name(&mut self, id: &AccountId) -> Result<()>;
 }
 
 /// Modifying operations on persistent wallet data with access to encrypted data
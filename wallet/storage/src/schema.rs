@@ -17,11 +17,12 @@
 
 use crate::RootKeyContent;
 use common::address::Address;
+use common::chain::classic_multisig::ClassicMultisigChallenge;
 use crypto::key::extended::ExtendedPublicKey;
 use utils::maybe_encrypted::MaybeEncrypted;
 use wallet_types::{
-    AccountDerivationPathId, AccountId, AccountInfo, AccountKeyPurposeId, AccountWalletTxId,
-    KeychainUsageState, RootKeyId, WalletTx,
+    AccountClassicMultisigId, AccountDerivationPathId, AccountId, AccountInfo, AccountKeyPurposeId,
+    AccountOutPointId, AccountWalletTxId, KeychainUsageState, RootKeyId, WalletTx,
 };
 
 storage::decl_schema! {
@@ -41,5 +42,15 @@ storage::decl_schema! {
         pub DBAddresses: Map<AccountDerivationPathId, Address>,
         /// Store for block/transaction entries
         pub DBTxs: Map<AccountWalletTxId, WalletTx>,
+        /// Store for the classic multisig challenges watched by the accounts
+        pub DBClassicMultisigChallenges: Map<AccountClassicMultisigId, ClassicMultisigChallenge>,
+        /// Store for the outpoints frozen by the accounts, skipped by the automatic coin selection
+        pub DBFrozenUtxos: Map<AccountOutPointId, ()>,
+        /// Store for the labels of the addresses that belong to the wallet
+        pub DBAddressLabels: Map<Address, String>,
+        /// Store for the names of the external addresses in the address book
+        pub DBAddressBook: Map<Address, String>,
+        /// Store for the names given to the accounts by the user
+        pub DBAccountNames: Map<AccountId, String>,
     }
 }
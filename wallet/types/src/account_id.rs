@@ -14,7 +14,10 @@
 // limitations under the License.
 
 use crate::keys::KeyPurpose;
-use common::{address::pubkeyhash::PublicKeyHash, chain::OutPointSourceId};
+use common::{
+    address::pubkeyhash::PublicKeyHash,
+    chain::{OutPoint, OutPointSourceId},
+};
 use crypto::key::extended::ExtendedPublicKey;
 use crypto::key::hdkd::derivation_path::DerivationPath;
 use serialization::{Decode, Encode};
@@ -62,3 +65,5 @@ impl<Id: Encode> HasPrefix<AccountId> for AccountPrefixedId<Id> {}
 pub type AccountWalletTxId = AccountPrefixedId<OutPointSourceId>;
 pub type AccountDerivationPathId = AccountPrefixedId<DerivationPath>;
 pub type AccountKeyPurposeId = AccountPrefixedId<KeyPurpose>;
+pub type AccountClassicMultisigId = AccountPrefixedId<PublicKeyHash>;
+pub type AccountOutPointId = AccountPrefixedId<OutPoint>;
@@ -16,10 +16,15 @@
 pub mod account_id;
 pub mod account_info;
 pub mod keys;
+pub mod partially_signed_transaction;
+pub mod tx_history;
 pub mod utxo_types;
 pub mod wallet_tx;
 
-pub use account_id::{AccountDerivationPathId, AccountId, AccountKeyPurposeId, AccountWalletTxId};
+pub use account_id::{
+    AccountClassicMultisigId, AccountDerivationPathId, AccountId, AccountKeyPurposeId,
+    AccountOutPointId, AccountWalletTxId,
+};
 pub use account_info::AccountInfo;
 pub use keys::{KeyPurpose, KeychainUsageState, RootKeyContent, RootKeyId};
 pub use wallet_tx::WalletTx;
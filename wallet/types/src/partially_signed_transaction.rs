@@ -0,0 +1,318 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A transaction that is not fully signed yet, together with everything needed to sign it.
+//!
+//! It can be passed between wallets, so that a transaction built in one wallet can be signed
+//! in another one (e.g. in an offline wallet), or by several owners of a classic multisig.
+
+use common::address::pubkeyhash::PublicKeyHash;
+use common::chain::classic_multisig::ClassicMultisigChallenge;
+use common::chain::signature::inputsig::classical_multisig::authorize_classical_multisig::AuthorizedClassicalMultisigSpend;
+use common::chain::signature::inputsig::standard_signature::StandardInputSignature;
+use common::chain::signature::inputsig::InputWitness;
+use common::chain::signature::sighash::sighashtype::SigHashType;
+use common::chain::signature::{verify_signature, TransactionSigError};
+use common::chain::{
+    ChainConfig, Destination, SignedTransaction, Transaction, TransactionCreationError, TxOutput,
+};
+use serialization::{Decode, Encode};
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum PartiallySignedTransactionError {
+    #[error(
+        "Invalid number of input UTXOs: {0}, destinations: {1} or witnesses: {2} for {3} inputs"
+    )]
+    InvalidInputsCount(usize, usize, usize, usize),
+    #[error("The partially signed transactions are for different transactions")]
+    TransactionMismatch,
+    #[error("Conflicting witnesses for input {0}")]
+    ConflictingWitnesses(usize),
+    #[error("Input {0} is not signed")]
+    MissingWitness(usize),
+    #[error("Invalid input index {0}")]
+    InvalidInputIndex(usize),
+    #[error("Input {0} is not a classic multisig input")]
+    NotClassicMultisigInput(usize),
+    #[error("The classic multisig challenge does not match the destination of input {0}")]
+    ClassicMultisigChallengeMismatch(usize),
+    #[error("Transaction creation error: {0}")]
+    TransactionCreation(#[from] TransactionCreationError),
+    #[error("Invalid signature of input {0}: {1}")]
+    InvalidSignature(usize, TransactionSigError),
+}
+
+/// A transaction with the UTXOs it spends, the destinations that have to sign them
+/// and the input witnesses collected so far.
+///
+/// The witness of a classic multisig input is stored as soon as its challenge is known,
+/// and then filled with the signatures one by one.
+#[derive(Debug, Clone, PartialEq, Eq, Encode)]
+pub struct PartiallySignedTransaction {
+    tx: Transaction,
+    witnesses: Vec<Option<InputWitness>>,
+    input_utxos: Vec<TxOutput>,
+    /// The destinations that have to sign the inputs, `None` if the destination is not known
+    destinations: Vec<Option<Destination>>,
+}
+
+impl PartiallySignedTransaction {
+    pub fn new(
+        tx: Transaction,
+        witnesses: Vec<Option<InputWitness>>,
+        input_utxos: Vec<TxOutput>,
+        destinations: Vec<Option<Destination>>,
+    ) -> Result<Self, PartiallySignedTransactionError> {
+        let inputs_count = tx.inputs().len();
+        if input_utxos.len() != inputs_count
+            || destinations.len() != inputs_count
+            || witnesses.len() != inputs_count
+        {
+            return Err(PartiallySignedTransactionError::InvalidInputsCount(
+                input_utxos.len(),
+                destinations.len(),
+                witnesses.len(),
+                inputs_count,
+            ));
+        }
+
+        Ok(Self {
+            tx,
+            witnesses,
+            input_utxos,
+            destinations,
+        })
+    }
+
+    pub fn tx(&self) -> &Transaction {
+        &self.tx
+    }
+
+    pub fn witnesses(&self) -> &[Option<InputWitness>] {
+        &self.witnesses
+    }
+
+    pub fn input_utxos(&self) -> &[TxOutput] {
+        &self.input_utxos
+    }
+
+    pub fn destinations(&self) -> &[Option<Destination>] {
+        &self.destinations
+    }
+
+    pub fn set_witness(
+        &mut self,
+        input_index: usize,
+        witness: InputWitness,
+    ) -> Result<(), PartiallySignedTransactionError> {
+        let slot = self.witnesses.get_mut(input_index).ok_or(
+            PartiallySignedTransactionError::InvalidInputIndex(input_index),
+        )?;
+        *slot = Some(witness);
+        Ok(())
+    }
+
+    /// The classic multisig authorization collected so far for an input, if any
+    pub fn multisig_spend(&self, input_index: usize) -> Option<AuthorizedClassicalMultisigSpend> {
+        match self.destinations.get(input_index)? {
+            Some(Destination::ClassicMultisig(_)) => {}
+            Some(
+                Destination::AnyoneCanSpend
+                | Destination::Address(_)
+                | Destination::PublicKey(_)
+                | Destination::ScriptHash(_),
+            )
+            | None => return None,
+        }
+        match self.witnesses.get(input_index)? {
+            Some(InputWitness::Standard(sig)) => {
+                AuthorizedClassicalMultisigSpend::from_data(sig.raw_signature()).ok()
+            }
+            Some(InputWitness::NoSignature(_) | InputWitness::ScriptHash(_)) | None => None,
+        }
+    }
+
+    /// Store the classic multisig authorization of an input
+    pub fn set_multisig_spend(
+        &mut self,
+        input_index: usize,
+        spend: &AuthorizedClassicalMultisigSpend,
+    ) -> Result<(), PartiallySignedTransactionError> {
+        let sighash_type = SigHashType::try_from(SigHashType::ALL).expect("Should not fail");
+        let witness =
+            InputWitness::Standard(StandardInputSignature::new(sighash_type, spend.encode()));
+        self.set_witness(input_index, witness)
+    }
+
+    /// Provide the challenge of a classic multisig input, so that the owners of its keys can sign it
+    pub fn add_multisig_challenge(
+        &mut self,
+        input_index: usize,
+        challenge: ClassicMultisigChallenge,
+    ) -> Result<(), PartiallySignedTransactionError> {
+        match self.destinations.get(input_index) {
+            Some(Some(Destination::ClassicMultisig(challenge_hash))) => {
+                if *challenge_hash != PublicKeyHash::from(&challenge) {
+                    return Err(
+                        PartiallySignedTransactionError::ClassicMultisigChallengeMismatch(
+                            input_index,
+                        ),
+                    );
+                }
+            }
+            Some(_) => {
+                return Err(PartiallySignedTransactionError::NotClassicMultisigInput(
+                    input_index,
+                ))
+            }
+            None => {
+                return Err(PartiallySignedTransactionError::InvalidInputIndex(
+                    input_index,
+                ))
+            }
+        }
+        if self.multisig_spend(input_index).is_some() {
+            return Ok(());
+        }
+        self.set_multisig_spend(
+            input_index,
+            &AuthorizedClassicalMultisigSpend::new_empty(challenge),
+        )
+    }
+
+    /// Merge the witnesses collected by another signer of the same transaction
+    pub fn combine(mut self, other: Self) -> Result<Self, PartiallySignedTransactionError> {
+        if self.tx != other.tx
+            || self.input_utxos != other.input_utxos
+            || self.destinations != other.destinations
+        {
+            return Err(PartiallySignedTransactionError::TransactionMismatch);
+        }
+
+        for (input_index, other_witness) in other.witnesses.iter().enumerate() {
+            let witness = self.witnesses.get(input_index).ok_or(
+                PartiallySignedTransactionError::InvalidInputIndex(input_index),
+            )?;
+            let combined = match (witness, other_witness) {
+                (_, None) => continue,
+                (None, Some(other_witness)) => other_witness.clone(),
+                (Some(witness), Some(other_witness)) if witness == other_witness => continue,
+                (Some(_), Some(_)) => {
+                    let mut spend = self.multisig_spend(input_index).ok_or(
+                        PartiallySignedTransactionError::ConflictingWitnesses(input_index),
+                    )?;
+                    let other_spend = other.multisig_spend(input_index).ok_or(
+                        PartiallySignedTransactionError::ConflictingWitnesses(input_index),
+                    )?;
+                    if spend.challenge() != other_spend.challenge() {
+                        return Err(PartiallySignedTransactionError::ConflictingWitnesses(
+                            input_index,
+                        ));
+                    }
+                    for (key_index, signature) in other_spend.iter() {
+                        if spend.signatures().get(&key_index).is_none() {
+                            spend.add_signature(key_index, signature.clone());
+                        }
+                    }
+                    self.set_multisig_spend(input_index, &spend)?;
+                    continue;
+                }
+            };
+            self.set_witness(input_index, combined)?;
+        }
+
+        Ok(self)
+    }
+
+    /// The number of inputs that have a valid and complete witness
+    pub fn signed_inputs_count(&self, chain_config: &ChainConfig) -> usize {
+        (0..self.witnesses.len())
+            .filter(|input_index| self.verify_input(chain_config, *input_index).is_ok())
+            .count()
+    }
+
+    pub fn is_fully_signed(&self, chain_config: &ChainConfig) -> bool {
+        self.signed_inputs_count(chain_config) == self.witnesses.len()
+    }
+
+    /// Check that the witness of an input is present, complete and valid
+    pub fn verify_input(
+        &self,
+        chain_config: &ChainConfig,
+        input_index: usize,
+    ) -> Result<(), PartiallySignedTransactionError> {
+        match self.witnesses.get(input_index) {
+            Some(Some(_)) => {}
+            Some(None) => return Err(PartiallySignedTransactionError::MissingWitness(input_index)),
+            None => {
+                return Err(PartiallySignedTransactionError::InvalidInputIndex(
+                    input_index,
+                ))
+            }
+        }
+        let destination = match self.destinations.get(input_index) {
+            Some(Some(destination)) => destination,
+            // Nothing to verify against
+            Some(None) => return Ok(()),
+            None => {
+                return Err(PartiallySignedTransactionError::InvalidInputIndex(
+                    input_index,
+                ))
+            }
+        };
+
+        // The signatures of the other inputs don't affect the verification of this one
+        let witnesses = self
+            .witnesses
+            .iter()
+            .map(|witness| witness.clone().unwrap_or(InputWitness::NoSignature(None)))
+            .collect();
+        let tx = SignedTransaction::new(self.tx.clone(), witnesses)?;
+        let input_utxos = self.input_utxos.iter().collect::<Vec<_>>();
+        verify_signature(chain_config, destination, &tx, &input_utxos, input_index)
+            .map_err(|err| PartiallySignedTransactionError::InvalidSignature(input_index, err))
+    }
+
+    /// Check all the witnesses and return the signed transaction, ready to be submitted
+    pub fn into_signed_tx(
+        self,
+        chain_config: &ChainConfig,
+    ) -> Result<SignedTransaction, PartiallySignedTransactionError> {
+        for input_index in 0..self.witnesses.len() {
+            self.verify_input(chain_config, input_index)?;
+        }
+
+        let witnesses = self
+            .witnesses
+            .into_iter()
+            .map(|witness| witness.expect("checked above"))
+            .collect();
+        Ok(SignedTransaction::new(self.tx, witnesses)?)
+    }
+}
+
+impl Decode for PartiallySignedTransaction {
+    fn decode<I: serialization::Input>(input: &mut I) -> Result<Self, serialization::Error> {
+        let tx = Transaction::decode(input)?;
+        let witnesses = Vec::<Option<InputWitness>>::decode(input)?;
+        let input_utxos = Vec::<TxOutput>::decode(input)?;
+        let destinations = Vec::<Option<Destination>>::decode(input)?;
+        // The lengths are checked by the constructor, the decoded data comes from other wallets
+        Self::new(tx, witnesses, input_utxos, destinations).map_err(|err| {
+            serialization::Error::from("Invalid partially signed transaction")
+                .chain(err.to_string())
+        })
+    }
+}
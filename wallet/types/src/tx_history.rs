@@ -0,0 +1,95 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+
+use common::chain::block::timestamp::BlockTimestamp;
+use common::chain::tokens::TokenId;
+use common::chain::{Destination, OutPointSourceId};
+use common::primitives::signed_amount::SignedAmount;
+use common::primitives::{Amount, BlockHeight};
+
+use crate::wallet_tx::TxState;
+
+/// A transaction or a block reward stored by an account, with its effect on the account balance
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TxHistoryEntry {
+    pub tx_id: OutPointSourceId,
+    pub state: TxState,
+    /// The number of blocks that confirm the transaction, 0 if it's not confirmed
+    pub confirmations: u64,
+    /// The coins received by the account minus the coins spent by it
+    pub coin_change: SignedAmount,
+    /// The tokens received by the account minus the tokens spent by it
+    pub token_changes: BTreeMap<TokenId, SignedAmount>,
+    /// The fee paid by the transaction, known only if all of its inputs are known to the account
+    pub fee: Option<Amount>,
+    /// The destinations of the outputs that don't belong to the account if the account
+    /// has spent anything in the transaction. The senders of incoming transactions are unknown.
+    pub counterparties: Vec<Destination>,
+}
+
+impl TxHistoryEntry {
+    pub fn block_height(&self) -> Option<BlockHeight> {
+        match self.state {
+            TxState::Confirmed(height, _) => Some(height),
+            TxState::InMempool
+            | TxState::Conflicted(_)
+            | TxState::Inactive
+            | TxState::Replaced(_)
+            | TxState::Abandoned => None,
+        }
+    }
+
+    pub fn timestamp(&self) -> Option<BlockTimestamp> {
+        match self.state {
+            TxState::Confirmed(_, timestamp) => Some(timestamp),
+            TxState::InMempool
+            | TxState::Conflicted(_)
+            | TxState::Inactive
+            | TxState::Replaced(_)
+            | TxState::Abandoned => None,
+        }
+    }
+}
+
+/// The transactions to include in a transaction history query
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TxHistoryFilter {
+    /// Skip the transactions confirmed below this height
+    pub min_height: Option<BlockHeight>,
+    /// Skip the transactions confirmed above this height and the unconfirmed transactions
+    pub max_height: Option<BlockHeight>,
+    /// The number of the most recent transactions to skip
+    pub skip: usize,
+    /// The maximum number of transactions to return
+    pub limit: usize,
+}
+
+impl TxHistoryFilter {
+    pub fn contains(&self, state: &TxState) -> bool {
+        match state {
+            TxState::Confirmed(height, _) => {
+                self.min_height.map_or(true, |min_height| *height >= min_height)
+                    && self.max_height.map_or(true, |max_height| *height <= max_height)
+            }
+            TxState::InMempool
+            | TxState::Conflicted(_)
+            | TxState::Inactive
+            | TxState::Replaced(_)
+            | TxState::Abandoned => self.max_height.is_none(),
+        }
+    }
+}
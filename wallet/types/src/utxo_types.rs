@@ -15,6 +15,8 @@
 
 use common::chain::TxOutput;
 
+use crate::wallet_tx::TxState;
+
 pub type UtxoTypeInt = u16;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -75,3 +77,60 @@ impl UtxoTypes {
         (self.0 & value as UtxoTypeInt) != 0
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum UtxoState {
+    Confirmed = 1 << 0,
+    Conflicted = 1 << 1,
+    InMempool = 1 << 2,
+    Inactive = 1 << 3,
+    Replaced = 1 << 4,
+    Abandoned = 1 << 5,
+}
+
+pub fn get_utxo_state(tx_state: &TxState) -> UtxoState {
+    match tx_state {
+        TxState::Confirmed(_, _) => UtxoState::Confirmed,
+        TxState::Conflicted(_) => UtxoState::Conflicted,
+        TxState::InMempool => UtxoState::InMempool,
+        TxState::Inactive => UtxoState::Inactive,
+        TxState::Replaced(_) => UtxoState::Replaced,
+        TxState::Abandoned => UtxoState::Abandoned,
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct UtxoStates(u8);
+
+impl UtxoStates {
+    pub const ALL: UtxoStates = UtxoStates(u8::MAX);
+}
+
+impl std::ops::BitOr<UtxoState> for UtxoStates {
+    type Output = UtxoStates;
+
+    fn bitor(self, rhs: UtxoState) -> Self::Output {
+        Self(self.0 | rhs as u8)
+    }
+}
+
+impl std::ops::BitOr<UtxoState> for UtxoState {
+    type Output = UtxoStates;
+
+    fn bitor(self, rhs: UtxoState) -> Self::Output {
+        UtxoStates::from(self) | rhs
+    }
+}
+
+impl From<UtxoState> for UtxoStates {
+    fn from(value: UtxoState) -> Self {
+        Self(value as u8)
+    }
+}
+
+impl UtxoStates {
+    pub fn contains(&self, value: UtxoState) -> bool {
+        (self.0 & value as u8) != 0
+    }
+}
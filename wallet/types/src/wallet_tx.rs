@@ -13,6 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use common::chain::block::timestamp::BlockTimestamp;
 use common::chain::block::ConsensusData;
 use serialization::{Decode, Encode};
 
@@ -22,9 +23,9 @@ use common::primitives::{BlockHeight, Id, Idable};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Decode, Encode)]
 pub enum TxState {
-    /// Confirmed transaction in a block
+    /// Confirmed transaction in a block with the given height and timestamp
     #[codec(index = 0)]
-    Confirmed(BlockHeight),
+    Confirmed(BlockHeight, BlockTimestamp),
     /// Unconfirmed transaction in the mempool
     #[codec(index = 1)]
     InMempool,
@@ -34,6 +35,14 @@ pub enum TxState {
     /// Transaction that is not confirmed or conflicted and is not in the mempool.
     #[codec(index = 3)]
     Inactive,
+    /// Unconfirmed transaction replaced by the given transaction that spends the same outputs,
+    /// e.g. to pay a higher fee
+    #[codec(index = 4)]
+    Replaced(Id<Transaction>),
+    /// Unconfirmed transaction that is not in the mempool and was abandoned by the user,
+    /// its inputs can be spent by other transactions
+    #[codec(index = 5)]
+    Abandoned,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Decode, Encode)]
@@ -64,6 +73,8 @@ pub struct BlockData {
 
     height: BlockHeight,
 
+    timestamp: BlockTimestamp,
+
     kernel_inputs: Vec<TxInput>,
 
     reward: Vec<TxOutput>,
@@ -79,7 +90,7 @@ impl WalletTx {
 
     pub fn state(&self) -> TxState {
         match self {
-            WalletTx::Block(block) => TxState::Confirmed(block.height()),
+            WalletTx::Block(block) => TxState::Confirmed(block.height(), block.timestamp()),
             WalletTx::Tx(tx) => tx.state,
         }
     }
@@ -103,6 +114,14 @@ impl TxData {
     pub fn new(tx: WithId<Transaction>, state: TxState) -> Self {
         Self { tx, state }
     }
+
+    pub fn get_transaction(&self) -> &Transaction {
+        self.tx.as_ref()
+    }
+
+    pub fn state(&self) -> TxState {
+        self.state
+    }
 }
 
 impl BlockData {
@@ -110,6 +129,7 @@ impl BlockData {
         BlockData {
             block_id: genesis.get_id().into(),
             height: BlockHeight::zero(),
+            timestamp: genesis.timestamp(),
             kernel_inputs: Vec::new(),
             reward: genesis.utxos().to_vec(),
         }
@@ -124,6 +144,7 @@ impl BlockData {
         BlockData {
             block_id: block.get_id().into(),
             height: block_height,
+            timestamp: block.timestamp(),
             kernel_inputs,
             reward: block.block_reward().outputs().to_vec(),
         }
@@ -137,6 +158,10 @@ impl BlockData {
         self.height
     }
 
+    pub fn timestamp(&self) -> BlockTimestamp {
+        self.timestamp
+    }
+
     pub fn kernel_inputs(&self) -> &[TxInput] {
         &self.kernel_inputs
     }
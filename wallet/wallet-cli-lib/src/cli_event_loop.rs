@@ -16,6 +16,7 @@
 use std::sync::Arc;
 
 use common::chain::ChainConfig;
+use crypto::key::hdkd::u31::U31;
 use tokio::sync::{mpsc, oneshot};
 use wallet_controller::{NodeRpcClient, RpcController};
 
@@ -28,6 +29,7 @@ use crate::{
 pub enum Event {
     HandleCommand {
         command: WalletCommand,
+        progress_tx: mpsc::UnboundedSender<String>,
         res_tx: oneshot::Sender<Result<ConsoleCommand, WalletCliError>>,
     },
 }
@@ -36,12 +38,24 @@ async fn handle_event(
     chain_config: &Arc<ChainConfig>,
     rpc_client: &NodeRpcClient,
     controller_opt: &mut Option<RpcController>,
+    selected_account: &mut U31,
     event: Event,
 ) {
     match event {
-        Event::HandleCommand { command, res_tx } => {
-            let res =
-                handle_wallet_command(chain_config, rpc_client, controller_opt, command).await;
+        Event::HandleCommand {
+            command,
+            progress_tx,
+            res_tx,
+        } => {
+            let res = handle_wallet_command(
+                chain_config,
+                rpc_client,
+                controller_opt,
+                selected_account,
+                &progress_tx,
+                command,
+            )
+            .await;
             let _ = res_tx.send(res);
         }
     }
@@ -51,6 +65,7 @@ pub async fn run(
     chain_config: &Arc<ChainConfig>,
     rpc_client: &NodeRpcClient,
     mut controller_opt: Option<RpcController>,
+    mut selected_account: U31,
     mut event_rx: mpsc::UnboundedReceiver<Event>,
 ) {
     loop {
@@ -65,7 +80,14 @@ pub async fn run(
             event_opt = event_rx.recv() => {
                 match event_opt {
                     Some(event) => {
-                        handle_event(chain_config, rpc_client, &mut controller_opt, event).await;
+                        handle_event(
+                            chain_config,
+                            rpc_client,
+                            &mut controller_opt,
+                            &mut selected_account,
+                            event,
+                        )
+                        .await;
                     },
                     None => return,
                 }
@@ -14,7 +14,9 @@
 // limitations under the License.
 
 use clap::ValueEnum;
-use wallet_controller::{UtxoType, UtxoTypes};
+use common::chain::{block::timestamp::BlockTimestamp, timelock::OutputTimeLock};
+use common::primitives::BlockHeight;
+use wallet_controller::{UtxoState, UtxoStates, UtxoType, UtxoTypes};
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
 pub enum CliUtxoTypes {
@@ -42,3 +44,46 @@ impl CliUtxoTypes {
         }
     }
 }
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum CliUtxoStates {
+    All,
+    Confirmed,
+    /// Not confirmed yet, whether in the mempool or not
+    Pending,
+}
+
+impl CliUtxoStates {
+    pub fn to_wallet_states(self) -> UtxoStates {
+        match self {
+            CliUtxoStates::All => UtxoStates::ALL,
+            CliUtxoStates::Confirmed => UtxoState::Confirmed.into(),
+            CliUtxoStates::Pending => UtxoState::InMempool | UtxoState::Inactive,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum CliTimeLockType {
+    /// Locked until the block height
+    UntilHeight,
+    /// Locked until the median time past of the chain reaches the UNIX timestamp in seconds
+    UntilTime,
+    /// Locked for the number of blocks after the transaction is confirmed
+    ForBlockCount,
+    /// Locked for the number of seconds after the transaction is confirmed
+    ForSeconds,
+}
+
+impl CliTimeLockType {
+    pub fn to_time_lock(self, value: u64) -> OutputTimeLock {
+        match self {
+            CliTimeLockType::UntilHeight => OutputTimeLock::UntilHeight(BlockHeight::new(value)),
+            CliTimeLockType::UntilTime => {
+                OutputTimeLock::UntilTime(BlockTimestamp::from_int_seconds(value))
+            }
+            CliTimeLockType::ForBlockCount => OutputTimeLock::ForBlockCount(value),
+            CliTimeLockType::ForSeconds => OutputTimeLock::ForSeconds(value),
+        }
+    }
+}
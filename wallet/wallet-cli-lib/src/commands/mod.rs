@@ -15,20 +15,33 @@
 
 mod helper_types;
 
-use std::{path::PathBuf, str::FromStr, sync::Arc};
+use std::{collections::BTreeMap, num::NonZeroU8, path::PathBuf, str::FromStr, sync::Arc};
 
 use clap::Parser;
 use common::{
     address::Address,
-    chain::{Block, ChainConfig, PoolId, SignedTransaction},
-    primitives::{Amount, BlockHeight, H256},
+    chain::{
+        classic_multisig::ClassicMultisigChallenge,
+        signature::inputsig::arbitrary_message::ArbitraryMessageSignature,
+        timelock::OutputTimeLock,
+        tokens::{Metadata, OutputValue, RPCTokenInfo, TokenId, TokenIssuance},
+        Block, ChainConfig, DelegationId, Destination, OutPoint, OutPointSourceId, PoolId,
+        SignedTransaction, Transaction, TxOutput,
+    },
+    primitives::{Amount, BlockHeight, Id, Idable, H256},
 };
+use crypto::key::{extended::ExtendedPublicKey, hdkd::u31::U31, PublicKey};
 use serialization::{hex::HexEncode, hex_encoded::HexEncoded};
-use wallet_controller::{NodeInterface, NodeRpcClient, PeerId, RpcController};
+use tokio::sync::mpsc;
+use utils::qrcode::QrCode;
+use wallet_controller::{
+    FeeRate, NodeInterface, NodeRpcClient, PartiallySignedTransaction, PeerId, RpcController,
+    TxHistoryEntry, TxHistoryFilter, DEFAULT_ACCOUNT_INDEX,
+};
 
 use crate::errors::WalletCliError;
 
-use self::helper_types::CliUtxoTypes;
+use self::helper_types::{CliTimeLockType, CliUtxoStates, CliUtxoTypes};
 
 #[derive(Debug, Parser)]
 #[clap(rename_all = "lower")]
@@ -42,6 +55,48 @@ pub enum WalletCommand {
         mnemonic: Option<String>,
     },
 
+    /// Restore a wallet from its mnemonic, rescanning the blockchain to find the accounts and addresses used before
+    RecoverWallet {
+        /// File path
+        wallet_path: PathBuf,
+
+        /// Mnemonic phrase (12, 15, or 24 words as a single quoted argument)
+        mnemonic: String,
+
+        /// The number of unused addresses after the last used one that are checked, 20 if not specified
+        #[arg(long)]
+        gap_limit: Option<u32>,
+
+        /// The height of the first block that can have transactions of the wallet, the rescan starts from genesis if not specified
+        #[arg(long)]
+        birthday_height: Option<u64>,
+    },
+
+    /// Create new watch-only wallet, which tracks the account of another wallet but cannot sign its transactions
+    CreateWatchOnlyWallet {
+        /// File path
+        wallet_path: PathBuf,
+
+        /// The hex encoded account extended public key, as printed by getaccountpublickey
+        account_public_key: HexEncoded<ExtendedPublicKey>,
+    },
+
+    /// Restore a wallet from an encrypted backup file, rescanning the blockchain to find the transactions of its accounts
+    RestoreWalletFromBackup {
+        /// File path of the new wallet
+        wallet_path: PathBuf,
+
+        /// File path of the backup
+        backup_path: PathBuf,
+
+        /// The password used to encrypt the backup
+        password: String,
+
+        /// The height of the first block that can have transactions of the wallet, the rescan starts from genesis if not specified
+        #[arg(long)]
+        birthday_height: Option<u64>,
+    },
+
     /// Open exiting wallet
     OpenWallet {
         /// File path
@@ -69,6 +124,37 @@ pub enum WalletCommand {
     // Locks the private keys so they can't be used until they are unlocked again
     LockPrivateKeys,
 
+    /// Export the root key, the accounts, the labels and the address book to a backup file encrypted with a password.
+    /// The private keys must be unlocked.
+    ExportWalletBackup {
+        /// File path of the backup
+        file_path: PathBuf,
+
+        /// The password to encrypt the backup with
+        password: String,
+    },
+
+    /// Create a new account after the last one, the last account must have transactions
+    CreateNewAccount {
+        /// Optional name of the new account
+        name: Option<String>,
+    },
+
+    /// Set the name of the selected account
+    RenameAccount {
+        /// The new name, the name is removed if not specified
+        name: Option<String>,
+    },
+
+    /// List the accounts of the wallet, the selected account is marked with `*`
+    ListAccounts,
+
+    /// Select the account used by the other commands
+    SelectAccount {
+        /// Account index
+        account_index: U31,
+    },
+
     /// Returns the node chainstate
     ChainstateInfo,
 
@@ -129,35 +215,358 @@ pub enum WalletCommand {
         transaction: HexEncoded<SignedTransaction>,
     },
 
+    /// Create a transaction sending coins to the given address without signing it.
+    /// The printed partially signed transaction can be signed by another wallet.
+    CreateUnsignedTransaction {
+        address: String,
+        amount: String,
+
+        /// Fee rate in coins per 1000 bytes, the current fee rate of the node is used if not specified
+        #[arg(long)]
+        fee_rate: Option<String>,
+    },
+
+    /// Print the inputs and outputs of a partially signed transaction and which inputs are signed
+    InspectTransaction {
+        /// Hex encoded partially signed transaction
+        transaction: HexEncoded<PartiallySignedTransaction>,
+    },
+
+    /// Add the signatures the wallet can make to a partially signed transaction
+    SignTransaction {
+        /// Hex encoded partially signed transaction
+        transaction: HexEncoded<PartiallySignedTransaction>,
+    },
+
+    /// Add the challenge of a classic multisig input to a partially signed transaction,
+    /// so that the owners of its keys can sign it
+    AddMultisigChallenge {
+        /// Hex encoded partially signed transaction
+        transaction: HexEncoded<PartiallySignedTransaction>,
+        input_index: usize,
+        /// Hex encoded classic multisig challenge
+        challenge: HexEncoded<ClassicMultisigChallenge>,
+    },
+
+    /// Merge the signatures of several copies of the same partially signed transaction
+    CombineTransactions {
+        /// Hex encoded partially signed transactions
+        #[arg(required = true)]
+        transactions: Vec<HexEncoded<PartiallySignedTransaction>>,
+    },
+
+    /// Check that all the inputs of a partially signed transaction are signed
+    /// and print the signed transaction, which can be submitted with `submittransaction`
+    FinalizeTransaction {
+        /// Hex encoded partially signed transaction
+        transaction: HexEncoded<PartiallySignedTransaction>,
+    },
+
+    /// Sign a message with the key of an address of the selected account to prove its ownership.
+    /// Print the hex encoded signature.
+    SignMessage {
+        address: String,
+        message: String,
+    },
+
+    /// Check that a message was signed by the owner of an address, no wallet is needed
+    VerifyMessage {
+        address: String,
+        message: String,
+        /// Hex encoded signature
+        signature: HexEncoded<ArbitraryMessageSignature>,
+    },
+
+    /// Create an address that needs the signatures of `min_required_signatures` of the given public keys
+    /// and watch its funds in the selected account.
+    /// Print the address and the hex encoded multisig challenge.
+    NewMultisigAddress {
+        min_required_signatures: NonZeroU8,
+        /// Hex encoded public keys
+        #[arg(required = true)]
+        public_keys: Vec<HexEncoded<PublicKey>>,
+    },
+
+    /// List the multisig addresses watched by the selected account with their confirmed balances
+    ListMultisigAddresses,
+
+    /// Create a transaction sending coins from a watched multisig address to the given address,
+    /// the change goes back to the multisig address.
+    /// The printed partially signed transaction has to be signed by the owners of the multisig keys.
+    CreateMultisigSpendTransaction {
+        multisig_address: String,
+        address: String,
+        amount: String,
+
+        /// Fee rate in coins per 1000 bytes, the current fee rate of the node is used if not specified
+        #[arg(long)]
+        fee_rate: Option<String>,
+    },
+
     /// Rescan
     Rescan,
 
     SyncWallet,
 
-    GetBalance,
+    GetBalance {
+        #[arg(value_enum, default_value_t = CliUtxoStates::Confirmed)]
+        utxo_states: CliUtxoStates,
+    },
 
     ListUtxo {
         #[arg(value_enum, default_value_t = CliUtxoTypes::All)]
         utxo_type: CliUtxoTypes,
+
+        #[arg(value_enum, default_value_t = CliUtxoStates::Confirmed)]
+        utxo_states: CliUtxoStates,
+    },
+
+    /// List the time-locked UTXOs that can't be spent yet, with the block height or time
+    /// after which they can be spent
+    ListLockedUtxos {
+        #[arg(value_enum, default_value_t = CliUtxoStates::Confirmed)]
+        utxo_states: CliUtxoStates,
+    },
+
+    /// Exclude a UTXO from the automatic input selection, it can still be spent with SendFromUtxos.
+    /// The UTXO is given as `tx:<transaction id>:<output index>`
+    /// or `block:<block id>:<output index>` for the block rewards.
+    FreezeUtxo {
+        utxo: String,
+    },
+
+    /// Return a frozen UTXO to the automatic input selection
+    UnfreezeUtxo {
+        utxo: String,
+    },
+
+    /// List the frozen UTXOs that are not spent yet
+    ListFrozenUtxos,
+
+    /// List the transactions and block rewards of an account, the most recent ones first
+    ListTransactions {
+        /// Account index, the selected account is used if not specified
+        #[arg(long)]
+        account: Option<U31>,
+
+        /// Skip the transactions confirmed below this block height
+        #[arg(long)]
+        min_height: Option<BlockHeight>,
+
+        /// Skip the transactions confirmed above this block height and the unconfirmed ones
+        #[arg(long)]
+        max_height: Option<BlockHeight>,
+
+        /// The number of the most recent transactions to skip
+        #[arg(long, default_value_t = 0)]
+        skip: usize,
+
+        /// The maximum number of transactions to show
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
     },
 
     /// Generate a new unused address
-    NewAddress,
+    NewAddress {
+        /// Also print the address as a QR code
+        #[arg(long)]
+        qr_code: bool,
+
+        /// The amount of coins to request in the QR code
+        #[arg(long, requires = "qr_code")]
+        amount: Option<String>,
+    },
 
     /// Generate a new unused public key
     NewPublicKey,
 
+    /// Set the label of an address of the wallet, the label is removed if it's not specified
+    SetAddressLabel {
+        address: String,
+        label: Option<String>,
+    },
+
+    /// List the labels of the wallet addresses
+    ListAddressLabels,
+
+    /// Add an external address to the address book, the name can be used instead of the address
+    /// when sending coins or tokens. The address is renamed if it's already in the address book.
+    AddContact {
+        name: String,
+        address: String,
+    },
+
+    /// Remove a contact from the address book
+    RemoveContact {
+        name: String,
+    },
+
+    /// List the address book contacts
+    ListContacts,
+
     GetVrfPublicKey,
 
+    /// Print the hex encoded account extended public key, which can be used to create a watch-only wallet
+    GetAccountPublicKey,
+
     SendToAddress {
+        /// Address or address book contact name
+        address: String,
+        amount: String,
+
+        /// Fee rate in coins per 1000 bytes, the current fee rate of the node is used if not specified
+        #[arg(long)]
+        fee_rate: Option<String>,
+    },
+
+    /// Send coins to the given address spending exactly the given UTXOs, the rest goes back
+    /// to the wallet as change. The UTXOs are given in the same format as in FreezeUtxo.
+    SendFromUtxos {
+        /// Address or address book contact name
+        address: String,
+        amount: String,
+
+        #[arg(required = true)]
+        utxos: Vec<String>,
+
+        /// Fee rate in coins per 1000 bytes, the current fee rate of the node is used if not specified
+        #[arg(long)]
+        fee_rate: Option<String>,
+    },
+
+    /// Send coins to the given address, they can't be spent until the lock expires
+    SendLockedToAddress {
+        /// Address or address book contact name
+        address: String,
+        amount: String,
+
+        #[arg(value_enum)]
+        lock_type: CliTimeLockType,
+
+        /// The block height, the UNIX timestamp in seconds, the number of blocks
+        /// or the number of seconds, depending on the lock type
+        lock_value: u64,
+
+        /// Fee rate in coins per 1000 bytes, the current fee rate of the node is used if not specified
+        #[arg(long)]
+        fee_rate: Option<String>,
+    },
+
+    /// Replace an unconfirmed transaction of the wallet with one that spends the same inputs
+    /// and pays a higher fee, the fee increase is taken from the change output
+    BumpFee {
+        transaction_id: HexEncoded<Id<Transaction>>,
+
+        /// The new fee rate in coins per 1000 bytes
+        fee_rate: String,
+    },
+
+    /// Abandon an unconfirmed transaction of the wallet that is no longer in the mempool,
+    /// together with the transactions that spend its outputs, so its inputs can be spent again
+    AbandonTransaction {
+        transaction_id: HexEncoded<Id<Transaction>>,
+    },
+
+    /// Send tokens to the given address, the amount is in units of the token
+    SendTokensToAddress {
+        token_id: HexEncoded<TokenId>,
+        /// Address or address book contact name
         address: String,
         amount: String,
+
+        /// Fee rate in coins per 1000 bytes, the current fee rate of the node is used if not specified
+        #[arg(long)]
+        fee_rate: Option<String>,
+    },
+
+    /// Issue new fungible tokens to the given address, the issuance fee is paid in coins
+    IssueNewToken {
+        token_ticker: String,
+        amount_to_issue: String,
+        number_of_decimals: u8,
+        metadata_uri: String,
+        destination_address: String,
+
+        /// Fee rate in coins per 1000 bytes, the current fee rate of the node is used if not specified
+        #[arg(long)]
+        fee_rate: Option<String>,
+    },
+
+    /// Issue a new NFT to the given address, the issuance fee is paid in coins
+    IssueNewNft {
+        destination_address: String,
+        /// Hex encoded hash of the media
+        media_hash: String,
+        name: String,
+        description: String,
+        ticker: String,
+        #[arg(long)]
+        icon_uri: Option<String>,
+        #[arg(long)]
+        additional_metadata_uri: Option<String>,
+        #[arg(long)]
+        media_uri: Option<String>,
+
+        /// Fee rate in coins per 1000 bytes, the current fee rate of the node is used if not specified
+        #[arg(long)]
+        fee_rate: Option<String>,
     },
 
     CreateStakePool {
         amount: String,
+
+        /// Fee rate in coins per 1000 bytes, the current fee rate of the node is used if not specified
+        #[arg(long)]
+        fee_rate: Option<String>,
+    },
+
+    /// Decommission a stake pool of the wallet, the pledge is locked for the decommission maturity period
+    DecommissionStakePool {
+        pool_id: HexEncoded<PoolId>,
+
+        /// Fee rate in coins per 1000 bytes, the current fee rate of the node is used if not specified
+        #[arg(long)]
+        fee_rate: Option<String>,
+    },
+
+    /// List the stake pools of the wallet with their pledge, balance and delegation shares
+    ListPools,
+
+    /// Create a new delegation to the given pool, the delegated coins can be withdrawn to the given address
+    CreateDelegation {
+        pool_id: HexEncoded<PoolId>,
+        address: String,
+
+        /// Fee rate in coins per 1000 bytes, the current fee rate of the node is used if not specified
+        #[arg(long)]
+        fee_rate: Option<String>,
     },
 
+    /// Add coins to the stake of a delegation
+    DelegateStaking {
+        delegation_id: HexEncoded<DelegationId>,
+        amount: String,
+
+        /// Fee rate in coins per 1000 bytes, the current fee rate of the node is used if not specified
+        #[arg(long)]
+        fee_rate: Option<String>,
+    },
+
+    /// Withdraw coins from a delegation to the given address.
+    /// The coins can be spent after the spend share maturity period.
+    WithdrawFromDelegation {
+        delegation_id: HexEncoded<DelegationId>,
+        address: String,
+        amount: String,
+
+        /// Fee rate in coins per 1000 bytes, the current fee rate of the node is used if not specified
+        #[arg(long)]
+        fee_rate: Option<String>,
+    },
+
+    /// List the delegations of the wallet with their pools and current balances
+    ListDelegations,
+
     /// Node version
     NodeVersion,
 
@@ -218,19 +627,287 @@ fn parse_address(chain_config: &ChainConfig, address: &str) -> Result<Address, W
         .map_err(|e| WalletCliError::InvalidInput(format!("Invalid address '{address}': {e}")))
 }
 
+/// Parse an address or find it by its name in the address book
+fn parse_send_address(
+    chain_config: &ChainConfig,
+    controller: &RpcController,
+    value: &str,
+) -> Result<Address, WalletCliError> {
+    if let Ok(address) = Address::from_str(chain_config, value) {
+        return Ok(address);
+    }
+    controller
+        .find_contact(value)
+        .map_err(WalletCliError::Controller)?
+        .ok_or_else(|| {
+            WalletCliError::InvalidInput(format!("Invalid address or contact name '{value}'"))
+        })
+}
+
+/// Parse a UTXO given as `tx:<transaction id>:<output index>` or `block:<block id>:<output index>`
+fn parse_outpoint(value: &str) -> Result<OutPoint, WalletCliError> {
+    let invalid_outpoint = || WalletCliError::InvalidInput(format!("Invalid UTXO '{value}'"));
+    let mut parts = value.split(':');
+    let (source_type, source_id, output_index) =
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(source_type), Some(source_id), Some(output_index), None) => {
+                (source_type, source_id, output_index)
+            }
+            _ => return Err(invalid_outpoint()),
+        };
+    let source_id = HexEncoded::<H256>::from_str(source_id).map_err(|_| invalid_outpoint())?.take();
+    let source_id = match source_type {
+        "tx" => OutPointSourceId::Transaction(Id::new(source_id)),
+        "block" => OutPointSourceId::BlockReward(Id::new(source_id)),
+        _ => return Err(invalid_outpoint()),
+    };
+    let output_index = output_index.parse::<u32>().map_err(|_| invalid_outpoint())?;
+    Ok(OutPoint::new(source_id, output_index))
+}
+
 fn parse_coin_amount(chain_config: &ChainConfig, value: &str) -> Result<Amount, WalletCliError> {
     Amount::from_fixedpoint_str(value, chain_config.coin_decimals())
         .ok_or_else(|| WalletCliError::InvalidInput(value.to_owned()))
 }
 
+fn parse_fee_rate(
+    chain_config: &ChainConfig,
+    value: Option<String>,
+) -> Result<Option<FeeRate>, WalletCliError> {
+    value
+        .map(|value| parse_coin_amount(chain_config, &value).map(FeeRate::new))
+        .transpose()
+}
+
+fn parse_token_amount(token_number_of_decimals: u8, value: &str) -> Result<Amount, WalletCliError> {
+    Amount::from_fixedpoint_str(value, token_number_of_decimals)
+        .ok_or_else(|| WalletCliError::InvalidInput(value.to_owned()))
+}
+
 fn print_coin_amount(chain_config: &ChainConfig, value: Amount) -> String {
     value.into_fixedpoint_str(chain_config.coin_decimals())
 }
 
+fn print_token_amount(token_number_of_decimals: u8, value: Amount) -> String {
+    value.into_fixedpoint_str(token_number_of_decimals)
+}
+
+fn destination_address(chain_config: &ChainConfig, destination: &Destination) -> Option<Address> {
+    match destination {
+        Destination::Address(public_key_hash) => {
+            Address::from_public_key_hash(chain_config, public_key_hash).ok()
+        }
+        Destination::ClassicMultisig(challenge_hash) => {
+            Address::from_classic_multisig_hash(chain_config, challenge_hash).ok()
+        }
+        Destination::AnyoneCanSpend | Destination::PublicKey(_) | Destination::ScriptHash(_) => {
+            None
+        }
+    }
+}
+
+fn print_destination(chain_config: &ChainConfig, destination: &Destination) -> String {
+    destination_address(chain_config, destination).map_or_else(
+        || destination.hex_encode(),
+        |address| address.get().to_owned(),
+    )
+}
+
+/// Print the destination followed by its address label or contact name, if it has one
+fn print_named_destination(
+    chain_config: &ChainConfig,
+    destination: &Destination,
+    names: &BTreeMap<Address, String>,
+) -> String {
+    match destination_address(chain_config, destination) {
+        Some(address) => match names.get(&address) {
+            Some(name) => format!("{} ({name})", address.get()),
+            None => address.get().to_owned(),
+        },
+        None => destination.hex_encode(),
+    }
+}
+
+fn print_outpoint_source(source: &OutPointSourceId) -> String {
+    match source {
+        OutPointSourceId::Transaction(id) => format!("transaction {}", id.get().hex_encode()),
+        OutPointSourceId::BlockReward(id) => format!("block reward {}", id.get().hex_encode()),
+    }
+}
+
+fn print_utxo(
+    chain_config: &ChainConfig,
+    outpoint: &OutPoint,
+    utxo: &TxOutput,
+    names: &BTreeMap<Address, String>,
+) -> String {
+    let output = match utxo {
+        TxOutput::Transfer(OutputValue::Coin(amount), destination)
+        | TxOutput::LockThenTransfer(OutputValue::Coin(amount), destination, _) => format!(
+            "coins: {}, destination: {}",
+            print_coin_amount(chain_config, *amount),
+            print_named_destination(chain_config, destination, names)
+        ),
+        _ => format!("{utxo:?}"),
+    };
+    format!(
+        "{}, output: {}, {output}",
+        print_outpoint_source(&outpoint.tx_id()),
+        outpoint.output_index()
+    )
+}
+
+fn print_locked_utxo(
+    chain_config: &ChainConfig,
+    outpoint: &OutPoint,
+    utxo: &TxOutput,
+    unlock_point: &OutputTimeLock,
+) -> String {
+    let value = match utxo {
+        TxOutput::LockThenTransfer(OutputValue::Coin(amount), _, _) => {
+            format!("coins: {}", print_coin_amount(chain_config, *amount))
+        }
+        _ => format!("{utxo:?}"),
+    };
+    let unlock = match unlock_point {
+        OutputTimeLock::UntilHeight(height) => format!("unlocks at height {height}"),
+        OutputTimeLock::UntilTime(time) => {
+            format!("unlocks at timestamp {}", time.as_int_seconds())
+        }
+        OutputTimeLock::ForBlockCount(block_count) => {
+            format!("unlocks {block_count} blocks after confirmation")
+        }
+        OutputTimeLock::ForSeconds(seconds) => {
+            format!("unlocks {seconds} seconds after confirmation")
+        }
+    };
+    format!(
+        "{}, output: {}, {value}, {unlock}",
+        print_outpoint_source(&outpoint.tx_id()),
+        outpoint.output_index()
+    )
+}
+
+fn print_partially_signed_transaction(
+    chain_config: &ChainConfig,
+    ptx: &PartiallySignedTransaction,
+) -> String {
+    let mut lines = vec![
+        format!("Transaction {}", ptx.tx().get_id().get().hex_encode()),
+        format!(
+            "Signed inputs: {} of {}",
+            ptx.signed_inputs_count(chain_config),
+            ptx.tx().inputs().len()
+        ),
+    ];
+    for (input_index, input) in ptx.tx().inputs().iter().enumerate() {
+        let source = print_outpoint_source(&input.outpoint().tx_id());
+        let destination = ptx.destinations().get(input_index).and_then(Option::as_ref).map_or_else(
+            || "unknown".to_owned(),
+            |destination| print_destination(chain_config, destination),
+        );
+        let status = if ptx.verify_input(chain_config, input_index).is_ok() {
+            "signed".to_owned()
+        } else if let Some(spend) = ptx.multisig_spend(input_index) {
+            format!(
+                "{} of {} signatures",
+                spend.available_signatures_count(),
+                spend.challenge().min_required_signatures()
+            )
+        } else if ptx.witnesses().get(input_index).map_or(false, Option::is_some) {
+            "invalid signature".to_owned()
+        } else {
+            "not signed".to_owned()
+        };
+        lines.push(format!(
+            "Input {input_index}: {source}, output: {}, destination: {destination}, {status}",
+            input.outpoint().output_index()
+        ));
+    }
+    for (output_index, output) in ptx.tx().outputs().iter().enumerate() {
+        lines.push(format!("Output {output_index}: {output:?}"));
+    }
+    lines.join("\n")
+}
+
+async fn print_history_entry(
+    chain_config: &ChainConfig,
+    rpc_client: &NodeRpcClient,
+    names: &BTreeMap<Address, String>,
+    entry: TxHistoryEntry,
+) -> String {
+    let mut parts = vec![match &entry.tx_id {
+        OutPointSourceId::Transaction(id) => format!("Transaction {}", id.get().hex_encode()),
+        OutPointSourceId::BlockReward(id) => format!("Block reward {}", id.get().hex_encode()),
+    }];
+    match (entry.block_height(), entry.timestamp()) {
+        (Some(height), Some(timestamp)) => {
+            parts.push(format!("height: {height}"));
+            parts.push(format!("timestamp: {}", timestamp.as_int_seconds()));
+        }
+        _ => parts.push(format!("state: {:?}", entry.state)),
+    }
+    parts.push(format!("confirmations: {}", entry.confirmations));
+    parts.push(format!(
+        "coins: {}",
+        entry.coin_change.into_fixedpoint_str(chain_config.coin_decimals())
+    ));
+    for (token_id, change) in entry.token_changes {
+        // Tokens that are not confirmed yet are shown in atoms
+        let change = match get_token_number_of_decimals(rpc_client, token_id).await {
+            Ok(decimals) => change.into_fixedpoint_str(decimals),
+            Err(_) => change.into_atoms().to_string(),
+        };
+        parts.push(format!("token {}: {}", token_id.hex_encode(), change));
+    }
+    if let Some(fee) = entry.fee {
+        parts.push(format!("fee: {}", print_coin_amount(chain_config, fee)));
+    }
+    if !entry.counterparties.is_empty() {
+        let counterparties = entry
+            .counterparties
+            .iter()
+            .map(|destination| print_named_destination(chain_config, destination, names))
+            .collect::<Vec<_>>();
+        parts.push(format!("counterparties: {}", counterparties.join(", ")));
+    }
+    parts.join(", ")
+}
+
+async fn get_token_number_of_decimals(
+    rpc_client: &NodeRpcClient,
+    token_id: TokenId,
+) -> Result<u8, WalletCliError> {
+    let token_info = rpc_client.get_token_info(token_id).await.map_err(WalletCliError::RpcError)?;
+    match token_info {
+        Some(RPCTokenInfo::FungibleToken(token_info)) => Ok(token_info.number_of_decimals),
+        Some(RPCTokenInfo::NonFungibleToken(_)) => Ok(0),
+        None => Err(WalletCliError::InvalidInput(format!(
+            "Token {} not found",
+            token_id.hex_encode()
+        ))),
+    }
+}
+
+/// Send the rescan progress of a recovered or restored wallet to the console
+fn rescan_progress_printer(
+    progress_tx: &mpsc::UnboundedSender<String>,
+) -> impl FnMut(U31, BlockHeight, BlockHeight) + '_ {
+    move |account_index, height, target_height| {
+        // The console may be closed already, the rescan goes on anyway
+        let _ = progress_tx.send(format!(
+            "Account {account_index} rescanned up to block height {height} of {target_height}"
+        ));
+    }
+}
+
+/// Handle a wallet command, the lines sent to `progress_tx` are printed while it's running
 pub async fn handle_wallet_command(
     chain_config: &Arc<ChainConfig>,
     rpc_client: &NodeRpcClient,
     controller_opt: &mut Option<RpcController>,
+    selected_account: &mut U31,
+    progress_tx: &mpsc::UnboundedSender<String>,
     command: WalletCommand,
 ) -> Result<ConsoleCommand, WalletCliError> {
     match command {
@@ -265,6 +942,7 @@ pub async fn handle_wallet_command(
                 rpc_client.clone(),
                 wallet,
             ));
+            *selected_account = DEFAULT_ACCOUNT_INDEX;
 
             let msg = if need_mnemonic_backup {
                 format!(
@@ -276,6 +954,107 @@ pub async fn handle_wallet_command(
             Ok(ConsoleCommand::Print(msg))
         }
 
+        WalletCommand::RecoverWallet {
+            wallet_path,
+            mnemonic,
+            gap_limit,
+            birthday_height,
+        } => {
+            utils::ensure!(
+                controller_opt.is_none(),
+                WalletCliError::WalletFileAlreadyOpen
+            );
+
+            // TODO: Support other languages
+            let language = wallet::wallet::Language::English;
+            let mnemonic = wallet_controller::mnemonic::parse_mnemonic(language, &mnemonic)
+                .map_err(WalletCliError::InvalidMnemonic)?;
+
+            let controller = RpcController::recover_wallet(
+                Arc::clone(chain_config),
+                rpc_client.clone(),
+                wallet_path,
+                mnemonic,
+                None,
+                gap_limit,
+                birthday_height.map(BlockHeight::new),
+                rescan_progress_printer(progress_tx),
+            )
+            .await
+            .map_err(WalletCliError::Controller)?;
+            let accounts_count = controller.account_indexes().count();
+
+            *controller_opt = Some(controller);
+            *selected_account = DEFAULT_ACCOUNT_INDEX;
+
+            Ok(ConsoleCommand::Print(format!(
+                "Wallet recovered successfully, accounts found: {accounts_count}"
+            )))
+        }
+
+        WalletCommand::CreateWatchOnlyWallet {
+            wallet_path,
+            account_public_key,
+        } => {
+            utils::ensure!(
+                controller_opt.is_none(),
+                WalletCliError::WalletFileAlreadyOpen
+            );
+
+            let wallet = RpcController::create_watch_only_wallet(
+                Arc::clone(chain_config),
+                wallet_path,
+                account_public_key.take(),
+            )
+            .map_err(WalletCliError::Controller)?;
+
+            *controller_opt = Some(RpcController::new(
+                Arc::clone(chain_config),
+                rpc_client.clone(),
+                wallet,
+            ));
+            *selected_account = DEFAULT_ACCOUNT_INDEX;
+
+            Ok(ConsoleCommand::Print(
+                "New watch-only wallet created successfully".to_owned(),
+            ))
+        }
+
+        WalletCommand::RestoreWalletFromBackup {
+            wallet_path,
+            backup_path,
+            password,
+            birthday_height,
+        } => {
+            utils::ensure!(
+                controller_opt.is_none(),
+                WalletCliError::WalletFileAlreadyOpen
+            );
+
+            let backup = std::fs::read(&backup_path)
+                .map_err(|e| WalletCliError::FileError(backup_path, e))?;
+
+            let controller = RpcController::restore_wallet_from_backup(
+                Arc::clone(chain_config),
+                rpc_client.clone(),
+                wallet_path,
+                &backup,
+                &password,
+                birthday_height.map(BlockHeight::new),
+                rescan_progress_printer(progress_tx),
+            )
+            .await
+            .map_err(WalletCliError::Controller)?;
+            let accounts_count = controller.account_indexes().count();
+
+            *controller_opt = Some(controller);
+            *selected_account = DEFAULT_ACCOUNT_INDEX;
+
+            Ok(ConsoleCommand::Print(format!(
+                "Wallet restored successfully, accounts restored: {accounts_count}"
+            )))
+        }
+
         WalletCommand::OpenWallet { wallet_path } => {
             utils::ensure!(
                 controller_opt.is_none(),
@@ -290,6 +1069,7 @@ pub async fn handle_wallet_command(
                 rpc_client.clone(),
                 wallet,
             ));
+            *selected_account = DEFAULT_ACCOUNT_INDEX;
 
             Ok(ConsoleCommand::Print(
                 "Wallet loaded successfully".to_owned(),
@@ -300,6 +1080,7 @@ pub async fn handle_wallet_command(
             utils::ensure!(controller_opt.is_some(), WalletCliError::NoWallet);
 
             *controller_opt = None;
+            *selected_account = DEFAULT_ACCOUNT_INDEX;
 
             Ok(ConsoleCommand::Print("Success".to_owned()))
         }
@@ -358,6 +1139,77 @@ pub async fn handle_wallet_command(
             Ok(ConsoleCommand::Print("Success".to_owned()))
         }
 
+        WalletCommand::ExportWalletBackup {
+            file_path,
+            password,
+        } => {
+            let backup = controller_opt
+                .as_ref()
+                .ok_or(WalletCliError::NoWallet)?
+                .export_backup(&password)
+                .map_err(WalletCliError::Controller)?;
+            std::fs::write(&file_path, backup)
+                .map_err(|e| WalletCliError::FileError(file_path, e))?;
+
+            Ok(ConsoleCommand::Print(
+                "Wallet backup exported successfully".to_owned(),
+            ))
+        }
+
+        WalletCommand::CreateNewAccount { name } => {
+            let account_index = controller_opt
+                .as_mut()
+                .ok_or(WalletCliError::NoWallet)?
+                .create_account(name)
+                .map_err(WalletCliError::Controller)?;
+            Ok(ConsoleCommand::Print(format!(
+                "Success, the new account index is: {account_index}"
+            )))
+        }
+
+        WalletCommand::RenameAccount { name } => {
+            controller_opt
+                .as_mut()
+                .ok_or(WalletCliError::NoWallet)?
+                .set_account_name(*selected_account, name)
+                .map_err(WalletCliError::Controller)?;
+            Ok(ConsoleCommand::Print("Success".to_owned()))
+        }
+
+        WalletCommand::ListAccounts => {
+            let lines = controller_opt
+                .as_ref()
+                .ok_or(WalletCliError::NoWallet)?
+                .account_names()
+                .map(|(account_index, name)| {
+                    let selected = if account_index == selected_account {
+                        "*"
+                    } else {
+                        " "
+                    };
+                    match name {
+                        Some(name) => format!("{selected} Account {account_index}: {name}"),
+                        None => format!("{selected} Account {account_index}"),
+                    }
+                })
+                .collect::<Vec<_>>();
+            Ok(ConsoleCommand::Print(lines.join("\n")))
+        }
+
+        WalletCommand::SelectAccount { account_index } => {
+            let account_exists = controller_opt
+                .as_ref()
+                .ok_or(WalletCliError::NoWallet)?
+                .account_indexes()
+                .any(|index| *index == account_index);
+            utils::ensure!(
+                account_exists,
+                WalletCliError::AccountNotFound(account_index)
+            );
+            *selected_account = account_index;
+            Ok(ConsoleCommand::Print("Success".to_owned()))
+        }
+
         WalletCommand::ChainstateInfo => {
             let info = rpc_client.chainstate_info().await.map_err(WalletCliError::RpcError)?;
             Ok(ConsoleCommand::Print(format!("{info:?}")))
@@ -467,6 +1319,163 @@ pub async fn handle_wallet_command(
             ))
         }
 
+        WalletCommand::CreateUnsignedTransaction {
+            address,
+            amount,
+            fee_rate,
+        } => {
+            let address = parse_address(chain_config, &address)?;
+            let amount = parse_coin_amount(chain_config, &amount)?;
+            let fee_rate = parse_fee_rate(chain_config, fee_rate)?;
+            let ptx = controller_opt
+                .as_mut()
+                .ok_or(WalletCliError::NoWallet)?
+                .create_partially_signed_transaction(*selected_account, address, amount, fee_rate)
+                .await
+                .map_err(WalletCliError::Controller)?;
+            Ok(ConsoleCommand::Print(ptx.hex_encode()))
+        }
+
+        WalletCommand::InspectTransaction { transaction } => Ok(ConsoleCommand::Print(
+            print_partially_signed_transaction(chain_config, transaction.as_ref()),
+        )),
+
+        WalletCommand::SignTransaction { transaction } => {
+            let ptx = controller_opt
+                .as_mut()
+                .ok_or(WalletCliError::NoWallet)?
+                .sign_partially_signed_transaction(*selected_account, transaction.take())
+                .map_err(WalletCliError::Controller)?;
+            Ok(ConsoleCommand::Print(format!(
+                "Signed inputs: {} of {}\n{}",
+                ptx.signed_inputs_count(chain_config),
+                ptx.tx().inputs().len(),
+                ptx.hex_encode()
+            )))
+        }
+
+        WalletCommand::AddMultisigChallenge {
+            transaction,
+            input_index,
+            challenge,
+        } => {
+            let mut ptx = transaction.take();
+            ptx.add_multisig_challenge(input_index, challenge.take())
+                .map_err(WalletCliError::PartiallySignedTransaction)?;
+            Ok(ConsoleCommand::Print(ptx.hex_encode()))
+        }
+
+        WalletCommand::CombineTransactions { transactions } => {
+            let mut transactions = transactions.into_iter().map(HexEncoded::take);
+            let first = transactions.next().expect("at least one transaction is required");
+            let ptx = transactions
+                .try_fold(first, |combined, ptx| combined.combine(ptx))
+                .map_err(WalletCliError::PartiallySignedTransaction)?;
+            Ok(ConsoleCommand::Print(ptx.hex_encode()))
+        }
+
+        WalletCommand::FinalizeTransaction { transaction } => {
+            let tx = transaction
+                .take()
+                .into_signed_tx(chain_config)
+                .map_err(WalletCliError::PartiallySignedTransaction)?;
+            Ok(ConsoleCommand::Print(tx.hex_encode()))
+        }
+
+        WalletCommand::SignMessage { address, message } => {
+            let address = parse_address(chain_config, &address)?;
+            let signature = controller_opt
+                .as_mut()
+                .ok_or(WalletCliError::NoWallet)?
+                .sign_message(*selected_account, &address, message.as_bytes())
+                .map_err(WalletCliError::Controller)?;
+            Ok(ConsoleCommand::Print(signature.hex_encode()))
+        }
+
+        WalletCommand::VerifyMessage {
+            address,
+            message,
+            signature,
+        } => {
+            signature
+                .as_ref()
+                .verify_signature_for_address(chain_config, &address, message.as_bytes())
+                .map_err(WalletCliError::MessageSignature)?;
+            Ok(ConsoleCommand::Print(
+                "The message signature is valid".to_owned(),
+            ))
+        }
+
+        WalletCommand::NewMultisigAddress {
+            min_required_signatures,
+            public_keys,
+        } => {
+            let public_keys = public_keys.into_iter().map(HexEncoded::take).collect::<Vec<_>>();
+            let address = controller_opt
+                .as_mut()
+                .ok_or(WalletCliError::NoWallet)?
+                .add_classic_multisig(
+                    *selected_account,
+                    min_required_signatures,
+                    public_keys.clone(),
+                )
+                .map_err(WalletCliError::Controller)?;
+            let challenge =
+                ClassicMultisigChallenge::new(chain_config, min_required_signatures, public_keys)
+                    .expect("checked by the wallet");
+            Ok(ConsoleCommand::Print(format!(
+                "Address: {}\nChallenge: {}",
+                address.get(),
+                challenge.hex_encode()
+            )))
+        }
+
+        WalletCommand::ListMultisigAddresses => {
+            let multisigs = controller_opt
+                .as_mut()
+                .ok_or(WalletCliError::NoWallet)?
+                .get_classic_multisigs(*selected_account)
+                .map_err(WalletCliError::Controller)?;
+            let lines = multisigs
+                .iter()
+                .map(|(address, challenge, balance)| {
+                    format!(
+                        "{} ({} of {}), balance: {}",
+                        address.get(),
+                        challenge.min_required_signatures(),
+                        challenge.public_keys().len(),
+                        print_coin_amount(chain_config, *balance)
+                    )
+                })
+                .collect::<Vec<_>>();
+            Ok(ConsoleCommand::Print(lines.join("\n")))
+        }
+
+        WalletCommand::CreateMultisigSpendTransaction {
+            multisig_address,
+            address,
+            amount,
+            fee_rate,
+        } => {
+            let multisig_address = parse_address(chain_config, &multisig_address)?;
+            let address = parse_address(chain_config, &address)?;
+            let amount = parse_coin_amount(chain_config, &amount)?;
+            let fee_rate = parse_fee_rate(chain_config, fee_rate)?;
+            let ptx = controller_opt
+                .as_mut()
+                .ok_or(WalletCliError::NoWallet)?
+                .create_classic_multisig_spend(
+                    *selected_account,
+                    multisig_address,
+                    address,
+                    amount,
+                    fee_rate,
+                )
+                .await
+                .map_err(WalletCliError::Controller)?;
+            Ok(ConsoleCommand::Print(ptx.hex_encode()))
+        }
+
         WalletCommand::Rescan => Ok(ConsoleCommand::Print("Not implemented".to_owned())),
 
         WalletCommand::SyncWallet => {
@@ -479,77 +1488,534 @@ pub async fn handle_wallet_command(
             Ok(ConsoleCommand::Print("Success".to_owned()))
         }
 
-        WalletCommand::GetBalance => {
-            let (coin_balance, _tokens_balance) = controller_opt
+        WalletCommand::GetBalance { utxo_states } => {
+            let (coin_balance, tokens_balance) = controller_opt
                 .as_mut()
                 .ok_or(WalletCliError::NoWallet)?
-                .get_balance()
+                .get_balance(*selected_account, utxo_states.to_wallet_states())
                 .map_err(WalletCliError::Controller)?;
-            Ok(ConsoleCommand::Print(print_coin_amount(
-                chain_config,
-                coin_balance,
-            )))
+            let mut lines = vec![print_coin_amount(chain_config, coin_balance)];
+            for (token_id, amount) in tokens_balance {
+                // Tokens that are not confirmed yet are shown in atoms
+                let amount = match get_token_number_of_decimals(rpc_client, token_id).await {
+                    Ok(decimals) => print_token_amount(decimals, amount),
+                    Err(_) => amount.into_atoms().to_string(),
+                };
+                lines.push(format!("Token {}: {}", token_id.hex_encode(), amount));
+            }
+            Ok(ConsoleCommand::Print(lines.join("\n")))
+        }
+
+        WalletCommand::ListUtxo {
+            utxo_type,
+            utxo_states,
+        } => {
+            let controller = controller_opt.as_mut().ok_or(WalletCliError::NoWallet)?;
+            let names = controller.get_address_names().map_err(WalletCliError::Controller)?;
+            let utxos = controller
+                .get_utxos(
+                    *selected_account,
+                    utxo_type.to_wallet_types(),
+                    utxo_states.to_wallet_states(),
+                )
+                .map_err(WalletCliError::Controller)?;
+            let lines = utxos
+                .iter()
+                .map(|(outpoint, utxo)| print_utxo(chain_config, outpoint, utxo, &names))
+                .collect::<Vec<_>>();
+            Ok(ConsoleCommand::Print(lines.join("\n")))
         }
 
-        WalletCommand::ListUtxo { utxo_type } => {
+        WalletCommand::ListLockedUtxos { utxo_states } => {
             let utxos = controller_opt
                 .as_mut()
                 .ok_or(WalletCliError::NoWallet)?
-                .get_utxos(utxo_type.to_wallet_types())
+                .get_locked_utxos(*selected_account, utxo_states.to_wallet_states())
+                .await
                 .map_err(WalletCliError::Controller)?;
-            Ok(ConsoleCommand::Print(format!("{utxos:?}")))
+            let lines = utxos
+                .iter()
+                .map(|(outpoint, (utxo, unlock_point))| {
+                    print_locked_utxo(chain_config, outpoint, utxo, unlock_point)
+                })
+                .collect::<Vec<_>>();
+            Ok(ConsoleCommand::Print(lines.join("\n")))
         }
 
-        WalletCommand::NewAddress => {
+        WalletCommand::FreezeUtxo { utxo } => {
+            let outpoint = parse_outpoint(&utxo)?;
+            controller_opt
+                .as_mut()
+                .ok_or(WalletCliError::NoWallet)?
+                .freeze_utxo(*selected_account, outpoint)
+                .map_err(WalletCliError::Controller)?;
+            Ok(ConsoleCommand::Print("Success".to_owned()))
+        }
+
+        WalletCommand::UnfreezeUtxo { utxo } => {
+            let outpoint = parse_outpoint(&utxo)?;
+            controller_opt
+                .as_mut()
+                .ok_or(WalletCliError::NoWallet)?
+                .unfreeze_utxo(*selected_account, outpoint)
+                .map_err(WalletCliError::Controller)?;
+            Ok(ConsoleCommand::Print("Success".to_owned()))
+        }
+
+        WalletCommand::ListFrozenUtxos => {
+            let controller = controller_opt.as_mut().ok_or(WalletCliError::NoWallet)?;
+            let names = controller.get_address_names().map_err(WalletCliError::Controller)?;
+            let utxos = controller
+                .get_frozen_utxos(*selected_account)
+                .map_err(WalletCliError::Controller)?;
+            let lines = utxos
+                .iter()
+                .map(|(outpoint, utxo)| print_utxo(chain_config, outpoint, utxo, &names))
+                .collect::<Vec<_>>();
+            Ok(ConsoleCommand::Print(lines.join("\n")))
+        }
+
+        WalletCommand::ListTransactions {
+            account,
+            min_height,
+            max_height,
+            skip,
+            limit,
+        } => {
+            let filter = TxHistoryFilter {
+                min_height,
+                max_height,
+                skip,
+                limit,
+            };
+            let controller = controller_opt.as_mut().ok_or(WalletCliError::NoWallet)?;
+            let names = controller.get_address_names().map_err(WalletCliError::Controller)?;
+            let history = controller
+                .get_transaction_history(account.unwrap_or(*selected_account), &filter)
+                .map_err(WalletCliError::Controller)?;
+            let mut lines = Vec::new();
+            for entry in history {
+                lines.push(print_history_entry(chain_config, rpc_client, &names, entry).await);
+            }
+            Ok(ConsoleCommand::Print(lines.join("\n")))
+        }
+
+        WalletCommand::NewAddress { qr_code, amount } => {
+            let amount =
+                amount.map(|amount| parse_coin_amount(chain_config, &amount)).transpose()?;
             let address = controller_opt
                 .as_mut()
                 .ok_or(WalletCliError::NoWallet)?
-                .new_address()
+                .new_address(*selected_account)
                 .map_err(WalletCliError::Controller)?;
-            Ok(ConsoleCommand::Print(address.get().to_owned()))
+            if !qr_code {
+                return Ok(ConsoleCommand::Print(address.get().to_owned()));
+            }
+
+            let qr_code = address
+                .payment_request_qrcode(chain_config, amount)
+                .map_err(|e| WalletCliError::InvalidInput(e.to_string()))?;
+            Ok(ConsoleCommand::Print(format!(
+                "{}\n{}",
+                address.payment_request(chain_config, amount),
+                qr_code.encode_to_console_string_with_defaults(1)
+            )))
         }
 
         WalletCommand::NewPublicKey => {
             let public_key = controller_opt
                 .as_mut()
                 .ok_or(WalletCliError::NoWallet)?
-                .new_public_key()
+                .new_public_key(*selected_account)
                 .map_err(WalletCliError::Controller)?;
             Ok(ConsoleCommand::Print(public_key.hex_encode()))
         }
 
+        WalletCommand::SetAddressLabel { address, label } => {
+            let address = parse_address(chain_config, &address)?;
+            controller_opt
+                .as_mut()
+                .ok_or(WalletCliError::NoWallet)?
+                .set_address_label(&address, label)
+                .map_err(WalletCliError::Controller)?;
+            Ok(ConsoleCommand::Print("Success".to_owned()))
+        }
+
+        WalletCommand::ListAddressLabels => {
+            let labels = controller_opt
+                .as_ref()
+                .ok_or(WalletCliError::NoWallet)?
+                .get_address_labels()
+                .map_err(WalletCliError::Controller)?;
+            let lines = labels
+                .iter()
+                .map(|(address, label)| format!("{}: {label}", address.get()))
+                .collect::<Vec<_>>();
+            Ok(ConsoleCommand::Print(lines.join("\n")))
+        }
+
+        WalletCommand::AddContact { name, address } => {
+            let address = parse_address(chain_config, &address)?;
+            controller_opt
+                .as_mut()
+                .ok_or(WalletCliError::NoWallet)?
+                .add_contact(name, &address)
+                .map_err(WalletCliError::Controller)?;
+            Ok(ConsoleCommand::Print("Success".to_owned()))
+        }
+
+        WalletCommand::RemoveContact { name } => {
+            controller_opt
+                .as_mut()
+                .ok_or(WalletCliError::NoWallet)?
+                .remove_contact(&name)
+                .map_err(WalletCliError::Controller)?;
+            Ok(ConsoleCommand::Print("Success".to_owned()))
+        }
+
+        WalletCommand::ListContacts => {
+            let contacts = controller_opt
+                .as_ref()
+                .ok_or(WalletCliError::NoWallet)?
+                .get_address_book()
+                .map_err(WalletCliError::Controller)?;
+            let lines = contacts
+                .iter()
+                .map(|(address, name)| format!("{name}: {}", address.get()))
+                .collect::<Vec<_>>();
+            Ok(ConsoleCommand::Print(lines.join("\n")))
+        }
+
         WalletCommand::GetVrfPublicKey => {
             let vrf_public_key = controller_opt
                 .as_mut()
                 .ok_or(WalletCliError::NoWallet)?
-                .get_vrf_public_key()
+                .get_vrf_public_key(*selected_account)
                 .map_err(WalletCliError::Controller)?;
             Ok(ConsoleCommand::Print(vrf_public_key.hex_encode()))
         }
 
-        WalletCommand::SendToAddress { address, amount } => {
+        WalletCommand::GetAccountPublicKey => {
+            let account_public_key = controller_opt
+                .as_ref()
+                .ok_or(WalletCliError::NoWallet)?
+                .get_account_public_key(*selected_account)
+                .map_err(WalletCliError::Controller)?;
+            Ok(ConsoleCommand::Print(account_public_key.hex_encode()))
+        }
+
+        WalletCommand::SendToAddress {
+            address,
+            amount,
+            fee_rate,
+        } => {
+            let amount = parse_coin_amount(chain_config, &amount)?;
+            let controller = controller_opt.as_mut().ok_or(WalletCliError::NoWallet)?;
+            let address = parse_send_address(chain_config, controller, &address)?;
+            let fee_rate = parse_fee_rate(chain_config, fee_rate)?;
+            controller
+                .send_to_address(*selected_account, address, amount, fee_rate)
+                .await
+                .map_err(WalletCliError::Controller)?;
+            Ok(ConsoleCommand::Print("Success".to_owned()))
+        }
+
+        WalletCommand::SendFromUtxos {
+            address,
+            amount,
+            utxos,
+            fee_rate,
+        } => {
+            let amount = parse_coin_amount(chain_config, &amount)?;
+            let inputs =
+                utxos.iter().map(|utxo| parse_outpoint(utxo)).collect::<Result<Vec<_>, _>>()?;
+            let controller = controller_opt.as_mut().ok_or(WalletCliError::NoWallet)?;
+            let address = parse_send_address(chain_config, controller, &address)?;
+            let fee_rate = parse_fee_rate(chain_config, fee_rate)?;
+            controller
+                .send_to_address_from_utxos(*selected_account, address, amount, inputs, fee_rate)
+                .await
+                .map_err(WalletCliError::Controller)?;
+            Ok(ConsoleCommand::Print("Success".to_owned()))
+        }
+
+        WalletCommand::SendLockedToAddress {
+            address,
+            amount,
+            lock_type,
+            lock_value,
+            fee_rate,
+        } => {
             let amount = parse_coin_amount(chain_config, &amount)?;
+            let controller = controller_opt.as_mut().ok_or(WalletCliError::NoWallet)?;
+            let address = parse_send_address(chain_config, controller, &address)?;
+            let lock = lock_type.to_time_lock(lock_value);
+            let fee_rate = parse_fee_rate(chain_config, fee_rate)?;
+            controller
+                .send_locked_to_address(*selected_account, address, amount, lock, fee_rate)
+                .await
+                .map_err(WalletCliError::Controller)?;
+            Ok(ConsoleCommand::Print("Success".to_owned()))
+        }
+
+        WalletCommand::BumpFee {
+            transaction_id,
+            fee_rate,
+        } => {
+            let fee_rate = FeeRate::new(parse_coin_amount(chain_config, &fee_rate)?);
+            let new_tx_id = controller_opt
+                .as_mut()
+                .ok_or(WalletCliError::NoWallet)?
+                .bump_transaction_fee(*selected_account, transaction_id.take(), fee_rate)
+                .await
+                .map_err(WalletCliError::Controller)?;
+            Ok(ConsoleCommand::Print(format!(
+                "The transaction is replaced by {}",
+                new_tx_id.get().hex_encode()
+            )))
+        }
+
+        WalletCommand::AbandonTransaction { transaction_id } => {
+            controller_opt
+                .as_mut()
+                .ok_or(WalletCliError::NoWallet)?
+                .abandon_transaction(*selected_account, transaction_id.take())
+                .map_err(WalletCliError::Controller)?;
+            Ok(ConsoleCommand::Print("Success".to_owned()))
+        }
+
+        WalletCommand::SendTokensToAddress {
+            token_id,
+            address,
+            amount,
+            fee_rate,
+        } => {
+            let token_id = token_id.take();
+            let decimals = get_token_number_of_decimals(rpc_client, token_id).await?;
+            let amount = parse_token_amount(decimals, &amount)?;
+            let controller = controller_opt.as_mut().ok_or(WalletCliError::NoWallet)?;
+            let address = parse_send_address(chain_config, controller, &address)?;
+            let fee_rate = parse_fee_rate(chain_config, fee_rate)?;
+            controller
+                .send_tokens_to_address(*selected_account, token_id, address, amount, fee_rate)
+                .await
+                .map_err(WalletCliError::Controller)?;
+            Ok(ConsoleCommand::Print("Success".to_owned()))
+        }
+
+        WalletCommand::IssueNewToken {
+            token_ticker,
+            amount_to_issue,
+            number_of_decimals,
+            metadata_uri,
+            destination_address,
+            fee_rate,
+        } => {
+            let amount_to_issue = parse_token_amount(number_of_decimals, &amount_to_issue)?;
+            let destination_address = parse_address(chain_config, &destination_address)?;
+            let fee_rate = parse_fee_rate(chain_config, fee_rate)?;
+            let token_issuance = TokenIssuance {
+                token_ticker: token_ticker.into_bytes(),
+                amount_to_issue,
+                number_of_decimals,
+                metadata_uri: metadata_uri.into_bytes(),
+            };
+            let token_id = controller_opt
+                .as_mut()
+                .ok_or(WalletCliError::NoWallet)?
+                .issue_new_token(
+                    *selected_account,
+                    destination_address,
+                    token_issuance,
+                    fee_rate,
+                )
+                .await
+                .map_err(WalletCliError::Controller)?;
+            Ok(ConsoleCommand::Print(format!(
+                "A new token has been issued with ID: {}",
+                token_id.hex_encode()
+            )))
+        }
+
+        WalletCommand::IssueNewNft {
+            destination_address,
+            media_hash,
+            name,
+            description,
+            ticker,
+            icon_uri,
+            additional_metadata_uri,
+            media_uri,
+            fee_rate,
+        } => {
+            let destination_address = parse_address(chain_config, &destination_address)?;
+            let media_hash = hex::decode(&media_hash)
+                .map_err(|e| WalletCliError::InvalidInput(format!("Invalid media hash: {e}")))?;
+            let fee_rate = parse_fee_rate(chain_config, fee_rate)?;
+            let metadata = Metadata {
+                creator: None,
+                name: name.into_bytes(),
+                description: description.into_bytes(),
+                ticker: ticker.into_bytes(),
+                icon_uri: icon_uri.map(String::into_bytes).into(),
+                additional_metadata_uri: additional_metadata_uri.map(String::into_bytes).into(),
+                media_uri: media_uri.map(String::into_bytes).into(),
+                media_hash,
+            };
+            let token_id = controller_opt
+                .as_mut()
+                .ok_or(WalletCliError::NoWallet)?
+                .issue_new_nft(*selected_account, destination_address, metadata, fee_rate)
+                .await
+                .map_err(WalletCliError::Controller)?;
+            Ok(ConsoleCommand::Print(format!(
+                "A new NFT has been issued with ID: {}",
+                token_id.hex_encode()
+            )))
+        }
+
+        WalletCommand::CreateStakePool { amount, fee_rate } => {
+            let amount = parse_coin_amount(chain_config, &amount)?;
+            let fee_rate = parse_fee_rate(chain_config, fee_rate)?;
+            controller_opt
+                .as_mut()
+                .ok_or(WalletCliError::NoWallet)?
+                .create_stake_pool_tx(*selected_account, amount, fee_rate)
+                .await
+                .map_err(WalletCliError::Controller)?;
+            Ok(ConsoleCommand::Print("Success".to_owned()))
+        }
+
+        WalletCommand::DecommissionStakePool { pool_id, fee_rate } => {
+            let fee_rate = parse_fee_rate(chain_config, fee_rate)?;
+            controller_opt
+                .as_mut()
+                .ok_or(WalletCliError::NoWallet)?
+                .decommission_stake_pool(*selected_account, pool_id.take(), fee_rate)
+                .await
+                .map_err(WalletCliError::Controller)?;
+            Ok(ConsoleCommand::Print("Success".to_owned()))
+        }
+
+        WalletCommand::ListPools => {
+            let pools = controller_opt
+                .as_mut()
+                .ok_or(WalletCliError::NoWallet)?
+                .list_pools(*selected_account)
+                .await
+                .map_err(WalletCliError::Controller)?;
+            let print_optional_amount = |amount: Option<Amount>| {
+                amount.map_or_else(
+                    || "Not found".to_owned(),
+                    |amount| print_coin_amount(chain_config, amount),
+                )
+            };
+            let lines = pools
+                .into_iter()
+                .map(|pool| {
+                    let mut line = format!(
+                        "Pool {}, pledge: {}, balance: {}",
+                        pool.pool_id.hex_encode(),
+                        print_optional_amount(pool.pledge),
+                        print_optional_amount(pool.balance),
+                    );
+                    for (delegation_id, share) in pool.delegations_shares {
+                        line.push_str(&format!(
+                            "\n  Delegation {}, share: {}",
+                            delegation_id.hex_encode(),
+                            print_coin_amount(chain_config, share)
+                        ));
+                    }
+                    line
+                })
+                .collect::<Vec<_>>();
+            Ok(ConsoleCommand::Print(lines.join("\n")))
+        }
+
+        WalletCommand::CreateDelegation {
+            pool_id,
+            address,
+            fee_rate,
+        } => {
             let address = parse_address(chain_config, &address)?;
+            let fee_rate = parse_fee_rate(chain_config, fee_rate)?;
+            let delegation_id = controller_opt
+                .as_mut()
+                .ok_or(WalletCliError::NoWallet)?
+                .create_delegation(*selected_account, address, pool_id.take(), fee_rate)
+                .await
+                .map_err(WalletCliError::Controller)?;
+            Ok(ConsoleCommand::Print(format!(
+                "A new delegation has been created with ID: {}",
+                delegation_id.hex_encode()
+            )))
+        }
+
+        WalletCommand::DelegateStaking {
+            delegation_id,
+            amount,
+            fee_rate,
+        } => {
+            let amount = parse_coin_amount(chain_config, &amount)?;
+            let fee_rate = parse_fee_rate(chain_config, fee_rate)?;
             controller_opt
                 .as_mut()
                 .ok_or(WalletCliError::NoWallet)?
-                .send_to_address(address, amount)
+                .delegate_staking(*selected_account, amount, delegation_id.take(), fee_rate)
                 .await
                 .map_err(WalletCliError::Controller)?;
             Ok(ConsoleCommand::Print("Success".to_owned()))
         }
 
-        WalletCommand::CreateStakePool { amount } => {
+        WalletCommand::WithdrawFromDelegation {
+            delegation_id,
+            address,
+            amount,
+            fee_rate,
+        } => {
+            let address = parse_address(chain_config, &address)?;
             let amount = parse_coin_amount(chain_config, &amount)?;
+            let fee_rate = parse_fee_rate(chain_config, fee_rate)?;
             controller_opt
                 .as_mut()
                 .ok_or(WalletCliError::NoWallet)?
-                .create_stake_pool_tx(amount)
+                .withdraw_from_delegation(
+                    *selected_account,
+                    address,
+                    amount,
+                    delegation_id.take(),
+                    fee_rate,
+                )
                 .await
                 .map_err(WalletCliError::Controller)?;
             Ok(ConsoleCommand::Print("Success".to_owned()))
         }
 
+        WalletCommand::ListDelegations => {
+            let delegations = controller_opt
+                .as_mut()
+                .ok_or(WalletCliError::NoWallet)?
+                .list_delegations(*selected_account)
+                .await
+                .map_err(WalletCliError::Controller)?;
+            let lines = delegations
+                .into_iter()
+                .map(|(delegation_id, pool_id, balance)| {
+                    let balance = balance.map_or_else(
+                        || "Not found".to_owned(),
+                        |balance| print_coin_amount(chain_config, balance),
+                    );
+                    format!(
+                        "Delegation {}, pool: {}, balance: {}",
+                        delegation_id.hex_encode(),
+                        pool_id.hex_encode(),
+                        balance
+                    )
+                })
+                .collect::<Vec<_>>();
+            Ok(ConsoleCommand::Print(lines.join("\n")))
+        }
+
         WalletCommand::NodeVersion => {
             let version = rpc_client.node_version().await.map_err(WalletCliError::RpcError)?;
             Ok(ConsoleCommand::Print(version))
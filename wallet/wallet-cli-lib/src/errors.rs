@@ -15,6 +15,8 @@
 
 use std::path::PathBuf;
 
+use crypto::key::hdkd::u31::U31;
+
 #[derive(thiserror::Error, Debug)]
 pub enum WalletCliError {
     #[error("Controller error: {0}")]
@@ -41,4 +43,12 @@ pub enum WalletCliError {
     WalletFileAlreadyOpen,
     #[error("Please open or create wallet file first")]
     NoWallet,
+    #[error("Account not found for index: {0}")]
+    AccountNotFound(U31),
+    #[error("Partially signed transaction error: {0}")]
+    PartiallySignedTransaction(wallet_controller::PartiallySignedTransactionError),
+    #[error("Message signature error: {0}")]
+    MessageSignature(
+        common::chain::signature::inputsig::arbitrary_message::SignArbitraryMessageError,
+    ),
 }
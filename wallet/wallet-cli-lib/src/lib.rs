@@ -31,6 +31,7 @@ use utils::{
     cookie::{load_cookie, COOKIE_FILENAME},
     default_data_dir::default_data_dir_for_chain,
 };
+use wallet_controller::DEFAULT_ACCOUNT_INDEX;
 
 enum Mode {
     Interactive {
@@ -103,12 +104,17 @@ pub async fn run(
             .map_err(WalletCliError::RpcError)?;
 
     let mut controller_opt = None;
+    let mut selected_account = DEFAULT_ACCOUNT_INDEX;
 
     if let Some(wallet_path) = wallet_file {
+        // Opening a wallet doesn't report any progress
+        let (progress_tx, _) = mpsc::unbounded_channel();
         commands::handle_wallet_command(
             &chain_config,
             &rpc_client,
             &mut controller_opt,
+            &mut selected_account,
+            &progress_tx,
             commands::WalletCommand::OpenWallet { wallet_path },
         )
         .await?;
@@ -137,7 +143,14 @@ pub async fn run(
         }
     });
 
-    cli_event_loop::run(&chain_config, &rpc_client, controller_opt, event_rx).await;
+    cli_event_loop::run(
+        &chain_config,
+        &rpc_client,
+        controller_opt,
+        selected_account,
+        event_rx,
+    )
+    .await;
 
     repl_handle.join().expect("Should not panic")
 }
@@ -102,6 +102,7 @@ fn create_line_editor(
 fn process_line(
     repl_command: &Command,
     event_tx: &mpsc::UnboundedSender<Event>,
+    console: &mut impl ConsoleOutput,
     sig: reedline::Signal,
 ) -> Result<Option<ConsoleCommand>, WalletCliError> {
     let line = match sig {
@@ -122,7 +123,7 @@ fn process_line(
         None => return Ok(None),
     };
 
-    super::run_command_blocking(event_tx, command).map(Option::Some)
+    super::run_command_blocking(event_tx, console, command).map(Option::Some)
 }
 
 pub fn run(
@@ -152,7 +153,7 @@ pub fn run(
         let sig = line_editor.read_line(&prompt).expect("Should not fail normally");
         logger.set_print_directly(true);
 
-        let res = process_line(&repl_command, &event_tx, sig);
+        let res = process_line(&repl_command, &event_tx, &mut console, sig);
 
         match res {
             Ok(Some(ConsoleCommand::Print(text))) => {
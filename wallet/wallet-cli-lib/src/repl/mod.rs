@@ -22,6 +22,7 @@ use tokio::sync::mpsc;
 use crate::{
     cli_event_loop::Event,
     commands::{ConsoleCommand, WalletCommand},
+    console::ConsoleOutput,
     errors::WalletCliError,
 };
 
@@ -80,11 +81,21 @@ fn parse_input(
 
 fn run_command_blocking(
     event_tx: &mpsc::UnboundedSender<Event>,
+    console: &mut impl ConsoleOutput,
     command: WalletCommand,
 ) -> Result<ConsoleCommand, WalletCliError> {
+    let (progress_tx, mut progress_rx) = mpsc::unbounded_channel();
     let (res_tx, res_rx) = tokio::sync::oneshot::channel();
     event_tx
-        .send(Event::HandleCommand { command, res_tx })
+        .send(Event::HandleCommand {
+            command,
+            progress_tx,
+            res_tx,
+        })
         .expect("Channel must be open");
+    // The progress channel is closed once the command is handled
+    while let Some(line) = progress_rx.blocking_recv() {
+        console.print_line(&line);
+    }
     res_rx.blocking_recv().expect("Channel must be open")
 }
@@ -35,6 +35,7 @@ enum LineOutput {
 fn process_line(
     repl_command: &Command,
     event_tx: &mpsc::UnboundedSender<Event>,
+    output: &mut impl ConsoleOutput,
     line: &str,
 ) -> Result<LineOutput, WalletCliError> {
     let command_opt = parse_input(line, repl_command)?;
@@ -44,7 +45,7 @@ fn process_line(
         None => return Ok(LineOutput::None),
     };
 
-    let command_output = super::run_command_blocking(event_tx, command)?;
+    let command_output = super::run_command_blocking(event_tx, output, command)?;
 
     match command_output {
         ConsoleCommand::Print(text) => Ok(LineOutput::Print(text)),
@@ -67,7 +68,7 @@ pub fn run(
     let repl_command = get_repl_command();
 
     while let Some(line) = input.read_line() {
-        let res = process_line(&repl_command, &event_tx, &line);
+        let res = process_line(&repl_command, &event_tx, &mut output, &line);
 
         match res {
             Ok(LineOutput::Print(text)) => {
@@ -23,27 +23,47 @@ const ERROR_DELAY: Duration = Duration::from_secs(10);
 
 use std::{
     collections::BTreeMap,
+    num::NonZeroU8,
     path::{Path, PathBuf},
     sync::Arc,
     time::Duration,
 };
 
+pub use common::primitives::FeeRate;
 use common::{
     address::Address,
-    chain::{tokens::TokenId, Block, ChainConfig, OutPoint, SignedTransaction, TxOutput},
-    primitives::{Amount, Idable},
+    chain::{
+        block::timestamp::BlockTimestamp,
+        classic_multisig::ClassicMultisigChallenge,
+        signature::inputsig::arbitrary_message::ArbitraryMessageSignature,
+        timelock::OutputTimeLock,
+        tokens::{token_id, Metadata, TokenId, TokenIssuance},
+        Block, ChainConfig, DelegationId, GenBlock, OutPoint, PoolId, SignedTransaction,
+        Transaction, TxOutput,
+    },
+    primitives::{Amount, BlockHeight, Id, Idable},
 };
 use consensus::GenerateBlockInputData;
-use crypto::{key::PublicKey, vrf::VRFPublicKey};
+use crypto::{
+    key::{extended::ExtendedPublicKey, hdkd::u31::U31, PublicKey},
+    vrf::VRFPublicKey,
+};
 use logging::log;
 pub use node_comm::node_traits::{ConnectedPeer, NodeInterface, PeerId};
 pub use node_comm::{
     handles_client::WalletHandlesClient, make_rpc_client, rpc_client::NodeRpcClient,
 };
-use wallet::{send_request::make_address_output, DefaultWallet};
+use serialization::hex::HexEncode;
+use wallet::{
+    backup::WalletBackup,
+    send_request::{make_address_output, make_address_output_token, make_locked_address_output},
+    DefaultWallet,
+};
 pub use wallet_types::{
     account_info::DEFAULT_ACCOUNT_INDEX,
-    utxo_types::{UtxoType, UtxoTypes},
+    partially_signed_transaction::{PartiallySignedTransaction, PartiallySignedTransactionError},
+    tx_history::{TxHistoryEntry, TxHistoryFilter},
+    utxo_types::{UtxoState, UtxoStates, UtxoType, UtxoTypes},
 };
 
 #[derive(thiserror::Error, Debug)]
@@ -58,6 +78,23 @@ pub enum ControllerError<T: NodeInterface> {
     WalletError(wallet::wallet::WalletError),
 }
 
+/// A stake pool of the wallet with its state in the chainstate.
+/// The pledge and the balance are unknown if the pool is not confirmed yet.
+#[derive(Debug, Clone)]
+pub struct PoolInfo {
+    pub pool_id: PoolId,
+    pub pledge: Option<Amount>,
+    pub balance: Option<Amount>,
+    pub delegations_shares: BTreeMap<DelegationId, Amount>,
+}
+
+/// Remove the file of a wallet whose creation has failed, the wallet must be closed already
+fn remove_wallet_file(file_path: &Path) {
+    if let Err(e) = std::fs::remove_file(file_path) {
+        log::error!("Removing wallet file {file_path:?} failed: {e}");
+    }
+}
+
 pub struct Controller<T: NodeInterface> {
     chain_config: Arc<ChainConfig>,
 
@@ -112,6 +149,254 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static> Controller<T> {
         Ok(wallet)
     }
 
+    /// Restore a wallet from its mnemonic, finding the accounts and addresses used before.
+    ///
+    /// The blocks of the node's chain are scanned from `birthday_height` (or from genesis) for each account,
+    /// and the next account is added as long as the last one has transactions.
+    /// `gap_limit` is the number of unused addresses after the last used one that are checked.
+    /// `on_progress` is called with the account index, the rescanned height and the target height.
+    /// The wallet file is removed if the recovery fails, so that it can be retried.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn recover_wallet(
+        chain_config: Arc<ChainConfig>,
+        rpc_client: T,
+        file_path: impl AsRef<Path>,
+        mnemonic: mnemonic::Mnemonic,
+        passphrase: Option<&str>,
+        gap_limit: Option<u32>,
+        birthday_height: Option<BlockHeight>,
+        mut on_progress: impl FnMut(U31, BlockHeight, BlockHeight),
+    ) -> Result<Self, ControllerError<T>> {
+        let file_path = file_path.as_ref();
+        utils::ensure!(
+            !file_path.exists(),
+            ControllerError::WalletFileError(
+                file_path.to_owned(),
+                "File already exists".to_owned()
+            )
+        );
+
+        let res: Result<Self, ControllerError<T>> = async {
+            let db = wallet::wallet::open_or_create_wallet_file(file_path)
+                .map_err(ControllerError::WalletError)?;
+            let wallet = wallet::Wallet::new_wallet(
+                Arc::clone(&chain_config),
+                db,
+                &mnemonic.to_string(),
+                passphrase,
+            )
+            .map_err(ControllerError::WalletError)?;
+
+            let mut controller = Self::new(chain_config, rpc_client, wallet);
+            controller
+                .recover_accounts(
+                    gap_limit,
+                    birthday_height.unwrap_or(BlockHeight::one()),
+                    &mut on_progress,
+                )
+                .await?;
+
+            Ok(controller)
+        }
+        .await;
+
+        if res.is_err() {
+            remove_wallet_file(file_path);
+        }
+        res
+    }
+
+    async fn recover_accounts(
+        &mut self,
+        gap_limit: Option<u32>,
+        start_height: BlockHeight,
+        on_progress: &mut impl FnMut(U31, BlockHeight, BlockHeight),
+    ) -> Result<(), ControllerError<T>> {
+        // All the accounts are scanned up to the same block, the regular sync continues from there
+        let chain_info = self
+            .rpc_client
+            .chainstate_info()
+            .await
+            .map_err(ControllerError::NodeCallError)?;
+        let target_block = (chain_info.best_block_id, chain_info.best_block_height);
+
+        let mut account_index = DEFAULT_ACCOUNT_INDEX;
+        loop {
+            match gap_limit {
+                Some(gap_limit) => {
+                    self.wallet.create_account_with_lookahead_size(account_index, gap_limit)
+                }
+                None => self.wallet.create_account(account_index),
+            }
+            .map_err(ControllerError::WalletError)?;
+
+            sync::rescan_account(
+                &self.chain_config,
+                &self.rpc_client,
+                &mut self.wallet,
+                account_index,
+                start_height,
+                target_block,
+                on_progress,
+            )
+            .await?;
+
+            let used = self
+                .wallet
+                .account_has_transactions(account_index)
+                .map_err(ControllerError::WalletError)?;
+            if !used {
+                // Keep the default account even if it is empty
+                if account_index != DEFAULT_ACCOUNT_INDEX {
+                    self.wallet
+                        .remove_account(account_index)
+                        .map_err(ControllerError::WalletError)?;
+                }
+                return Ok(());
+            }
+
+            log::info!("Recovered account {account_index}");
+            account_index = match account_index.plus_one() {
+                Ok(next_account_index) => next_account_index,
+                Err(_) => return Ok(()),
+            };
+        }
+    }
+
+    /// Restore a wallet from an encrypted backup made by `export_backup`
+    /// and rescan its accounts from `birthday_height`.
+    /// The progress is reported and a failed restore is cleaned up as in `recover_wallet`.
+    pub async fn restore_wallet_from_backup(
+        chain_config: Arc<ChainConfig>,
+        rpc_client: T,
+        file_path: impl AsRef<Path>,
+        backup: &[u8],
+        password: &String,
+        birthday_height: Option<BlockHeight>,
+        mut on_progress: impl FnMut(U31, BlockHeight, BlockHeight),
+    ) -> Result<Self, ControllerError<T>> {
+        let file_path = file_path.as_ref();
+        utils::ensure!(
+            !file_path.exists(),
+            ControllerError::WalletFileError(
+                file_path.to_owned(),
+                "File already exists".to_owned()
+            )
+        );
+
+        // Check the password before the wallet file is created
+        let backup =
+            WalletBackup::decrypt(backup, password).map_err(ControllerError::WalletError)?;
+
+        let res: Result<Self, ControllerError<T>> = async {
+            let db = wallet::wallet::open_or_create_wallet_file(file_path)
+                .map_err(ControllerError::WalletError)?;
+            let wallet =
+                wallet::Wallet::restore_from_backup(Arc::clone(&chain_config), db, &backup)
+                    .map_err(ControllerError::WalletError)?;
+
+            let mut controller = Self::new(chain_config, rpc_client, wallet);
+            controller
+                .rescan_restored_accounts(
+                    &backup,
+                    birthday_height.unwrap_or(BlockHeight::one()),
+                    &mut on_progress,
+                )
+                .await?;
+
+            Ok(controller)
+        }
+        .await;
+
+        if res.is_err() {
+            remove_wallet_file(file_path);
+        }
+        res
+    }
+
+    async fn rescan_restored_accounts(
+        &mut self,
+        backup: &WalletBackup,
+        start_height: BlockHeight,
+        on_progress: &mut impl FnMut(U31, BlockHeight, BlockHeight),
+    ) -> Result<(), ControllerError<T>> {
+        let chain_info = self
+            .rpc_client
+            .chainstate_info()
+            .await
+            .map_err(ControllerError::NodeCallError)?;
+        let target_block = (chain_info.best_block_id, chain_info.best_block_height);
+
+        for account_backup in &backup.accounts {
+            let account_index = account_backup.account_index;
+            sync::rescan_account(
+                &self.chain_config,
+                &self.rpc_client,
+                &mut self.wallet,
+                account_index,
+                start_height,
+                target_block,
+                on_progress,
+            )
+            .await?;
+
+            // The pools and the delegations are found by the rescan,
+            // the ones that are missing were created before the birthday height
+            let pool_ids =
+                self.wallet.get_pool_ids(account_index).map_err(ControllerError::WalletError)?;
+            for pool_id in account_backup.pool_ids.iter().filter(|id| !pool_ids.contains(id)) {
+                log::warn!(
+                    "Stake pool {} of account {account_index} is not found after the rescan",
+                    pool_id.hex_encode()
+                );
+            }
+            let delegations = self
+                .wallet
+                .get_delegations(account_index)
+                .map_err(ControllerError::WalletError)?;
+            for delegation_id in account_backup
+                .delegation_ids
+                .iter()
+                .filter(|id| !delegations.iter().any(|(delegation_id, _)| delegation_id == *id))
+            {
+                log::warn!(
+                    "Delegation {} of account {account_index} is not found after the rescan",
+                    delegation_id.hex_encode()
+                );
+            }
+
+            log::info!("Restored account {account_index}");
+        }
+
+        Ok(())
+    }
+
+    /// Create a wallet that watches the account of another wallet, given its extended public key
+    pub fn create_watch_only_wallet(
+        chain_config: Arc<ChainConfig>,
+        file_path: impl AsRef<Path>,
+        account_pubkey: ExtendedPublicKey,
+    ) -> Result<DefaultWallet, ControllerError<T>> {
+        utils::ensure!(
+            !file_path.as_ref().exists(),
+            ControllerError::WalletFileError(
+                file_path.as_ref().to_owned(),
+                "File already exists".to_owned()
+            )
+        );
+
+        let db = wallet::wallet::open_or_create_wallet_file(file_path)
+            .map_err(ControllerError::WalletError)?;
+        let mut wallet = wallet::Wallet::new_watch_only_wallet(Arc::clone(&chain_config), db)
+            .map_err(ControllerError::WalletError)?;
+
+        wallet
+            .create_watch_only_account(DEFAULT_ACCOUNT_INDEX, account_pubkey)
+            .map_err(ControllerError::WalletError)?;
+
+        Ok(wallet)
+    }
+
     pub fn open_wallet(
         chain_config: Arc<ChainConfig>,
         file_path: impl AsRef<Path>,
@@ -167,67 +452,605 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static> Controller<T> {
         self.wallet.lock_wallet().map_err(ControllerError::WalletError)
     }
 
-    pub fn get_balance(&self) -> Result<(Amount, BTreeMap<TokenId, Amount>), ControllerError<T>> {
+    /// Export the root key and the metadata of the wallet as a backup encrypted with `password`
+    pub fn export_backup(&self, password: &String) -> Result<Vec<u8>, ControllerError<T>> {
+        self.wallet.export_backup(password).map_err(ControllerError::WalletError)
+    }
+
+    pub fn account_indexes(&self) -> impl Iterator<Item = &U31> {
+        self.wallet.account_indexes()
+    }
+
+    /// Create the account after the last one, the last account must have transactions
+    pub fn create_account(&mut self, name: Option<String>) -> Result<U31, ControllerError<T>> {
+        self.wallet.create_next_account(name).map_err(ControllerError::WalletError)
+    }
+
+    pub fn account_names(&self) -> impl Iterator<Item = (&U31, &Option<String>)> {
+        self.wallet.account_names()
+    }
+
+    pub fn set_account_name(
+        &mut self,
+        account_index: U31,
+        name: Option<String>,
+    ) -> Result<(), ControllerError<T>> {
+        self.wallet
+            .set_account_name(account_index, name)
+            .map_err(ControllerError::WalletError)
+    }
+
+    pub fn get_balance(
+        &self,
+        account_index: U31,
+        utxo_states: UtxoStates,
+    ) -> Result<(Amount, BTreeMap<TokenId, Amount>), ControllerError<T>> {
         self.wallet
             .get_balance(
-                DEFAULT_ACCOUNT_INDEX,
+                account_index,
                 UtxoType::Transfer | UtxoType::LockThenTransfer,
+                utxo_states,
             )
             .map_err(ControllerError::WalletError)
     }
 
     pub fn get_utxos(
         &self,
+        account_index: U31,
         utxo_types: UtxoTypes,
+        utxo_states: UtxoStates,
+    ) -> Result<BTreeMap<OutPoint, TxOutput>, ControllerError<T>> {
+        self.wallet
+            .get_utxos(account_index, utxo_types, utxo_states)
+            .map_err(ControllerError::WalletError)
+    }
+
+    /// The time-locked UTXOs that can't be spent yet, with the block height or time
+    /// after which they can be spent
+    pub async fn get_locked_utxos(
+        &self,
+        account_index: U31,
+        utxo_states: UtxoStates,
+    ) -> Result<BTreeMap<OutPoint, (TxOutput, OutputTimeLock)>, ControllerError<T>> {
+        let median_time = self.get_median_time().await?;
+        self.wallet
+            .get_locked_utxos(account_index, utxo_states, median_time)
+            .map_err(ControllerError::WalletError)
+    }
+
+    pub fn freeze_utxo(
+        &mut self,
+        account_index: U31,
+        outpoint: OutPoint,
+    ) -> Result<(), ControllerError<T>> {
+        self.wallet
+            .freeze_utxo(account_index, outpoint)
+            .map_err(ControllerError::WalletError)
+    }
+
+    pub fn unfreeze_utxo(
+        &mut self,
+        account_index: U31,
+        outpoint: OutPoint,
+    ) -> Result<(), ControllerError<T>> {
+        self.wallet
+            .unfreeze_utxo(account_index, outpoint)
+            .map_err(ControllerError::WalletError)
+    }
+
+    pub fn get_frozen_utxos(
+        &self,
+        account_index: U31,
     ) -> Result<BTreeMap<OutPoint, TxOutput>, ControllerError<T>> {
         self.wallet
-            .get_utxos(DEFAULT_ACCOUNT_INDEX, utxo_types)
+            .get_frozen_utxos(account_index)
+            .map_err(ControllerError::WalletError)
+    }
+
+    pub fn get_transaction_history(
+        &self,
+        account_index: U31,
+        filter: &TxHistoryFilter,
+    ) -> Result<Vec<TxHistoryEntry>, ControllerError<T>> {
+        self.wallet
+            .get_transaction_history(account_index, filter)
+            .map_err(ControllerError::WalletError)
+    }
+
+    pub fn new_address(&mut self, account_index: U31) -> Result<Address, ControllerError<T>> {
+        self.wallet.get_new_address(account_index).map_err(ControllerError::WalletError)
+    }
+
+    /// Set the label of an address of the wallet, or remove it if `label` is `None`
+    pub fn set_address_label(
+        &mut self,
+        address: &Address,
+        label: Option<String>,
+    ) -> Result<(), ControllerError<T>> {
+        self.wallet
+            .set_address_label(address, label)
             .map_err(ControllerError::WalletError)
     }
 
-    pub fn new_address(&mut self) -> Result<Address, ControllerError<T>> {
+    pub fn get_address_labels(&self) -> Result<BTreeMap<Address, String>, ControllerError<T>> {
+        self.wallet.get_address_labels().map_err(ControllerError::WalletError)
+    }
+
+    /// Add an external address to the address book, or rename it
+    pub fn add_contact(
+        &mut self,
+        name: String,
+        address: &Address,
+    ) -> Result<(), ControllerError<T>> {
+        self.wallet.add_contact(name, address).map_err(ControllerError::WalletError)
+    }
+
+    pub fn remove_contact(&mut self, name: &str) -> Result<(), ControllerError<T>> {
+        self.wallet.remove_contact(name).map_err(ControllerError::WalletError)
+    }
+
+    pub fn get_address_book(&self) -> Result<BTreeMap<Address, String>, ControllerError<T>> {
+        self.wallet.get_address_book().map_err(ControllerError::WalletError)
+    }
+
+    pub fn find_contact(&self, name: &str) -> Result<Option<Address>, ControllerError<T>> {
+        self.wallet.find_contact(name).map_err(ControllerError::WalletError)
+    }
+
+    /// The labels of the wallet addresses together with the names of the address book contacts
+    pub fn get_address_names(&self) -> Result<BTreeMap<Address, String>, ControllerError<T>> {
+        let mut names = self.get_address_book()?;
+        names.extend(self.get_address_labels()?);
+        Ok(names)
+    }
+
+    pub fn new_public_key(&mut self, account_index: U31) -> Result<PublicKey, ControllerError<T>> {
         self.wallet
-            .get_new_address(DEFAULT_ACCOUNT_INDEX)
+            .get_new_public_key(account_index)
             .map_err(ControllerError::WalletError)
     }
 
-    pub fn new_public_key(&mut self) -> Result<PublicKey, ControllerError<T>> {
+    pub fn get_vrf_public_key(
+        &mut self,
+        account_index: U31,
+    ) -> Result<VRFPublicKey, ControllerError<T>> {
         self.wallet
-            .get_new_public_key(DEFAULT_ACCOUNT_INDEX)
+            .get_vrf_public_key(account_index)
             .map_err(ControllerError::WalletError)
     }
 
-    pub fn get_vrf_public_key(&mut self) -> Result<VRFPublicKey, ControllerError<T>> {
+    pub fn get_account_public_key(
+        &self,
+        account_index: U31,
+    ) -> Result<ExtendedPublicKey, ControllerError<T>> {
         self.wallet
-            .get_vrf_public_key(DEFAULT_ACCOUNT_INDEX)
+            .get_account_public_key(account_index)
             .map_err(ControllerError::WalletError)
     }
 
+    /// Use the fee rate given by the user or the current fee rate of the node
+    async fn get_fee_rate(&self, fee_rate: Option<FeeRate>) -> Result<FeeRate, ControllerError<T>> {
+        match fee_rate {
+            Some(fee_rate) => Ok(fee_rate),
+            None => self
+                .rpc_client
+                .mempool_get_fee_rate()
+                .await
+                .map_err(ControllerError::NodeCallError),
+        }
+    }
+
+    /// The median time past of the best block of the node, the time-locked outputs
+    /// are unlocked by it
+    async fn get_median_time(&self) -> Result<BlockTimestamp, ControllerError<T>> {
+        let chain_info = self
+            .rpc_client
+            .chainstate_info()
+            .await
+            .map_err(ControllerError::NodeCallError)?;
+        Ok(chain_info.median_time)
+    }
+
     pub async fn send_to_address(
         &mut self,
+        account_index: U31,
         address: Address,
         amount: Amount,
+        fee_rate: Option<FeeRate>,
     ) -> Result<(), ControllerError<T>> {
+        let fee_rate = self.get_fee_rate(fee_rate).await?;
+        let median_time = self.get_median_time().await?;
         let output = make_address_output(address, amount).map_err(ControllerError::WalletError)?;
         let tx = self
             .wallet
-            .create_transaction_to_addresses(DEFAULT_ACCOUNT_INDEX, [output])
+            .create_transaction_to_addresses(account_index, [output], median_time, fee_rate)
             .map_err(ControllerError::WalletError)?;
+        self.submit_transaction(tx).await
+    }
+
+    /// Send `amount` coins to `address` spending exactly the UTXOs of `inputs`,
+    /// the rest goes back to the account as change
+    pub async fn send_to_address_from_utxos(
+        &mut self,
+        account_index: U31,
+        address: Address,
+        amount: Amount,
+        inputs: Vec<OutPoint>,
+        fee_rate: Option<FeeRate>,
+    ) -> Result<(), ControllerError<T>> {
+        let fee_rate = self.get_fee_rate(fee_rate).await?;
+        let median_time = self.get_median_time().await?;
+        let output = make_address_output(address, amount).map_err(ControllerError::WalletError)?;
+        let tx = self
+            .wallet
+            .create_transaction_with_inputs(account_index, inputs, [output], median_time, fee_rate)
+            .map_err(ControllerError::WalletError)?;
+        self.submit_transaction(tx).await
+    }
+
+    /// Replace the unconfirmed transaction `tx_id` with one that spends the same inputs
+    /// and pays a fee at `fee_rate`, return the id of the new transaction
+    pub async fn bump_transaction_fee(
+        &mut self,
+        account_index: U31,
+        tx_id: Id<Transaction>,
+        fee_rate: FeeRate,
+    ) -> Result<Id<Transaction>, ControllerError<T>> {
+        let relay_fee_rate = self.get_fee_rate(None).await?;
+        let tx = self
+            .wallet
+            .create_fee_bump_transaction(account_index, tx_id, fee_rate, relay_fee_rate)
+            .map_err(ControllerError::WalletError)?;
+        let new_tx_id = tx.transaction().get_id();
+        self.submit_transaction(tx).await?;
+        Ok(new_tx_id)
+    }
+
+    /// Abandon the unconfirmed transaction `tx_id` that is no longer in the mempool,
+    /// so that the outputs it spends can be used again
+    pub fn abandon_transaction(
+        &mut self,
+        account_index: U31,
+        tx_id: Id<Transaction>,
+    ) -> Result<(), ControllerError<T>> {
+        self.wallet
+            .abandon_transaction(account_index, tx_id)
+            .map_err(ControllerError::WalletError)
+    }
+
+    /// Send `amount` coins to `address`, they can't be spent until `lock` expires
+    pub async fn send_locked_to_address(
+        &mut self,
+        account_index: U31,
+        address: Address,
+        amount: Amount,
+        lock: OutputTimeLock,
+        fee_rate: Option<FeeRate>,
+    ) -> Result<(), ControllerError<T>> {
+        let fee_rate = self.get_fee_rate(fee_rate).await?;
+        let median_time = self.get_median_time().await?;
+        let output = make_locked_address_output(address, amount, lock)
+            .map_err(ControllerError::WalletError)?;
+        let tx = self
+            .wallet
+            .create_transaction_to_addresses(account_index, [output], median_time, fee_rate)
+            .map_err(ControllerError::WalletError)?;
+        self.submit_transaction(tx).await
+    }
+
+    /// Create a transaction sending `amount` coins to `address` without signing it,
+    /// so that it can be signed by another wallet
+    pub async fn create_partially_signed_transaction(
+        &mut self,
+        account_index: U31,
+        address: Address,
+        amount: Amount,
+        fee_rate: Option<FeeRate>,
+    ) -> Result<PartiallySignedTransaction, ControllerError<T>> {
+        let fee_rate = self.get_fee_rate(fee_rate).await?;
+        let median_time = self.get_median_time().await?;
+        let output = make_address_output(address, amount).map_err(ControllerError::WalletError)?;
+        self.wallet
+            .create_partially_signed_transaction(account_index, [output], median_time, fee_rate)
+            .map_err(ControllerError::WalletError)
+    }
+
+    /// Add the signatures the wallet can make to a partially signed transaction
+    pub fn sign_partially_signed_transaction(
+        &self,
+        account_index: U31,
+        ptx: PartiallySignedTransaction,
+    ) -> Result<PartiallySignedTransaction, ControllerError<T>> {
+        self.wallet
+            .sign_partially_signed_transaction(account_index, ptx)
+            .map_err(ControllerError::WalletError)
+    }
+
+    /// Sign `message` with the key of `address` to prove its ownership
+    pub fn sign_message(
+        &self,
+        account_index: U31,
+        address: &Address,
+        message: &[u8],
+    ) -> Result<ArbitraryMessageSignature, ControllerError<T>> {
+        self.wallet
+            .sign_message(account_index, address, message)
+            .map_err(ControllerError::WalletError)
+    }
+
+    /// Create a classic multisig address and watch its funds in the account
+    pub fn add_classic_multisig(
+        &mut self,
+        account_index: U31,
+        min_required_signatures: NonZeroU8,
+        public_keys: Vec<PublicKey>,
+    ) -> Result<Address, ControllerError<T>> {
+        self.wallet
+            .add_classic_multisig(account_index, min_required_signatures, public_keys)
+            .map_err(ControllerError::WalletError)
+    }
+
+    pub fn get_classic_multisigs(
+        &self,
+        account_index: U31,
+    ) -> Result<Vec<(Address, ClassicMultisigChallenge, Amount)>, ControllerError<T>> {
+        self.wallet
+            .get_classic_multisigs(account_index)
+            .map_err(ControllerError::WalletError)
+    }
+
+    /// Create a transaction sending `amount` coins from a watched classic multisig to `address`,
+    /// it has to be signed by the owners of the multisig keys
+    pub async fn create_classic_multisig_spend(
+        &mut self,
+        account_index: U31,
+        multisig_address: Address,
+        address: Address,
+        amount: Amount,
+        fee_rate: Option<FeeRate>,
+    ) -> Result<PartiallySignedTransaction, ControllerError<T>> {
+        let fee_rate = self.get_fee_rate(fee_rate).await?;
+        let median_time = self.get_median_time().await?;
+        let output = make_address_output(address, amount).map_err(ControllerError::WalletError)?;
+        self.wallet
+            .create_classic_multisig_spend(
+                account_index,
+                &multisig_address,
+                [output],
+                median_time,
+                fee_rate,
+            )
+            .map_err(ControllerError::WalletError)
+    }
+
+    pub async fn send_tokens_to_address(
+        &mut self,
+        account_index: U31,
+        token_id: TokenId,
+        address: Address,
+        amount: Amount,
+        fee_rate: Option<FeeRate>,
+    ) -> Result<(), ControllerError<T>> {
+        let fee_rate = self.get_fee_rate(fee_rate).await?;
+        let median_time = self.get_median_time().await?;
+        let output = make_address_output_token(address, token_id, amount)
+            .map_err(ControllerError::WalletError)?;
+        let tx = self
+            .wallet
+            .create_transaction_to_addresses(account_index, [output], median_time, fee_rate)
+            .map_err(ControllerError::WalletError)?;
+        self.submit_transaction(tx).await
+    }
+
+    /// Issue new fungible tokens and return their id
+    pub async fn issue_new_token(
+        &mut self,
+        account_index: U31,
+        address: Address,
+        token_issuance: TokenIssuance,
+        fee_rate: Option<FeeRate>,
+    ) -> Result<TokenId, ControllerError<T>> {
+        let fee_rate = self.get_fee_rate(fee_rate).await?;
+        let median_time = self.get_median_time().await?;
+        let tx = self
+            .wallet
+            .issue_new_token(
+                account_index,
+                address,
+                token_issuance,
+                median_time,
+                fee_rate,
+            )
+            .map_err(ControllerError::WalletError)?;
+        self.submit_issuance_transaction(tx).await
+    }
+
+    /// Issue a new NFT and return its id
+    pub async fn issue_new_nft(
+        &mut self,
+        account_index: U31,
+        address: Address,
+        metadata: Metadata,
+        fee_rate: Option<FeeRate>,
+    ) -> Result<TokenId, ControllerError<T>> {
+        let fee_rate = self.get_fee_rate(fee_rate).await?;
+        let median_time = self.get_median_time().await?;
+        let tx = self
+            .wallet
+            .issue_new_nft(account_index, address, metadata, median_time, fee_rate)
+            .map_err(ControllerError::WalletError)?;
+        self.submit_issuance_transaction(tx).await
+    }
+
+    async fn submit_issuance_transaction(
+        &mut self,
+        tx: SignedTransaction,
+    ) -> Result<TokenId, ControllerError<T>> {
+        let token_id =
+            token_id(tx.transaction()).expect("the wallet adds inputs to the transaction");
+        self.submit_transaction(tx).await?;
+        Ok(token_id)
+    }
+
+    /// Submit a transaction created by the wallet and keep track of it as unconfirmed,
+    /// so that its change can be spent right away
+    async fn submit_transaction(
+        &mut self,
+        tx: SignedTransaction,
+    ) -> Result<(), ControllerError<T>> {
         self.rpc_client
-            .submit_transaction(tx)
+            .submit_transaction(tx.clone())
             .await
-            .map_err(ControllerError::NodeCallError)
+            .map_err(ControllerError::NodeCallError)?;
+        self.wallet.add_unconfirmed_tx(&tx).map_err(ControllerError::WalletError)
     }
 
-    pub async fn create_stake_pool_tx(&mut self, amount: Amount) -> Result<(), ControllerError<T>> {
+    pub async fn create_stake_pool_tx(
+        &mut self,
+        account_index: U31,
+        amount: Amount,
+        fee_rate: Option<FeeRate>,
+    ) -> Result<(), ControllerError<T>> {
+        let fee_rate = self.get_fee_rate(fee_rate).await?;
+        let median_time = self.get_median_time().await?;
         let tx = self
             .wallet
-            .create_stake_pool_tx(DEFAULT_ACCOUNT_INDEX, amount)
+            .create_stake_pool_tx(account_index, amount, median_time, fee_rate)
             .map_err(ControllerError::WalletError)?;
-        self.rpc_client
-            .submit_transaction(tx)
+        self.submit_transaction(tx).await
+    }
+
+    /// Decommission a stake pool of the wallet, the pledge is returned to the wallet
+    /// minus the fee and locked for the decommission maturity period
+    pub async fn decommission_stake_pool(
+        &mut self,
+        account_index: U31,
+        pool_id: PoolId,
+        fee_rate: Option<FeeRate>,
+    ) -> Result<(), ControllerError<T>> {
+        let fee_rate = self.get_fee_rate(fee_rate).await?;
+        let pool_pledge = self
+            .rpc_client
+            .get_stake_pool_pledge(pool_id)
             .await
-            .map_err(ControllerError::NodeCallError)
+            .map_err(ControllerError::NodeCallError)?
+            .ok_or(ControllerError::WalletError(
+                wallet::wallet::WalletError::UnknownPoolId(pool_id),
+            ))?;
+        let tx = self
+            .wallet
+            .decommission_stake_pool(account_index, pool_id, pool_pledge, fee_rate)
+            .map_err(ControllerError::WalletError)?;
+        self.submit_transaction(tx).await
+    }
+
+    /// List the stake pools of the wallet with their pledge, balance and delegation shares
+    pub async fn list_pools(
+        &self,
+        account_index: U31,
+    ) -> Result<Vec<PoolInfo>, ControllerError<T>> {
+        let pool_ids =
+            self.wallet.get_pool_ids(account_index).map_err(ControllerError::WalletError)?;
+        let mut result = Vec::with_capacity(pool_ids.len());
+        for pool_id in pool_ids {
+            let pledge = self
+                .rpc_client
+                .get_stake_pool_pledge(pool_id)
+                .await
+                .map_err(ControllerError::NodeCallError)?;
+            let balance = self
+                .rpc_client
+                .get_stake_pool_balance(pool_id)
+                .await
+                .map_err(ControllerError::NodeCallError)?;
+            let delegations_shares = self
+                .rpc_client
+                .get_stake_pool_delegations_shares(pool_id)
+                .await
+                .map_err(ControllerError::NodeCallError)?
+                .unwrap_or_default();
+            result.push(PoolInfo {
+                pool_id,
+                pledge,
+                balance,
+                delegations_shares,
+            });
+        }
+        Ok(result)
+    }
+
+    /// Create a new delegation to `pool_id` and return its id
+    pub async fn create_delegation(
+        &mut self,
+        account_index: U31,
+        address: Address,
+        pool_id: PoolId,
+        fee_rate: Option<FeeRate>,
+    ) -> Result<DelegationId, ControllerError<T>> {
+        let fee_rate = self.get_fee_rate(fee_rate).await?;
+        let median_time = self.get_median_time().await?;
+        let tx = self
+            .wallet
+            .create_delegation(account_index, address, pool_id, median_time, fee_rate)
+            .map_err(ControllerError::WalletError)?;
+        let input0 = tx.transaction().inputs().get(0).expect("the wallet adds inputs");
+        let delegation_id = pos_accounting::make_delegation_id(input0.outpoint());
+        self.submit_transaction(tx).await?;
+        Ok(delegation_id)
+    }
+
+    pub async fn delegate_staking(
+        &mut self,
+        account_index: U31,
+        amount: Amount,
+        delegation_id: DelegationId,
+        fee_rate: Option<FeeRate>,
+    ) -> Result<(), ControllerError<T>> {
+        let fee_rate = self.get_fee_rate(fee_rate).await?;
+        let median_time = self.get_median_time().await?;
+        let tx = self
+            .wallet
+            .delegate_staking(account_index, amount, delegation_id, median_time, fee_rate)
+            .map_err(ControllerError::WalletError)?;
+        self.submit_transaction(tx).await
+    }
+
+    pub async fn withdraw_from_delegation(
+        &mut self,
+        account_index: U31,
+        address: Address,
+        amount: Amount,
+        delegation_id: DelegationId,
+        fee_rate: Option<FeeRate>,
+    ) -> Result<(), ControllerError<T>> {
+        let fee_rate = self.get_fee_rate(fee_rate).await?;
+        let tx = self
+            .wallet
+            .withdraw_from_delegation(account_index, address, amount, delegation_id, fee_rate)
+            .map_err(ControllerError::WalletError)?;
+        self.submit_transaction(tx).await
+    }
+
+    /// List the delegations of the wallet with the pools they delegate to and their current
+    /// balances, the balance is unknown for the delegations that are not confirmed yet
+    pub async fn list_delegations(
+        &self,
+        account_index: U31,
+    ) -> Result<Vec<(DelegationId, PoolId, Option<Amount>)>, ControllerError<T>> {
+        let delegations = self
+            .wallet
+            .get_delegations(account_index)
+            .map_err(ControllerError::WalletError)?;
+        let mut result = Vec::with_capacity(delegations.len());
+        for (delegation_id, pool_id) in delegations {
+            let balance = self
+                .rpc_client
+                .get_stake_delegation_balance(delegation_id)
+                .await
+                .map_err(ControllerError::NodeCallError)?;
+            result.push((delegation_id, pool_id, balance));
+        }
+        Ok(result)
     }
 
     pub async fn generate_block(
@@ -261,6 +1084,14 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static> Controller<T> {
         self.sync_once().await
     }
 
+    pub fn best_block(&self) -> Result<(Id<GenBlock>, BlockHeight), ControllerError<T>> {
+        self.wallet.get_best_block().map_err(ControllerError::WalletError)
+    }
+
+    pub fn is_staking(&self) -> bool {
+        self.staking_started
+    }
+
     pub fn start_staking(&mut self) -> Result<(), ControllerError<T>> {
         self.staking_started = true;
         Ok(())
@@ -271,9 +1102,35 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static> Controller<T> {
         Ok(())
     }
 
-    /// Synchronize the wallet to the current node tip height and return
+    /// Synchronize the wallet to the current node tip height and mempool and return
     pub async fn sync_once(&mut self) -> Result<(), ControllerError<T>> {
         sync::sync_once(&self.chain_config, &self.rpc_client, &mut self.wallet).await?;
+        self.scan_mempool().await
+    }
+
+    /// Scan the next block of the node's best chain, or the mempool transactions
+    /// if the wallet is already at the node's best block.
+    /// Returns `true` once the wallet is synced.
+    async fn sync_step(&mut self) -> Result<bool, ControllerError<T>> {
+        if !sync::sync_step(&self.chain_config, &self.rpc_client, &mut self.wallet).await? {
+            return Ok(false);
+        }
+        self.scan_mempool().await?;
+        Ok(true)
+    }
+
+    async fn scan_mempool(&mut self) -> Result<(), ControllerError<T>> {
+        // The mempool is only a hint about the pending transactions,
+        // the block sync and the staking go on without it
+        match self.rpc_client.mempool_get_transactions().await {
+            Ok(mempool_txs) => {
+                self.wallet.scan_mempool(&mempool_txs).map_err(ControllerError::WalletError)?;
+            }
+            Err(e) => {
+                log::warn!("Mempool scan skipped, getting the mempool transactions failed: {e}")
+            }
+        }
+
         Ok(())
     }
 
@@ -281,34 +1138,45 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static> Controller<T> {
     /// Try staking new blocks if staking was started.
     pub async fn run(&mut self) {
         loop {
-            let sync_res = self.sync_once().await;
+            let delay = self.run_once().await;
+            tokio::time::sleep(delay).await;
+        }
+    }
 
-            if let Err(e) = sync_res {
+    /// Scan the next block and try staking a new block once the wallet is synced
+    /// if staking was started. Returns how long to wait before the next call.
+    ///
+    /// Only a single block is scanned per call, so that the callers sharing the controller
+    /// can release it between the calls while a long sync is in progress.
+    pub async fn run_once(&mut self) -> Duration {
+        match self.sync_step().await {
+            Ok(true) => {}
+            Ok(false) => return Duration::ZERO,
+            Err(e) => {
                 log::error!("Wallet sync error: {e}");
-                tokio::time::sleep(ERROR_DELAY).await;
-                continue;
+                return ERROR_DELAY;
             }
+        }
 
-            if self.staking_started {
-                let generate_res = self.generate_block(None).await;
-
-                if let Ok(block) = generate_res {
-                    log::info!(
-                        "New block generated successfully, block id: {}",
-                        block.get_id()
-                    );
+        if self.staking_started {
+            let generate_res = self.generate_block(None).await;
 
-                    let submit_res = self.rpc_client.submit_block(block).await;
-                    if let Err(e) = submit_res {
-                        log::error!("Block submit failed: {e}");
-                        tokio::time::sleep(ERROR_DELAY).await;
-                    }
+            if let Ok(block) = generate_res {
+                log::info!(
+                    "New block generated successfully, block id: {}",
+                    block.get_id()
+                );
 
-                    continue;
+                let submit_res = self.rpc_client.submit_block(block).await;
+                if let Err(e) = submit_res {
+                    log::error!("Block submit failed: {e}");
+                    return ERROR_DELAY;
                 }
-            }
 
-            tokio::time::sleep(NORMAL_DELAY).await;
+                return Duration::ZERO;
+            }
         }
+
+        NORMAL_DELAY
     }
 }
@@ -17,6 +17,7 @@ use common::{
     chain::{Block, ChainConfig, GenBlock},
     primitives::{BlockHeight, Id},
 };
+use crypto::key::hdkd::u31::U31;
 use logging::log;
 use node_comm::node_traits::NodeInterface;
 use serialization::hex::HexEncode;
@@ -71,49 +72,170 @@ enum FetchBlockError<T: NodeInterface> {
     BlockNotFound(Id<Block>),
     #[error("Invalid prev block id: {0}, expected: {1}")]
     InvalidPrevBlockId(Id<GenBlock>, Id<GenBlock>),
+    #[error("The node's best chain no longer contains block {0}")]
+    BlockNoLongerInBestChain(Id<GenBlock>),
 }
 
+/// How often the rescan progress is reported
+const RESCAN_PROGRESS_INTERVAL: u64 = 1000;
+
 pub async fn sync_once<T: NodeInterface>(
     chain_config: &ChainConfig,
     rpc_client: &T,
     wallet: &mut impl SyncingWallet,
 ) -> Result<(), ControllerError<T>> {
-    loop {
-        let chain_info =
-            rpc_client.chainstate_info().await.map_err(ControllerError::NodeCallError)?;
+    while !sync_step(chain_config, rpc_client, wallet).await? {}
+    Ok(())
+}
+
+/// Scan the next block of the node's best chain.
+///
+/// Returns `true` without scanning anything if the wallet is already at the node's best block.
+pub async fn sync_step<T: NodeInterface>(
+    chain_config: &ChainConfig,
+    rpc_client: &T,
+    wallet: &mut impl SyncingWallet,
+) -> Result<bool, ControllerError<T>> {
+    let chain_info = rpc_client.chainstate_info().await.map_err(ControllerError::NodeCallError)?;
 
-        let (wallet_block_id, wallet_block_height) =
-            wallet.best_block().map_err(ControllerError::WalletError)?;
+    let (wallet_block_id, wallet_block_height) =
+        wallet.best_block().map_err(ControllerError::WalletError)?;
 
-        if chain_info.best_block_id == wallet_block_id {
-            return Ok(());
-        }
+    if chain_info.best_block_id == wallet_block_id {
+        return Ok(true);
+    }
+
+    let FetchedBlock {
+        block,
+        common_block_height,
+    } = fetch_new_block(
+        chain_config,
+        rpc_client,
+        chain_info.best_block_id,
+        chain_info.best_block_height,
+        wallet_block_id,
+        wallet_block_height,
+    )
+    .await
+    .map_err(|e| ControllerError::SyncError(e.to_string()))?;
+
+    let block_id = block.header().block_id();
+    wallet
+        .scan_blocks(common_block_height, vec![block])
+        .map_err(ControllerError::WalletError)?;
+
+    log::info!(
+        "Node chainstate updated, block height: {}, top block id: {}",
+        common_block_height,
+        block_id.hex_encode()
+    );
+
+    Ok(false)
+}
+
+/// Scan the blocks of the node's best chain from `start_height` up to `target_block` for a single account.
+///
+/// The blocks below `start_height` are skipped, they must not contain anything relevant to the account.
+/// Fails if the best chain of the node no longer contains `target_block`,
+/// so that all the rescanned accounts end up on the same chain.
+/// `on_progress` is called with the account index, the rescanned height and the target height.
+pub async fn rescan_account<T: NodeInterface>(
+    chain_config: &ChainConfig,
+    rpc_client: &T,
+    wallet: &mut DefaultWallet,
+    account_index: U31,
+    start_height: BlockHeight,
+    target_block: (Id<GenBlock>, BlockHeight),
+    on_progress: &mut impl FnMut(U31, BlockHeight, BlockHeight),
+) -> Result<(), ControllerError<T>> {
+    let (target_block_id, target_block_height) = target_block;
+
+    let mut common_block_height = start_height.prev_height().unwrap_or(BlockHeight::zero());
+    let mut common_block_id = chain_config.genesis_block_id();
+    if common_block_height > target_block_height {
+        return Err(ControllerError::SyncError(format!(
+            "Start height {start_height} is above the best block height {target_block_height}"
+        )));
+    }
+    if common_block_height > BlockHeight::zero() {
+        common_block_id = rpc_client
+            .get_block_id_at_height(common_block_height)
+            .await
+            .map_err(ControllerError::NodeCallError)?
+            .ok_or_else(|| {
+                ControllerError::SyncError(
+                    FetchBlockError::<T>::NoBlockAtHeight(common_block_height).to_string(),
+                )
+            })?;
+        wallet
+            .set_account_best_block(account_index, common_block_height, common_block_id)
+            .map_err(ControllerError::WalletError)?;
+    }
 
-        let FetchedBlock {
-            block,
-            common_block_height,
-        } = fetch_new_block(
+    while common_block_height < target_block_height {
+        let block = fetch_block_at_height(
             chain_config,
             rpc_client,
-            chain_info.best_block_id,
-            chain_info.best_block_height,
-            wallet_block_id,
-            wallet_block_height,
+            common_block_height.next_height(),
+            common_block_id,
         )
         .await
         .map_err(|e| ControllerError::SyncError(e.to_string()))?;
 
-        let block_id = block.header().block_id();
+        common_block_id = block.header().block_id().into();
         wallet
-            .scan_blocks(common_block_height, vec![block])
+            .scan_new_blocks_for_account(account_index, common_block_height, vec![block])
             .map_err(ControllerError::WalletError)?;
+        common_block_height = common_block_height.next_height();
 
-        log::info!(
-            "Node chainstate updated, block height: {}, top block id: {}",
-            common_block_height,
-            block_id.hex_encode()
-        );
+        if common_block_height.into_int() % RESCAN_PROGRESS_INTERVAL == 0
+            || common_block_height == target_block_height
+        {
+            on_progress(account_index, common_block_height, target_block_height);
+        }
     }
+
+    utils::ensure!(
+        common_block_id == target_block_id,
+        ControllerError::SyncError(
+            FetchBlockError::<T>::BlockNoLongerInBestChain(target_block_id).to_string()
+        )
+    );
+
+    Ok(())
+}
+
+/// Fetch the block of the node's best chain at the given height, checking that it follows `prev_block_id`
+async fn fetch_block_at_height<T: NodeInterface>(
+    chain_config: &ChainConfig,
+    rpc_client: &T,
+    block_height: BlockHeight,
+    prev_block_id: Id<GenBlock>,
+) -> Result<Block, FetchBlockError<T>> {
+    let gen_block_id = rpc_client
+        .get_block_id_at_height(block_height)
+        .await
+        .map_err(FetchBlockError::UnexpectedRpcError)?
+        .ok_or(FetchBlockError::NoBlockAtHeight(block_height))?;
+
+    let block_id = match gen_block_id.classify(chain_config) {
+        common::chain::GenBlockId::Genesis(_) => {
+            return Err(FetchBlockError::UnexpectedGenesisBlock(block_height))
+        }
+        common::chain::GenBlockId::Block(id) => id,
+    };
+
+    let block = rpc_client
+        .get_block(block_id)
+        .await
+        .map_err(FetchBlockError::UnexpectedRpcError)?
+        .ok_or(FetchBlockError::BlockNotFound(block_id))?;
+    utils::ensure!(
+        *block.header().prev_block_id() == prev_block_id,
+        FetchBlockError::InvalidPrevBlockId(*block.header().prev_block_id(), prev_block_id)
+    );
+
+    Ok(block)
 }
 
 // TODO: For security reasons, the wallet should probably keep track of latest blocks
@@ -14,6 +14,7 @@
 // limitations under the License.
 
 use std::{
+    collections::BTreeMap,
     sync::{Arc, Mutex},
     time::Duration,
 };
@@ -21,8 +22,11 @@ use std::{
 use chainstate::ChainInfo;
 use chainstate_test_framework::TestFramework;
 use common::{
-    chain::{PoolId, SignedTransaction},
-    primitives::Amount,
+    chain::{
+        tokens::{RPCTokenInfo, TokenId},
+        DelegationId, PoolId, SignedTransaction,
+    },
+    primitives::{Amount, FeeRate},
 };
 use consensus::GenerateBlockInputData;
 use crypto::random::{seq::IteratorRandom, CryptoRng, Rng};
@@ -149,6 +153,31 @@ impl NodeInterface for MockNode {
         unreachable!()
     }
 
+    async fn get_stake_pool_pledge(&self, _pool_id: PoolId) -> Result<Option<Amount>, Self::Error> {
+        unreachable!()
+    }
+
+    async fn get_stake_pool_delegations_shares(
+        &self,
+        _pool_id: PoolId,
+    ) -> Result<Option<BTreeMap<DelegationId, Amount>>, Self::Error> {
+        unreachable!()
+    }
+
+    async fn get_stake_delegation_balance(
+        &self,
+        _delegation_id: DelegationId,
+    ) -> Result<Option<Amount>, Self::Error> {
+        unreachable!()
+    }
+
+    async fn get_token_info(
+        &self,
+        _token_id: TokenId,
+    ) -> Result<Option<RPCTokenInfo>, Self::Error> {
+        unreachable!()
+    }
+
     async fn generate_block(
         &self,
         _input_data: GenerateBlockInputData,
@@ -163,6 +192,14 @@ impl NodeInterface for MockNode {
         unreachable!()
     }
 
+    async fn mempool_get_transactions(&self) -> Result<Vec<SignedTransaction>, Self::Error> {
+        unreachable!()
+    }
+
+    async fn mempool_get_fee_rate(&self) -> Result<FeeRate, Self::Error> {
+        unreachable!()
+    }
+
     async fn node_shutdown(&self) -> Result<(), Self::Error> {
         unreachable!()
     }
@@ -13,11 +13,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
+
 use blockprod::{BlockProductionError, BlockProductionHandle};
 use chainstate::{BlockSource, ChainInfo, ChainstateError, ChainstateHandle};
 use common::{
-    chain::{Block, GenBlock, PoolId, SignedTransaction},
-    primitives::{Amount, BlockHeight, Id},
+    chain::{
+        tokens::{RPCTokenInfo, TokenId},
+        Block, DelegationId, GenBlock, PoolId, SignedTransaction,
+    },
+    primitives::{Amount, BlockHeight, FeeRate, Id},
 };
 use consensus::GenerateBlockInputData;
 use mempool::MempoolHandle;
@@ -29,7 +34,7 @@ use crate::node_traits::NodeInterface;
 #[derive(Clone)]
 pub struct WalletHandlesClient {
     chainstate: ChainstateHandle,
-    _mempool: MempoolHandle,
+    mempool: MempoolHandle,
     block_prod: BlockProductionHandle,
     p2p: P2pHandle,
 }
@@ -40,6 +45,8 @@ pub enum WalletHandlesClientError {
     CallError(#[from] subsystem::subsystem::CallError),
     #[error("Chainstate error: {0}")]
     Chainstate(#[from] ChainstateError),
+    #[error("Mempool error: {0}")]
+    Mempool(#[from] mempool::error::Error),
     #[error("P2p error: {0}")]
     P2p(#[from] P2pError),
     #[error("Block production error: {0}")]
@@ -57,7 +64,7 @@ impl WalletHandlesClient {
     ) -> Result<Self, WalletHandlesClientError> {
         let result = Self {
             chainstate,
-            _mempool: mempool,
+            mempool,
             block_prod,
             p2p,
         };
@@ -126,6 +133,41 @@ impl NodeInterface for WalletHandlesClient {
         Ok(result)
     }
 
+    async fn get_stake_pool_pledge(&self, pool_id: PoolId) -> Result<Option<Amount>, Self::Error> {
+        let result = self.chainstate.call(move |this| this.get_stake_pool_data(pool_id)).await??;
+        Ok(result.map(|pool_data| pool_data.pledge_amount()))
+    }
+
+    async fn get_stake_pool_delegations_shares(
+        &self,
+        pool_id: PoolId,
+    ) -> Result<Option<BTreeMap<DelegationId, Amount>>, Self::Error> {
+        let result = self
+            .chainstate
+            .call(move |this| this.get_stake_pool_delegations_shares(pool_id))
+            .await??;
+        Ok(result)
+    }
+
+    async fn get_stake_delegation_balance(
+        &self,
+        delegation_id: DelegationId,
+    ) -> Result<Option<Amount>, Self::Error> {
+        let result = self
+            .chainstate
+            .call(move |this| this.get_stake_delegation_balance(delegation_id))
+            .await??;
+        Ok(result)
+    }
+
+    async fn get_token_info(&self, token_id: TokenId) -> Result<Option<RPCTokenInfo>, Self::Error> {
+        let result = self
+            .chainstate
+            .call(move |this| this.get_token_info_for_rpc(token_id))
+            .await??;
+        Ok(result)
+    }
+
     async fn generate_block(
         &self,
         input_data: GenerateBlockInputData,
@@ -151,6 +193,16 @@ impl NodeInterface for WalletHandlesClient {
         Ok(())
     }
 
+    async fn mempool_get_transactions(&self) -> Result<Vec<SignedTransaction>, Self::Error> {
+        let txs = self.mempool.call(move |this| this.get_all()).await??;
+        Ok(txs)
+    }
+
+    async fn mempool_get_fee_rate(&self) -> Result<FeeRate, Self::Error> {
+        let fee_rate = self.mempool.call(move |this| this.get_fee_rate()).await?;
+        Ok(fee_rate)
+    }
+
     async fn node_shutdown(&self) -> Result<(), Self::Error> {
         unimplemented!()
     }
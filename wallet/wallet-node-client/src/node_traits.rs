@@ -13,10 +13,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
+
 use chainstate::ChainInfo;
 use common::{
-    chain::{Block, GenBlock, PoolId, SignedTransaction},
-    primitives::{Amount, BlockHeight, Id},
+    chain::{
+        tokens::{RPCTokenInfo, TokenId},
+        Block, DelegationId, GenBlock, PoolId, SignedTransaction,
+    },
+    primitives::{Amount, BlockHeight, FeeRate, Id},
 };
 
 use consensus::GenerateBlockInputData;
@@ -40,6 +45,16 @@ pub trait NodeInterface {
         second_block: Id<GenBlock>,
     ) -> Result<Option<(Id<GenBlock>, BlockHeight)>, Self::Error>;
     async fn get_stake_pool_balance(&self, pool_id: PoolId) -> Result<Option<Amount>, Self::Error>;
+    async fn get_stake_pool_pledge(&self, pool_id: PoolId) -> Result<Option<Amount>, Self::Error>;
+    async fn get_stake_pool_delegations_shares(
+        &self,
+        pool_id: PoolId,
+    ) -> Result<Option<BTreeMap<DelegationId, Amount>>, Self::Error>;
+    async fn get_stake_delegation_balance(
+        &self,
+        delegation_id: DelegationId,
+    ) -> Result<Option<Amount>, Self::Error>;
+    async fn get_token_info(&self, token_id: TokenId) -> Result<Option<RPCTokenInfo>, Self::Error>;
     async fn generate_block(
         &self,
         input_data: GenerateBlockInputData,
@@ -47,6 +62,8 @@ pub trait NodeInterface {
     ) -> Result<Block, Self::Error>;
     async fn submit_block(&self, block: Block) -> Result<(), Self::Error>;
     async fn submit_transaction(&self, tx: SignedTransaction) -> Result<(), Self::Error>;
+    async fn mempool_get_fee_rate(&self) -> Result<FeeRate, Self::Error>;
+    async fn mempool_get_transactions(&self) -> Result<Vec<SignedTransaction>, Self::Error>;
 
     async fn node_shutdown(&self) -> Result<(), Self::Error>;
     async fn node_version(&self) -> Result<String, Self::Error>;
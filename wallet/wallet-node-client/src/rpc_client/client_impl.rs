@@ -13,13 +13,19 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
+
 use blockprod::rpc::BlockProductionRpcClient;
 use chainstate::{rpc::ChainstateRpcClient, ChainInfo};
 use common::{
-    chain::{Block, GenBlock, PoolId, SignedTransaction},
-    primitives::{Amount, BlockHeight, Id},
+    chain::{
+        tokens::{RPCTokenInfo, TokenId},
+        Block, DelegationId, GenBlock, PoolId, SignedTransaction,
+    },
+    primitives::{Amount, BlockHeight, FeeRate, Id},
 };
 use consensus::GenerateBlockInputData;
+use mempool::rpc::MempoolRpcClient;
 use p2p::{interface::types::ConnectedPeer, rpc::P2pRpcClient, types::peer_id::PeerId};
 use serialization::hex_encoded::HexEncoded;
 
@@ -85,6 +91,36 @@ impl NodeInterface for NodeRpcClient {
             .map_err(NodeRpcError::ResponseError)
     }
 
+    async fn get_stake_pool_pledge(&self, pool_id: PoolId) -> Result<Option<Amount>, Self::Error> {
+        ChainstateRpcClient::stake_pool_pledge(&self.http_client, pool_id)
+            .await
+            .map_err(NodeRpcError::ResponseError)
+    }
+
+    async fn get_stake_pool_delegations_shares(
+        &self,
+        pool_id: PoolId,
+    ) -> Result<Option<BTreeMap<DelegationId, Amount>>, Self::Error> {
+        ChainstateRpcClient::stake_pool_delegations_shares(&self.http_client, pool_id)
+            .await
+            .map_err(NodeRpcError::ResponseError)
+    }
+
+    async fn get_stake_delegation_balance(
+        &self,
+        delegation_id: DelegationId,
+    ) -> Result<Option<Amount>, Self::Error> {
+        ChainstateRpcClient::stake_delegation_balance(&self.http_client, delegation_id)
+            .await
+            .map_err(NodeRpcError::ResponseError)
+    }
+
+    async fn get_token_info(&self, token_id: TokenId) -> Result<Option<RPCTokenInfo>, Self::Error> {
+        ChainstateRpcClient::token_info(&self.http_client, token_id)
+            .await
+            .map_err(NodeRpcError::ResponseError)
+    }
+
     async fn generate_block(
         &self,
         input_data: GenerateBlockInputData,
@@ -108,6 +144,19 @@ impl NodeInterface for NodeRpcClient {
             .map_err(NodeRpcError::ResponseError)
     }
 
+    async fn mempool_get_transactions(&self) -> Result<Vec<SignedTransaction>, Self::Error> {
+        MempoolRpcClient::get_all_transactions(&self.http_client)
+            .await
+            .map(|txs| txs.into_iter().map(HexEncoded::take).collect())
+            .map_err(NodeRpcError::ResponseError)
+    }
+
+    async fn mempool_get_fee_rate(&self) -> Result<FeeRate, Self::Error> {
+        MempoolRpcClient::get_fee_rate(&self.http_client)
+            .await
+            .map_err(NodeRpcError::ResponseError)
+    }
+
     async fn node_shutdown(&self) -> Result<(), Self::Error> {
         node_lib::rpc::NodeRpcClient::shutdown(&self.http_client)
             .await
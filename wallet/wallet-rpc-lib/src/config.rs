@@ -0,0 +1,86 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{net::SocketAddr, path::PathBuf};
+
+use clap::Parser;
+use common::chain::config::ChainType;
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+pub enum Network {
+    Mainnet,
+    Testnet,
+    Regtest,
+    Signet,
+}
+
+#[derive(Parser, Debug)]
+pub struct WalletRpcDaemonArgs {
+    /// Network
+    #[arg(long, value_enum, default_value_t = Network::Mainnet)]
+    pub network: Network,
+
+    /// Path to the wallet file
+    #[clap(long)]
+    pub wallet_file: PathBuf,
+
+    /// Optional node RPC address
+    #[clap(long)]
+    pub node_rpc_address: Option<SocketAddr>,
+
+    /// Path to the node RPC cookie file. If not set, the value is read from the default cookie file location.
+    #[clap(long)]
+    pub node_rpc_cookie_file: Option<String>,
+
+    /// Node RPC username (either provide a username and password, or use a cookie file. You cannot use both)
+    #[clap(long)]
+    pub node_rpc_username: Option<String>,
+
+    /// Node RPC password (either provide a username and password, or use a cookie file. You cannot use both)
+    #[clap(long)]
+    pub node_rpc_password: Option<String>,
+
+    /// Address to bind the wallet http RPC to
+    #[clap(long, default_value = "127.0.0.1:3034")]
+    pub rpc_bind_address: SocketAddr,
+
+    /// Address to bind the wallet websocket RPC to
+    #[clap(long, default_value = "127.0.0.1:3035")]
+    pub rpc_ws_bind_address: SocketAddr,
+
+    /// Path to the wallet RPC cookie file, created when the daemon starts.
+    /// If not set, the cookie file is created next to the wallet file.
+    #[clap(long)]
+    pub rpc_cookie_file: Option<String>,
+
+    /// Wallet RPC username (either provide a username and password, or use a cookie file. You cannot use both)
+    #[clap(long)]
+    pub rpc_username: Option<String>,
+
+    /// Wallet RPC password (either provide a username and password, or use a cookie file. You cannot use both)
+    #[clap(long)]
+    pub rpc_password: Option<String>,
+}
+
+impl From<Network> for ChainType {
+    fn from(value: Network) -> Self {
+        match value {
+            Network::Mainnet => ChainType::Mainnet,
+            Network::Testnet => ChainType::Testnet,
+            Network::Regtest => ChainType::Regtest,
+            Network::Signet => ChainType::Signet,
+        }
+    }
+}
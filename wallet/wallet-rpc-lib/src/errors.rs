@@ -0,0 +1,36 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::PathBuf;
+
+#[derive(thiserror::Error, Debug)]
+pub enum WalletRpcError {
+    #[error("Controller error: {0}")]
+    Controller(wallet_controller::ControllerError<wallet_controller::NodeRpcClient>),
+    #[error("RPC error: {0}")]
+    RpcError(node_comm::rpc_client::NodeRpcError),
+    #[error(
+        "RPC authentication cookie-file read error: {0}: {1}. Please make sure the node is started."
+    )]
+    CookieFileReadError(PathBuf, String),
+    #[error("Invalid config: {0}")]
+    InvalidConfig(String),
+    #[error("RPC credentials error: {0}")]
+    RpcCreds(rpc::rpc_creds::RpcCredsError),
+    #[error("RPC server start error: {0}")]
+    RpcServer(String),
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+}
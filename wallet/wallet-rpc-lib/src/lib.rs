@@ -0,0 +1,157 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+pub mod config;
+pub mod errors;
+mod service;
+pub mod types;
+
+use std::{net::SocketAddr, path::PathBuf, str::FromStr, sync::Arc};
+
+use common::chain::{config::ChainType, ChainConfig};
+use config::WalletRpcDaemonArgs;
+use errors::WalletRpcError;
+use logging::log;
+use rpc::{rpc_creds::RpcCreds, RpcConfig};
+use subsystem::Subsystem;
+use tokio::sync::Mutex;
+use utils::{
+    cookie::{load_cookie, COOKIE_FILENAME},
+    default_data_dir::default_data_dir_for_chain,
+};
+use wallet_controller::RpcController;
+
+pub use service::{WalletRpc, WalletRpcClient, WalletRpcServer};
+
+/// The name of the cookie file created next to the wallet file,
+/// different from the node's one in case the wallet file is in the node's data directory
+const WALLET_COOKIE_FILENAME: &str = ".wallet_rpc_cookie";
+
+/// Open the wallet, start the wallet RPC server and keep the wallet in sync with the node
+/// until Ctrl-C is pressed
+pub async fn run(
+    args: WalletRpcDaemonArgs,
+    chain_config: Option<Arc<ChainConfig>>,
+) -> Result<(), WalletRpcError> {
+    let WalletRpcDaemonArgs {
+        network,
+        wallet_file,
+        node_rpc_address,
+        node_rpc_cookie_file,
+        node_rpc_username,
+        node_rpc_password,
+        rpc_bind_address,
+        rpc_ws_bind_address,
+        rpc_cookie_file,
+        rpc_username,
+        rpc_password,
+    } = args;
+
+    let chain_type: ChainType = network.into();
+    let chain_config = chain_config
+        .unwrap_or_else(|| Arc::new(common::chain::config::Builder::new(chain_type).build()));
+
+    // TODO: Use the constant with the node
+    let default_http_rpc_addr = || SocketAddr::from_str("127.0.0.1:3030").expect("Can't fail");
+    let node_rpc_address = node_rpc_address.unwrap_or_else(default_http_rpc_addr);
+
+    let (node_rpc_username, node_rpc_password) =
+        match (node_rpc_cookie_file, node_rpc_username, node_rpc_password) {
+            (None, None, None) => {
+                let cookie_file_path =
+                    default_data_dir_for_chain(chain_type.name()).join(COOKIE_FILENAME);
+                load_cookie(cookie_file_path.clone())
+                    .map_err(|e| WalletRpcError::CookieFileReadError(cookie_file_path, e))?
+            }
+            (Some(cookie_file_path), None, None) => load_cookie(&cookie_file_path)
+                .map_err(|e| WalletRpcError::CookieFileReadError(cookie_file_path.into(), e))?,
+            (None, Some(username), Some(password)) => (username, password),
+            _ => {
+                return Err(WalletRpcError::InvalidConfig(
+                    "Invalid node RPC cookie/username/password combination".to_owned(),
+                ))
+            }
+        };
+
+    let rpc_client = wallet_controller::make_rpc_client(
+        node_rpc_address,
+        Some((&node_rpc_username, &node_rpc_password)),
+    )
+    .await
+    .map_err(WalletRpcError::RpcError)?;
+
+    let wallet = RpcController::open_wallet(Arc::clone(&chain_config), &wallet_file)
+        .map_err(WalletRpcError::Controller)?;
+    let controller = Arc::new(Mutex::new(RpcController::new(
+        Arc::clone(&chain_config),
+        rpc_client,
+        wallet,
+    )));
+
+    let wallet_dir = wallet_file.parent().map_or_else(PathBuf::new, |dir| dir.to_owned());
+    let rpc_cookie_file = match (&rpc_cookie_file, &rpc_username, &rpc_password) {
+        (None, None, None) => {
+            Some(wallet_dir.join(WALLET_COOKIE_FILENAME).to_string_lossy().into_owned())
+        }
+        _ => rpc_cookie_file,
+    };
+    let rpc_creds = RpcCreds::new(&wallet_dir, rpc_username, rpc_password, rpc_cookie_file)
+        .map_err(WalletRpcError::RpcCreds)?;
+
+    let rpc_config = RpcConfig {
+        http_bind_address: rpc_bind_address.into(),
+        http_enabled: true.into(),
+        ws_bind_address: rpc_ws_bind_address.into(),
+        ws_enabled: true.into(),
+    };
+
+    let rpc = rpc::Builder::new(rpc_config, Some(rpc_creds))
+        .register(WalletRpc::new(Arc::clone(&chain_config), Arc::clone(&controller)).into_rpc())
+        .build()
+        .await
+        .map_err(|e| WalletRpcError::RpcServer(e.to_string()))?;
+
+    if let Some(address) = rpc.http_address() {
+        log::info!("Wallet http RPC is listening on {address}");
+    }
+    if let Some(address) = rpc.websocket_address() {
+        log::info!("Wallet websocket RPC is listening on {address}");
+    }
+
+    // The RPC requests wait while the wallet is synced, the lock is released between the syncs
+    let sync_loop = async {
+        loop {
+            let delay = controller.lock().await.run_once().await;
+            tokio::time::sleep(delay).await;
+        }
+    };
+
+    tokio::select! {
+        _ = sync_loop => {},
+        res = tokio::signal::ctrl_c() => {
+            if let Err(e) = res {
+                log::error!("Ctrl-C signal handler failed: {e}");
+            }
+        },
+    }
+
+    log::info!("Shutting down the wallet RPC server");
+    rpc.shutdown().await;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests;
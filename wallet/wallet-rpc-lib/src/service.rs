@@ -0,0 +1,356 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Wallet RPC methods
+
+use std::sync::Arc;
+
+use common::{
+    address::Address,
+    chain::{
+        signature::inputsig::arbitrary_message::ArbitraryMessageSignature, ChainConfig, PoolId,
+    },
+    primitives::Amount,
+};
+use rpc::Result as RpcResult;
+use serialization::hex::{HexDecode, HexEncode};
+use tokio::sync::Mutex;
+use wallet_controller::{FeeRate, RpcController, UtxoState, UtxoStates, DEFAULT_ACCOUNT_INDEX};
+
+use crate::{
+    errors::WalletRpcError,
+    types::{Balances, BlockInfo, PoolInfo},
+};
+
+#[rpc::rpc(server, client, namespace = "wallet")]
+trait WalletRpc {
+    /// Get the id and the height of the last block scanned by the wallet
+    #[method(name = "best_block")]
+    async fn best_block(&self) -> RpcResult<BlockInfo>;
+
+    /// Synchronize the wallet with the node's blockchain and mempool without waiting for the background sync
+    #[method(name = "sync")]
+    async fn sync(&self) -> RpcResult<()>;
+
+    /// Get the coin and token balances of the wallet.
+    /// Only confirmed UTXOs are counted unless `with_unconfirmed` is set.
+    #[method(name = "get_balance")]
+    async fn get_balance(&self, with_unconfirmed: Option<bool>) -> RpcResult<Balances>;
+
+    /// Generate a new unused address
+    #[method(name = "new_address")]
+    async fn new_address(&self) -> RpcResult<String>;
+
+    /// Generate a new unused public key, hex encoded
+    #[method(name = "new_public_key")]
+    async fn new_public_key(&self) -> RpcResult<String>;
+
+    /// Sign a message with the key of a wallet address, the signature is hex encoded
+    #[method(name = "sign_message")]
+    async fn sign_message(&self, address: String, message: String) -> RpcResult<String>;
+
+    /// Check that a message was signed by the owner of an address
+    #[method(name = "verify_message")]
+    async fn verify_message(
+        &self,
+        address: String,
+        message: String,
+        signature: String,
+    ) -> RpcResult<bool>;
+
+    /// Send coins to an address, the fee rate of the node is used if `fee_rate` is not set
+    #[method(name = "send_to_address")]
+    async fn send_to_address(
+        &self,
+        address: String,
+        amount: String,
+        fee_rate: Option<String>,
+    ) -> RpcResult<()>;
+
+    /// Create a new stake pool with the given pledge
+    #[method(name = "create_stake_pool")]
+    async fn create_stake_pool(&self, amount: String, fee_rate: Option<String>) -> RpcResult<()>;
+
+    /// Decommission a stake pool of the wallet
+    #[method(name = "decommission_stake_pool")]
+    async fn decommission_stake_pool(
+        &self,
+        pool_id: PoolId,
+        fee_rate: Option<String>,
+    ) -> RpcResult<()>;
+
+    /// List the stake pools of the wallet
+    #[method(name = "list_pools")]
+    async fn list_pools(&self) -> RpcResult<Vec<PoolInfo>>;
+
+    /// Start staking with the stake pools of the wallet
+    #[method(name = "start_staking")]
+    async fn start_staking(&self) -> RpcResult<()>;
+
+    /// Stop staking
+    #[method(name = "stop_staking")]
+    async fn stop_staking(&self) -> RpcResult<()>;
+
+    /// Check whether staking is started
+    #[method(name = "is_staking")]
+    async fn is_staking(&self) -> RpcResult<bool>;
+
+    /// Encrypt the private keys of the wallet with a new password
+    #[method(name = "encrypt_private_keys")]
+    async fn encrypt_private_keys(&self, password: String) -> RpcResult<()>;
+
+    /// Remove the encryption of the private keys
+    #[method(name = "remove_private_keys_encryption")]
+    async fn remove_private_keys_encryption(&self) -> RpcResult<()>;
+
+    /// Unlock the encrypted private keys for usage
+    #[method(name = "unlock_private_keys")]
+    async fn unlock_private_keys(&self, password: String) -> RpcResult<()>;
+
+    /// Lock the encrypted private keys so they can't be used until they are unlocked again
+    #[method(name = "lock_private_keys")]
+    async fn lock_private_keys(&self) -> RpcResult<()>;
+}
+
+pub struct WalletRpc {
+    chain_config: Arc<ChainConfig>,
+    controller: Arc<Mutex<RpcController>>,
+}
+
+impl WalletRpc {
+    pub fn new(chain_config: Arc<ChainConfig>, controller: Arc<Mutex<RpcController>>) -> Self {
+        Self {
+            chain_config,
+            controller,
+        }
+    }
+
+    fn parse_address(&self, address: &str) -> Result<Address, WalletRpcError> {
+        Address::from_str(&self.chain_config, address)
+            .map_err(|e| WalletRpcError::InvalidInput(format!("Invalid address '{address}': {e}")))
+    }
+
+    fn parse_coin_amount(&self, value: &str) -> Result<Amount, WalletRpcError> {
+        Amount::from_fixedpoint_str(value, self.chain_config.coin_decimals())
+            .ok_or_else(|| WalletRpcError::InvalidInput(format!("Invalid amount '{value}'")))
+    }
+
+    fn parse_fee_rate(&self, value: Option<String>) -> Result<Option<FeeRate>, WalletRpcError> {
+        value.map(|value| self.parse_coin_amount(&value).map(FeeRate::new)).transpose()
+    }
+}
+
+#[async_trait::async_trait]
+impl WalletRpcServer for WalletRpc {
+    async fn best_block(&self) -> RpcResult<BlockInfo> {
+        let (id, height) = rpc::handle_result(
+            self.controller.lock().await.best_block().map_err(WalletRpcError::Controller),
+        )?;
+        Ok(BlockInfo { id, height })
+    }
+
+    async fn sync(&self) -> RpcResult<()> {
+        rpc::handle_result(
+            self.controller
+                .lock()
+                .await
+                .sync_once()
+                .await
+                .map_err(WalletRpcError::Controller),
+        )
+    }
+
+    async fn get_balance(&self, with_unconfirmed: Option<bool>) -> RpcResult<Balances> {
+        let utxo_states = if with_unconfirmed.unwrap_or(false) {
+            UtxoStates::ALL
+        } else {
+            UtxoState::Confirmed.into()
+        };
+        let (coins, tokens) = rpc::handle_result(
+            self.controller
+                .lock()
+                .await
+                .get_balance(DEFAULT_ACCOUNT_INDEX, utxo_states)
+                .map_err(WalletRpcError::Controller),
+        )?;
+        Ok(Balances::new(&self.chain_config, coins, tokens))
+    }
+
+    async fn new_address(&self) -> RpcResult<String> {
+        let address = rpc::handle_result(
+            self.controller
+                .lock()
+                .await
+                .new_address(DEFAULT_ACCOUNT_INDEX)
+                .map_err(WalletRpcError::Controller),
+        )?;
+        Ok(address.get().to_owned())
+    }
+
+    async fn new_public_key(&self) -> RpcResult<String> {
+        let public_key = rpc::handle_result(
+            self.controller
+                .lock()
+                .await
+                .new_public_key(DEFAULT_ACCOUNT_INDEX)
+                .map_err(WalletRpcError::Controller),
+        )?;
+        Ok(public_key.hex_encode())
+    }
+
+    async fn sign_message(&self, address: String, message: String) -> RpcResult<String> {
+        let address = rpc::handle_result(self.parse_address(&address))?;
+        let signature = rpc::handle_result(
+            self.controller
+                .lock()
+                .await
+                .sign_message(DEFAULT_ACCOUNT_INDEX, &address, message.as_bytes())
+                .map_err(WalletRpcError::Controller),
+        )?;
+        Ok(signature.hex_encode())
+    }
+
+    async fn verify_message(
+        &self,
+        address: String,
+        message: String,
+        signature: String,
+    ) -> RpcResult<bool> {
+        let address = rpc::handle_result(self.parse_address(&address))?;
+        let destination = rpc::handle_result(address.destination().map_err(|e| {
+            WalletRpcError::InvalidInput(format!("Invalid address '{}': {e}", address.get()))
+        }))?;
+        let signature = rpc::handle_result(
+            ArbitraryMessageSignature::hex_decode_all(&signature)
+                .map_err(|e| WalletRpcError::InvalidInput(format!("Invalid signature: {e}"))),
+        )?;
+        Ok(signature.verify_signature(&destination, message.as_bytes()).is_ok())
+    }
+
+    async fn send_to_address(
+        &self,
+        address: String,
+        amount: String,
+        fee_rate: Option<String>,
+    ) -> RpcResult<()> {
+        let address = rpc::handle_result(self.parse_address(&address))?;
+        let amount = rpc::handle_result(self.parse_coin_amount(&amount))?;
+        let fee_rate = rpc::handle_result(self.parse_fee_rate(fee_rate))?;
+        rpc::handle_result(
+            self.controller
+                .lock()
+                .await
+                .send_to_address(DEFAULT_ACCOUNT_INDEX, address, amount, fee_rate)
+                .await
+                .map_err(WalletRpcError::Controller),
+        )
+    }
+
+    async fn create_stake_pool(&self, amount: String, fee_rate: Option<String>) -> RpcResult<()> {
+        let amount = rpc::handle_result(self.parse_coin_amount(&amount))?;
+        let fee_rate = rpc::handle_result(self.parse_fee_rate(fee_rate))?;
+        rpc::handle_result(
+            self.controller
+                .lock()
+                .await
+                .create_stake_pool_tx(DEFAULT_ACCOUNT_INDEX, amount, fee_rate)
+                .await
+                .map_err(WalletRpcError::Controller),
+        )
+    }
+
+    async fn decommission_stake_pool(
+        &self,
+        pool_id: PoolId,
+        fee_rate: Option<String>,
+    ) -> RpcResult<()> {
+        let fee_rate = rpc::handle_result(self.parse_fee_rate(fee_rate))?;
+        rpc::handle_result(
+            self.controller
+                .lock()
+                .await
+                .decommission_stake_pool(DEFAULT_ACCOUNT_INDEX, pool_id, fee_rate)
+                .await
+                .map_err(WalletRpcError::Controller),
+        )
+    }
+
+    async fn list_pools(&self) -> RpcResult<Vec<PoolInfo>> {
+        let pools = rpc::handle_result(
+            self.controller
+                .lock()
+                .await
+                .list_pools(DEFAULT_ACCOUNT_INDEX)
+                .await
+                .map_err(WalletRpcError::Controller),
+        )?;
+        Ok(pools
+            .into_iter()
+            .map(|pool_info| PoolInfo::new(&self.chain_config, pool_info))
+            .collect())
+    }
+
+    async fn start_staking(&self) -> RpcResult<()> {
+        rpc::handle_result(
+            self.controller.lock().await.start_staking().map_err(WalletRpcError::Controller),
+        )
+    }
+
+    async fn stop_staking(&self) -> RpcResult<()> {
+        rpc::handle_result(
+            self.controller.lock().await.stop_staking().map_err(WalletRpcError::Controller),
+        )
+    }
+
+    async fn is_staking(&self) -> RpcResult<bool> {
+        Ok(self.controller.lock().await.is_staking())
+    }
+
+    async fn encrypt_private_keys(&self, password: String) -> RpcResult<()> {
+        rpc::handle_result(
+            self.controller
+                .lock()
+                .await
+                .encrypt_wallet(&Some(password))
+                .map_err(WalletRpcError::Controller),
+        )
+    }
+
+    async fn remove_private_keys_encryption(&self) -> RpcResult<()> {
+        rpc::handle_result(
+            self.controller
+                .lock()
+                .await
+                .encrypt_wallet(&None)
+                .map_err(WalletRpcError::Controller),
+        )
+    }
+
+    async fn unlock_private_keys(&self, password: String) -> RpcResult<()> {
+        rpc::handle_result(
+            self.controller
+                .lock()
+                .await
+                .unlock_wallet(&password)
+                .map_err(WalletRpcError::Controller),
+        )
+    }
+
+    async fn lock_private_keys(&self) -> RpcResult<()> {
+        rpc::handle_result(
+            self.controller.lock().await.lock_wallet().map_err(WalletRpcError::Controller),
+        )
+    }
+}
@@ -0,0 +1,130 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::net::SocketAddr;
+
+use common::{
+    address::Address,
+    chain::{config::create_unit_test_config, Destination, GenBlock},
+    primitives::Id,
+};
+use jsonrpsee::http_client::HttpClientBuilder;
+use rpc::make_http_header_with_auth;
+use wallet_controller::mnemonic;
+
+use super::*;
+
+const MNEMONIC: &str =
+    "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+// The wallet only asks the node for the best block id when connecting
+#[rpc::rpc(server, namespace = "chainstate")]
+trait NodeStubRpc {
+    #[method(name = "best_block_id")]
+    fn best_block_id(&self) -> rpc::Result<Id<GenBlock>>;
+}
+
+struct NodeStub {
+    best_block_id: Id<GenBlock>,
+}
+
+impl NodeStubRpcServer for NodeStub {
+    fn best_block_id(&self) -> rpc::Result<Id<GenBlock>> {
+        Ok(self.best_block_id)
+    }
+}
+
+fn rpc_config() -> RpcConfig {
+    RpcConfig {
+        http_bind_address: "127.0.0.1:0".parse::<SocketAddr>().unwrap().into(),
+        http_enabled: true.into(),
+        ws_bind_address: "127.0.0.1:0".parse::<SocketAddr>().unwrap().into(),
+        ws_enabled: false.into(),
+    }
+}
+
+#[tokio::test]
+async fn wallet_rpc_cookie_auth() {
+    let chain_config = Arc::new(create_unit_test_config());
+    let data_dir = tempfile::TempDir::new().unwrap();
+
+    let node_rpc = rpc::Builder::new(rpc_config(), None)
+        .register(
+            NodeStub {
+                best_block_id: chain_config.genesis_block_id(),
+            }
+            .into_rpc(),
+        )
+        .build()
+        .await
+        .unwrap();
+    let node_client = wallet_controller::make_rpc_client(*node_rpc.http_address().unwrap(), None)
+        .await
+        .unwrap();
+
+    let mnemonic = mnemonic::parse_mnemonic(mnemonic::Language::English, MNEMONIC).unwrap();
+    let wallet = RpcController::create_wallet(
+        Arc::clone(&chain_config),
+        data_dir.path().join("wallet.sqlite"),
+        mnemonic,
+        None,
+    )
+    .unwrap();
+    let controller = Arc::new(Mutex::new(RpcController::new(
+        Arc::clone(&chain_config),
+        node_client,
+        wallet,
+    )));
+
+    let cookie_file_path = data_dir.path().join(WALLET_COOKIE_FILENAME);
+    let rpc_creds = RpcCreds::new(
+        data_dir.path(),
+        None::<String>,
+        None::<String>,
+        Some(cookie_file_path.to_string_lossy()),
+    )
+    .unwrap();
+    let rpc = rpc::Builder::new(rpc_config(), Some(rpc_creds))
+        .register(WalletRpc::new(Arc::clone(&chain_config), controller).into_rpc())
+        .build()
+        .await
+        .unwrap();
+    let url = format!("http://{}", rpc.http_address().unwrap());
+
+    let client = HttpClientBuilder::default().build(&url).unwrap();
+    assert!(client.get_balance(None).await.is_err());
+
+    let (username, password) = load_cookie(&cookie_file_path).unwrap();
+    let client = HttpClientBuilder::default()
+        .set_headers(make_http_header_with_auth(Some((&username, &password))))
+        .build(&url)
+        .unwrap();
+
+    let balances = client.get_balance(Some(true)).await.unwrap();
+    assert_eq!(balances.coins, "0");
+    assert!(balances.tokens.is_empty());
+
+    let address = client.new_address().await.unwrap();
+    let address = Address::from_str(&chain_config, &address).unwrap();
+    assert!(matches!(
+        address.destination().unwrap(),
+        Destination::Address(_)
+    ));
+    let next_address = client.new_address().await.unwrap();
+    assert_ne!(next_address, address.get());
+
+    rpc.shutdown().await;
+    node_rpc.shutdown().await;
+}
@@ -0,0 +1,79 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Types used by the wallet RPC interface.
+//!
+//! Coin amounts are decimal strings in coins, token amounts are decimal strings in atoms.
+
+use std::collections::BTreeMap;
+
+use common::{
+    chain::{tokens::TokenId, ChainConfig, DelegationId, GenBlock, PoolId},
+    primitives::{Amount, BlockHeight, Id},
+};
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct BlockInfo {
+    pub id: Id<GenBlock>,
+    pub height: BlockHeight,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Balances {
+    pub coins: String,
+    pub tokens: BTreeMap<TokenId, String>,
+}
+
+impl Balances {
+    pub fn new(
+        chain_config: &ChainConfig,
+        coins: Amount,
+        tokens: BTreeMap<TokenId, Amount>,
+    ) -> Self {
+        Self {
+            coins: coins.into_fixedpoint_str(chain_config.coin_decimals()),
+            tokens: tokens
+                .into_iter()
+                .map(|(token_id, amount)| (token_id, amount.into_atoms().to_string()))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct PoolInfo {
+    pub pool_id: PoolId,
+    /// The pledge and the balance are not known until the pool is confirmed
+    pub pledge: Option<String>,
+    pub balance: Option<String>,
+    pub delegations_shares: BTreeMap<DelegationId, String>,
+}
+
+impl PoolInfo {
+    pub fn new(chain_config: &ChainConfig, pool_info: wallet_controller::PoolInfo) -> Self {
+        let print_amount =
+            |amount: Amount| amount.into_fixedpoint_str(chain_config.coin_decimals());
+        Self {
+            pool_id: pool_info.pool_id,
+            pledge: pool_info.pledge.map(print_amount),
+            balance: pool_info.balance.map(print_amount),
+            delegations_shares: pool_info
+                .delegations_shares
+                .into_iter()
+                .map(|(delegation_id, shares)| (delegation_id, print_amount(shares)))
+                .collect(),
+        }
+    }
+}